@@ -0,0 +1,109 @@
+int printf(const char *format, ...);
+
+void show(long n) {
+    char format[] = {'%', 'l', 'd', '\n', 0};
+    printf(format, n);
+}
+
+_Atomic int hits;
+_Atomic(unsigned long) total;
+_Atomic(char *) cursor;
+
+// a spin lock: 0 when free, 1 when held
+int lock;
+
+void acquire(int *l) {
+    int expected = 0;
+    while (!__atomic_compare_exchange_n(l, &expected, 1, 0, __ATOMIC_ACQUIRE, __ATOMIC_RELAXED)) {
+        expected = 0;
+    }
+}
+
+void release(int *l) {
+    __atomic_store_n(l, 0, __ATOMIC_RELEASE);
+}
+
+long guarded_sum(long *values, int n) {
+    long sum = 0;
+    acquire(&lock);
+    for (int i = 0; i < n; i++) {
+        sum += values[i];
+    }
+    release(&lock);
+    return sum;
+}
+
+void record(int weight) {
+    hits++;
+    total += weight;
+}
+
+// bumps the shared cursor by n bytes, returns where it was
+char *reserve(int n) {
+    return __atomic_fetch_add(&cursor, n, __ATOMIC_RELAXED);
+}
+
+int main(void) {
+    for (int i = 1; i <= 10; i++) {
+        record(i * 3);
+    }
+    show(hits);
+    show((long)total);
+
+    long values[4];
+    values[0] = 1;
+    values[1] = 20;
+    values[2] = 300;
+    values[3] = 4000;
+    show(guarded_sum(values, 4));
+    show(__atomic_load_n(&lock, __ATOMIC_ACQUIRE));
+
+    // fetch operations return the previous value
+    unsigned int flags = 0xf0;
+    show(__atomic_fetch_or(&flags, 0x0f, __ATOMIC_SEQ_CST));
+    show(__atomic_fetch_and(&flags, 0x3c, __ATOMIC_ACQ_REL));
+    show(__atomic_fetch_xor(&flags, 0xff, __ATOMIC_RELEASE));
+    show(__atomic_fetch_sub(&flags, 3, __ATOMIC_CONSUME));
+    show(flags);
+
+    // narrow objects wrap around like the type
+    signed char small = 120;
+    show(__atomic_fetch_add(&small, 10, __ATOMIC_SEQ_CST));
+    show(small);
+    show(__atomic_exchange_n(&small, -7, __ATOMIC_ACQUIRE));
+    show(__atomic_load_n(&small, __ATOMIC_RELAXED));
+
+    short s = 5;
+    short expected = 6;
+    show(__atomic_compare_exchange_n(&s, &expected, 9, 1, __ATOMIC_SEQ_CST, __ATOMIC_SEQ_CST));
+    show(expected);
+    show(__atomic_compare_exchange_n(&s, &expected, 9, 0, __ATOMIC_SEQ_CST, __ATOMIC_ACQUIRE));
+    show(s);
+
+    char buffer[16];
+    cursor = buffer;
+    char *first = reserve(4);
+    char *second = reserve(8);
+    show(second - first);
+    show(cursor - buffer);
+
+    _Atomic long counter = 100;
+    counter -= 30;
+    counter *= 2;
+    counter <<= 1;
+    counter /= 7;
+    show(counter);
+    counter--;
+    show(counter);
+
+    _Atomic unsigned char byte = 250;
+    byte += 10;
+    show(byte);
+    byte |= 0x80;
+    show(byte);
+
+    _Atomic int scaled = 7;
+    scaled *= 2.5;
+    show(scaled);
+    return 0;
+}
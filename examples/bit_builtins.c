@@ -0,0 +1,108 @@
+int printf(const char *format, ...);
+
+void show(long n) {
+    char format[] = {'%', 'l', 'd', '\n', 0};
+    printf(format, n);
+}
+
+void show_hex(unsigned long n) {
+    char format[] = {'%', 'l', 'x', '\n', 0};
+    printf(format, n);
+}
+
+// folded at compile time, so usable as an array size
+int table[__builtin_popcount(0xff) + __builtin_ctz(16)];
+
+// a set of small integers, one bit each
+int bitset_count(unsigned long *words, int n) {
+    int count = 0;
+    for (int i = 0; i < n; i++) {
+        count += __builtin_popcountl(words[i]);
+    }
+    return count;
+}
+
+int bitset_first(unsigned long *words, int n) {
+    for (int i = 0; i < n; i++) {
+        if (__builtin_expect(words[i] != 0, 1)) {
+            return i * 64 + __builtin_ctzll(words[i]);
+        }
+    }
+    return -1;
+}
+
+// index of the highest set bit, or -1
+int highest_bit(unsigned int x) {
+    if (x == 0) {
+        return -1;
+    }
+    return 31 - __builtin_clz(x);
+}
+
+unsigned int next_power_of_two(unsigned int x) {
+    if (x <= 1) {
+        return 1;
+    }
+    return 1u << (32 - __builtin_clz(x - 1));
+}
+
+unsigned long hash(unsigned long h, unsigned int value) {
+    h ^= __builtin_bswap32(value);
+    h *= 0x100000001b3ul;
+    return __builtin_bswap64(h) ^ h;
+}
+
+int sign(int x) {
+    if (x > 0) {
+        return 1;
+    }
+    if (x < 0) {
+        return -1;
+    }
+    if (x == 0) {
+        return 0;
+    }
+    __builtin_unreachable();
+}
+
+int checked_index(int i, int n) {
+    if (i < 0 || i >= n) {
+        __builtin_trap();
+    }
+    return i;
+}
+
+int main(void) {
+    unsigned long words[3];
+    words[0] = 0;
+    words[1] = 0x8100000000000000ul;
+    words[2] = 0xf0f0;
+    show(bitset_count(words, 3));
+    show(bitset_first(words, 3));
+
+    show(highest_bit(1));
+    show(highest_bit(0x80000000u));
+    show(highest_bit(0));
+    show(next_power_of_two(5));
+    show(next_power_of_two(1024));
+    show(next_power_of_two(1025));
+
+    show(__builtin_clzl(1ul));
+    show(__builtin_ctz(0x80));
+    show(__builtin_popcount(-1));
+    show_hex(__builtin_bswap32(0x12345678));
+    show_hex(__builtin_bswap64(0x0102030405060708ul));
+    table[11] = 11;
+    show(table[11]);
+
+    unsigned long h = 0xcbf29ce484222325ul;
+    for (unsigned int i = 0; i < 4; i++) {
+        h = hash(h, i * 0x01010101u);
+    }
+    show_hex(h);
+
+    show(sign(-7) + sign(3) * 10 + sign(0) * 100);
+    show(checked_index(2, 3));
+    show(__builtin_expect(words[2] > 5, 0));
+    return 0;
+}
@@ -0,0 +1,79 @@
+int putchar(int c);
+
+int limit = 4;
+static int hits;
+
+static void print_num(int n) {
+    // a block-scope prototype, completed by the file-scope definition below
+    void print_digits(int n);
+    if (n < 0) {
+        putchar(45);
+        n = -n;
+    }
+    print_digits(n);
+    putchar(10);
+}
+
+void print_digits(int n) {
+    if (n >= 10) {
+        print_digits(n / 10);
+    }
+    putchar(48 + n % 10);
+}
+
+int twice(int x) {
+    return 2 * x;
+}
+
+int count(void) {
+    extern int total; // defined after its first use
+    total = total + 1;
+    return total;
+}
+
+int total = 100;
+
+int shadowed(void) {
+    int limit = 1;
+    {
+        // refers to the file-scope object, hiding the local
+        extern int limit;
+        limit = limit + 10;
+    }
+    return limit;
+}
+
+int main(void) {
+    int twice = 5;
+    print_num(twice);
+    {
+        // the function, hiding the local object
+        int twice(int), square(int);
+        print_num(twice(21));
+        print_num(square(-7));
+    }
+    print_num(twice);
+
+    print_num(count());
+    print_num(count());
+    print_num(shadowed());
+    print_num(limit);
+
+    for (int i = 0; i < 3; i++) {
+        extern int limit;
+        static int calls;
+        int helper(void);
+        calls = calls + helper();
+        print_num(calls * limit);
+    }
+    return hits;
+}
+
+int helper(void) {
+    hits = hits + 1;
+    return hits;
+}
+
+int square(int x) {
+    return x * x;
+}
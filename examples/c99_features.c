@@ -0,0 +1,23 @@
+int printf(const char *format, ...);
+
+/* C99 features, each of which -std=c89 rejects or -pedantic reports there;
+   -std=c99 -pedantic accepts all of them silently */
+
+long long sum(int n) {
+    long long total = 0;
+    for (int i = 1; i <= n; i++) // a declaration in a for loop, and a line comment
+        total += i;
+    return total;
+}
+
+int main(void) {
+    char format[] = {'%', 'l', 'l', 'd', '\n', 0};
+    int n = 4;
+    printf(format, sum(n));
+
+    int squares[n]; /* a declaration after a statement, of a variable length array */
+    for (int i = 0; i < n; i++)
+        squares[i] = i * i;
+    printf(format, (long long)squares[n - 1] * 3000000000ll);
+    return 0;
+}
@@ -0,0 +1,72 @@
+int putchar(int c);
+
+int print_num(long n) {
+    if (n < 0) {
+        putchar(45);
+        n = -n;
+    }
+    if (n >= 10) {
+        print_num(n / 10);
+    }
+    putchar(48 + (int)(n % 10));
+    return 0;
+}
+
+int line(long n) {
+    print_num(n);
+    putchar(10);
+    return 0;
+}
+
+double half(int x) {
+    return x / 2.0;
+}
+
+int main() {
+    int big = 300;
+    long wide = 4294967296L + 7;
+    unsigned int u = 4294967295u;
+    double d = -3.75;
+    float f = 2.5f;
+
+    // truncation and sign/zero extension
+    line((char)big);
+    line((unsigned char)big);
+    line((short)70000);
+    line((unsigned short)-1);
+    line((char)-1);
+    line((unsigned char)-1);
+
+    // int <-> long
+    line((int)wide);
+    line((long)u);
+    line((long)(int)u);
+    line((unsigned long)(unsigned int)-1 + 1);
+
+    // int <-> floating
+    line((int)d);
+    line((long)(d * 100));
+    line((int)(f * 4));
+    line((int)half(7));
+    line((int)(half(7) * 10));
+    line((int)(float)16777217);
+    line((unsigned int)3.99);
+    // out of range, which is undefined only if it runs
+    if (d > 0)
+        line((unsigned int)-1.0 + (int)1e10);
+
+    // usual arithmetic conversions
+    line(-1 < u);
+    line((long)-1 < (long)u);
+    line(u + 1);
+    line(big / 7 * 7.0 == 294.0);
+
+    // int <-> pointer
+    int *p = (int *)4096;
+    long back = (long)(p == (int *)(long)4096);
+    line((long)p);
+    line((long)(char *)(long)big);
+    line(back);
+
+    return (unsigned char)(big + 1);
+}
@@ -0,0 +1,89 @@
+int putchar(int c);
+
+const int squares[8] = {0, 1, 4, 9, 16, 25, 36, 49};
+const unsigned char digits[] = {48, 49, 50, 51, 52, 53, 54, 55, 56, 57};
+const int grid[2][3] = {{1, 2, 3}, {4, 5, 6}};
+const double scale = 2.5;
+
+// stands in for a block of memory-mapped device registers
+unsigned int device[4];
+int counter = 0;
+
+int print_num(int n) {
+    if (n < 0) {
+        putchar(45);
+        n = -n;
+    }
+    if (n >= 10) {
+        print_num(n / 10);
+    }
+    putchar(digits[n % 10]);
+    return 0;
+}
+
+int line(int n) {
+    print_num(n);
+    putchar(10);
+    return 0;
+}
+
+int sum(const int *values, int count) {
+    int total = 0;
+    for (int i = 0; i < count; i++) {
+        total += values[i];
+    }
+    return total;
+}
+
+int wait_ready(volatile unsigned int *status, int spins) {
+    // every iteration must re-read the status register
+    while (!(*status & 1)) {
+        *status = *status + 2;
+        if (*status > 6) {
+            *status |= 1;
+        }
+        spins++;
+    }
+    return spins;
+}
+
+int main() {
+    volatile unsigned int *const regs = device;
+    volatile int ticks = 0;
+    const int limit = 5;
+    int local[5] = {1, 2};
+    int *p = &local[0];
+
+    for (int i = 0; i < limit; i++) {
+        ticks++;
+    }
+    line(ticks);
+
+    line(squares[3] + squares[7]);
+    line(sum(squares, 8));
+    line(grid[1][2] * 10 + grid[0][1]);
+    line((int)(scale * 4));
+
+    regs[0] = 0;
+    line(wait_ready(&regs[0], 0));
+    line(device[0]);
+
+    regs[2] = 42;
+    regs[2];
+    line(regs[2]);
+
+    *(p + 2) = 7;
+    p[3] = local[1] * 10;
+    line(sum(local, 5));
+    line(&local[4] - p);
+
+    int x = 3;
+    int *px = &x;
+    *px += 4;
+    line(x);
+
+    counter = sum(local, 2);
+    line(counter);
+
+    return squares[2];
+}
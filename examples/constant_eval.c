@@ -0,0 +1,64 @@
+int printf(const char *format, ...);
+
+void show(long n) {
+    char format[] = {'%', 'l', 'd', '\n', 0};
+    printf(format, n);
+}
+
+// folded at compile time, with the semantics the program would have at run time
+int int_min = -2147483647 - 1;
+long long_min = -9223372036854775807l - 1;
+unsigned int wrapped = 4294967295u + 2;
+unsigned long wrapped_long = 0ul - 1;
+int shifted = -16 >> 2;
+long sign_bit = 1l << 63;
+int remainder = -7 % 3;
+int quotient = -7 / 2;
+unsigned int logical = 4294967280u >> 4;
+char narrowed = (char)300;
+int converted = (int)-2.75;
+
+// the operands that are never evaluated may be undefined
+int short_circuit = 0 && 1 / 0;
+int or_else = 1 || 2147483647 + 1;
+int selected = 1 ? 5 : 1 % 0;
+
+int sizes[10 - 2 * 3];
+
+// undefined only where it runs, so outside constant expressions it compiles with a warning
+int never_divides(int d) {
+    if (d)
+        return 1 / 0;
+    int x = d ? 2147483647 + 1 : 3;
+    return x + d % 1;
+}
+
+int main(void) {
+    show(int_min);
+    show(long_min);
+    show(wrapped);
+    show(wrapped_long == 18446744073709551615ul);
+    show(shifted);
+    show(sign_bit < 0);
+    show(remainder);
+    show(quotient);
+    show(logical);
+    show(narrowed);
+    show(converted);
+    show(short_circuit);
+    show(or_else);
+    show(selected);
+    sizes[3] = 7;
+    show(sizes[3]);
+    show(never_divides(0));
+
+    show(-2147483647 - 1 < 0);
+    show(-1 >> 31);
+    show(~0u);
+    show(-(unsigned char)1);
+    show((unsigned short)65535 * 65535u);
+    show(2147483647 + 1u);
+    show(-17 % -5);
+    show(17 % -5);
+    return 0;
+}
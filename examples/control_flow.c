@@ -0,0 +1,54 @@
+int printf(const char *format, ...);
+
+void show(int n) {
+    char format[] = {'%', 'd', '\n', 0};
+    printf(format, n);
+}
+
+// returns only from inside a loop that never ends otherwise
+int first_multiple(int step, int above) {
+    int n = 0;
+    while (1) {
+        n = n + step;
+        if (n > above)
+            return n;
+    }
+}
+
+// every branch returns
+int sign(int x) {
+    if (x < 0) {
+        return -1;
+    } else if (x > 0) {
+        return 1;
+    } else {
+        return 0;
+    }
+}
+
+int countdown(int n) {
+    for (;;) {
+        if (n == 0)
+            break;
+        n = n - 1;
+    }
+    return n + 7;
+}
+
+int after_do(int n) {
+    do {
+        n = n * 2;
+        if (n > 100)
+            return n;
+    } while (1);
+}
+
+int main(void) {
+    show(first_multiple(7, 30));
+    show(sign(-5));
+    show(sign(0));
+    show(sign(12));
+    show(countdown(5));
+    show(after_do(3));
+    // falls off the end of main, which returns 0
+}
@@ -0,0 +1,92 @@
+int printf(const char *format, ...);
+int putchar(int c);
+
+int add(int a, int b) {
+    return a + b;
+}
+
+int sub(int a, int b) {
+    return a - b;
+}
+
+int mul(int a, int b) {
+    return a * b;
+}
+
+static int negate(int x) {
+    return -x;
+}
+
+double half(double x) {
+    return x / 2;
+}
+
+void show(long n) {
+    char format[] = {'%', 'l', 'd', '\n', 0};
+    printf(format, n);
+}
+
+void show_double(double x) {
+    char format[] = {'%', '.', '2', 'f', '\n', 0};
+    printf(format, x);
+}
+
+// a dispatch table, filled in by the linker
+int (*ops[3])(int, int) = {add, sub, &mul};
+int (*const default_op)(int, int) = add;
+int (*unset)(int, int);
+
+int apply(int (*op)(int, int), int a, int b) {
+    return op(a, b);
+}
+
+// returns a pointer to a function taking (int, int)
+int (*pick(int i))(int, int) {
+    if (i < 0 || i > 2)
+        return 0;
+    return ops[i];
+}
+
+long sum_many(long a, long b, long c, long d, long e, long f, long g, long h, long i, long j) {
+    return a + b + c + d + e + f + g + h + i + j;
+}
+
+int main(void) {
+    for (int i = 0; i < 3; i++) {
+        show(ops[i](7, 3));
+    }
+
+    int (*fp)(int, int) = sub;
+    show(fp(10, 4));
+    show((*fp)(10, 4));
+    fp = &mul;
+    show(apply(fp, 6, 7));
+    show(apply(add, 6, 7));
+    show(default_op(1, 2));
+
+    show(pick(1)(9, 5));
+    show(pick(5) == 0);
+    show(unset == 0);
+    show(fp == mul);
+    show(fp == add);
+
+    int (*local_ops[2])(int) = {negate, negate};
+    show(local_ops[1](8));
+
+    double (*h)(double) = half;
+    show_double(h(5));
+
+    long (*many)(long, long, long, long, long, long, long, long, long, long) = sum_many;
+    show(many(1, 2, 3, 4, 5, 6, 7, 8, 9, 10));
+
+    // a variadic callee keeps its convention through a pointer
+    int (*print)(const char *, ...) = printf;
+    char format[] = {'%', 'd', ' ', '%', '.', '1', 'f', '\n', 0};
+    print(format, 42, 2.5);
+
+    void (*out)(long) = show;
+    out(-1);
+
+    static int (*saved)(int, int) = sub;
+    return saved(ops[2](2, 3), 6);
+}
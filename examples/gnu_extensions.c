@@ -0,0 +1,130 @@
+int printf(const char *format, ...);
+
+void show(long n) {
+    char format[] = {'%', 'l', 'd', '\n', 0};
+    printf(format, n);
+}
+
+int calls = 0;
+
+int next(void) {
+    calls++;
+    return calls;
+}
+
+// a statement expression may leave the function
+int checked_half(int n) {
+    int half = ({
+        if (n % 2) {
+            return -1;
+        }
+        n / 2;
+    });
+    return half + 100;
+}
+
+// `break` and `continue` in a statement expression go to the loop around it,
+// even with operands of the enclosing expression already evaluated
+int sum_until(int stop) {
+    int total = 0;
+    int i = 0;
+    do {
+        ({ if (i == stop) break; total += i; });
+        i++;
+    } while (i < 10);
+    for (i = 0; i < 10; i++)
+        total = total + next() * 0 + ({ if (i % 2) continue; if (i > 6) break; 100; });
+    return total;
+}
+
+// a case range matches every value from the first to the last
+int kind(char c) {
+    switch (c) {
+    case '0' ... '9':
+        return 1;
+    case 'a' ... 'z':
+    case 'A' ... 'Z':
+        return 2;
+    case -128 ... -1:
+        return 3;
+    case 5 ... 4: // empty, never matches
+        return 4;
+    default:
+        return 0;
+    }
+}
+
+int bucket(unsigned long n) {
+    switch (n) {
+    case 0 ... 9:
+        return 1;
+    case 10 ... 4294967296ul:
+        return 2;
+    case 18446744073709551614ul ... 18446744073709551615ul:
+        return 3;
+    }
+    return 0;
+}
+
+int first_nonzero(int a, int b, int c) {
+    return a ?: b ?: c;
+}
+
+__extension__ long scaled(__typeof__(calls) n) {
+    __typeof__(long) factor = 1000000000;
+    return __extension__ ({ n * factor; });
+}
+
+int main(void) {
+    // what `#define square(x) ({ __typeof__(x) _v = (x); _v * _v; })` expands to:
+    // the argument is evaluated once, whatever its type
+    show(({ __typeof__(next()) _v = (next()); _v * _v; }));
+    show(({ __typeof__(next()) _v = (next()); _v * _v; }));
+    show(calls);
+    show((long)({ __typeof__(1.5) _v = (1.5); _v * _v; }));
+
+    show(checked_half(10));
+    show(checked_half(7));
+    show(sum_until(3));
+    show(calls);
+
+    show(kind('7') + kind('q') * 10 + kind('Q') * 100 + kind(-5) * 1000 + kind(4) * 10000);
+    show(kind('{'));
+    show(bucket(3) + bucket(4294967296ul) * 10 + bucket(4294967297ul) * 100);
+    show(bucket(0ul - 1) + bucket(0ul - 2) * 10 + bucket(0ul - 3) * 100);
+
+    show(first_nonzero(0, 0, 3));
+    show(first_nonzero(0, 5, 3));
+    show(first_nonzero(4, 5, 3));
+    // the condition is evaluated once
+    show(next() ?: 42);
+    show(calls);
+
+    // nested scopes and shadowing inside statement expressions
+    int x = 1;
+    int y = ({
+        int x = 10;
+        int z = ({ int x = 100; x + 1; });
+        x + z;
+    }) + x;
+    show(y);
+
+    int values[4] = {1, 2, 3, 4};
+    __typeof__(values) copy;
+    __typeof__(&values[0]) p = copy;
+    int total = 0;
+    for (int i = 0; i < 4; i++) {
+        copy[i] = values[i] * 2;
+        total += ({
+            int v = p[i];
+            v > 4 ? v : 0;
+        });
+    }
+    show(total);
+    show(scaled(3));
+
+    const __typeof__(x) limit = 7;
+    __typeof__(limit + 1L) widened = limit;
+    show(widened * 1000000000);
+    return 0;
+}
@@ -0,0 +1,97 @@
+int printf(const char *format, ...);
+
+void show(long n) {
+    char format[] = {'%', 'l', 'd', '\n', 0};
+    printf(format, n);
+}
+
+int add(int a, int b) {
+    int sum;
+    asm("add %w0, %w1, %w2" : "=r"(sum) : "r"(a), "r"(b));
+    return sum;
+}
+
+long add_immediate(long a) {
+    long result;
+    asm("add %0, %1, #%2" : "=r"(result) : "r"(a), "i"(40 + 2));
+    return result;
+}
+
+// read and written in the same register
+void double_in_place(int *p) {
+    asm("lsl %w0, %w0, #1" : "+r"(*p));
+}
+
+// an input that shares the register of the output
+unsigned long rotate(unsigned long value, int amount) {
+    unsigned long result;
+    asm("ror %[out], %[out], %x[amount]" : [out] "=r"(result) : "0"(value), [amount] "r"(amount));
+    return result;
+}
+
+int load_third(int *values) {
+    int result;
+    asm volatile("ldr %w0, %1" : "=r"(result) : "m"(values[2]));
+    return result;
+}
+
+void store(long *p, long value) {
+    asm volatile("str %1, %0" : "=m"(*p) : "r"(value) : "memory");
+}
+
+// the condition flags after comparing a and b, read from the system register
+long flags_after_compare(long a, long b) {
+    long nzcv;
+    asm volatile("cmp %1, %2\n\tmrs %0, nzcv" : "=r"(nzcv) : "r"(a), "r"(b) : "cc");
+    return nzcv >> 28;
+}
+
+// locals in callee-saved registers survive a template that clobbers them
+long clobbers(long a) {
+    long b = a + 1;
+    long c = a + 2;
+    long d = a + 3;
+    asm volatile("mov x19, #0\n\tmov x20, #0\n\tmov x27, #0\n\tmov x28, #0"
+                 :
+                 :
+                 : "x19", "x20", "x27", "x28");
+    return a * 1000 + b * 100 + c * 10 + d;
+}
+
+int counter;
+
+int main(void) {
+    show(add(40, 2));
+    show(add_immediate(100));
+
+    int x = 21;
+    double_in_place(&x);
+    show(x);
+
+    show((long)rotate(0x10, 4));
+
+    int values[4];
+    values[0] = 1;
+    values[1] = 2;
+    values[2] = 3;
+    values[3] = 4;
+    show(load_third(values));
+
+    long stored = 0;
+    store(&stored, 1234567890123l);
+    show(stored);
+
+    show(flags_after_compare(5, 5));
+    show(flags_after_compare(3, 5));
+
+    show(clobbers(1));
+
+    // barriers between plain stores
+    counter = 1;
+    asm volatile("dmb ish" ::: "memory");
+    counter = counter + 1;
+    __asm__ __volatile__("" ::: "memory");
+    asm("nop");
+    show(counter);
+    return 0;
+}
@@ -0,0 +1,39 @@
+int putchar(int c);
+
+// nothing from block_extern_clang.c is declared at file scope
+
+void print_num(long n) {
+    if (n < 0) {
+        putchar(45);
+        n = -n;
+    }
+    if (n >= 10) {
+        print_num(n / 10);
+    }
+    putchar(48 + n % 10);
+}
+
+void show(long n) {
+    print_num(n);
+    putchar(10);
+}
+
+int main(void) {
+    {
+        extern int clang_counter;
+        extern long clang_table[4];
+        int clang_bump(int by);
+
+        show(clang_counter);
+        show(clang_bump(5));
+        show(clang_bump(-2));
+        show(clang_counter);
+        clang_counter = 40;
+        clang_table[2] = clang_table[1] + clang_table[3];
+    }
+    extern int clang_counter;
+    long clang_sum(void);
+    show(clang_counter);
+    show(clang_sum());
+    return 0;
+}
@@ -0,0 +1,13 @@
+// compiled by clang and linked with block_extern.c
+
+int clang_counter = 7;
+long clang_table[4] = {1, 20, 300, 4000};
+
+int clang_bump(int by) {
+    clang_counter += by;
+    return clang_counter;
+}
+
+long clang_sum(void) {
+    return clang_counter + clang_table[0] + clang_table[1] + clang_table[2] + clang_table[3];
+}
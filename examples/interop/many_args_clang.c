@@ -0,0 +1,31 @@
+// compiled by clang and linked with many_args.c
+
+long dumbc_weighted(int a, int b, int c, int d, int e, int f, int g, int h, int i, int j, int k);
+double dumbc_mixed(double a, long b, double c, long d, double e, long f, double g, long h,
+                   double i, long j, double k, long l, double m, long n, double o, long p,
+                   double q, long r, float s, short t);
+int dumbc_narrow(long a, long b, long c, long d, long e, long f, long g, long h, char i, short j,
+                 char k, unsigned char l, int m, long n);
+
+long clang_weighted(int a, int b, int c, int d, int e, int f, int g, int h, int i, int j, int k) {
+    return a + 2 * b + 3 * c + 4 * d + 5 * e + 6 * f + 7 * g + 8 * h + 9 * i + 10 * j + 11 * k;
+}
+
+int clang_narrow(long a, long b, long c, long d, long e, long f, long g, long h, char i, short j,
+                 char k, unsigned char l, int m, long n) {
+    return (int)(a + b + c + d + e + f + g + h) * 1000 + i + j + k + l + m + n;
+}
+
+double clang_mixed(double a, long b, double c, long d, double e, long f, double g, long h,
+                   double i, long j, double k, long l, double m, long n, double o, long p,
+                   double q, long r, float s, short t) {
+    return a + b + c + d + e + f + g + h + i + j + k + l + m + n + o + p + q + r + s + t;
+}
+
+long clang_calls_dumbc(void) {
+    long w = dumbc_weighted(1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11);
+    int n = dumbc_narrow(1, 2, 3, 4, 5, 6, 7, 8, -1, 300, 'a', 200, -70000, 5000000000);
+    double m = dumbc_mixed(0.5, 1, 1.5, 2, 2.5, 3, 3.5, 4, 4.5, 5, 5.5, 6, 6.5, 7, 7.5, 8, 8.5,
+                           9, 0.25f, -100);
+    return w + n + (long)(m * 4);
+}
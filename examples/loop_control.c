@@ -0,0 +1,70 @@
+int printf(const char *format, ...);
+
+void show(long n) {
+    char format[] = {'%', 'l', 'd', '\n', 0};
+    printf(format, n);
+}
+
+int main(void) {
+    // a break after an inner loop leaves the outer loop
+    int rounds = 0;
+    for (int i = 0; i < 10; i++) {
+        for (int j = 0; j < 3; j++) {
+            rounds++;
+        }
+        if (i == 2) {
+            break;
+        }
+    }
+    show(rounds);
+
+    // continue in a while loop checks the condition again
+    int n = 0;
+    int odd = 0;
+    while (n < 10) {
+        n++;
+        if (n % 2 == 0) {
+            continue;
+        }
+        odd += n;
+    }
+    show(odd);
+
+    // continue in a do loop goes to the condition, not to the top of the body
+    int k = 0;
+    int visits = 0;
+    do {
+        k++;
+        if (k < 5) {
+            continue;
+        }
+        visits++;
+    } while (k < 8);
+    show(k);
+    show(visits);
+
+    // each loop keeps its own targets
+    int total = 0;
+    int a = 0;
+    while (1) {
+        a++;
+        int b = 0;
+        do {
+            b++;
+            if (b == 2) {
+                continue;
+            }
+            if (b > 4) {
+                break;
+            }
+            total += b;
+        } while (b < 10);
+        if (a == 3) {
+            break;
+        }
+        continue;
+    }
+    show(total);
+    show(a);
+    return 0;
+}
@@ -0,0 +1,67 @@
+int putchar(int c);
+
+int print_num(long n) {
+    if (n < 0) {
+        putchar(45);
+        n = -n;
+    }
+    if (n >= 10) {
+        print_num(n / 10);
+    }
+    putchar(48 + n % 10);
+    return 0;
+}
+
+int line(long n) {
+    print_num(n);
+    putchar(10);
+    return 0;
+}
+
+// parameters 9 and up are read from the caller's frame
+long weighted(int a, int b, int c, int d, int e, int f, int g, int h, int i, int j, int k) {
+    return a + 2 * b + 3 * c + 4 * d + 5 * e + 6 * f + 7 * g + 8 * h + 9 * i + 10 * j + 11 * k;
+}
+
+// narrow stack arguments are packed tightly on macOS
+int narrow(long a, long b, long c, long d, long e, long f, long g, long h, char i, short j, char k,
+           unsigned char l, int m, long n) {
+    return (int)(a + b + c + d + e + f + g + h) * 1000 + i + j + k + l + m + n;
+}
+
+// integer and floating-point registers run out independently
+double mixed(double a, int b, double c, int d, double e, int f, double g, int h, double i, int j,
+             double k, int l, double m, int n, double o, int p, double q, int r, float s,
+             long t) {
+    return a + b + c + d + e + f + g + h + i + j + k + l + m + n + o + p + q + r + s + t;
+}
+
+int pointers(int *a, int *b, int *c, int *d, int *e, int *f, int *g, int *h, int *i, int *j) {
+    *i = *a + *b;
+    *j = *c * *d * *e * *f * *g * *h;
+    return *i - *j;
+}
+
+// calls inside arguments must not disturb the outgoing argument area
+long nested(int depth) {
+    if (depth == 0) {
+        return 1;
+    }
+    return weighted(depth, 1, 1, 1, 1, 1, 1, 1, (int)nested(depth - 1), 2,
+                    (int)weighted(0, 0, 0, 0, 0, 0, 0, 0, depth, 0, 1));
+}
+
+int main() {
+    line(weighted(1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11));
+    line(narrow(1, 2, 3, 4, 5, 6, 7, 8, -1, 300, 'a', 200, -70000, 5000000000));
+    line((long)(mixed(0.5, 1, 1.5, 2, 2.5, 3, 3.5, 4, 4.5, 5, 5.5, 6, 6.5, 7, 7.5, 8, 8.5, 9,
+                      0.25f, -100) * 4));
+
+    int v[10] = {1, 2, 3, 4, 5, 6, 7, 8, 0, 0};
+    line(pointers(&v[0], &v[1], &v[2], &v[3], &v[4], &v[5], &v[6], &v[7], &v[8], &v[9]));
+    line(v[8]);
+    line(v[9]);
+
+    line(nested(3));
+    return (int)weighted(1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1) - 10;
+}
@@ -0,0 +1,61 @@
+int printf(const char *format, ...);
+
+void show(long n) {
+    char format[] = {'%', 'l', 'd', '\n', 0};
+    printf(format, n);
+}
+
+int x = 5;
+
+// static locals of the same name in different functions are different objects
+int counter(void) {
+    static int n;
+    n = n + 1;
+    return n;
+}
+
+int other(void) {
+    static int n = 100;
+    n = n + 1;
+    return n;
+}
+
+// a parameter hides the global, a nested block hides the parameter
+int hide(int x) {
+    int sum = x;
+    {
+        int x = 1000;
+        sum = sum + x;
+    }
+    return sum + x;
+}
+
+int main(void) {
+    int x = 1;
+    show(x);
+    {
+        int x = 7;
+        {
+            extern int x;
+            show(x);
+            x = 6;
+        }
+        show(x);
+    }
+    show(x);
+
+    for (int x = 0; x < 2; x++) {
+        int x = 40;
+        show(x);
+    }
+
+    // a local that hides a function can still be called through
+    int (*show_ptr)(void) = counter;
+    {
+        int counter = 3;
+        show(counter + show_ptr());
+    }
+    show(counter() + other());
+    show(hide(20));
+    return 0;
+}
@@ -0,0 +1,1077 @@
+int printf(const char *format, ...);
+
+void show(unsigned long n) {
+    char format[] = {'%', 'l', 'u', '\n', 0};
+    printf(format, n);
+}
+
+// generated by scripts/gen_operator_semantics.py, which says how to regenerate it
+//
+// every binary operator on edge values of each integer type, computed at run time by
+// the functions below and folded at compile time by the constant expressions in main
+
+int checks;
+int mismatches;
+unsigned long digest;
+
+void check(unsigned long runtime, unsigned long folded) {
+    checks = checks + 1;
+    digest = digest * 31 + runtime;
+    if (runtime != folded) {
+        mismatches = mismatches + 1;
+        show(checks);
+    }
+}
+
+int add_int(int a, int b) {
+    return a + b;
+}
+
+int sub_int(int a, int b) {
+    return a - b;
+}
+
+int mul_int(int a, int b) {
+    return a * b;
+}
+
+int div_int(int a, int b) {
+    return a / b;
+}
+
+int rem_int(int a, int b) {
+    return a % b;
+}
+
+int shr_int(int a, int b) {
+    return a >> b;
+}
+
+int shl_int(int a, int b) {
+    return a << b;
+}
+
+int and_int(int a, int b) {
+    return a & b;
+}
+
+int xor_int(int a, int b) {
+    return a ^ b;
+}
+
+int lt_int(int a, int b) {
+    return a < b;
+}
+
+int ge_int(int a, int b) {
+    return a >= b;
+}
+
+unsigned int add_uint(unsigned int a, unsigned int b) {
+    return a + b;
+}
+
+unsigned int sub_uint(unsigned int a, unsigned int b) {
+    return a - b;
+}
+
+unsigned int mul_uint(unsigned int a, unsigned int b) {
+    return a * b;
+}
+
+unsigned int div_uint(unsigned int a, unsigned int b) {
+    return a / b;
+}
+
+unsigned int rem_uint(unsigned int a, unsigned int b) {
+    return a % b;
+}
+
+unsigned int shr_uint(unsigned int a, unsigned int b) {
+    return a >> b;
+}
+
+unsigned int shl_uint(unsigned int a, unsigned int b) {
+    return a << b;
+}
+
+unsigned int and_uint(unsigned int a, unsigned int b) {
+    return a & b;
+}
+
+unsigned int xor_uint(unsigned int a, unsigned int b) {
+    return a ^ b;
+}
+
+int lt_uint(unsigned int a, unsigned int b) {
+    return a < b;
+}
+
+int ge_uint(unsigned int a, unsigned int b) {
+    return a >= b;
+}
+
+long add_long(long a, long b) {
+    return a + b;
+}
+
+long sub_long(long a, long b) {
+    return a - b;
+}
+
+long mul_long(long a, long b) {
+    return a * b;
+}
+
+long div_long(long a, long b) {
+    return a / b;
+}
+
+long rem_long(long a, long b) {
+    return a % b;
+}
+
+long shr_long(long a, long b) {
+    return a >> b;
+}
+
+long shl_long(long a, long b) {
+    return a << b;
+}
+
+long and_long(long a, long b) {
+    return a & b;
+}
+
+long xor_long(long a, long b) {
+    return a ^ b;
+}
+
+int lt_long(long a, long b) {
+    return a < b;
+}
+
+int ge_long(long a, long b) {
+    return a >= b;
+}
+
+unsigned long add_ulong(unsigned long a, unsigned long b) {
+    return a + b;
+}
+
+unsigned long sub_ulong(unsigned long a, unsigned long b) {
+    return a - b;
+}
+
+unsigned long mul_ulong(unsigned long a, unsigned long b) {
+    return a * b;
+}
+
+unsigned long div_ulong(unsigned long a, unsigned long b) {
+    return a / b;
+}
+
+unsigned long rem_ulong(unsigned long a, unsigned long b) {
+    return a % b;
+}
+
+unsigned long shr_ulong(unsigned long a, unsigned long b) {
+    return a >> b;
+}
+
+unsigned long shl_ulong(unsigned long a, unsigned long b) {
+    return a << b;
+}
+
+unsigned long and_ulong(unsigned long a, unsigned long b) {
+    return a & b;
+}
+
+unsigned long xor_ulong(unsigned long a, unsigned long b) {
+    return a ^ b;
+}
+
+int lt_ulong(unsigned long a, unsigned long b) {
+    return a < b;
+}
+
+int ge_ulong(unsigned long a, unsigned long b) {
+    return a >= b;
+}
+
+// the quotient doesn't fit in the type, so neither result is defined
+void overflows(int never) {
+    if (never) {
+        check(div_int((-2147483647 - 1), -1), (-2147483647 - 1) / -1);
+        check(rem_int((-2147483647 - 1), -1), (-2147483647 - 1) % -1);
+        check(div_long((-9223372036854775807l - 1), -1l), (-9223372036854775807l - 1) / -1l);
+        check(rem_long((-9223372036854775807l - 1), -1l), (-9223372036854775807l - 1) % -1l);
+    }
+}
+
+int main(void) {
+    overflows(0);
+    check(add_int((-2147483647 - 1), 3), (-2147483647 - 1) + 3);
+    check(add_int((-2147483647 - 1), 2147483647), (-2147483647 - 1) + 2147483647);
+    check(add_int(-7, -7), -7 + -7);
+    check(add_int(-7, -1), -7 + -1);
+    check(add_int(-7, 3), -7 + 3);
+    check(add_int(-7, 2147483647), -7 + 2147483647);
+    check(add_int(-1, -7), -1 + -7);
+    check(add_int(-1, -1), -1 + -1);
+    check(add_int(-1, 3), -1 + 3);
+    check(add_int(-1, 2147483647), -1 + 2147483647);
+    check(add_int(3, (-2147483647 - 1)), 3 + (-2147483647 - 1));
+    check(add_int(3, -7), 3 + -7);
+    check(add_int(3, -1), 3 + -1);
+    check(add_int(3, 3), 3 + 3);
+    check(add_int(2147483647, (-2147483647 - 1)), 2147483647 + (-2147483647 - 1));
+    check(add_int(2147483647, -7), 2147483647 + -7);
+    check(add_int(2147483647, -1), 2147483647 + -1);
+    check(sub_int((-2147483647 - 1), (-2147483647 - 1)), (-2147483647 - 1) - (-2147483647 - 1));
+    check(sub_int((-2147483647 - 1), -7), (-2147483647 - 1) - -7);
+    check(sub_int((-2147483647 - 1), -1), (-2147483647 - 1) - -1);
+    check(sub_int(-7, (-2147483647 - 1)), -7 - (-2147483647 - 1));
+    check(sub_int(-7, -7), -7 - -7);
+    check(sub_int(-7, -1), -7 - -1);
+    check(sub_int(-7, 3), -7 - 3);
+    check(sub_int(-1, (-2147483647 - 1)), -1 - (-2147483647 - 1));
+    check(sub_int(-1, -7), -1 - -7);
+    check(sub_int(-1, -1), -1 - -1);
+    check(sub_int(-1, 3), -1 - 3);
+    check(sub_int(-1, 2147483647), -1 - 2147483647);
+    check(sub_int(3, -7), 3 - -7);
+    check(sub_int(3, -1), 3 - -1);
+    check(sub_int(3, 3), 3 - 3);
+    check(sub_int(3, 2147483647), 3 - 2147483647);
+    check(sub_int(2147483647, 3), 2147483647 - 3);
+    check(sub_int(2147483647, 2147483647), 2147483647 - 2147483647);
+    check(mul_int(-7, -7), -7 * -7);
+    check(mul_int(-7, -1), -7 * -1);
+    check(mul_int(-7, 3), -7 * 3);
+    check(mul_int(-1, -7), -1 * -7);
+    check(mul_int(-1, -1), -1 * -1);
+    check(mul_int(-1, 3), -1 * 3);
+    check(mul_int(-1, 2147483647), -1 * 2147483647);
+    check(mul_int(3, -7), 3 * -7);
+    check(mul_int(3, -1), 3 * -1);
+    check(mul_int(3, 3), 3 * 3);
+    check(mul_int(2147483647, -1), 2147483647 * -1);
+    check(div_int((-2147483647 - 1), (-2147483647 - 1)), (-2147483647 - 1) / (-2147483647 - 1));
+    check(div_int((-2147483647 - 1), -7), (-2147483647 - 1) / -7);
+    check(div_int((-2147483647 - 1), 3), (-2147483647 - 1) / 3);
+    check(div_int((-2147483647 - 1), 2147483647), (-2147483647 - 1) / 2147483647);
+    check(div_int(-7, (-2147483647 - 1)), -7 / (-2147483647 - 1));
+    check(div_int(-7, -7), -7 / -7);
+    check(div_int(-7, -1), -7 / -1);
+    check(div_int(-7, 3), -7 / 3);
+    check(div_int(-7, 2147483647), -7 / 2147483647);
+    check(div_int(-1, (-2147483647 - 1)), -1 / (-2147483647 - 1));
+    check(div_int(-1, -7), -1 / -7);
+    check(div_int(-1, -1), -1 / -1);
+    check(div_int(-1, 3), -1 / 3);
+    check(div_int(-1, 2147483647), -1 / 2147483647);
+    check(div_int(3, (-2147483647 - 1)), 3 / (-2147483647 - 1));
+    check(div_int(3, -7), 3 / -7);
+    check(div_int(3, -1), 3 / -1);
+    check(div_int(3, 3), 3 / 3);
+    check(div_int(3, 2147483647), 3 / 2147483647);
+    check(div_int(2147483647, (-2147483647 - 1)), 2147483647 / (-2147483647 - 1));
+    check(div_int(2147483647, -7), 2147483647 / -7);
+    check(div_int(2147483647, -1), 2147483647 / -1);
+    check(div_int(2147483647, 3), 2147483647 / 3);
+    check(div_int(2147483647, 2147483647), 2147483647 / 2147483647);
+    check(rem_int((-2147483647 - 1), (-2147483647 - 1)), (-2147483647 - 1) % (-2147483647 - 1));
+    check(rem_int((-2147483647 - 1), -7), (-2147483647 - 1) % -7);
+    check(rem_int((-2147483647 - 1), 3), (-2147483647 - 1) % 3);
+    check(rem_int((-2147483647 - 1), 2147483647), (-2147483647 - 1) % 2147483647);
+    check(rem_int(-7, (-2147483647 - 1)), -7 % (-2147483647 - 1));
+    check(rem_int(-7, -7), -7 % -7);
+    check(rem_int(-7, -1), -7 % -1);
+    check(rem_int(-7, 3), -7 % 3);
+    check(rem_int(-7, 2147483647), -7 % 2147483647);
+    check(rem_int(-1, (-2147483647 - 1)), -1 % (-2147483647 - 1));
+    check(rem_int(-1, -7), -1 % -7);
+    check(rem_int(-1, -1), -1 % -1);
+    check(rem_int(-1, 3), -1 % 3);
+    check(rem_int(-1, 2147483647), -1 % 2147483647);
+    check(rem_int(3, (-2147483647 - 1)), 3 % (-2147483647 - 1));
+    check(rem_int(3, -7), 3 % -7);
+    check(rem_int(3, -1), 3 % -1);
+    check(rem_int(3, 3), 3 % 3);
+    check(rem_int(3, 2147483647), 3 % 2147483647);
+    check(rem_int(2147483647, (-2147483647 - 1)), 2147483647 % (-2147483647 - 1));
+    check(rem_int(2147483647, -7), 2147483647 % -7);
+    check(rem_int(2147483647, -1), 2147483647 % -1);
+    check(rem_int(2147483647, 3), 2147483647 % 3);
+    check(rem_int(2147483647, 2147483647), 2147483647 % 2147483647);
+    check(shr_int((-2147483647 - 1), 0), (-2147483647 - 1) >> 0);
+    check(shr_int((-2147483647 - 1), 1), (-2147483647 - 1) >> 1);
+    check(shr_int((-2147483647 - 1), 3), (-2147483647 - 1) >> 3);
+    check(shr_int((-2147483647 - 1), 31), (-2147483647 - 1) >> 31);
+    check(shr_int(-7, 0), -7 >> 0);
+    check(shr_int(-7, 1), -7 >> 1);
+    check(shr_int(-7, 3), -7 >> 3);
+    check(shr_int(-7, 31), -7 >> 31);
+    check(shr_int(-1, 0), -1 >> 0);
+    check(shr_int(-1, 1), -1 >> 1);
+    check(shr_int(-1, 3), -1 >> 3);
+    check(shr_int(-1, 31), -1 >> 31);
+    check(shr_int(3, 0), 3 >> 0);
+    check(shr_int(3, 1), 3 >> 1);
+    check(shr_int(3, 3), 3 >> 3);
+    check(shr_int(3, 31), 3 >> 31);
+    check(shr_int(2147483647, 0), 2147483647 >> 0);
+    check(shr_int(2147483647, 1), 2147483647 >> 1);
+    check(shr_int(2147483647, 3), 2147483647 >> 3);
+    check(shr_int(2147483647, 31), 2147483647 >> 31);
+    check(shl_int(3, 0), 3 << 0);
+    check(shl_int(3, 1), 3 << 1);
+    check(shl_int(3, 3), 3 << 3);
+    check(shl_int(2147483647, 0), 2147483647 << 0);
+    check(and_int((-2147483647 - 1), (-2147483647 - 1)), (-2147483647 - 1) & (-2147483647 - 1));
+    check(and_int((-2147483647 - 1), -7), (-2147483647 - 1) & -7);
+    check(and_int((-2147483647 - 1), -1), (-2147483647 - 1) & -1);
+    check(and_int((-2147483647 - 1), 3), (-2147483647 - 1) & 3);
+    check(and_int((-2147483647 - 1), 2147483647), (-2147483647 - 1) & 2147483647);
+    check(and_int(-7, (-2147483647 - 1)), -7 & (-2147483647 - 1));
+    check(and_int(-7, -7), -7 & -7);
+    check(and_int(-7, -1), -7 & -1);
+    check(and_int(-7, 3), -7 & 3);
+    check(and_int(-7, 2147483647), -7 & 2147483647);
+    check(and_int(-1, (-2147483647 - 1)), -1 & (-2147483647 - 1));
+    check(and_int(-1, -7), -1 & -7);
+    check(and_int(-1, -1), -1 & -1);
+    check(and_int(-1, 3), -1 & 3);
+    check(and_int(-1, 2147483647), -1 & 2147483647);
+    check(and_int(3, (-2147483647 - 1)), 3 & (-2147483647 - 1));
+    check(and_int(3, -7), 3 & -7);
+    check(and_int(3, -1), 3 & -1);
+    check(and_int(3, 3), 3 & 3);
+    check(and_int(3, 2147483647), 3 & 2147483647);
+    check(and_int(2147483647, (-2147483647 - 1)), 2147483647 & (-2147483647 - 1));
+    check(and_int(2147483647, -7), 2147483647 & -7);
+    check(and_int(2147483647, -1), 2147483647 & -1);
+    check(and_int(2147483647, 3), 2147483647 & 3);
+    check(and_int(2147483647, 2147483647), 2147483647 & 2147483647);
+    check(xor_int((-2147483647 - 1), (-2147483647 - 1)), (-2147483647 - 1) ^ (-2147483647 - 1));
+    check(xor_int((-2147483647 - 1), -7), (-2147483647 - 1) ^ -7);
+    check(xor_int((-2147483647 - 1), -1), (-2147483647 - 1) ^ -1);
+    check(xor_int((-2147483647 - 1), 3), (-2147483647 - 1) ^ 3);
+    check(xor_int((-2147483647 - 1), 2147483647), (-2147483647 - 1) ^ 2147483647);
+    check(xor_int(-7, (-2147483647 - 1)), -7 ^ (-2147483647 - 1));
+    check(xor_int(-7, -7), -7 ^ -7);
+    check(xor_int(-7, -1), -7 ^ -1);
+    check(xor_int(-7, 3), -7 ^ 3);
+    check(xor_int(-7, 2147483647), -7 ^ 2147483647);
+    check(xor_int(-1, (-2147483647 - 1)), -1 ^ (-2147483647 - 1));
+    check(xor_int(-1, -7), -1 ^ -7);
+    check(xor_int(-1, -1), -1 ^ -1);
+    check(xor_int(-1, 3), -1 ^ 3);
+    check(xor_int(-1, 2147483647), -1 ^ 2147483647);
+    check(xor_int(3, (-2147483647 - 1)), 3 ^ (-2147483647 - 1));
+    check(xor_int(3, -7), 3 ^ -7);
+    check(xor_int(3, -1), 3 ^ -1);
+    check(xor_int(3, 3), 3 ^ 3);
+    check(xor_int(3, 2147483647), 3 ^ 2147483647);
+    check(xor_int(2147483647, (-2147483647 - 1)), 2147483647 ^ (-2147483647 - 1));
+    check(xor_int(2147483647, -7), 2147483647 ^ -7);
+    check(xor_int(2147483647, -1), 2147483647 ^ -1);
+    check(xor_int(2147483647, 3), 2147483647 ^ 3);
+    check(xor_int(2147483647, 2147483647), 2147483647 ^ 2147483647);
+    check(lt_int((-2147483647 - 1), (-2147483647 - 1)), (-2147483647 - 1) < (-2147483647 - 1));
+    check(lt_int((-2147483647 - 1), -7), (-2147483647 - 1) < -7);
+    check(lt_int((-2147483647 - 1), -1), (-2147483647 - 1) < -1);
+    check(lt_int((-2147483647 - 1), 3), (-2147483647 - 1) < 3);
+    check(lt_int((-2147483647 - 1), 2147483647), (-2147483647 - 1) < 2147483647);
+    check(lt_int(-7, (-2147483647 - 1)), -7 < (-2147483647 - 1));
+    check(lt_int(-7, -7), -7 < -7);
+    check(lt_int(-7, -1), -7 < -1);
+    check(lt_int(-7, 3), -7 < 3);
+    check(lt_int(-7, 2147483647), -7 < 2147483647);
+    check(lt_int(-1, (-2147483647 - 1)), -1 < (-2147483647 - 1));
+    check(lt_int(-1, -7), -1 < -7);
+    check(lt_int(-1, -1), -1 < -1);
+    check(lt_int(-1, 3), -1 < 3);
+    check(lt_int(-1, 2147483647), -1 < 2147483647);
+    check(lt_int(3, (-2147483647 - 1)), 3 < (-2147483647 - 1));
+    check(lt_int(3, -7), 3 < -7);
+    check(lt_int(3, -1), 3 < -1);
+    check(lt_int(3, 3), 3 < 3);
+    check(lt_int(3, 2147483647), 3 < 2147483647);
+    check(lt_int(2147483647, (-2147483647 - 1)), 2147483647 < (-2147483647 - 1));
+    check(lt_int(2147483647, -7), 2147483647 < -7);
+    check(lt_int(2147483647, -1), 2147483647 < -1);
+    check(lt_int(2147483647, 3), 2147483647 < 3);
+    check(lt_int(2147483647, 2147483647), 2147483647 < 2147483647);
+    check(ge_int((-2147483647 - 1), (-2147483647 - 1)), (-2147483647 - 1) >= (-2147483647 - 1));
+    check(ge_int((-2147483647 - 1), -7), (-2147483647 - 1) >= -7);
+    check(ge_int((-2147483647 - 1), -1), (-2147483647 - 1) >= -1);
+    check(ge_int((-2147483647 - 1), 3), (-2147483647 - 1) >= 3);
+    check(ge_int((-2147483647 - 1), 2147483647), (-2147483647 - 1) >= 2147483647);
+    check(ge_int(-7, (-2147483647 - 1)), -7 >= (-2147483647 - 1));
+    check(ge_int(-7, -7), -7 >= -7);
+    check(ge_int(-7, -1), -7 >= -1);
+    check(ge_int(-7, 3), -7 >= 3);
+    check(ge_int(-7, 2147483647), -7 >= 2147483647);
+    check(ge_int(-1, (-2147483647 - 1)), -1 >= (-2147483647 - 1));
+    check(ge_int(-1, -7), -1 >= -7);
+    check(ge_int(-1, -1), -1 >= -1);
+    check(ge_int(-1, 3), -1 >= 3);
+    check(ge_int(-1, 2147483647), -1 >= 2147483647);
+    check(ge_int(3, (-2147483647 - 1)), 3 >= (-2147483647 - 1));
+    check(ge_int(3, -7), 3 >= -7);
+    check(ge_int(3, -1), 3 >= -1);
+    check(ge_int(3, 3), 3 >= 3);
+    check(ge_int(3, 2147483647), 3 >= 2147483647);
+    check(ge_int(2147483647, (-2147483647 - 1)), 2147483647 >= (-2147483647 - 1));
+    check(ge_int(2147483647, -7), 2147483647 >= -7);
+    check(ge_int(2147483647, -1), 2147483647 >= -1);
+    check(ge_int(2147483647, 3), 2147483647 >= 3);
+    check(ge_int(2147483647, 2147483647), 2147483647 >= 2147483647);
+    check(add_uint(0u, 0u), 0u + 0u);
+    check(add_uint(0u, 1u), 0u + 1u);
+    check(add_uint(0u, 7u), 0u + 7u);
+    check(add_uint(0u, 2147483648u), 0u + 2147483648u);
+    check(add_uint(0u, 4294967295u), 0u + 4294967295u);
+    check(add_uint(1u, 0u), 1u + 0u);
+    check(add_uint(1u, 1u), 1u + 1u);
+    check(add_uint(1u, 7u), 1u + 7u);
+    check(add_uint(1u, 2147483648u), 1u + 2147483648u);
+    check(add_uint(1u, 4294967295u), 1u + 4294967295u);
+    check(add_uint(7u, 0u), 7u + 0u);
+    check(add_uint(7u, 1u), 7u + 1u);
+    check(add_uint(7u, 7u), 7u + 7u);
+    check(add_uint(7u, 2147483648u), 7u + 2147483648u);
+    check(add_uint(7u, 4294967295u), 7u + 4294967295u);
+    check(add_uint(2147483648u, 0u), 2147483648u + 0u);
+    check(add_uint(2147483648u, 1u), 2147483648u + 1u);
+    check(add_uint(2147483648u, 7u), 2147483648u + 7u);
+    check(add_uint(2147483648u, 2147483648u), 2147483648u + 2147483648u);
+    check(add_uint(2147483648u, 4294967295u), 2147483648u + 4294967295u);
+    check(add_uint(4294967295u, 0u), 4294967295u + 0u);
+    check(add_uint(4294967295u, 1u), 4294967295u + 1u);
+    check(add_uint(4294967295u, 7u), 4294967295u + 7u);
+    check(add_uint(4294967295u, 2147483648u), 4294967295u + 2147483648u);
+    check(add_uint(4294967295u, 4294967295u), 4294967295u + 4294967295u);
+    check(sub_uint(0u, 0u), 0u - 0u);
+    check(sub_uint(0u, 1u), 0u - 1u);
+    check(sub_uint(0u, 7u), 0u - 7u);
+    check(sub_uint(0u, 2147483648u), 0u - 2147483648u);
+    check(sub_uint(0u, 4294967295u), 0u - 4294967295u);
+    check(sub_uint(1u, 0u), 1u - 0u);
+    check(sub_uint(1u, 1u), 1u - 1u);
+    check(sub_uint(1u, 7u), 1u - 7u);
+    check(sub_uint(1u, 2147483648u), 1u - 2147483648u);
+    check(sub_uint(1u, 4294967295u), 1u - 4294967295u);
+    check(sub_uint(7u, 0u), 7u - 0u);
+    check(sub_uint(7u, 1u), 7u - 1u);
+    check(sub_uint(7u, 7u), 7u - 7u);
+    check(sub_uint(7u, 2147483648u), 7u - 2147483648u);
+    check(sub_uint(7u, 4294967295u), 7u - 4294967295u);
+    check(sub_uint(2147483648u, 0u), 2147483648u - 0u);
+    check(sub_uint(2147483648u, 1u), 2147483648u - 1u);
+    check(sub_uint(2147483648u, 7u), 2147483648u - 7u);
+    check(sub_uint(2147483648u, 2147483648u), 2147483648u - 2147483648u);
+    check(sub_uint(2147483648u, 4294967295u), 2147483648u - 4294967295u);
+    check(sub_uint(4294967295u, 0u), 4294967295u - 0u);
+    check(sub_uint(4294967295u, 1u), 4294967295u - 1u);
+    check(sub_uint(4294967295u, 7u), 4294967295u - 7u);
+    check(sub_uint(4294967295u, 2147483648u), 4294967295u - 2147483648u);
+    check(sub_uint(4294967295u, 4294967295u), 4294967295u - 4294967295u);
+    check(mul_uint(0u, 0u), 0u * 0u);
+    check(mul_uint(0u, 1u), 0u * 1u);
+    check(mul_uint(0u, 7u), 0u * 7u);
+    check(mul_uint(0u, 2147483648u), 0u * 2147483648u);
+    check(mul_uint(0u, 4294967295u), 0u * 4294967295u);
+    check(mul_uint(1u, 0u), 1u * 0u);
+    check(mul_uint(1u, 1u), 1u * 1u);
+    check(mul_uint(1u, 7u), 1u * 7u);
+    check(mul_uint(1u, 2147483648u), 1u * 2147483648u);
+    check(mul_uint(1u, 4294967295u), 1u * 4294967295u);
+    check(mul_uint(7u, 0u), 7u * 0u);
+    check(mul_uint(7u, 1u), 7u * 1u);
+    check(mul_uint(7u, 7u), 7u * 7u);
+    check(mul_uint(7u, 2147483648u), 7u * 2147483648u);
+    check(mul_uint(7u, 4294967295u), 7u * 4294967295u);
+    check(mul_uint(2147483648u, 0u), 2147483648u * 0u);
+    check(mul_uint(2147483648u, 1u), 2147483648u * 1u);
+    check(mul_uint(2147483648u, 7u), 2147483648u * 7u);
+    check(mul_uint(2147483648u, 2147483648u), 2147483648u * 2147483648u);
+    check(mul_uint(2147483648u, 4294967295u), 2147483648u * 4294967295u);
+    check(mul_uint(4294967295u, 0u), 4294967295u * 0u);
+    check(mul_uint(4294967295u, 1u), 4294967295u * 1u);
+    check(mul_uint(4294967295u, 7u), 4294967295u * 7u);
+    check(mul_uint(4294967295u, 2147483648u), 4294967295u * 2147483648u);
+    check(mul_uint(4294967295u, 4294967295u), 4294967295u * 4294967295u);
+    check(div_uint(0u, 1u), 0u / 1u);
+    check(div_uint(0u, 7u), 0u / 7u);
+    check(div_uint(0u, 2147483648u), 0u / 2147483648u);
+    check(div_uint(0u, 4294967295u), 0u / 4294967295u);
+    check(div_uint(1u, 1u), 1u / 1u);
+    check(div_uint(1u, 7u), 1u / 7u);
+    check(div_uint(1u, 2147483648u), 1u / 2147483648u);
+    check(div_uint(1u, 4294967295u), 1u / 4294967295u);
+    check(div_uint(7u, 1u), 7u / 1u);
+    check(div_uint(7u, 7u), 7u / 7u);
+    check(div_uint(7u, 2147483648u), 7u / 2147483648u);
+    check(div_uint(7u, 4294967295u), 7u / 4294967295u);
+    check(div_uint(2147483648u, 1u), 2147483648u / 1u);
+    check(div_uint(2147483648u, 7u), 2147483648u / 7u);
+    check(div_uint(2147483648u, 2147483648u), 2147483648u / 2147483648u);
+    check(div_uint(2147483648u, 4294967295u), 2147483648u / 4294967295u);
+    check(div_uint(4294967295u, 1u), 4294967295u / 1u);
+    check(div_uint(4294967295u, 7u), 4294967295u / 7u);
+    check(div_uint(4294967295u, 2147483648u), 4294967295u / 2147483648u);
+    check(div_uint(4294967295u, 4294967295u), 4294967295u / 4294967295u);
+    check(rem_uint(0u, 1u), 0u % 1u);
+    check(rem_uint(0u, 7u), 0u % 7u);
+    check(rem_uint(0u, 2147483648u), 0u % 2147483648u);
+    check(rem_uint(0u, 4294967295u), 0u % 4294967295u);
+    check(rem_uint(1u, 1u), 1u % 1u);
+    check(rem_uint(1u, 7u), 1u % 7u);
+    check(rem_uint(1u, 2147483648u), 1u % 2147483648u);
+    check(rem_uint(1u, 4294967295u), 1u % 4294967295u);
+    check(rem_uint(7u, 1u), 7u % 1u);
+    check(rem_uint(7u, 7u), 7u % 7u);
+    check(rem_uint(7u, 2147483648u), 7u % 2147483648u);
+    check(rem_uint(7u, 4294967295u), 7u % 4294967295u);
+    check(rem_uint(2147483648u, 1u), 2147483648u % 1u);
+    check(rem_uint(2147483648u, 7u), 2147483648u % 7u);
+    check(rem_uint(2147483648u, 2147483648u), 2147483648u % 2147483648u);
+    check(rem_uint(2147483648u, 4294967295u), 2147483648u % 4294967295u);
+    check(rem_uint(4294967295u, 1u), 4294967295u % 1u);
+    check(rem_uint(4294967295u, 7u), 4294967295u % 7u);
+    check(rem_uint(4294967295u, 2147483648u), 4294967295u % 2147483648u);
+    check(rem_uint(4294967295u, 4294967295u), 4294967295u % 4294967295u);
+    check(shr_uint(0u, 0), 0u >> 0);
+    check(shr_uint(0u, 1), 0u >> 1);
+    check(shr_uint(0u, 3), 0u >> 3);
+    check(shr_uint(0u, 31), 0u >> 31);
+    check(shr_uint(1u, 0), 1u >> 0);
+    check(shr_uint(1u, 1), 1u >> 1);
+    check(shr_uint(1u, 3), 1u >> 3);
+    check(shr_uint(1u, 31), 1u >> 31);
+    check(shr_uint(7u, 0), 7u >> 0);
+    check(shr_uint(7u, 1), 7u >> 1);
+    check(shr_uint(7u, 3), 7u >> 3);
+    check(shr_uint(7u, 31), 7u >> 31);
+    check(shr_uint(2147483648u, 0), 2147483648u >> 0);
+    check(shr_uint(2147483648u, 1), 2147483648u >> 1);
+    check(shr_uint(2147483648u, 3), 2147483648u >> 3);
+    check(shr_uint(2147483648u, 31), 2147483648u >> 31);
+    check(shr_uint(4294967295u, 0), 4294967295u >> 0);
+    check(shr_uint(4294967295u, 1), 4294967295u >> 1);
+    check(shr_uint(4294967295u, 3), 4294967295u >> 3);
+    check(shr_uint(4294967295u, 31), 4294967295u >> 31);
+    check(shl_uint(0u, 0), 0u << 0);
+    check(shl_uint(0u, 1), 0u << 1);
+    check(shl_uint(0u, 3), 0u << 3);
+    check(shl_uint(0u, 31), 0u << 31);
+    check(shl_uint(1u, 0), 1u << 0);
+    check(shl_uint(1u, 1), 1u << 1);
+    check(shl_uint(1u, 3), 1u << 3);
+    check(shl_uint(1u, 31), 1u << 31);
+    check(shl_uint(7u, 0), 7u << 0);
+    check(shl_uint(7u, 1), 7u << 1);
+    check(shl_uint(7u, 3), 7u << 3);
+    check(shl_uint(7u, 31), 7u << 31);
+    check(shl_uint(2147483648u, 0), 2147483648u << 0);
+    check(shl_uint(2147483648u, 1), 2147483648u << 1);
+    check(shl_uint(2147483648u, 3), 2147483648u << 3);
+    check(shl_uint(2147483648u, 31), 2147483648u << 31);
+    check(shl_uint(4294967295u, 0), 4294967295u << 0);
+    check(shl_uint(4294967295u, 1), 4294967295u << 1);
+    check(shl_uint(4294967295u, 3), 4294967295u << 3);
+    check(shl_uint(4294967295u, 31), 4294967295u << 31);
+    check(and_uint(0u, 0u), 0u & 0u);
+    check(and_uint(0u, 1u), 0u & 1u);
+    check(and_uint(0u, 7u), 0u & 7u);
+    check(and_uint(0u, 2147483648u), 0u & 2147483648u);
+    check(and_uint(0u, 4294967295u), 0u & 4294967295u);
+    check(and_uint(1u, 0u), 1u & 0u);
+    check(and_uint(1u, 1u), 1u & 1u);
+    check(and_uint(1u, 7u), 1u & 7u);
+    check(and_uint(1u, 2147483648u), 1u & 2147483648u);
+    check(and_uint(1u, 4294967295u), 1u & 4294967295u);
+    check(and_uint(7u, 0u), 7u & 0u);
+    check(and_uint(7u, 1u), 7u & 1u);
+    check(and_uint(7u, 7u), 7u & 7u);
+    check(and_uint(7u, 2147483648u), 7u & 2147483648u);
+    check(and_uint(7u, 4294967295u), 7u & 4294967295u);
+    check(and_uint(2147483648u, 0u), 2147483648u & 0u);
+    check(and_uint(2147483648u, 1u), 2147483648u & 1u);
+    check(and_uint(2147483648u, 7u), 2147483648u & 7u);
+    check(and_uint(2147483648u, 2147483648u), 2147483648u & 2147483648u);
+    check(and_uint(2147483648u, 4294967295u), 2147483648u & 4294967295u);
+    check(and_uint(4294967295u, 0u), 4294967295u & 0u);
+    check(and_uint(4294967295u, 1u), 4294967295u & 1u);
+    check(and_uint(4294967295u, 7u), 4294967295u & 7u);
+    check(and_uint(4294967295u, 2147483648u), 4294967295u & 2147483648u);
+    check(and_uint(4294967295u, 4294967295u), 4294967295u & 4294967295u);
+    check(xor_uint(0u, 0u), 0u ^ 0u);
+    check(xor_uint(0u, 1u), 0u ^ 1u);
+    check(xor_uint(0u, 7u), 0u ^ 7u);
+    check(xor_uint(0u, 2147483648u), 0u ^ 2147483648u);
+    check(xor_uint(0u, 4294967295u), 0u ^ 4294967295u);
+    check(xor_uint(1u, 0u), 1u ^ 0u);
+    check(xor_uint(1u, 1u), 1u ^ 1u);
+    check(xor_uint(1u, 7u), 1u ^ 7u);
+    check(xor_uint(1u, 2147483648u), 1u ^ 2147483648u);
+    check(xor_uint(1u, 4294967295u), 1u ^ 4294967295u);
+    check(xor_uint(7u, 0u), 7u ^ 0u);
+    check(xor_uint(7u, 1u), 7u ^ 1u);
+    check(xor_uint(7u, 7u), 7u ^ 7u);
+    check(xor_uint(7u, 2147483648u), 7u ^ 2147483648u);
+    check(xor_uint(7u, 4294967295u), 7u ^ 4294967295u);
+    check(xor_uint(2147483648u, 0u), 2147483648u ^ 0u);
+    check(xor_uint(2147483648u, 1u), 2147483648u ^ 1u);
+    check(xor_uint(2147483648u, 7u), 2147483648u ^ 7u);
+    check(xor_uint(2147483648u, 2147483648u), 2147483648u ^ 2147483648u);
+    check(xor_uint(2147483648u, 4294967295u), 2147483648u ^ 4294967295u);
+    check(xor_uint(4294967295u, 0u), 4294967295u ^ 0u);
+    check(xor_uint(4294967295u, 1u), 4294967295u ^ 1u);
+    check(xor_uint(4294967295u, 7u), 4294967295u ^ 7u);
+    check(xor_uint(4294967295u, 2147483648u), 4294967295u ^ 2147483648u);
+    check(xor_uint(4294967295u, 4294967295u), 4294967295u ^ 4294967295u);
+    check(lt_uint(0u, 0u), 0u < 0u);
+    check(lt_uint(0u, 1u), 0u < 1u);
+    check(lt_uint(0u, 7u), 0u < 7u);
+    check(lt_uint(0u, 2147483648u), 0u < 2147483648u);
+    check(lt_uint(0u, 4294967295u), 0u < 4294967295u);
+    check(lt_uint(1u, 0u), 1u < 0u);
+    check(lt_uint(1u, 1u), 1u < 1u);
+    check(lt_uint(1u, 7u), 1u < 7u);
+    check(lt_uint(1u, 2147483648u), 1u < 2147483648u);
+    check(lt_uint(1u, 4294967295u), 1u < 4294967295u);
+    check(lt_uint(7u, 0u), 7u < 0u);
+    check(lt_uint(7u, 1u), 7u < 1u);
+    check(lt_uint(7u, 7u), 7u < 7u);
+    check(lt_uint(7u, 2147483648u), 7u < 2147483648u);
+    check(lt_uint(7u, 4294967295u), 7u < 4294967295u);
+    check(lt_uint(2147483648u, 0u), 2147483648u < 0u);
+    check(lt_uint(2147483648u, 1u), 2147483648u < 1u);
+    check(lt_uint(2147483648u, 7u), 2147483648u < 7u);
+    check(lt_uint(2147483648u, 2147483648u), 2147483648u < 2147483648u);
+    check(lt_uint(2147483648u, 4294967295u), 2147483648u < 4294967295u);
+    check(lt_uint(4294967295u, 0u), 4294967295u < 0u);
+    check(lt_uint(4294967295u, 1u), 4294967295u < 1u);
+    check(lt_uint(4294967295u, 7u), 4294967295u < 7u);
+    check(lt_uint(4294967295u, 2147483648u), 4294967295u < 2147483648u);
+    check(lt_uint(4294967295u, 4294967295u), 4294967295u < 4294967295u);
+    check(ge_uint(0u, 0u), 0u >= 0u);
+    check(ge_uint(0u, 1u), 0u >= 1u);
+    check(ge_uint(0u, 7u), 0u >= 7u);
+    check(ge_uint(0u, 2147483648u), 0u >= 2147483648u);
+    check(ge_uint(0u, 4294967295u), 0u >= 4294967295u);
+    check(ge_uint(1u, 0u), 1u >= 0u);
+    check(ge_uint(1u, 1u), 1u >= 1u);
+    check(ge_uint(1u, 7u), 1u >= 7u);
+    check(ge_uint(1u, 2147483648u), 1u >= 2147483648u);
+    check(ge_uint(1u, 4294967295u), 1u >= 4294967295u);
+    check(ge_uint(7u, 0u), 7u >= 0u);
+    check(ge_uint(7u, 1u), 7u >= 1u);
+    check(ge_uint(7u, 7u), 7u >= 7u);
+    check(ge_uint(7u, 2147483648u), 7u >= 2147483648u);
+    check(ge_uint(7u, 4294967295u), 7u >= 4294967295u);
+    check(ge_uint(2147483648u, 0u), 2147483648u >= 0u);
+    check(ge_uint(2147483648u, 1u), 2147483648u >= 1u);
+    check(ge_uint(2147483648u, 7u), 2147483648u >= 7u);
+    check(ge_uint(2147483648u, 2147483648u), 2147483648u >= 2147483648u);
+    check(ge_uint(2147483648u, 4294967295u), 2147483648u >= 4294967295u);
+    check(ge_uint(4294967295u, 0u), 4294967295u >= 0u);
+    check(ge_uint(4294967295u, 1u), 4294967295u >= 1u);
+    check(ge_uint(4294967295u, 7u), 4294967295u >= 7u);
+    check(ge_uint(4294967295u, 2147483648u), 4294967295u >= 2147483648u);
+    check(ge_uint(4294967295u, 4294967295u), 4294967295u >= 4294967295u);
+    check(add_long((-9223372036854775807l - 1), 5l), (-9223372036854775807l - 1) + 5l);
+    check(add_long((-9223372036854775807l - 1), 9223372036854775807l), (-9223372036854775807l - 1) + 9223372036854775807l);
+    check(add_long(-8l, -8l), -8l + -8l);
+    check(add_long(-8l, -1l), -8l + -1l);
+    check(add_long(-8l, 5l), -8l + 5l);
+    check(add_long(-8l, 9223372036854775807l), -8l + 9223372036854775807l);
+    check(add_long(-1l, -8l), -1l + -8l);
+    check(add_long(-1l, -1l), -1l + -1l);
+    check(add_long(-1l, 5l), -1l + 5l);
+    check(add_long(-1l, 9223372036854775807l), -1l + 9223372036854775807l);
+    check(add_long(5l, (-9223372036854775807l - 1)), 5l + (-9223372036854775807l - 1));
+    check(add_long(5l, -8l), 5l + -8l);
+    check(add_long(5l, -1l), 5l + -1l);
+    check(add_long(5l, 5l), 5l + 5l);
+    check(add_long(9223372036854775807l, (-9223372036854775807l - 1)), 9223372036854775807l + (-9223372036854775807l - 1));
+    check(add_long(9223372036854775807l, -8l), 9223372036854775807l + -8l);
+    check(add_long(9223372036854775807l, -1l), 9223372036854775807l + -1l);
+    check(sub_long((-9223372036854775807l - 1), (-9223372036854775807l - 1)), (-9223372036854775807l - 1) - (-9223372036854775807l - 1));
+    check(sub_long((-9223372036854775807l - 1), -8l), (-9223372036854775807l - 1) - -8l);
+    check(sub_long((-9223372036854775807l - 1), -1l), (-9223372036854775807l - 1) - -1l);
+    check(sub_long(-8l, (-9223372036854775807l - 1)), -8l - (-9223372036854775807l - 1));
+    check(sub_long(-8l, -8l), -8l - -8l);
+    check(sub_long(-8l, -1l), -8l - -1l);
+    check(sub_long(-8l, 5l), -8l - 5l);
+    check(sub_long(-1l, (-9223372036854775807l - 1)), -1l - (-9223372036854775807l - 1));
+    check(sub_long(-1l, -8l), -1l - -8l);
+    check(sub_long(-1l, -1l), -1l - -1l);
+    check(sub_long(-1l, 5l), -1l - 5l);
+    check(sub_long(-1l, 9223372036854775807l), -1l - 9223372036854775807l);
+    check(sub_long(5l, -8l), 5l - -8l);
+    check(sub_long(5l, -1l), 5l - -1l);
+    check(sub_long(5l, 5l), 5l - 5l);
+    check(sub_long(5l, 9223372036854775807l), 5l - 9223372036854775807l);
+    check(sub_long(9223372036854775807l, 5l), 9223372036854775807l - 5l);
+    check(sub_long(9223372036854775807l, 9223372036854775807l), 9223372036854775807l - 9223372036854775807l);
+    check(mul_long(-8l, -8l), -8l * -8l);
+    check(mul_long(-8l, -1l), -8l * -1l);
+    check(mul_long(-8l, 5l), -8l * 5l);
+    check(mul_long(-1l, -8l), -1l * -8l);
+    check(mul_long(-1l, -1l), -1l * -1l);
+    check(mul_long(-1l, 5l), -1l * 5l);
+    check(mul_long(-1l, 9223372036854775807l), -1l * 9223372036854775807l);
+    check(mul_long(5l, -8l), 5l * -8l);
+    check(mul_long(5l, -1l), 5l * -1l);
+    check(mul_long(5l, 5l), 5l * 5l);
+    check(mul_long(9223372036854775807l, -1l), 9223372036854775807l * -1l);
+    check(div_long((-9223372036854775807l - 1), (-9223372036854775807l - 1)), (-9223372036854775807l - 1) / (-9223372036854775807l - 1));
+    check(div_long((-9223372036854775807l - 1), -8l), (-9223372036854775807l - 1) / -8l);
+    check(div_long((-9223372036854775807l - 1), 5l), (-9223372036854775807l - 1) / 5l);
+    check(div_long((-9223372036854775807l - 1), 9223372036854775807l), (-9223372036854775807l - 1) / 9223372036854775807l);
+    check(div_long(-8l, (-9223372036854775807l - 1)), -8l / (-9223372036854775807l - 1));
+    check(div_long(-8l, -8l), -8l / -8l);
+    check(div_long(-8l, -1l), -8l / -1l);
+    check(div_long(-8l, 5l), -8l / 5l);
+    check(div_long(-8l, 9223372036854775807l), -8l / 9223372036854775807l);
+    check(div_long(-1l, (-9223372036854775807l - 1)), -1l / (-9223372036854775807l - 1));
+    check(div_long(-1l, -8l), -1l / -8l);
+    check(div_long(-1l, -1l), -1l / -1l);
+    check(div_long(-1l, 5l), -1l / 5l);
+    check(div_long(-1l, 9223372036854775807l), -1l / 9223372036854775807l);
+    check(div_long(5l, (-9223372036854775807l - 1)), 5l / (-9223372036854775807l - 1));
+    check(div_long(5l, -8l), 5l / -8l);
+    check(div_long(5l, -1l), 5l / -1l);
+    check(div_long(5l, 5l), 5l / 5l);
+    check(div_long(5l, 9223372036854775807l), 5l / 9223372036854775807l);
+    check(div_long(9223372036854775807l, (-9223372036854775807l - 1)), 9223372036854775807l / (-9223372036854775807l - 1));
+    check(div_long(9223372036854775807l, -8l), 9223372036854775807l / -8l);
+    check(div_long(9223372036854775807l, -1l), 9223372036854775807l / -1l);
+    check(div_long(9223372036854775807l, 5l), 9223372036854775807l / 5l);
+    check(div_long(9223372036854775807l, 9223372036854775807l), 9223372036854775807l / 9223372036854775807l);
+    check(rem_long((-9223372036854775807l - 1), (-9223372036854775807l - 1)), (-9223372036854775807l - 1) % (-9223372036854775807l - 1));
+    check(rem_long((-9223372036854775807l - 1), -8l), (-9223372036854775807l - 1) % -8l);
+    check(rem_long((-9223372036854775807l - 1), 5l), (-9223372036854775807l - 1) % 5l);
+    check(rem_long((-9223372036854775807l - 1), 9223372036854775807l), (-9223372036854775807l - 1) % 9223372036854775807l);
+    check(rem_long(-8l, (-9223372036854775807l - 1)), -8l % (-9223372036854775807l - 1));
+    check(rem_long(-8l, -8l), -8l % -8l);
+    check(rem_long(-8l, -1l), -8l % -1l);
+    check(rem_long(-8l, 5l), -8l % 5l);
+    check(rem_long(-8l, 9223372036854775807l), -8l % 9223372036854775807l);
+    check(rem_long(-1l, (-9223372036854775807l - 1)), -1l % (-9223372036854775807l - 1));
+    check(rem_long(-1l, -8l), -1l % -8l);
+    check(rem_long(-1l, -1l), -1l % -1l);
+    check(rem_long(-1l, 5l), -1l % 5l);
+    check(rem_long(-1l, 9223372036854775807l), -1l % 9223372036854775807l);
+    check(rem_long(5l, (-9223372036854775807l - 1)), 5l % (-9223372036854775807l - 1));
+    check(rem_long(5l, -8l), 5l % -8l);
+    check(rem_long(5l, -1l), 5l % -1l);
+    check(rem_long(5l, 5l), 5l % 5l);
+    check(rem_long(5l, 9223372036854775807l), 5l % 9223372036854775807l);
+    check(rem_long(9223372036854775807l, (-9223372036854775807l - 1)), 9223372036854775807l % (-9223372036854775807l - 1));
+    check(rem_long(9223372036854775807l, -8l), 9223372036854775807l % -8l);
+    check(rem_long(9223372036854775807l, -1l), 9223372036854775807l % -1l);
+    check(rem_long(9223372036854775807l, 5l), 9223372036854775807l % 5l);
+    check(rem_long(9223372036854775807l, 9223372036854775807l), 9223372036854775807l % 9223372036854775807l);
+    check(shr_long((-9223372036854775807l - 1), 0), (-9223372036854775807l - 1) >> 0);
+    check(shr_long((-9223372036854775807l - 1), 1), (-9223372036854775807l - 1) >> 1);
+    check(shr_long((-9223372036854775807l - 1), 3), (-9223372036854775807l - 1) >> 3);
+    check(shr_long((-9223372036854775807l - 1), 63), (-9223372036854775807l - 1) >> 63);
+    check(shr_long(-8l, 0), -8l >> 0);
+    check(shr_long(-8l, 1), -8l >> 1);
+    check(shr_long(-8l, 3), -8l >> 3);
+    check(shr_long(-8l, 63), -8l >> 63);
+    check(shr_long(-1l, 0), -1l >> 0);
+    check(shr_long(-1l, 1), -1l >> 1);
+    check(shr_long(-1l, 3), -1l >> 3);
+    check(shr_long(-1l, 63), -1l >> 63);
+    check(shr_long(5l, 0), 5l >> 0);
+    check(shr_long(5l, 1), 5l >> 1);
+    check(shr_long(5l, 3), 5l >> 3);
+    check(shr_long(5l, 63), 5l >> 63);
+    check(shr_long(9223372036854775807l, 0), 9223372036854775807l >> 0);
+    check(shr_long(9223372036854775807l, 1), 9223372036854775807l >> 1);
+    check(shr_long(9223372036854775807l, 3), 9223372036854775807l >> 3);
+    check(shr_long(9223372036854775807l, 63), 9223372036854775807l >> 63);
+    check(shl_long(5l, 0), 5l << 0);
+    check(shl_long(5l, 1), 5l << 1);
+    check(shl_long(5l, 3), 5l << 3);
+    check(shl_long(9223372036854775807l, 0), 9223372036854775807l << 0);
+    check(and_long((-9223372036854775807l - 1), (-9223372036854775807l - 1)), (-9223372036854775807l - 1) & (-9223372036854775807l - 1));
+    check(and_long((-9223372036854775807l - 1), -8l), (-9223372036854775807l - 1) & -8l);
+    check(and_long((-9223372036854775807l - 1), -1l), (-9223372036854775807l - 1) & -1l);
+    check(and_long((-9223372036854775807l - 1), 5l), (-9223372036854775807l - 1) & 5l);
+    check(and_long((-9223372036854775807l - 1), 9223372036854775807l), (-9223372036854775807l - 1) & 9223372036854775807l);
+    check(and_long(-8l, (-9223372036854775807l - 1)), -8l & (-9223372036854775807l - 1));
+    check(and_long(-8l, -8l), -8l & -8l);
+    check(and_long(-8l, -1l), -8l & -1l);
+    check(and_long(-8l, 5l), -8l & 5l);
+    check(and_long(-8l, 9223372036854775807l), -8l & 9223372036854775807l);
+    check(and_long(-1l, (-9223372036854775807l - 1)), -1l & (-9223372036854775807l - 1));
+    check(and_long(-1l, -8l), -1l & -8l);
+    check(and_long(-1l, -1l), -1l & -1l);
+    check(and_long(-1l, 5l), -1l & 5l);
+    check(and_long(-1l, 9223372036854775807l), -1l & 9223372036854775807l);
+    check(and_long(5l, (-9223372036854775807l - 1)), 5l & (-9223372036854775807l - 1));
+    check(and_long(5l, -8l), 5l & -8l);
+    check(and_long(5l, -1l), 5l & -1l);
+    check(and_long(5l, 5l), 5l & 5l);
+    check(and_long(5l, 9223372036854775807l), 5l & 9223372036854775807l);
+    check(and_long(9223372036854775807l, (-9223372036854775807l - 1)), 9223372036854775807l & (-9223372036854775807l - 1));
+    check(and_long(9223372036854775807l, -8l), 9223372036854775807l & -8l);
+    check(and_long(9223372036854775807l, -1l), 9223372036854775807l & -1l);
+    check(and_long(9223372036854775807l, 5l), 9223372036854775807l & 5l);
+    check(and_long(9223372036854775807l, 9223372036854775807l), 9223372036854775807l & 9223372036854775807l);
+    check(xor_long((-9223372036854775807l - 1), (-9223372036854775807l - 1)), (-9223372036854775807l - 1) ^ (-9223372036854775807l - 1));
+    check(xor_long((-9223372036854775807l - 1), -8l), (-9223372036854775807l - 1) ^ -8l);
+    check(xor_long((-9223372036854775807l - 1), -1l), (-9223372036854775807l - 1) ^ -1l);
+    check(xor_long((-9223372036854775807l - 1), 5l), (-9223372036854775807l - 1) ^ 5l);
+    check(xor_long((-9223372036854775807l - 1), 9223372036854775807l), (-9223372036854775807l - 1) ^ 9223372036854775807l);
+    check(xor_long(-8l, (-9223372036854775807l - 1)), -8l ^ (-9223372036854775807l - 1));
+    check(xor_long(-8l, -8l), -8l ^ -8l);
+    check(xor_long(-8l, -1l), -8l ^ -1l);
+    check(xor_long(-8l, 5l), -8l ^ 5l);
+    check(xor_long(-8l, 9223372036854775807l), -8l ^ 9223372036854775807l);
+    check(xor_long(-1l, (-9223372036854775807l - 1)), -1l ^ (-9223372036854775807l - 1));
+    check(xor_long(-1l, -8l), -1l ^ -8l);
+    check(xor_long(-1l, -1l), -1l ^ -1l);
+    check(xor_long(-1l, 5l), -1l ^ 5l);
+    check(xor_long(-1l, 9223372036854775807l), -1l ^ 9223372036854775807l);
+    check(xor_long(5l, (-9223372036854775807l - 1)), 5l ^ (-9223372036854775807l - 1));
+    check(xor_long(5l, -8l), 5l ^ -8l);
+    check(xor_long(5l, -1l), 5l ^ -1l);
+    check(xor_long(5l, 5l), 5l ^ 5l);
+    check(xor_long(5l, 9223372036854775807l), 5l ^ 9223372036854775807l);
+    check(xor_long(9223372036854775807l, (-9223372036854775807l - 1)), 9223372036854775807l ^ (-9223372036854775807l - 1));
+    check(xor_long(9223372036854775807l, -8l), 9223372036854775807l ^ -8l);
+    check(xor_long(9223372036854775807l, -1l), 9223372036854775807l ^ -1l);
+    check(xor_long(9223372036854775807l, 5l), 9223372036854775807l ^ 5l);
+    check(xor_long(9223372036854775807l, 9223372036854775807l), 9223372036854775807l ^ 9223372036854775807l);
+    check(lt_long((-9223372036854775807l - 1), (-9223372036854775807l - 1)), (-9223372036854775807l - 1) < (-9223372036854775807l - 1));
+    check(lt_long((-9223372036854775807l - 1), -8l), (-9223372036854775807l - 1) < -8l);
+    check(lt_long((-9223372036854775807l - 1), -1l), (-9223372036854775807l - 1) < -1l);
+    check(lt_long((-9223372036854775807l - 1), 5l), (-9223372036854775807l - 1) < 5l);
+    check(lt_long((-9223372036854775807l - 1), 9223372036854775807l), (-9223372036854775807l - 1) < 9223372036854775807l);
+    check(lt_long(-8l, (-9223372036854775807l - 1)), -8l < (-9223372036854775807l - 1));
+    check(lt_long(-8l, -8l), -8l < -8l);
+    check(lt_long(-8l, -1l), -8l < -1l);
+    check(lt_long(-8l, 5l), -8l < 5l);
+    check(lt_long(-8l, 9223372036854775807l), -8l < 9223372036854775807l);
+    check(lt_long(-1l, (-9223372036854775807l - 1)), -1l < (-9223372036854775807l - 1));
+    check(lt_long(-1l, -8l), -1l < -8l);
+    check(lt_long(-1l, -1l), -1l < -1l);
+    check(lt_long(-1l, 5l), -1l < 5l);
+    check(lt_long(-1l, 9223372036854775807l), -1l < 9223372036854775807l);
+    check(lt_long(5l, (-9223372036854775807l - 1)), 5l < (-9223372036854775807l - 1));
+    check(lt_long(5l, -8l), 5l < -8l);
+    check(lt_long(5l, -1l), 5l < -1l);
+    check(lt_long(5l, 5l), 5l < 5l);
+    check(lt_long(5l, 9223372036854775807l), 5l < 9223372036854775807l);
+    check(lt_long(9223372036854775807l, (-9223372036854775807l - 1)), 9223372036854775807l < (-9223372036854775807l - 1));
+    check(lt_long(9223372036854775807l, -8l), 9223372036854775807l < -8l);
+    check(lt_long(9223372036854775807l, -1l), 9223372036854775807l < -1l);
+    check(lt_long(9223372036854775807l, 5l), 9223372036854775807l < 5l);
+    check(lt_long(9223372036854775807l, 9223372036854775807l), 9223372036854775807l < 9223372036854775807l);
+    check(ge_long((-9223372036854775807l - 1), (-9223372036854775807l - 1)), (-9223372036854775807l - 1) >= (-9223372036854775807l - 1));
+    check(ge_long((-9223372036854775807l - 1), -8l), (-9223372036854775807l - 1) >= -8l);
+    check(ge_long((-9223372036854775807l - 1), -1l), (-9223372036854775807l - 1) >= -1l);
+    check(ge_long((-9223372036854775807l - 1), 5l), (-9223372036854775807l - 1) >= 5l);
+    check(ge_long((-9223372036854775807l - 1), 9223372036854775807l), (-9223372036854775807l - 1) >= 9223372036854775807l);
+    check(ge_long(-8l, (-9223372036854775807l - 1)), -8l >= (-9223372036854775807l - 1));
+    check(ge_long(-8l, -8l), -8l >= -8l);
+    check(ge_long(-8l, -1l), -8l >= -1l);
+    check(ge_long(-8l, 5l), -8l >= 5l);
+    check(ge_long(-8l, 9223372036854775807l), -8l >= 9223372036854775807l);
+    check(ge_long(-1l, (-9223372036854775807l - 1)), -1l >= (-9223372036854775807l - 1));
+    check(ge_long(-1l, -8l), -1l >= -8l);
+    check(ge_long(-1l, -1l), -1l >= -1l);
+    check(ge_long(-1l, 5l), -1l >= 5l);
+    check(ge_long(-1l, 9223372036854775807l), -1l >= 9223372036854775807l);
+    check(ge_long(5l, (-9223372036854775807l - 1)), 5l >= (-9223372036854775807l - 1));
+    check(ge_long(5l, -8l), 5l >= -8l);
+    check(ge_long(5l, -1l), 5l >= -1l);
+    check(ge_long(5l, 5l), 5l >= 5l);
+    check(ge_long(5l, 9223372036854775807l), 5l >= 9223372036854775807l);
+    check(ge_long(9223372036854775807l, (-9223372036854775807l - 1)), 9223372036854775807l >= (-9223372036854775807l - 1));
+    check(ge_long(9223372036854775807l, -8l), 9223372036854775807l >= -8l);
+    check(ge_long(9223372036854775807l, -1l), 9223372036854775807l >= -1l);
+    check(ge_long(9223372036854775807l, 5l), 9223372036854775807l >= 5l);
+    check(ge_long(9223372036854775807l, 9223372036854775807l), 9223372036854775807l >= 9223372036854775807l);
+    check(add_ulong(0ul, 0ul), 0ul + 0ul);
+    check(add_ulong(0ul, 3ul), 0ul + 3ul);
+    check(add_ulong(0ul, 9223372036854775808ul), 0ul + 9223372036854775808ul);
+    check(add_ulong(0ul, 18446744073709551615ul), 0ul + 18446744073709551615ul);
+    check(add_ulong(3ul, 0ul), 3ul + 0ul);
+    check(add_ulong(3ul, 3ul), 3ul + 3ul);
+    check(add_ulong(3ul, 9223372036854775808ul), 3ul + 9223372036854775808ul);
+    check(add_ulong(3ul, 18446744073709551615ul), 3ul + 18446744073709551615ul);
+    check(add_ulong(9223372036854775808ul, 0ul), 9223372036854775808ul + 0ul);
+    check(add_ulong(9223372036854775808ul, 3ul), 9223372036854775808ul + 3ul);
+    check(add_ulong(9223372036854775808ul, 9223372036854775808ul), 9223372036854775808ul + 9223372036854775808ul);
+    check(add_ulong(9223372036854775808ul, 18446744073709551615ul), 9223372036854775808ul + 18446744073709551615ul);
+    check(add_ulong(18446744073709551615ul, 0ul), 18446744073709551615ul + 0ul);
+    check(add_ulong(18446744073709551615ul, 3ul), 18446744073709551615ul + 3ul);
+    check(add_ulong(18446744073709551615ul, 9223372036854775808ul), 18446744073709551615ul + 9223372036854775808ul);
+    check(add_ulong(18446744073709551615ul, 18446744073709551615ul), 18446744073709551615ul + 18446744073709551615ul);
+    check(sub_ulong(0ul, 0ul), 0ul - 0ul);
+    check(sub_ulong(0ul, 3ul), 0ul - 3ul);
+    check(sub_ulong(0ul, 9223372036854775808ul), 0ul - 9223372036854775808ul);
+    check(sub_ulong(0ul, 18446744073709551615ul), 0ul - 18446744073709551615ul);
+    check(sub_ulong(3ul, 0ul), 3ul - 0ul);
+    check(sub_ulong(3ul, 3ul), 3ul - 3ul);
+    check(sub_ulong(3ul, 9223372036854775808ul), 3ul - 9223372036854775808ul);
+    check(sub_ulong(3ul, 18446744073709551615ul), 3ul - 18446744073709551615ul);
+    check(sub_ulong(9223372036854775808ul, 0ul), 9223372036854775808ul - 0ul);
+    check(sub_ulong(9223372036854775808ul, 3ul), 9223372036854775808ul - 3ul);
+    check(sub_ulong(9223372036854775808ul, 9223372036854775808ul), 9223372036854775808ul - 9223372036854775808ul);
+    check(sub_ulong(9223372036854775808ul, 18446744073709551615ul), 9223372036854775808ul - 18446744073709551615ul);
+    check(sub_ulong(18446744073709551615ul, 0ul), 18446744073709551615ul - 0ul);
+    check(sub_ulong(18446744073709551615ul, 3ul), 18446744073709551615ul - 3ul);
+    check(sub_ulong(18446744073709551615ul, 9223372036854775808ul), 18446744073709551615ul - 9223372036854775808ul);
+    check(sub_ulong(18446744073709551615ul, 18446744073709551615ul), 18446744073709551615ul - 18446744073709551615ul);
+    check(mul_ulong(0ul, 0ul), 0ul * 0ul);
+    check(mul_ulong(0ul, 3ul), 0ul * 3ul);
+    check(mul_ulong(0ul, 9223372036854775808ul), 0ul * 9223372036854775808ul);
+    check(mul_ulong(0ul, 18446744073709551615ul), 0ul * 18446744073709551615ul);
+    check(mul_ulong(3ul, 0ul), 3ul * 0ul);
+    check(mul_ulong(3ul, 3ul), 3ul * 3ul);
+    check(mul_ulong(3ul, 9223372036854775808ul), 3ul * 9223372036854775808ul);
+    check(mul_ulong(3ul, 18446744073709551615ul), 3ul * 18446744073709551615ul);
+    check(mul_ulong(9223372036854775808ul, 0ul), 9223372036854775808ul * 0ul);
+    check(mul_ulong(9223372036854775808ul, 3ul), 9223372036854775808ul * 3ul);
+    check(mul_ulong(9223372036854775808ul, 9223372036854775808ul), 9223372036854775808ul * 9223372036854775808ul);
+    check(mul_ulong(9223372036854775808ul, 18446744073709551615ul), 9223372036854775808ul * 18446744073709551615ul);
+    check(mul_ulong(18446744073709551615ul, 0ul), 18446744073709551615ul * 0ul);
+    check(mul_ulong(18446744073709551615ul, 3ul), 18446744073709551615ul * 3ul);
+    check(mul_ulong(18446744073709551615ul, 9223372036854775808ul), 18446744073709551615ul * 9223372036854775808ul);
+    check(mul_ulong(18446744073709551615ul, 18446744073709551615ul), 18446744073709551615ul * 18446744073709551615ul);
+    check(div_ulong(0ul, 3ul), 0ul / 3ul);
+    check(div_ulong(0ul, 9223372036854775808ul), 0ul / 9223372036854775808ul);
+    check(div_ulong(0ul, 18446744073709551615ul), 0ul / 18446744073709551615ul);
+    check(div_ulong(3ul, 3ul), 3ul / 3ul);
+    check(div_ulong(3ul, 9223372036854775808ul), 3ul / 9223372036854775808ul);
+    check(div_ulong(3ul, 18446744073709551615ul), 3ul / 18446744073709551615ul);
+    check(div_ulong(9223372036854775808ul, 3ul), 9223372036854775808ul / 3ul);
+    check(div_ulong(9223372036854775808ul, 9223372036854775808ul), 9223372036854775808ul / 9223372036854775808ul);
+    check(div_ulong(9223372036854775808ul, 18446744073709551615ul), 9223372036854775808ul / 18446744073709551615ul);
+    check(div_ulong(18446744073709551615ul, 3ul), 18446744073709551615ul / 3ul);
+    check(div_ulong(18446744073709551615ul, 9223372036854775808ul), 18446744073709551615ul / 9223372036854775808ul);
+    check(div_ulong(18446744073709551615ul, 18446744073709551615ul), 18446744073709551615ul / 18446744073709551615ul);
+    check(rem_ulong(0ul, 3ul), 0ul % 3ul);
+    check(rem_ulong(0ul, 9223372036854775808ul), 0ul % 9223372036854775808ul);
+    check(rem_ulong(0ul, 18446744073709551615ul), 0ul % 18446744073709551615ul);
+    check(rem_ulong(3ul, 3ul), 3ul % 3ul);
+    check(rem_ulong(3ul, 9223372036854775808ul), 3ul % 9223372036854775808ul);
+    check(rem_ulong(3ul, 18446744073709551615ul), 3ul % 18446744073709551615ul);
+    check(rem_ulong(9223372036854775808ul, 3ul), 9223372036854775808ul % 3ul);
+    check(rem_ulong(9223372036854775808ul, 9223372036854775808ul), 9223372036854775808ul % 9223372036854775808ul);
+    check(rem_ulong(9223372036854775808ul, 18446744073709551615ul), 9223372036854775808ul % 18446744073709551615ul);
+    check(rem_ulong(18446744073709551615ul, 3ul), 18446744073709551615ul % 3ul);
+    check(rem_ulong(18446744073709551615ul, 9223372036854775808ul), 18446744073709551615ul % 9223372036854775808ul);
+    check(rem_ulong(18446744073709551615ul, 18446744073709551615ul), 18446744073709551615ul % 18446744073709551615ul);
+    check(shr_ulong(0ul, 0), 0ul >> 0);
+    check(shr_ulong(0ul, 1), 0ul >> 1);
+    check(shr_ulong(0ul, 3), 0ul >> 3);
+    check(shr_ulong(0ul, 63), 0ul >> 63);
+    check(shr_ulong(3ul, 0), 3ul >> 0);
+    check(shr_ulong(3ul, 1), 3ul >> 1);
+    check(shr_ulong(3ul, 3), 3ul >> 3);
+    check(shr_ulong(3ul, 63), 3ul >> 63);
+    check(shr_ulong(9223372036854775808ul, 0), 9223372036854775808ul >> 0);
+    check(shr_ulong(9223372036854775808ul, 1), 9223372036854775808ul >> 1);
+    check(shr_ulong(9223372036854775808ul, 3), 9223372036854775808ul >> 3);
+    check(shr_ulong(9223372036854775808ul, 63), 9223372036854775808ul >> 63);
+    check(shr_ulong(18446744073709551615ul, 0), 18446744073709551615ul >> 0);
+    check(shr_ulong(18446744073709551615ul, 1), 18446744073709551615ul >> 1);
+    check(shr_ulong(18446744073709551615ul, 3), 18446744073709551615ul >> 3);
+    check(shr_ulong(18446744073709551615ul, 63), 18446744073709551615ul >> 63);
+    check(shl_ulong(0ul, 0), 0ul << 0);
+    check(shl_ulong(0ul, 1), 0ul << 1);
+    check(shl_ulong(0ul, 3), 0ul << 3);
+    check(shl_ulong(0ul, 63), 0ul << 63);
+    check(shl_ulong(3ul, 0), 3ul << 0);
+    check(shl_ulong(3ul, 1), 3ul << 1);
+    check(shl_ulong(3ul, 3), 3ul << 3);
+    check(shl_ulong(3ul, 63), 3ul << 63);
+    check(shl_ulong(9223372036854775808ul, 0), 9223372036854775808ul << 0);
+    check(shl_ulong(9223372036854775808ul, 1), 9223372036854775808ul << 1);
+    check(shl_ulong(9223372036854775808ul, 3), 9223372036854775808ul << 3);
+    check(shl_ulong(9223372036854775808ul, 63), 9223372036854775808ul << 63);
+    check(shl_ulong(18446744073709551615ul, 0), 18446744073709551615ul << 0);
+    check(shl_ulong(18446744073709551615ul, 1), 18446744073709551615ul << 1);
+    check(shl_ulong(18446744073709551615ul, 3), 18446744073709551615ul << 3);
+    check(shl_ulong(18446744073709551615ul, 63), 18446744073709551615ul << 63);
+    check(and_ulong(0ul, 0ul), 0ul & 0ul);
+    check(and_ulong(0ul, 3ul), 0ul & 3ul);
+    check(and_ulong(0ul, 9223372036854775808ul), 0ul & 9223372036854775808ul);
+    check(and_ulong(0ul, 18446744073709551615ul), 0ul & 18446744073709551615ul);
+    check(and_ulong(3ul, 0ul), 3ul & 0ul);
+    check(and_ulong(3ul, 3ul), 3ul & 3ul);
+    check(and_ulong(3ul, 9223372036854775808ul), 3ul & 9223372036854775808ul);
+    check(and_ulong(3ul, 18446744073709551615ul), 3ul & 18446744073709551615ul);
+    check(and_ulong(9223372036854775808ul, 0ul), 9223372036854775808ul & 0ul);
+    check(and_ulong(9223372036854775808ul, 3ul), 9223372036854775808ul & 3ul);
+    check(and_ulong(9223372036854775808ul, 9223372036854775808ul), 9223372036854775808ul & 9223372036854775808ul);
+    check(and_ulong(9223372036854775808ul, 18446744073709551615ul), 9223372036854775808ul & 18446744073709551615ul);
+    check(and_ulong(18446744073709551615ul, 0ul), 18446744073709551615ul & 0ul);
+    check(and_ulong(18446744073709551615ul, 3ul), 18446744073709551615ul & 3ul);
+    check(and_ulong(18446744073709551615ul, 9223372036854775808ul), 18446744073709551615ul & 9223372036854775808ul);
+    check(and_ulong(18446744073709551615ul, 18446744073709551615ul), 18446744073709551615ul & 18446744073709551615ul);
+    check(xor_ulong(0ul, 0ul), 0ul ^ 0ul);
+    check(xor_ulong(0ul, 3ul), 0ul ^ 3ul);
+    check(xor_ulong(0ul, 9223372036854775808ul), 0ul ^ 9223372036854775808ul);
+    check(xor_ulong(0ul, 18446744073709551615ul), 0ul ^ 18446744073709551615ul);
+    check(xor_ulong(3ul, 0ul), 3ul ^ 0ul);
+    check(xor_ulong(3ul, 3ul), 3ul ^ 3ul);
+    check(xor_ulong(3ul, 9223372036854775808ul), 3ul ^ 9223372036854775808ul);
+    check(xor_ulong(3ul, 18446744073709551615ul), 3ul ^ 18446744073709551615ul);
+    check(xor_ulong(9223372036854775808ul, 0ul), 9223372036854775808ul ^ 0ul);
+    check(xor_ulong(9223372036854775808ul, 3ul), 9223372036854775808ul ^ 3ul);
+    check(xor_ulong(9223372036854775808ul, 9223372036854775808ul), 9223372036854775808ul ^ 9223372036854775808ul);
+    check(xor_ulong(9223372036854775808ul, 18446744073709551615ul), 9223372036854775808ul ^ 18446744073709551615ul);
+    check(xor_ulong(18446744073709551615ul, 0ul), 18446744073709551615ul ^ 0ul);
+    check(xor_ulong(18446744073709551615ul, 3ul), 18446744073709551615ul ^ 3ul);
+    check(xor_ulong(18446744073709551615ul, 9223372036854775808ul), 18446744073709551615ul ^ 9223372036854775808ul);
+    check(xor_ulong(18446744073709551615ul, 18446744073709551615ul), 18446744073709551615ul ^ 18446744073709551615ul);
+    check(lt_ulong(0ul, 0ul), 0ul < 0ul);
+    check(lt_ulong(0ul, 3ul), 0ul < 3ul);
+    check(lt_ulong(0ul, 9223372036854775808ul), 0ul < 9223372036854775808ul);
+    check(lt_ulong(0ul, 18446744073709551615ul), 0ul < 18446744073709551615ul);
+    check(lt_ulong(3ul, 0ul), 3ul < 0ul);
+    check(lt_ulong(3ul, 3ul), 3ul < 3ul);
+    check(lt_ulong(3ul, 9223372036854775808ul), 3ul < 9223372036854775808ul);
+    check(lt_ulong(3ul, 18446744073709551615ul), 3ul < 18446744073709551615ul);
+    check(lt_ulong(9223372036854775808ul, 0ul), 9223372036854775808ul < 0ul);
+    check(lt_ulong(9223372036854775808ul, 3ul), 9223372036854775808ul < 3ul);
+    check(lt_ulong(9223372036854775808ul, 9223372036854775808ul), 9223372036854775808ul < 9223372036854775808ul);
+    check(lt_ulong(9223372036854775808ul, 18446744073709551615ul), 9223372036854775808ul < 18446744073709551615ul);
+    check(lt_ulong(18446744073709551615ul, 0ul), 18446744073709551615ul < 0ul);
+    check(lt_ulong(18446744073709551615ul, 3ul), 18446744073709551615ul < 3ul);
+    check(lt_ulong(18446744073709551615ul, 9223372036854775808ul), 18446744073709551615ul < 9223372036854775808ul);
+    check(lt_ulong(18446744073709551615ul, 18446744073709551615ul), 18446744073709551615ul < 18446744073709551615ul);
+    check(ge_ulong(0ul, 0ul), 0ul >= 0ul);
+    check(ge_ulong(0ul, 3ul), 0ul >= 3ul);
+    check(ge_ulong(0ul, 9223372036854775808ul), 0ul >= 9223372036854775808ul);
+    check(ge_ulong(0ul, 18446744073709551615ul), 0ul >= 18446744073709551615ul);
+    check(ge_ulong(3ul, 0ul), 3ul >= 0ul);
+    check(ge_ulong(3ul, 3ul), 3ul >= 3ul);
+    check(ge_ulong(3ul, 9223372036854775808ul), 3ul >= 9223372036854775808ul);
+    check(ge_ulong(3ul, 18446744073709551615ul), 3ul >= 18446744073709551615ul);
+    check(ge_ulong(9223372036854775808ul, 0ul), 9223372036854775808ul >= 0ul);
+    check(ge_ulong(9223372036854775808ul, 3ul), 9223372036854775808ul >= 3ul);
+    check(ge_ulong(9223372036854775808ul, 9223372036854775808ul), 9223372036854775808ul >= 9223372036854775808ul);
+    check(ge_ulong(9223372036854775808ul, 18446744073709551615ul), 9223372036854775808ul >= 18446744073709551615ul);
+    check(ge_ulong(18446744073709551615ul, 0ul), 18446744073709551615ul >= 0ul);
+    check(ge_ulong(18446744073709551615ul, 3ul), 18446744073709551615ul >= 3ul);
+    check(ge_ulong(18446744073709551615ul, 9223372036854775808ul), 18446744073709551615ul >= 9223372036854775808ul);
+    check(ge_ulong(18446744073709551615ul, 18446744073709551615ul), 18446744073709551615ul >= 18446744073709551615ul);
+    show(checks);
+    show(mismatches);
+    show(digest);
+    return 0;
+}
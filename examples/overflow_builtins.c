@@ -0,0 +1,113 @@
+int printf(const char *format, ...);
+
+void show(long n) {
+    char format[] = {'%', 'l', 'd', '\n', 0};
+    printf(format, n);
+}
+
+void show_pair(long overflowed, long value) {
+    char format[] = {'%', 'l', 'd', ' ', '%', 'l', 'd', '\n', 0};
+    printf(format, overflowed, value);
+}
+
+// decimal digits to an int, -1 if the value doesn't fit
+int parse_int(char *digits) {
+    int value = 0;
+    for (int i = 0; digits[i] != 0; i++) {
+        if (__builtin_mul_overflow(value, 10, &value)) {
+            return -1;
+        }
+        if (__builtin_add_overflow(value, digits[i] - '0', &value)) {
+            return -1;
+        }
+    }
+    return value;
+}
+
+void same_types(int a, int b, long c, long d, unsigned int e, unsigned long f) {
+    int overflowed;
+    int i;
+    long l;
+    unsigned int u;
+    unsigned long ul;
+    overflowed = __builtin_add_overflow(a, b, &i);
+    show_pair(overflowed, i);
+    overflowed = __builtin_sub_overflow(a, b, &i);
+    show_pair(overflowed, i);
+    overflowed = __builtin_mul_overflow(a, b, &i);
+    show_pair(overflowed, i);
+    overflowed = __builtin_add_overflow(c, d, &l);
+    show_pair(overflowed, l);
+    overflowed = __builtin_sub_overflow(c, d, &l);
+    show_pair(overflowed, l);
+    overflowed = __builtin_mul_overflow(c, d, &l);
+    show_pair(overflowed, l);
+    overflowed = __builtin_add_overflow(e, e, &u);
+    show_pair(overflowed, u);
+    overflowed = __builtin_sub_overflow(e, e + 1, &u);
+    show_pair(overflowed, u);
+    overflowed = __builtin_mul_overflow(e, e, &u);
+    show_pair(overflowed, u);
+    overflowed = __builtin_add_overflow(f, f, &ul);
+    show_pair(overflowed, (long)ul);
+    overflowed = __builtin_sub_overflow(0ul, f, &ul);
+    show_pair(overflowed, (long)ul);
+    overflowed = __builtin_mul_overflow(f, 3ul, &ul);
+    show_pair(overflowed, (long)ul);
+}
+
+void mixed_types(long a, unsigned long b) {
+    int overflowed;
+    char c;
+    unsigned char uc;
+    short s;
+    int i;
+    unsigned int u;
+    long l;
+    unsigned long ul;
+    overflowed = __builtin_add_overflow(a, b, &c);
+    show_pair(overflowed, c);
+    overflowed = __builtin_add_overflow(a, 100, &uc);
+    show_pair(overflowed, uc);
+    overflowed = __builtin_mul_overflow(a, a, &s);
+    show_pair(overflowed, s);
+    overflowed = __builtin_sub_overflow(a, b, &i);
+    show_pair(overflowed, i);
+    overflowed = __builtin_mul_overflow(a, b, &u);
+    show_pair(overflowed, u);
+    overflowed = __builtin_mul_overflow(a, b, &l);
+    show_pair(overflowed, l);
+    overflowed = __builtin_sub_overflow(b, a, &ul);
+    show_pair(overflowed, (long)ul);
+    overflowed = __builtin_mul_overflow(a, -1, &ul);
+    show_pair(overflowed, (long)ul);
+    overflowed = __builtin_mul_overflow(b, b, &l);
+    show_pair(overflowed, l);
+}
+
+int main(void) {
+    char small[] = {'1', '2', '3', '4', 0};
+    char limit[] = {'2', '1', '4', '7', '4', '8', '3', '6', '4', '7', 0};
+    char large[] = {'2', '1', '4', '7', '4', '8', '3', '6', '4', '8', 0};
+    char huge[] = {'9', '9', '9', '9', '9', '9', '9', '9', '9', '9', '9', 0};
+    show(parse_int(small));
+    show(parse_int(limit));
+    show(parse_int(large));
+    show(parse_int(huge));
+
+    same_types(2000000000, 2000000000, 9000000000000000000l, -9000000000000000000l, 3000000000u,
+               10000000000000000000ul);
+    same_types(-5, 7, 3, 4, 65536u, 5ul);
+    mixed_types(-3, 5);
+    mixed_types(100, 200);
+    mixed_types(-4000000000l, 3000000000ul);
+    mixed_types(9223372036854775807l, 18446744073709551615ul);
+
+    // with constant operands, the check is folded
+    int flags[__builtin_add_overflow_p(2147483647, 1, (int)0) + 1];
+    flags[0] = __builtin_mul_overflow_p(65536, 65536, (long)0);
+    show(flags[0]);
+    show(__builtin_sub_overflow_p(0, 1, (unsigned char)0));
+    show(__builtin_add_overflow_p(200, 55, (unsigned char)0));
+    return 0;
+}
@@ -0,0 +1,24 @@
+int printf(const char *format, ...);
+
+// protocol constants, locked down at compile time
+_Static_assert(16 % 8 == 0, "header must stay 8-byte aligned");
+_Static_assert((1u << 31) > 0, "unsigned shifts don't overflow");
+_Static_assert(-1 < 0 && 4294967295u + 1 == 0, "constants have their usual types");
+
+int header_size = 16;
+
+int scaled(int n) {
+    _Static_assert((long)2147483647 * 2 == 4294967294l, "long arithmetic");
+    int factor = 3;
+    _Static_assert(3 > 2, "declarations may follow");
+    return n * factor;
+}
+
+int main(void) {
+    char format[] = {'%', 'd', '\n', 0};
+    for (int i = 0; i < 2; i++) {
+        _Static_assert(1, "inside a loop body");
+        printf(format, scaled(header_size + i));
+    }
+    return 0;
+}
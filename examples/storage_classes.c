@@ -0,0 +1,83 @@
+int putchar(int c);
+
+extern int total;
+extern const int limits[3];
+
+static int calls;
+static int scale = 3;
+
+static int print_num(int n) {
+    if (n < 0) {
+        putchar(45);
+        n = -n;
+    }
+    if (n >= 10) {
+        print_num(n / 10);
+    }
+    putchar(48 + n % 10);
+    return 0;
+}
+
+static int line(int n);
+
+int line(int n) {
+    print_num(n);
+    putchar(10);
+    return 0;
+}
+
+// every call sees the value left by the previous one
+int next_id() {
+    static int id = 100;
+    calls++;
+    id = id + 1;
+    return id;
+}
+
+int fib_cached(int n) {
+    static long memo[20];
+    if (n < 2) {
+        return n;
+    }
+    if (memo[n] == 0) {
+        memo[n] = fib_cached(n - 1) + fib_cached(n - 2);
+    }
+    return (int)memo[n];
+}
+
+int bump(int by) {
+    static int sum;
+    for (int i = 0; i < by; i++) {
+        static int steps = 0;
+        steps++;
+        sum += steps;
+    }
+    return sum;
+}
+
+int total = 7;
+const int limits[3] = {10, 20, 30};
+
+int main() {
+    line(next_id());
+    line(next_id());
+    line(next_id());
+    line(calls);
+
+    line(fib_cached(19));
+    line(fib_cached(10));
+
+    line(bump(2));
+    line(bump(3));
+
+    total += scale * limits[2];
+    line(total);
+
+    {
+        static int id = 5;
+        id++;
+        line(id);
+    }
+
+    return calls;
+}
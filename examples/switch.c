@@ -0,0 +1,144 @@
+int printf(const char *format, ...);
+
+void show(long n) {
+    char format[] = {'%', 'l', 'd', '\n', 0};
+    printf(format, n);
+}
+
+int calls = 0;
+
+int next(void) {
+    calls++;
+    return calls;
+}
+
+// falls through from one case to the next until a `break`
+int classify(int n) {
+    int result = 0;
+    switch (n) {
+    case 1:
+        result += 1;
+    case 2:
+        result += 10;
+        break;
+    default:
+        result = -1;
+        break;
+    case -3:
+        result = 300;
+    }
+    return result;
+}
+
+// labels can be anywhere in the body, even inside a loop
+int copy_rest(int *to, int *from, int count) {
+    int n = (count + 3) / 4;
+    switch (count % 4) {
+    case 0:
+        do {
+            *to = *from;
+            to = to + 1;
+            from = from + 1;
+        case 3:
+            *to = *from;
+            to = to + 1;
+            from = from + 1;
+        case 2:
+            *to = *from;
+            to = to + 1;
+            from = from + 1;
+        case 1:
+            *to = *from;
+            to = to + 1;
+            from = from + 1;
+            n = n - 1;
+        } while (n > 0);
+    }
+    return n;
+}
+
+// `break` leaves the switch, `continue` goes to the loop around it
+int count_kinds(int limit) {
+    int total = 0;
+    for (int i = 0; i < limit; i++) {
+        switch (i % 3) {
+        case 0:
+            continue;
+        case 1:
+            if (i > 6) {
+                break;
+            }
+            total += 100;
+            break;
+        default: {
+            switch (i) {
+            case 2:
+                total += 1000;
+                break;
+            }
+            total += 1;
+        }
+        }
+        total += 10;
+    }
+    return total;
+}
+
+// the value is converted like the operand of a binary operator
+long widen(unsigned char c, long l, unsigned int u) {
+    long result = 0;
+    switch (c) {
+    case 255:
+        result += 1;
+        break;
+    case 256:
+        result += 2;
+        break;
+    }
+    switch (l) {
+    case 4294967296l:
+        result += 10;
+        break;
+    case -1:
+        result += 20;
+        break;
+    }
+    switch (u) {
+    case -1:
+        result += 100;
+        break;
+    case 70000:
+        result += 200;
+        break;
+    }
+    return result;
+}
+
+int main(void) {
+    show(classify(1));
+    show(classify(2));
+    show(classify(-3));
+    show(classify(4));
+
+    int from[7] = {1, 2, 3, 4, 5, 6, 7};
+    int to[7] = {0, 0, 0, 0, 0, 0, 0};
+    show(copy_rest(to, from, 7));
+    show(to[0] + to[3] * 10 + to[6] * 100);
+
+    show(count_kinds(10));
+
+    show(widen(255, 4294967296l, 4294967295u));
+    show(widen(0, -1, 70000));
+    show(widen(1, 5, 5));
+
+    // the value is computed once, and nothing matches without a default
+    switch (next() + 10) {
+    case 1:
+        show(-1);
+    }
+    switch (next()) {
+    case 2:
+        show(calls);
+    }
+    return 0;
+}
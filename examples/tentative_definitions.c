@@ -0,0 +1,59 @@
+int putchar(int c);
+
+// a tentative definition completed by a later one
+int answer;
+int answer = 42;
+
+// repeated tentative definitions are a single zero-initialized object
+int hits;
+int hits;
+
+int zeros[64];
+long filled[4] = {0, 0, 0, 9};
+double ratio;
+static int cleared = 0;
+char flag;
+char *cursor;
+
+int print_num(int n) {
+    if (n < 0) {
+        putchar(45);
+        n = -n;
+    }
+    if (n >= 10) {
+        print_num(n / 10);
+    }
+    putchar(48 + n % 10);
+    return 0;
+}
+
+int line(int n) {
+    print_num(n);
+    putchar(10);
+    return 0;
+}
+
+int main() {
+    int sum = 0;
+    for (int i = 0; i < 64; i++) {
+        sum += zeros[i];
+        zeros[i] = i;
+    }
+    line(sum);
+    line(zeros[63]);
+
+    hits++;
+    hits++;
+    line(hits);
+    line(answer);
+    line(filled[3] + filled[0]);
+
+    line(ratio == 0.0);
+    ratio = 1.5;
+    line((int)(ratio * 4));
+
+    line(cleared + flag);
+    line(cursor == 0);
+
+    return answer - 40;
+}
@@ -0,0 +1,53 @@
+int printf(const char *format, ...);
+
+void show(long n) {
+    char format[] = {'%', 'l', 'd', '\n', 0};
+    printf(format, n);
+}
+
+// the error code of the last failed call, one per thread
+__thread int error_code;
+_Thread_local long calls = 100;
+static _Thread_local int history[4] = {1, 2, 3};
+
+int checked_divide(int a, int b) {
+    calls++;
+    if (b == 0) {
+        error_code = 33;
+        return 0;
+    }
+    error_code = 0;
+    return a / b;
+}
+
+int next_id(void) {
+    static _Thread_local int id = 10;
+    id = id + 1;
+    return id;
+}
+
+void bump(int *p) {
+    *p = *p + 5;
+}
+
+int main(void) {
+    show(error_code);
+    show(checked_divide(84, 2));
+    show(error_code);
+    show(checked_divide(1, 0));
+    show(error_code);
+    show(calls);
+
+    show(next_id());
+    show(next_id());
+
+    extern __thread int error_code;
+    bump(&error_code);
+    show(error_code);
+
+    history[3] = history[0] + history[1] + history[2];
+    int *last = &history[3];
+    bump(last);
+    show(history[3]);
+    return 0;
+}
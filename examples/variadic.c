@@ -0,0 +1,89 @@
+int printf(const char *format, ...);
+int putchar(int c);
+
+// declared without a prototype, completed by the definition below
+long scaled();
+
+long sum(int count, ...) {
+    __builtin_va_list ap;
+    __builtin_va_start(ap, count);
+    long total = 0;
+    for (int i = 0; i < count; i++) {
+        total += __builtin_va_arg(ap, int);
+    }
+    __builtin_va_end(ap);
+    return total;
+}
+
+long vsum_longs(int count, __builtin_va_list ap) {
+    long total = 0;
+    for (int i = 0; i < count; i++) {
+        total += __builtin_va_arg(ap, long);
+    }
+    return total;
+}
+
+// the first pass over the arguments is handed off, the copy is walked twice as far
+long sum_longs_twice(int count, ...) {
+    __builtin_va_list ap;
+    __builtin_va_list again;
+    __builtin_va_start(ap, count);
+    __builtin_va_copy(again, ap);
+    long total = vsum_longs(count, ap);
+    total += vsum_longs(count, again);
+    __builtin_va_end(again);
+    __builtin_va_end(ap);
+    return total;
+}
+
+// named parameters in both register classes, then a mix of unnamed ones
+double weigh(double bias, int count, ...) {
+    __builtin_va_list ap;
+    __builtin_va_start(ap, count);
+    double total = bias;
+    for (int i = 0; i < count; i++) {
+        int weight = __builtin_va_arg(ap, int);
+        total += weight * __builtin_va_arg(ap, double);
+    }
+    __builtin_va_end(ap);
+    return total;
+}
+
+// the unnamed arguments start after named ones that spilled to the stack
+long after_stack(int a, int b, int c, int d, int e, int f, int g, int h, int i, ...) {
+    __builtin_va_list ap;
+    __builtin_va_start(ap, i);
+    long first = __builtin_va_arg(ap, long);
+    char *text = __builtin_va_arg(ap, char *);
+    __builtin_va_end(ap);
+    return a + b + c + d + e + f + g + h + i + first + text[0];
+}
+
+long scaled(long n, int by) {
+    return n * by;
+}
+
+int main() {
+    char number[] = {'%', 'd', '\n', 0};
+    char pair[] = {'%', 'l', 'd', ' ', '%', 'c', '\n', 0};
+    char real[] = {'%', '.', '3', 'f', '\n', 0};
+    char many[] = {'%', 'd', '%', 'd', '%', 'd', '%', 'd', '%', 'd', '%', 'd', '%', 'd',
+                   '%', 'd', '%', 'd', '%', 'd', ' ', '%', 'f', '\n', 0};
+
+    printf(number, 42);
+    printf(pair, 5000000000, 'x');
+    float third = 1.0f / 3.0f;
+    printf(real, third); // a float argument is promoted to double
+    printf(many, 1, 2, 3, 4, 5, 6, 7, 8, 9, 0, 2.5);
+
+    short small = -7;
+    char letter = 'a';
+    printf(number, (int)sum(4, small, letter, 1000, -1));
+    printf(number, (int)sum(12, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12));
+    printf(number, (int)sum_longs_twice(3, 10l, 200l, 3000l));
+    printf(real, weigh(0.5, 3, 1, 1.5, 2, 0.25f, 10, -0.125));
+    printf(number, (int)after_stack(1, 2, 3, 4, 5, 6, 7, 8, 9, 100l, pair));
+    printf(number, (int)scaled(7, 6));
+
+    return 0;
+}
@@ -0,0 +1,102 @@
+int printf(const char *format, ...);
+
+void show(long n) {
+    char format[] = {'%', 'l', 'd', '\n', 0};
+    printf(format, n);
+}
+
+long sum(int n, long *values) {
+    long total = 0;
+    for (int i = 0; i < n; i++) {
+        total += values[i];
+    }
+    return total;
+}
+
+long squares(int n) {
+    long values[n];
+    for (int i = 0; i < n; i++) {
+        values[i] = (long)i * i;
+    }
+    return sum(n, values);
+}
+
+// rows of a fixed width, as many as asked for
+int grid(int rows) {
+    int cells[rows][3];
+    for (int r = 0; r < rows; r++) {
+        for (int c = 0; c < 3; c++) {
+            cells[r][c] = r * 10 + c;
+        }
+    }
+    return cells[rows - 1][2] + cells[0][1];
+}
+
+// a new array every iteration, released before the next one
+long triangle(int n) {
+    long total = 0;
+    for (int i = 1; i <= n; i++) {
+        char bytes[i * 1000];
+        bytes[0] = 1;
+        bytes[i * 1000 - 1] = (char)i;
+        if (i == 3) {
+            continue;
+        }
+        if (i == n) {
+            break;
+        }
+        total += bytes[0] + bytes[i * 1000 - 1];
+    }
+    return total;
+}
+
+int sizeof_int(void) {
+    return 4;
+}
+
+long copies(int n) {
+    int fixed = 7;
+    double weights[n];
+    int *scratch = __builtin_alloca(n * sizeof_int());
+    for (int i = 0; i < n; i++) {
+        weights[i] = i + 0.5;
+        scratch[i] = fixed + i;
+    }
+    double total = 0;
+    for (int i = 0; i < n; i++) {
+        total += weights[i] * scratch[i];
+    }
+    return (long)total + fixed;
+}
+
+long *fill(long *p, int n) {
+    p[0] = n;
+    p[1] = 2 * n;
+    return p;
+}
+
+// space allocated while the other operands and arguments are pending
+long nested(int n) {
+    long result = 1000 * n + sum(2, fill(__builtin_alloca(16), n));
+    long *p = fill(__builtin_alloca(2 * sizeof_int() * 2), n + 1);
+    return result + p[1];
+}
+
+int main(void) {
+    show(squares(5));
+    show(squares(100));
+    show(grid(4));
+    show(triangle(5));
+    show(copies(6));
+    show(nested(3));
+
+    long before = 0;
+    for (int round = 0; round < 3; round++) {
+        int n = round + 2;
+        int marks[n];
+        marks[n - 1] = round;
+        before += marks[n - 1];
+    }
+    show(before);
+    return 0;
+}
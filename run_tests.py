@@ -42,7 +42,7 @@ COMPILE_DISABLED = args.disable_compile
 ARCH_STYLE = args.arch_style
 
 
-def build_clang_command(source: Path, output: Path, arch: str, style: str) -> list[str]:
+def build_clang_command(sources: list[Path], output: Path, arch: str, style: str) -> list[str]:
     cmd = ["clang"]
     if style == "mac":
         cmd += ["-arch", "arm64"]
@@ -50,13 +50,13 @@ def build_clang_command(source: Path, output: Path, arch: str, style: str) -> li
         cmd += [f"--target={arch}-linux-gnu"]
     else:
         raise ValueError(f"Unsupported arch style: {style!r}. Expected 'mac' or 'gnu'.")
-    cmd += ["-o", str(output), str(source)]
+    cmd += ["-o", str(output)] + [str(source) for source in sources]
     return cmd
 
 
-def compile_and_run(source: Path, output: Path, arch: str) -> tuple[str, int]:
-    # Compile source to output binary
-    clang_cmd = build_clang_command(source, output, arch, ARCH_STYLE)
+def compile_and_run(sources: list[Path], output: Path, arch: str) -> tuple[str, int]:
+    # Compile sources to output binary
+    clang_cmd = build_clang_command(sources, output, arch, ARCH_STYLE)
 
     try:
         subprocess.run(
@@ -79,7 +79,9 @@ def compile_and_run(source: Path, output: Path, arch: str) -> tuple[str, int]:
     return result.stdout.strip(), result.returncode
 
 
-def compare_c_and_s_outputs(c_file: Path, s_file: Path, arch: str = "arm64") -> bool:
+def compare_c_and_s_outputs(
+    c_file: Path, s_file: Path, arch: str = "arm64", linked: tuple[Path, ...] = ()
+) -> bool:
     if not c_file.exists() or not s_file.exists():
         print(f"{RED}Missing file(s): {c_file}, {s_file}{RESET}")
         return False
@@ -89,8 +91,8 @@ def compare_c_and_s_outputs(c_file: Path, s_file: Path, arch: str = "arm64") ->
     bin_s = s_file.with_name(f"{base}.s.bin")
 
     try:
-        output_c, code_c = compile_and_run(c_file, bin_c, arch)
-        output_s, code_s = compile_and_run(s_file, bin_s, arch)
+        output_c, code_c = compile_and_run([c_file, *linked], bin_c, arch)
+        output_s, code_s = compile_and_run([s_file, *linked], bin_s, arch)
     except subprocess.CalledProcessError as e:
         print(f"{RED}Compilation failed: {e}{RESET}")
         return False
@@ -127,7 +129,7 @@ def compare_c_and_s_outputs(c_file: Path, s_file: Path, arch: str = "arm64") ->
     return False
 
 
-def run_test(c_file: Path, expect_success: bool, arch: str) -> bool:
+def run_test(c_file: Path, expect_success: bool, arch: str, linked: tuple[Path, ...] = ()) -> bool:
     print(f"Testing {c_file}...", end=" ")
 
     start = time.time()
@@ -155,7 +157,7 @@ def run_test(c_file: Path, expect_success: bool, arch: str) -> bool:
     else:
         if expect_success and not COMPILE_DISABLED:
             s_file = c_file.with_suffix(".s")
-            return compare_c_and_s_outputs(c_file, s_file, arch)
+            return compare_c_and_s_outputs(c_file, s_file, arch, linked)
         print(f"{GREEN}PASS{RESET}")
         return True
 
@@ -176,6 +178,17 @@ def main():
             else:
                 failed += 1
 
+        # interop tests: `name.c` is compiled by us, `name_clang.c` by clang, then linked together
+        for f in (examples_dir / "interop").glob("*.c"):
+            if f.stem.endswith("_clang"):
+                continue
+            total += 1
+            helper = f.with_name(f"{f.stem}_clang.c")
+            if run_test(f, expect_success=True, arch=arch, linked=(helper,)):
+                passed += 1
+            else:
+                failed += 1
+
         for stage in STAGES:
             stage_dir = BASE / f"stage_{stage}"
             valid = stage_dir / "valid"
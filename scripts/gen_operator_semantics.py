@@ -0,0 +1,136 @@
+"""Generates examples/operator_semantics.c.
+
+Every binary operator is applied to edge values of each integer type twice: at run
+time, through a function per operator and type, and as a constant expression that the
+compiler folds. The program counts the checks whose results differ.
+
+Only operand pairs with a defined result in C are checked. The quotients and
+remainders that overflow, such as `INT_MIN % -1`, are compiled too, in a function
+that never runs them: the compiler must leave them for run time rather than fold them.
+
+    python3 scripts/gen_operator_semantics.py > examples/operator_semantics.c
+"""
+
+# (type, short name, bits, signed, edge values as C literals)
+TYPES = [
+    ("int", "int", 32, True, ["(-2147483647 - 1)", "-7", "-1", "3", "2147483647"]),
+    ("unsigned int", "uint", 32, False, ["0u", "1u", "7u", "2147483648u", "4294967295u"]),
+    (
+        "long",
+        "long",
+        64,
+        True,
+        ["(-9223372036854775807l - 1)", "-8l", "-1l", "5l", "9223372036854775807l"],
+    ),
+    (
+        "unsigned long",
+        "ulong",
+        64,
+        False,
+        ["0ul", "3ul", "9223372036854775808ul", "18446744073709551615ul"],
+    ),
+]
+
+OPS = [
+    ("add", "+"),
+    ("sub", "-"),
+    ("mul", "*"),
+    ("div", "/"),
+    ("rem", "%"),
+    ("shr", ">>"),
+    ("shl", "<<"),
+    ("and", "&"),
+    ("xor", "^"),
+    ("lt", "<"),
+    ("ge", ">="),
+]
+
+HEADER = """\
+int printf(const char *format, ...);
+
+void show(unsigned long n) {
+    char format[] = {'%', 'l', 'u', '\\n', 0};
+    printf(format, n);
+}
+
+// generated by scripts/gen_operator_semantics.py, which says how to regenerate it
+//
+// every binary operator on edge values of each integer type, computed at run time by
+// the functions below and folded at compile time by the constant expressions in main
+
+int checks;
+int mismatches;
+unsigned long digest;
+
+void check(unsigned long runtime, unsigned long folded) {
+    checks = checks + 1;
+    digest = digest * 31 + runtime;
+    if (runtime != folded) {
+        mismatches = mismatches + 1;
+        show(checks);
+    }
+}
+"""
+
+FOOTER = """\
+    show(checks);
+    show(mismatches);
+    show(digest);
+    return 0;
+}"""
+
+
+def value(literal):
+    """The value of an integer literal of `TYPES`."""
+    return eval(literal.replace("u", "").replace("l", ""))
+
+
+def is_defined(op, a, b, bits, signed):
+    """Whether `a op b` has a defined result in C for operands of the given type."""
+    lo, hi = (-(1 << (bits - 1)), (1 << (bits - 1)) - 1) if signed else (0, (1 << bits) - 1)
+    if op in "/%" and b == 0:
+        return False
+    if op in ("<<", ">>"):
+        return 0 <= b < bits and (op == ">>" or not signed or a >= 0 and a << b <= hi)
+    if signed and op in "+-*/%":
+        # C division truncates toward zero; the quotient also decides whether the
+        # remainder is defined
+        q = abs(a) // abs(b) * (1 if (a < 0) == (b < 0) else -1) if b else 0
+        r = {"+": a + b, "-": a - b, "*": a * b, "/": q, "%": q}[op]
+        return lo <= r <= hi
+    return True
+
+
+def main():
+    functions, checks, overflows = [], [], []
+    for ty, short, bits, signed, values in TYPES:
+        for name, op in OPS:
+            return_type = "int" if op in ("<", ">=") else ty
+            functions.append(
+                f"{return_type} {name}_{short}({ty} a, {ty} b) {{\n    return a {op} b;\n}}\n"
+            )
+            amounts = ["0", "1", "3", str(bits - 1)] if op in ("<<", ">>") else values
+            for a in values:
+                for b in amounts:
+                    call = f"check({name}_{short}({a}, {b}), {a} {op} {b});"
+                    if is_defined(op, value(a), value(b), bits, signed):
+                        checks.append(f"    {call}")
+                    elif op in "/%" and value(b) != 0:
+                        overflows.append(f"        {call}")
+
+    print(HEADER)
+    print("\n".join(functions))
+    print("// the quotient doesn't fit in the type, so neither result is defined")
+    print("void overflows(int never) {")
+    print("    if (never) {")
+    print("\n".join(overflows))
+    print("    }")
+    print("}\n")
+    print("int main(void) {")
+    print("    overflows(0);")
+    print("\n".join(checks))
+    print(FOOTER)
+
+
+if __name__ == "__main__":
+    main()
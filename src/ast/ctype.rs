@@ -0,0 +1,569 @@
+use crate::ast::{
+    AsmOperand, AsmStatement, BinaryOp, Builtin, Constant, Declaration, Expr, Function,
+    FunctionType, MemoryOrder, Qualifiers, StorageClass, Type,
+};
+
+impl Qualifiers {
+    pub fn is_empty(&self) -> bool {
+        !self.is_const && !self.is_volatile && !self.is_atomic
+    }
+
+    /// Union of both sets of qualifiers.
+    pub fn union(self, other: Qualifiers) -> Qualifiers {
+        Qualifiers {
+            is_const: self.is_const || other.is_const,
+            is_volatile: self.is_volatile || other.is_volatile,
+            is_atomic: self.is_atomic || other.is_atomic,
+        }
+    }
+
+    /// `true` if every qualifier of `other` is also in `self`.
+    pub fn contains(&self, other: Qualifiers) -> bool {
+        (self.is_const || !other.is_const)
+            && (self.is_volatile || !other.is_volatile)
+            && (self.is_atomic || !other.is_atomic)
+    }
+}
+
+impl Type {
+    /// Adds `qualifiers` to the type, keeping the `Qualified` wrapper flat.
+    pub fn qualified(self, qualifiers: Qualifiers) -> Type {
+        match self {
+            _ if qualifiers.is_empty() => self,
+            Type::Qualified(q, inner) => Type::Qualified(q.union(qualifiers), inner),
+            // qualifiers of an array type apply to its elements
+            Type::Array(elem, n) => Type::Array(Box::new(elem.qualified(qualifiers)), n),
+            Type::VarArray(elem, len) => Type::VarArray(Box::new(elem.qualified(qualifiers)), len),
+            ty => Type::Qualified(qualifiers, Box::new(ty)),
+        }
+    }
+
+    /// The type with its top-level qualifiers removed.
+    pub fn unqualified(&self) -> &Type {
+        match self {
+            Type::Qualified(_, inner) => inner,
+            ty => ty,
+        }
+    }
+
+    /// Top-level qualifiers of the type.
+    pub fn qualifiers(&self) -> Qualifiers {
+        match self {
+            Type::Qualified(q, _) => *q,
+            Type::Array(elem, _) | Type::VarArray(elem, _) => elem.qualifiers(),
+            _ => Qualifiers::default(),
+        }
+    }
+
+    /// Type of the value of an expression of this type: arrays decay to a pointer
+    /// to their first element, functions to a pointer to the function, qualifiers are dropped.
+    pub fn decay(&self) -> Type {
+        match self.unqualified() {
+            Type::Array(elem, _) | Type::VarArray(elem, _) => Type::Pointer(elem.clone()),
+            ty @ Type::Function(_) => Type::Pointer(Box::new(ty.clone())),
+            ty => ty.clone(),
+        }
+    }
+
+    /// Size in bytes, as laid out in memory on arm64.
+    pub fn size(&self) -> i32 {
+        match self {
+            Type::Void => 0,
+            Type::Char | Type::UChar => 1,
+            Type::Short | Type::UShort => 2,
+            Type::Int | Type::UInt | Type::Float => 4,
+            Type::Long | Type::ULong | Type::Double | Type::Pointer(_) => 8,
+            Type::Array(elem, n) => elem.size() * *n as i32,
+            Type::VarArray(..) => 8, // the address of the elements
+            Type::VaList => 32,
+            Type::Function(_) => 0, // not an object
+            Type::Qualified(_, inner) => inner.size(),
+            Type::Typeof(_) => unreachable!("__typeof__ is expanded before code generation"),
+        }
+    }
+
+    /// Required alignment in bytes (all scalar types are naturally aligned).
+    pub fn alignment(&self) -> i32 {
+        match self {
+            Type::Array(elem, _) => elem.alignment(),
+            Type::VaList => 8,
+            Type::Qualified(_, inner) => inner.alignment(),
+            ty => ty.size().max(1),
+        }
+    }
+
+    pub fn is_integer(&self) -> bool {
+        matches!(
+            self.unqualified(),
+            Type::Char
+                | Type::UChar
+                | Type::Short
+                | Type::UShort
+                | Type::Int
+                | Type::UInt
+                | Type::Long
+                | Type::ULong
+        )
+    }
+
+    pub fn is_signed(&self) -> bool {
+        matches!(
+            self.unqualified(),
+            Type::Char | Type::Short | Type::Int | Type::Long
+        )
+    }
+
+    pub fn is_floating(&self) -> bool {
+        matches!(self.unqualified(), Type::Float | Type::Double)
+    }
+
+    pub fn is_arithmetic(&self) -> bool {
+        self.is_integer() || self.is_floating()
+    }
+
+    pub fn is_pointer(&self) -> bool {
+        matches!(self.unqualified(), Type::Pointer(_))
+    }
+
+    pub fn is_array(&self) -> bool {
+        matches!(self.unqualified(), Type::Array(..))
+    }
+
+    pub fn is_var_array(&self) -> bool {
+        matches!(self.unqualified(), Type::VarArray(..))
+    }
+
+    pub fn is_function(&self) -> bool {
+        matches!(self.unqualified(), Type::Function(_))
+    }
+
+    /// The function type a call through a value of this type uses:
+    /// a function designator or a pointer to a function.
+    pub fn callee(&self) -> Option<&FunctionType> {
+        match self.unqualified() {
+            Type::Function(func) => Some(func),
+            Type::Pointer(inner) => match inner.unqualified() {
+                Type::Function(func) => Some(func),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    /// Arithmetic or pointer type, i.e. anything that can be tested against zero.
+    pub fn is_scalar(&self) -> bool {
+        self.is_arithmetic() || self.is_pointer()
+    }
+
+    /// Integer promotion: everything narrower than `int` becomes `int`.
+    pub fn promoted(&self) -> Type {
+        match self.unqualified() {
+            Type::Char | Type::UChar | Type::Short | Type::UShort => Type::Int,
+            other => other.clone(),
+        }
+    }
+
+    /// Common real type of two arithmetic operands ("usual arithmetic conversions").
+    pub fn common(lhs: &Type, rhs: &Type) -> Type {
+        let (lhs, rhs) = (lhs.unqualified(), rhs.unqualified());
+        if *lhs == Type::Double || *rhs == Type::Double {
+            return Type::Double;
+        }
+        if *lhs == Type::Float || *rhs == Type::Float {
+            return Type::Float;
+        }
+
+        let (lhs, rhs) = (lhs.promoted(), rhs.promoted());
+        if lhs == rhs {
+            return lhs;
+        }
+        if lhs.is_signed() == rhs.is_signed() {
+            return if lhs.size() >= rhs.size() { lhs } else { rhs };
+        }
+
+        let (signed, unsigned) = if lhs.is_signed() {
+            (lhs, rhs)
+        } else {
+            (rhs, lhs)
+        };
+        if unsigned.size() >= signed.size() {
+            unsigned
+        } else {
+            // signed type is wider, so it can represent every value of the unsigned one
+            signed
+        }
+    }
+}
+
+macro_rules! convert_value {
+    ($v:expr, $ty:expr) => {
+        match $ty.unqualified() {
+            Type::Char => Some(Constant::Char($v as i8)),
+            Type::UChar => Some(Constant::UChar($v as u8)),
+            Type::Short => Some(Constant::Short($v as i16)),
+            Type::UShort => Some(Constant::UShort($v as u16)),
+            Type::Int => Some(Constant::Int($v as i32)),
+            Type::UInt => Some(Constant::UInt($v as u32)),
+            Type::Long => Some(Constant::Long($v as i64)),
+            Type::ULong => Some(Constant::ULong($v as u64)),
+            Type::Float => Some(Constant::Float($v as f32)),
+            Type::Double => Some(Constant::Double($v as f64)),
+            Type::Void
+            | Type::Pointer(_)
+            | Type::Array(..)
+            | Type::VarArray(..)
+            | Type::Function(_)
+            | Type::VaList
+            | Type::Qualified(..)
+            | Type::Typeof(_) => None,
+        }
+    };
+}
+
+impl Constant {
+    pub fn ty(&self) -> Type {
+        match self {
+            Constant::Char(_) => Type::Char,
+            Constant::UChar(_) => Type::UChar,
+            Constant::Short(_) => Type::Short,
+            Constant::UShort(_) => Type::UShort,
+            Constant::Int(_) => Type::Int,
+            Constant::UInt(_) => Type::UInt,
+            Constant::Long(_) => Type::Long,
+            Constant::ULong(_) => Type::ULong,
+            Constant::Float(_) => Type::Float,
+            Constant::Double(_) => Type::Double,
+        }
+    }
+
+    /// Integer value widened without loss; floating constants are truncated toward zero.
+    pub fn as_i128(&self) -> i128 {
+        match *self {
+            Constant::Char(v) => v as i128,
+            Constant::UChar(v) => v as i128,
+            Constant::Short(v) => v as i128,
+            Constant::UShort(v) => v as i128,
+            Constant::Int(v) => v as i128,
+            Constant::UInt(v) => v as i128,
+            Constant::Long(v) => v as i128,
+            Constant::ULong(v) => v as i128,
+            Constant::Float(v) => v as i128,
+            Constant::Double(v) => v as i128,
+        }
+    }
+
+    pub fn is_zero(&self) -> bool {
+        match *self {
+            Constant::Float(v) => v == 0.0,
+            Constant::Double(v) => v == 0.0,
+            _ => self.as_i128() == 0,
+        }
+    }
+
+    /// Converts the constant as if by a C cast to `ty`.
+    /// Returns `None` for targets that have no constant representation (`void`, pointers, arrays).
+    pub fn convert(self, ty: &Type) -> Option<Constant> {
+        match self {
+            Constant::Float(v) => convert_value!(v, ty),
+            Constant::Double(v) => convert_value!(v, ty),
+            _ => convert_value!(self.as_i128(), ty),
+        }
+    }
+
+    /// Raw bit pattern as stored in memory, zero-extended to 64 bits.
+    pub fn bits(&self) -> u64 {
+        match *self {
+            Constant::Float(v) => v.to_bits() as u64,
+            Constant::Double(v) => v.to_bits(),
+            _ => {
+                let size = self.ty().size() * 8;
+                (self.as_i128() as u64) & (u64::MAX >> (64 - size))
+            }
+        }
+    }
+}
+
+impl FunctionType {
+    /// Two function types are compatible if their return types match and, when
+    /// both have prototypes, so do their parameter lists. A declaration without
+    /// a prototype is compatible with any parameters.
+    pub fn compatible(&self, other: &FunctionType) -> bool {
+        self.return_type == other.return_type
+            && (!self.prototyped
+                || !other.prototyped
+                || (self.params == other.params && self.variadic == other.variadic))
+    }
+}
+
+impl AsmStatement {
+    /// Outputs, then inputs, in the order `%0`, `%1`, … refers to them.
+    pub fn operands(&self) -> impl Iterator<Item = &AsmOperand> {
+        self.outputs.iter().chain(&self.inputs)
+    }
+}
+
+impl Builtin {
+    /// The builtin called `name`, if there is one.
+    pub fn from_name(name: &str) -> Option<Builtin> {
+        Some(match name {
+            "__builtin_clz" => Builtin::Clz,
+            "__builtin_clzl" | "__builtin_clzll" => Builtin::ClzLong,
+            "__builtin_ctz" => Builtin::Ctz,
+            "__builtin_ctzl" | "__builtin_ctzll" => Builtin::CtzLong,
+            "__builtin_popcount" => Builtin::Popcount,
+            "__builtin_popcountl" | "__builtin_popcountll" => Builtin::PopcountLong,
+            "__builtin_bswap32" => Builtin::Bswap32,
+            "__builtin_bswap64" => Builtin::Bswap64,
+            "__builtin_expect" => Builtin::Expect,
+            "__builtin_unreachable" => Builtin::Unreachable,
+            "__builtin_trap" => Builtin::Trap,
+            "__builtin_add_overflow" => Builtin::AddOverflow,
+            "__builtin_sub_overflow" => Builtin::SubOverflow,
+            "__builtin_mul_overflow" => Builtin::MulOverflow,
+            "__builtin_add_overflow_p" => Builtin::AddOverflowP,
+            "__builtin_sub_overflow_p" => Builtin::SubOverflowP,
+            "__builtin_mul_overflow_p" => Builtin::MulOverflowP,
+            "__atomic_load_n" => Builtin::AtomicLoad,
+            "__atomic_store_n" => Builtin::AtomicStore,
+            "__atomic_exchange_n" => Builtin::AtomicExchange,
+            "__atomic_compare_exchange_n" => Builtin::AtomicCompareExchange,
+            "__atomic_fetch_add" => Builtin::AtomicFetchAdd,
+            "__atomic_fetch_sub" => Builtin::AtomicFetchSub,
+            "__atomic_fetch_and" => Builtin::AtomicFetchAnd,
+            "__atomic_fetch_or" => Builtin::AtomicFetchOr,
+            "__atomic_fetch_xor" => Builtin::AtomicFetchXor,
+            _ => return None,
+        })
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Builtin::Clz => "__builtin_clz",
+            Builtin::ClzLong => "__builtin_clzl",
+            Builtin::Ctz => "__builtin_ctz",
+            Builtin::CtzLong => "__builtin_ctzl",
+            Builtin::Popcount => "__builtin_popcount",
+            Builtin::PopcountLong => "__builtin_popcountl",
+            Builtin::Bswap32 => "__builtin_bswap32",
+            Builtin::Bswap64 => "__builtin_bswap64",
+            Builtin::Expect => "__builtin_expect",
+            Builtin::Unreachable => "__builtin_unreachable",
+            Builtin::Trap => "__builtin_trap",
+            Builtin::AddOverflow => "__builtin_add_overflow",
+            Builtin::SubOverflow => "__builtin_sub_overflow",
+            Builtin::MulOverflow => "__builtin_mul_overflow",
+            Builtin::AddOverflowP => "__builtin_add_overflow_p",
+            Builtin::SubOverflowP => "__builtin_sub_overflow_p",
+            Builtin::MulOverflowP => "__builtin_mul_overflow_p",
+            Builtin::AtomicLoad => "__atomic_load_n",
+            Builtin::AtomicStore => "__atomic_store_n",
+            Builtin::AtomicExchange => "__atomic_exchange_n",
+            Builtin::AtomicCompareExchange => "__atomic_compare_exchange_n",
+            Builtin::AtomicFetchAdd => "__atomic_fetch_add",
+            Builtin::AtomicFetchSub => "__atomic_fetch_sub",
+            Builtin::AtomicFetchAnd => "__atomic_fetch_and",
+            Builtin::AtomicFetchOr => "__atomic_fetch_or",
+            Builtin::AtomicFetchXor => "__atomic_fetch_xor",
+        }
+    }
+
+    /// `true` for the `__atomic` builtins, whose first operand points to the object.
+    pub fn is_atomic(&self) -> bool {
+        matches!(
+            self,
+            Builtin::AtomicLoad
+                | Builtin::AtomicStore
+                | Builtin::AtomicExchange
+                | Builtin::AtomicCompareExchange
+                | Builtin::AtomicFetchAdd
+                | Builtin::AtomicFetchSub
+                | Builtin::AtomicFetchAnd
+                | Builtin::AtomicFetchOr
+                | Builtin::AtomicFetchXor
+        )
+    }
+
+    /// The operation of an `__atomic_fetch_*` builtin.
+    pub fn fetch_op(&self) -> Option<BinaryOp> {
+        match self {
+            Builtin::AtomicFetchAdd => Some(BinaryOp::Add),
+            Builtin::AtomicFetchSub => Some(BinaryOp::Sub),
+            Builtin::AtomicFetchAnd => Some(BinaryOp::And),
+            Builtin::AtomicFetchOr => Some(BinaryOp::Or),
+            Builtin::AtomicFetchXor => Some(BinaryOp::Xor),
+            _ => None,
+        }
+    }
+
+    /// The operation of an overflow-checking builtin.
+    pub fn overflow_op(&self) -> Option<BinaryOp> {
+        match self {
+            Builtin::AddOverflow | Builtin::AddOverflowP => Some(BinaryOp::Add),
+            Builtin::SubOverflow | Builtin::SubOverflowP => Some(BinaryOp::Sub),
+            Builtin::MulOverflow | Builtin::MulOverflowP => Some(BinaryOp::Multiply),
+            _ => None,
+        }
+    }
+
+    /// `true` for the overflow checks that store their result through the third operand.
+    pub fn stores_result(&self) -> bool {
+        matches!(
+            self,
+            Builtin::AddOverflow | Builtin::SubOverflow | Builtin::MulOverflow
+        )
+    }
+
+    pub fn arity(&self) -> usize {
+        match self {
+            Builtin::AtomicLoad => 2,
+            Builtin::AtomicCompareExchange => 6,
+            _ => match self.function_type() {
+                Some(ty) => ty.params.len(),
+                None => 3,
+            },
+        }
+    }
+
+    /// Prototype of the builtin; arguments are converted to its parameter types.
+    /// `None` for the overflow checks and the atomics, whose operands keep their own types.
+    pub fn function_type(&self) -> Option<FunctionType> {
+        let (return_type, params) = match self {
+            Builtin::Clz | Builtin::Ctz | Builtin::Popcount => (Type::Int, vec![Type::UInt]),
+            Builtin::ClzLong | Builtin::CtzLong | Builtin::PopcountLong => {
+                (Type::Int, vec![Type::ULong])
+            }
+            Builtin::Bswap32 => (Type::UInt, vec![Type::UInt]),
+            Builtin::Bswap64 => (Type::ULong, vec![Type::ULong]),
+            Builtin::Expect => (Type::Long, vec![Type::Long, Type::Long]),
+            Builtin::Unreachable | Builtin::Trap => (Type::Void, vec![]),
+            _ => return None,
+        };
+        Some(FunctionType {
+            return_type,
+            params,
+            variadic: false,
+            prototyped: true,
+        })
+    }
+}
+
+impl Expr {
+    /// The direct operands of the expression, in the order they are written.
+    /// The statements of a statement expression are not operands.
+    pub fn operands(&self) -> Vec<&Expr> {
+        match self {
+            Expr::Const(_) | Expr::Var(_) | Expr::Block(_) => vec![],
+            Expr::UnOp(_, inner)
+            | Expr::Cast(_, inner)
+            | Expr::Deref(inner)
+            | Expr::AddrOf(inner)
+            | Expr::VaStart(inner)
+            | Expr::VaArg(inner, _)
+            | Expr::VaEnd(inner)
+            | Expr::Alloca(inner) => vec![inner],
+            Expr::BinOp(_, lhs, rhs) | Expr::Assign(lhs, rhs) | Expr::VaCopy(lhs, rhs) => {
+                vec![lhs, rhs]
+            }
+            Expr::Conditional { cond, then, els } => {
+                let mut operands = vec![cond.as_ref()];
+                operands.extend(then.as_deref());
+                operands.push(els);
+                operands
+            }
+            Expr::FunCall { parameters, .. } | Expr::Builtin(_, parameters) => {
+                parameters.iter().collect()
+            }
+            Expr::IndirectCall { callee, parameters } => {
+                let mut operands = vec![callee.as_ref()];
+                operands.extend(parameters);
+                operands
+            }
+        }
+    }
+
+    /// Like [`Expr::operands`], for changing them.
+    pub fn operands_mut(&mut self) -> Vec<&mut Expr> {
+        match self {
+            Expr::Const(_) | Expr::Var(_) | Expr::Block(_) => vec![],
+            Expr::UnOp(_, inner)
+            | Expr::Cast(_, inner)
+            | Expr::Deref(inner)
+            | Expr::AddrOf(inner)
+            | Expr::VaStart(inner)
+            | Expr::VaArg(inner, _)
+            | Expr::VaEnd(inner)
+            | Expr::Alloca(inner) => vec![inner],
+            Expr::BinOp(_, lhs, rhs) | Expr::Assign(lhs, rhs) | Expr::VaCopy(lhs, rhs) => {
+                vec![lhs, rhs]
+            }
+            Expr::Conditional { cond, then, els } => {
+                let mut operands = vec![cond.as_mut()];
+                operands.extend(then.as_deref_mut());
+                operands.push(els);
+                operands
+            }
+            Expr::FunCall { parameters, .. } | Expr::Builtin(_, parameters) => {
+                parameters.iter_mut().collect()
+            }
+            Expr::IndirectCall { callee, parameters } => {
+                let mut operands = vec![callee.as_mut()];
+                operands.extend(parameters);
+                operands
+            }
+        }
+    }
+}
+
+impl MemoryOrder {
+    /// The order numbered `value`, as in `__ATOMIC_RELAXED` (0) to `__ATOMIC_SEQ_CST` (5).
+    pub fn from_value(value: i128) -> Option<MemoryOrder> {
+        Some(match value {
+            0 => MemoryOrder::Relaxed,
+            1 => MemoryOrder::Consume,
+            2 => MemoryOrder::Acquire,
+            3 => MemoryOrder::Release,
+            4 => MemoryOrder::AcqRel,
+            5 => MemoryOrder::SeqCst,
+            _ => return None,
+        })
+    }
+
+    /// `true` if later accesses may not be performed before the operation's read.
+    /// Consume is treated as acquire.
+    pub fn acquires(&self) -> bool {
+        !matches!(self, MemoryOrder::Relaxed | MemoryOrder::Release)
+    }
+
+    /// `true` if earlier accesses must be performed before the operation's write.
+    pub fn releases(&self) -> bool {
+        matches!(
+            self,
+            MemoryOrder::Release | MemoryOrder::AcqRel | MemoryOrder::SeqCst
+        )
+    }
+}
+
+impl Function {
+    /// Type of the function, as designated by its name.
+    pub fn function_type(&self) -> FunctionType {
+        FunctionType {
+            return_type: self.return_type.clone(),
+            params: self
+                .params
+                .iter()
+                .map(|p| p.ty.unqualified().clone())
+                .collect(),
+            variadic: self.variadic,
+            prototyped: self.prototyped,
+        }
+    }
+}
+
+impl Declaration {
+    /// At block scope: the declaration refers to a function or an `extern`
+    /// object with linkage, instead of creating a new local object.
+    pub fn has_linkage(&self) -> bool {
+        let Declaration::Declare { ty, storage, .. } = self;
+        ty.is_function() || *storage == Some(StorageClass::Extern)
+    }
+}
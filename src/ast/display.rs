@@ -1,23 +1,162 @@
 use crate::ast::Declaration::Declare;
-use crate::ast::{BinaryOp, BlockItem, Declaration, Expr, Function, Program, Statement, UnaryOp};
+use crate::ast::{
+    AsmOperand, AsmStatement, BinaryOp, BlockItem, Constant, Declaration, Expr, Function,
+    FunctionType, Initializer, LoopLabel, MemoryOrder, Program, Qualifiers, Statement,
+    StorageClass, Type, UnaryOp,
+};
 use std::fmt;
 
+impl fmt::Display for Type {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Type::Void => write!(f, "void"),
+            Type::Char => write!(f, "char"),
+            Type::UChar => write!(f, "unsigned char"),
+            Type::Short => write!(f, "short"),
+            Type::UShort => write!(f, "unsigned short"),
+            Type::Int => write!(f, "int"),
+            Type::UInt => write!(f, "unsigned int"),
+            Type::Long => write!(f, "long"),
+            Type::ULong => write!(f, "unsigned long"),
+            Type::Float => write!(f, "float"),
+            Type::Double => write!(f, "double"),
+            Type::Pointer(inner) => match inner.unqualified() {
+                Type::Function(func) => {
+                    write!(f, "{} (*)({})", func.return_type, func.param_list())
+                }
+                _ => write!(f, "{} *", inner),
+            },
+            Type::Array(elem, n) => write!(f, "{}[{}]", elem, n),
+            Type::VarArray(elem, _) => write!(f, "{}[*]", elem),
+            Type::Function(func) => write!(f, "{func}"),
+            Type::VaList => write!(f, "va_list"),
+            Type::Qualified(q, inner) if inner.is_pointer() => write!(f, "{} {}", inner, q),
+            Type::Qualified(q, inner) => write!(f, "{} {}", q, inner),
+            Type::Typeof(expr) => write!(f, "__typeof__({expr})"),
+        }
+    }
+}
+
+impl FunctionType {
+    fn param_list(&self) -> String {
+        let mut params: Vec<String> = self.params.iter().map(ToString::to_string).collect();
+        if self.variadic {
+            params.push("...".into());
+        } else if self.prototyped && params.is_empty() {
+            params.push("void".into());
+        }
+        params.join(", ")
+    }
+}
+
+impl fmt::Display for FunctionType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} ({})", self.return_type, self.param_list())
+    }
+}
+
+impl fmt::Display for Qualifiers {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let names = [
+            (self.is_const, "const"),
+            (self.is_volatile, "volatile"),
+            (self.is_atomic, "_Atomic"),
+        ];
+        let present: Vec<&str> = names
+            .iter()
+            .filter(|(set, _)| *set)
+            .map(|(_, name)| *name)
+            .collect();
+        write!(f, "{}", present.join(" "))
+    }
+}
+
+impl fmt::Display for MemoryOrder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            MemoryOrder::Relaxed => "__ATOMIC_RELAXED",
+            MemoryOrder::Consume => "__ATOMIC_CONSUME",
+            MemoryOrder::Acquire => "__ATOMIC_ACQUIRE",
+            MemoryOrder::Release => "__ATOMIC_RELEASE",
+            MemoryOrder::AcqRel => "__ATOMIC_ACQ_REL",
+            MemoryOrder::SeqCst => "__ATOMIC_SEQ_CST",
+        };
+        write!(f, "{name}")
+    }
+}
+
+impl fmt::Display for StorageClass {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StorageClass::Static => write!(f, "static"),
+            StorageClass::Extern => write!(f, "extern"),
+        }
+    }
+}
+
+impl fmt::Display for Constant {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Constant::Char(n) => write!(f, "Char<{}>", n),
+            Constant::UChar(n) => write!(f, "UChar<{}>", n),
+            Constant::Short(n) => write!(f, "Short<{}>", n),
+            Constant::UShort(n) => write!(f, "UShort<{}>", n),
+            Constant::Int(n) => write!(f, "Int<{}>", n),
+            Constant::UInt(n) => write!(f, "UInt<{}>", n),
+            Constant::Long(n) => write!(f, "Long<{}>", n),
+            Constant::ULong(n) => write!(f, "ULong<{}>", n),
+            Constant::Float(n) => write!(f, "Float<{}>", n),
+            Constant::Double(n) => write!(f, "Double<{}>", n),
+        }
+    }
+}
+
 impl fmt::Display for Expr {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            Expr::Const(n) => write!(f, "Int<{}>", n),
+            Expr::Const(c) => write!(f, "{}", c),
             Expr::UnOp(op, expr) => write!(f, "{}{}", op, expr),
             Expr::BinOp(op, lhs, rhs) => write!(f, "({} {} {})", lhs, op, rhs),
             Expr::Var(name) => write!(f, "(var {})", name),
-            Expr::Assign(name, exp) => write!(f, "{} = {}", name, exp),
-            Expr::Conditional { cond, then, els } => {
-                write!(f, "({} ? {} : {})", cond, then, els)
-            }
+            Expr::Assign(lhs, exp) => write!(f, "{} = {}", lhs, exp),
+            Expr::Conditional {
+                cond,
+                then: Some(then),
+                els,
+            } => write!(f, "({} ? {} : {})", cond, then, els),
+            Expr::Conditional {
+                cond,
+                then: None,
+                els,
+            } => write!(f, "({} ?: {})", cond, els),
 
             Expr::FunCall { name, parameters } => {
                 let param_strs: Vec<String> = parameters.iter().map(|p| format!("{}", p)).collect();
                 write!(f, "{}({})", name, param_strs.join(", "))
             }
+            Expr::IndirectCall { callee, parameters } => {
+                let param_strs: Vec<String> = parameters.iter().map(|p| format!("{}", p)).collect();
+                write!(f, "({})({})", callee, param_strs.join(", "))
+            }
+            Expr::Cast(ty, expr) => write!(f, "(({}) {})", ty, expr),
+            Expr::Deref(expr) => write!(f, "*{}", expr),
+            Expr::AddrOf(expr) => write!(f, "&{}", expr),
+            Expr::VaStart(ap) => write!(f, "va_start({})", ap),
+            Expr::VaArg(ap, ty) => write!(f, "va_arg({}, {})", ap, ty),
+            Expr::VaEnd(ap) => write!(f, "va_end({})", ap),
+            Expr::VaCopy(dest, src) => write!(f, "va_copy({}, {})", dest, src),
+            Expr::Alloca(size) => write!(f, "__builtin_alloca({})", size),
+            Expr::Builtin(builtin, args) => {
+                let arg_strs: Vec<String> = args.iter().map(|a| format!("{}", a)).collect();
+                write!(f, "{}({})", builtin.name(), arg_strs.join(", "))
+            }
+            Expr::Block(items) => {
+                write!(f, "({{ ")?;
+                for item in items {
+                    write!(f, "{} ", item.to_string().trim_end())?;
+                }
+                write!(f, "}})")
+            }
         }
     }
 }
@@ -65,10 +204,54 @@ impl fmt::Display for BinaryOp {
     }
 }
 
+impl fmt::Display for AsmStatement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let volatile = if self.volatile { " volatile" } else { "" };
+        write!(f, "asm{volatile} ({:?}", self.template)?;
+        if !self.basic {
+            let list = |operands: &[AsmOperand]| {
+                let strs: Vec<String> = operands.iter().map(|o| format!("{o}")).collect();
+                strs.join(", ")
+            };
+            let clobbers: Vec<String> = self.clobbers.iter().map(|c| format!("{c:?}")).collect();
+            write!(
+                f,
+                " : {} : {} : {}",
+                list(&self.outputs),
+                list(&self.inputs),
+                clobbers.join(", ")
+            )?;
+        }
+        write!(f, ")")
+    }
+}
+
+impl fmt::Display for AsmOperand {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(name) = &self.name {
+            write!(f, "[{name}] ")?;
+        }
+        write!(f, "{:?}({})", self.constraint, self.expr)
+    }
+}
+
+/// ` // loop N` after a labeled loop or `switch`, `break`, `continue`, `case` or `default`
+struct LoopComment<'a>(&'a LoopLabel);
+
+impl fmt::Display for LoopComment<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.0 {
+            Some(n) => write!(f, " // loop {n}"),
+            None => Ok(()),
+        }
+    }
+}
+
 impl fmt::Display for Statement {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            Statement::Return(expr) => writeln!(f, "return {}", expr),
+            Statement::Return(Some(expr)) => writeln!(f, "return {}", expr),
+            Statement::Return(None) => writeln!(f, "return"),
             Statement::Expr(expr) => {
                 if let Some(expr) = expr {
                     writeln!(f, "{}", expr)
@@ -97,13 +280,15 @@ impl fmt::Display for Statement {
                 cond,
                 post,
                 body,
+                label,
             } => {
                 writeln!(
                     f,
-                    "for ({}; {}; {}) {{",
+                    "for ({}; {}; {}) {{{}",
                     init.as_ref().map_or(String::new(), |e| format!("{}", e)),
                     cond,
                     post.as_ref().map_or(String::new(), |e| format!("{}", e)),
+                    LoopComment(label),
                 )?;
                 writeln!(f, "\t{body}")?;
                 writeln!(f, "}}")
@@ -114,32 +299,61 @@ impl fmt::Display for Statement {
                 cond,
                 post,
                 body,
+                label,
             } => {
                 writeln!(
                     f,
-                    "for ({}; {}; {}) {{",
+                    "for ({}; {}; {}) {{{}",
                     decl,
                     cond,
                     post.as_ref().map_or(String::new(), |e| format!("{}", e)),
+                    LoopComment(label),
                 )?;
                 writeln!(f, "\t{body}")?;
                 writeln!(f, "}}")
             }
 
-            Statement::While { cond, body } => {
-                writeln!(f, "while ({cond}) {{")?;
+            Statement::While { cond, body, label } => {
+                writeln!(f, "while ({cond}) {{{}", LoopComment(label))?;
                 writeln!(f, "\t{body}")?;
                 writeln!(f, "}}")
             }
 
-            Statement::Do { body, cond } => {
-                writeln!(f, "do {{")?;
+            Statement::Do { body, cond, label } => {
+                writeln!(f, "do {{{}", LoopComment(label))?;
                 writeln!(f, "\t{body}")?;
                 writeln!(f, "}} while ({cond})")
             }
 
-            Statement::Break => writeln!(f, "break"),
-            Statement::Continue => writeln!(f, "continue"),
+            Statement::Switch { cond, body, label } => {
+                writeln!(f, "switch ({cond}) {{{}", LoopComment(label))?;
+                writeln!(f, "\t{body}")?;
+                writeln!(f, "}}")
+            }
+
+            Statement::Case {
+                value,
+                last,
+                body,
+                label,
+                ..
+            } => {
+                match last {
+                    Some(last) => write!(f, "case {value} ... {last}:")?,
+                    None => write!(f, "case {value}:")?,
+                }
+                writeln!(f, "{}", LoopComment(label))?;
+                writeln!(f, "\t{body}")
+            }
+
+            Statement::Default { body, label, .. } => {
+                writeln!(f, "default:{}", LoopComment(label))?;
+                writeln!(f, "\t{body}")
+            }
+
+            Statement::Break(label) => writeln!(f, "break{}", LoopComment(label)),
+            Statement::Continue(label) => writeln!(f, "continue{}", LoopComment(label)),
+            Statement::Asm(asm) => writeln!(f, "{asm}"),
         }
     }
 }
@@ -147,20 +361,46 @@ impl fmt::Display for Statement {
 impl fmt::Display for BlockItem {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            BlockItem::Stmt(stmt) => writeln!(f, "stmt {}", stmt),
+            BlockItem::Stmt(stmt, _) => writeln!(f, "stmt {}", stmt),
             BlockItem::Decl(decl) => writeln!(f, "decl {decl}"),
         }
     }
 }
 
+impl fmt::Display for Initializer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Initializer::Single(expr) => write!(f, "{}", expr),
+            Initializer::List(items) => {
+                let items: Vec<String> = items.iter().map(|i| format!("{}", i)).collect();
+                write!(f, "{{{}}}", items.join(", "))
+            }
+        }
+    }
+}
+
 impl fmt::Display for Declaration {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            Declare(name, expr) => {
-                if let Some(expr) = expr {
-                    writeln!(f, "declare {} = {}", name, expr)
+            Declare {
+                name,
+                ty,
+                init,
+                storage,
+                thread_local,
+                ..
+            } => {
+                write!(f, "declare ")?;
+                if let Some(storage) = storage {
+                    write!(f, "{storage} ")?;
+                }
+                if *thread_local {
+                    write!(f, "_Thread_local ")?;
+                }
+                if let Some(expr) = init {
+                    writeln!(f, "{} {} = {}", ty, name, expr)
                 } else {
-                    writeln!(f, "declare {}", name)
+                    writeln!(f, "{} {}", ty, name)
                 }
             }
         }
@@ -169,8 +409,22 @@ impl fmt::Display for Declaration {
 
 impl fmt::Display for Function {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        writeln!(f, "function int {}:", self.name)?;
-        writeln!(f, "    params: ()")?;
+        let params: Vec<String> = self
+            .params
+            .iter()
+            .map(|p| format!("{} {}", p.ty, p.name))
+            .collect();
+        write!(f, "function ")?;
+        if let Some(storage) = self.storage {
+            write!(f, "{storage} ")?;
+        }
+        writeln!(f, "{} {}:", self.return_type, self.name)?;
+        let ellipsis = if self.variadic { ", ..." } else { "" };
+        if self.prototyped {
+            writeln!(f, "    params: ({}{ellipsis})", params.join(", "))?;
+        } else {
+            writeln!(f, "    params: unspecified")?;
+        }
         writeln!(f, "    body:\n        {:?}", self.block_items)
     }
 }
@@ -0,0 +1,103 @@
+use crate::ast::{Expr, Initializer, Type};
+use std::iter::Peekable;
+use std::slice::Iter;
+
+/// One scalar part of an initialized object.
+#[derive(Debug)]
+pub struct InitSlot<'a> {
+    /// Byte offset from the start of the object
+    pub offset: i32,
+    pub ty: Type,
+    /// Initial value, `None` for parts that are implicitly zero-initialized
+    pub value: Option<&'a Expr>,
+}
+
+type Items<'a> = Peekable<Iter<'a, Initializer>>;
+
+/// Splits an object of type `ty` into its scalar parts and pairs each of them
+/// with its initial value, following C's rules for nested braces and brace elision.
+pub fn flatten_initializer<'a>(
+    ty: &Type,
+    init: Option<&'a Initializer>,
+) -> Result<Vec<InitSlot<'a>>, String> {
+    let mut slots = Vec::new();
+    flatten_into(ty, init, 0, &mut slots)?;
+    Ok(slots)
+}
+
+/// Number of elements of type `elem` the initializer list provides,
+/// used to complete array declarations such as `int a[] = {1, 2, 3};`.
+pub fn array_length(elem: &Type, items: &[Initializer]) -> Result<usize, String> {
+    let mut items = items.iter().peekable();
+    let mut scratch = Vec::new();
+    let mut len = 0;
+    while items.peek().is_some() {
+        fill_from(elem, &mut items, 0, &mut scratch)?;
+        len += 1;
+    }
+    Ok(len)
+}
+
+fn flatten_into<'a>(
+    ty: &Type,
+    init: Option<&'a Initializer>,
+    offset: i32,
+    out: &mut Vec<InitSlot<'a>>,
+) -> Result<(), String> {
+    match (ty.unqualified(), init) {
+        (Type::Array(elem, n), Some(Initializer::List(items))) => {
+            let mut items = items.iter().peekable();
+            for i in 0..*n {
+                fill_from(elem, &mut items, offset + i as i32 * elem.size(), out)?;
+            }
+            if items.peek().is_some() {
+                return Err("excess elements in array initializer".into());
+            }
+        }
+        (Type::Array(..), Some(Initializer::Single(_))) => {
+            return Err(format!(
+                "array of type {ty} must be initialized with a braced list"
+            ));
+        }
+        (Type::Array(elem, n), None) => {
+            for i in 0..*n {
+                flatten_into(elem, None, offset + i as i32 * elem.size(), out)?;
+            }
+        }
+        (_, Some(Initializer::List(items))) => match items.as_slice() {
+            [] => flatten_into(ty, None, offset, out)?,
+            [only] => flatten_into(ty, Some(only), offset, out)?,
+            _ => return Err("excess elements in scalar initializer".into()),
+        },
+        (_, Some(Initializer::Single(expr))) => out.push(InitSlot {
+            offset,
+            ty: ty.clone(),
+            value: Some(expr),
+        }),
+        (_, None) => out.push(InitSlot {
+            offset,
+            ty: ty.clone(),
+            value: None,
+        }),
+    }
+    Ok(())
+}
+
+/// Initializes one object of type `ty` from the front of an enclosing list.
+/// A sub-array without its own braces takes as many items as it needs (brace elision).
+fn fill_from<'a>(
+    ty: &Type,
+    items: &mut Items<'a>,
+    offset: i32,
+    out: &mut Vec<InitSlot<'a>>,
+) -> Result<(), String> {
+    match (ty.unqualified(), items.peek()) {
+        (Type::Array(elem, n), Some(Initializer::Single(_))) => {
+            for i in 0..*n {
+                fill_from(elem, items, offset + i as i32 * elem.size(), out)?;
+            }
+            Ok(())
+        }
+        _ => flatten_into(ty, items.next(), offset, out),
+    }
+}
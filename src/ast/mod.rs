@@ -1,4 +1,6 @@
+pub mod ctype;
 pub mod display;
+pub mod initializer;
 pub mod types;
 
 pub use types::*;
@@ -1,3 +1,5 @@
+pub use crate::lexer::Location;
+
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum UnaryOp {
     /// Unary negation operation "-"
@@ -53,32 +55,213 @@ pub enum BinaryOp {
     Modulo,
 }
 
+/// C object type of a declaration, a cast or an expression.
+///
+/// Plain `char` is treated as signed, like on Apple arm64.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Type {
+    /// `void`, only valid as a function return type, a cast target or a pointee
+    Void,
+    /// `char` / `signed char`
+    Char,
+    /// `unsigned char`
+    UChar,
+    /// `short`
+    Short,
+    /// `unsigned short`
+    UShort,
+    /// `int`
+    Int,
+    /// `unsigned int`
+    UInt,
+    /// `long` / `long long`
+    Long,
+    /// `unsigned long` / `unsigned long long`
+    ULong,
+    /// `float`
+    Float,
+    /// `double`
+    Double,
+    /// Pointer to the inner type
+    Pointer(Box<Type>),
+    /// Array of a fixed number of elements
+    Array(Box<Type>, usize),
+    /// Variable-length array: the length expression is evaluated when the declaration
+    /// is reached. The object itself only holds the address of the elements.
+    VarArray(Box<Type>, Box<Expr>),
+    /// Function type; only designators have it, objects are pointers to functions.
+    Function(Box<FunctionType>),
+    /// `va_list`, the state of a traversal of variadic arguments.
+    /// On Linux it is the AAPCS64 structure, on macOS only its first pointer is used.
+    VaList,
+    /// The inner type with `const`, `volatile` and/or `_Atomic` added.
+    /// Never nested and never wraps an empty set; see [`Type::qualified`].
+    Qualified(Qualifiers, Box<Type>),
+    /// `__typeof__(expr)`: the type of the expression, which isn't evaluated.
+    /// Replaced by that type in [`crate::semantic::analyze`].
+    Typeof(Box<Expr>),
+}
+
+/// Type qualifiers of an object or a pointee.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Qualifiers {
+    /// `const`: the object may not be modified after initialization
+    pub is_const: bool,
+    /// `volatile`: every access must be performed, in order, on memory
+    pub is_volatile: bool,
+    /// `_Atomic`: every access is an atomic operation, sequentially consistent
+    pub is_atomic: bool,
+}
+
+/// Typed compile-time constant
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Constant {
+    Char(i8),
+    UChar(u8),
+    Short(i16),
+    UShort(u16),
+    Int(i32),
+    UInt(u32),
+    Long(i64),
+    ULong(u64),
+    Float(f32),
+    Double(f64),
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum Expr {
-    /// `int` literal expression
-    Const(i32),
+    /// Literal or folded constant expression
+    Const(Constant),
     /// Unary operation expression
     UnOp(UnaryOp, Box<Expr>),
     /// Binary operation expression
     BinOp(BinaryOp, Box<Expr>, Box<Expr>),
     /// Variable expression
     Var(String),
-    /// Variable assignment expression
-    Assign(String, Box<Expr>),
-    /// Ternary expression (cond ? then : else)
+    /// Assignment to an lvalue ([`Expr::Var`] or [`Expr::Deref`])
+    Assign(Box<Expr>, Box<Expr>),
+    /// Ternary expression (cond ? then : else).
+    /// Without `then`, GNU `cond ?: else`: the value of `cond`, evaluated once, if it is non-zero.
     Conditional {
         cond: Box<Expr>,
-        then: Box<Expr>,
+        then: Option<Box<Expr>>,
         els: Box<Expr>,
     },
     /// Function call
     FunCall { name: String, parameters: Vec<Expr> },
+    /// Call through a function pointer produced by an expression, such as `(*fp)(x)` or `table[i](x)`
+    IndirectCall {
+        callee: Box<Expr>,
+        parameters: Vec<Expr>,
+    },
+    /// Explicit conversion `(type) expr`
+    Cast(Type, Box<Expr>),
+    /// Indirection `*expr`; `a[i]` is parsed as `*(a + i)`
+    Deref(Box<Expr>),
+    /// Address-of `&expr`
+    AddrOf(Box<Expr>),
+    /// `va_start(ap, last)`; the name of the last parameter is not kept
+    VaStart(Box<Expr>),
+    /// `va_arg(ap, type)`
+    VaArg(Box<Expr>, Type),
+    /// `va_end(ap)`
+    VaEnd(Box<Expr>),
+    /// `va_copy(dest, src)`
+    VaCopy(Box<Expr>, Box<Expr>),
+    /// `__builtin_alloca(size)`: `size` bytes of stack, released when the function returns
+    Alloca(Box<Expr>),
+    /// Call of a builtin with a fixed prototype, see [`Builtin::function_type`]
+    Builtin(Builtin, Vec<Expr>),
+    /// GNU statement expression `({ items })`, a block whose value is that of the
+    /// expression statement it ends with, or `void`
+    Block(Vec<BlockItem>),
+}
+
+/// Builtin functions that lower to a few instructions instead of a call.
+/// The `l` and `ll` variants of a builtin are the same, `long` is 64 bits.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Builtin {
+    /// `__builtin_clz`: number of leading zero bits of an `unsigned int`
+    Clz,
+    /// `__builtin_clzl` / `__builtin_clzll`
+    ClzLong,
+    /// `__builtin_ctz`: number of trailing zero bits of an `unsigned int`
+    Ctz,
+    /// `__builtin_ctzl` / `__builtin_ctzll`
+    CtzLong,
+    /// `__builtin_popcount`: number of one bits of an `unsigned int`
+    Popcount,
+    /// `__builtin_popcountl` / `__builtin_popcountll`
+    PopcountLong,
+    /// `__builtin_bswap32`: reverses the bytes of a 32-bit value
+    Bswap32,
+    /// `__builtin_bswap64`: reverses the bytes of a 64-bit value
+    Bswap64,
+    /// `__builtin_expect(value, expected)`: `value`, the hint is ignored
+    Expect,
+    /// `__builtin_unreachable()`: control never gets here
+    Unreachable,
+    /// `__builtin_trap()`: stops the program
+    Trap,
+    /// `__builtin_add_overflow(a, b, &result)`: stores `a + b` converted to the type
+    /// of `result`, and returns whether that conversion lost the exact value
+    AddOverflow,
+    /// `__builtin_sub_overflow(a, b, &result)`
+    SubOverflow,
+    /// `__builtin_mul_overflow(a, b, &result)`
+    MulOverflow,
+    /// `__builtin_add_overflow_p(a, b, (type)0)`: only checks, the third operand gives the type
+    AddOverflowP,
+    /// `__builtin_sub_overflow_p(a, b, (type)0)`
+    SubOverflowP,
+    /// `__builtin_mul_overflow_p(a, b, (type)0)`
+    MulOverflowP,
+    /// `__atomic_load_n(ptr, order)`: the value of `*ptr`, read atomically
+    AtomicLoad,
+    /// `__atomic_store_n(ptr, value, order)`
+    AtomicStore,
+    /// `__atomic_exchange_n(ptr, value, order)`: stores `value`, returns the previous value
+    AtomicExchange,
+    /// `__atomic_compare_exchange_n(ptr, &expected, desired, weak, success, failure)`:
+    /// stores `desired` if `*ptr` equals `expected`, otherwise copies `*ptr` to `expected`;
+    /// returns whether the store happened
+    AtomicCompareExchange,
+    /// `__atomic_fetch_add(ptr, value, order)`: adds `value`, returns the previous value
+    AtomicFetchAdd,
+    /// `__atomic_fetch_sub(ptr, value, order)`
+    AtomicFetchSub,
+    /// `__atomic_fetch_and(ptr, value, order)`
+    AtomicFetchAnd,
+    /// `__atomic_fetch_or(ptr, value, order)`
+    AtomicFetchOr,
+    /// `__atomic_fetch_xor(ptr, value, order)`
+    AtomicFetchXor,
+}
+
+/// Memory order of an atomic operation, numbered like the `__ATOMIC_*` constants.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum MemoryOrder {
+    Relaxed,
+    Consume,
+    Acquire,
+    Release,
+    AcqRel,
+    SeqCst,
+}
+
+/// Initial value of a declared object
+#[derive(Debug, Clone, PartialEq)]
+pub enum Initializer {
+    /// `= expr`
+    Single(Expr),
+    /// `= { init, init, ... }`, for arrays (or a scalar in braces)
+    List(Vec<Initializer>),
 }
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum Statement {
-    /// `return expr;` statement
-    Return(Expr),
+    /// `return expr;` statement, `return;` in `void` functions
+    Return(Option<Expr>),
     /// Arbitrary expression statement
     Expr(Option<Expr>),
     /// "if-then- optional else" block
@@ -99,6 +282,7 @@ pub enum Statement {
         cond: Expr,           // condition
         post: Option<Expr>,   // post-expression
         body: Box<Statement>, // body
+        label: LoopLabel,
     },
 
     /// For loop with declaration
@@ -107,46 +291,154 @@ pub enum Statement {
         cond: Expr,           // condition
         post: Option<Expr>,   // post-expression
         body: Box<Statement>, // body
+        label: LoopLabel,
     },
 
     /// While loop
     While {
         cond: Expr,           // condition
         body: Box<Statement>, // body
+        label: LoopLabel,
     },
 
     /// Do loop, stmt is executed first
     Do {
         body: Box<Statement>, // body
         cond: Expr,           // condition
+        label: LoopLabel,
+    },
+
+    /// `switch (cond) body`, goes to the `case` in the body that matches the value
+    /// of `cond`, or to `default`
+    Switch {
+        cond: Expr,
+        body: Box<Statement>,
+        label: LoopLabel,
+    },
+
+    /// `case value: body`, or GNU `case value ... last: body` for every value from
+    /// `value` to `last`; the labeled `switch` goes here
+    Case {
+        value: Constant,
+        last: Option<Constant>,
+        body: Box<Statement>,
+        location: Location,
+        label: LoopLabel,
+    },
+
+    /// `default: body`, where the labeled `switch` goes if no `case` matches
+    Default {
+        body: Box<Statement>,
+        location: Location,
+        label: LoopLabel,
     },
 
-    /// Break loop
-    Break,
+    /// Break loop, leaves the labeled loop or `switch`
+    Break(LoopLabel),
 
-    /// Continue loop
-    Continue,
+    /// Continue loop, goes to the next iteration of the labeled loop
+    Continue(LoopLabel),
+
+    /// Inline assembly
+    Asm(AsmStatement),
+}
+
+/// Number of a loop or `switch`, unique in the program, given to it and to every
+/// `break`, `continue`, `case` and `default` that refers to it by
+/// [`crate::semantic::analyze`]. The parser leaves it unset.
+pub type LoopLabel = Option<usize>;
+
+/// `asm volatile ("template" : outputs : inputs : clobbers);`
+#[derive(Debug, Clone, PartialEq)]
+pub struct AsmStatement {
+    /// instructions, with `%0`, `%w1`, `%[name]` referring to operands
+    /// (outputs first, then inputs) unless the statement is basic
+    pub template: String,
+    pub outputs: Vec<AsmOperand>,
+    pub inputs: Vec<AsmOperand>,
+    /// registers, `"memory"` and `"cc"`
+    pub clobbers: Vec<String>,
+    pub volatile: bool,
+    /// basic asm, without a colon: the template is emitted as is
+    pub basic: bool,
+}
+
+/// `[name] "constraint" (expr)` in an [`AsmStatement`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct AsmOperand {
+    pub name: Option<String>,
+    /// `r`, `m`, `i`, `n` or the number of an output it shares a register with;
+    /// outputs start with `=` (written) or `+` (read and written)
+    pub constraint: String,
+    pub expr: Expr,
+}
+
+/// Storage-class specifier of a declaration
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StorageClass {
+    /// `static`: internal linkage at file scope, static storage duration in a block
+    Static,
+    /// `extern`: refers to an object or function that may be defined elsewhere
+    Extern,
 }
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum Declaration {
     /// Variable declaration with optional initial value.
-    Declare(String, Option<Expr>),
+    /// At block scope, `ty` may also be a function type.
+    Declare {
+        name: String,
+        ty: Type,
+        init: Option<Initializer>,
+        storage: Option<StorageClass>,
+        /// `_Thread_local`: each thread has its own instance of the object
+        thread_local: bool,
+        /// where the declared name is
+        location: Location,
+    },
 }
 
 /// Item of a [`Statement::Compound`]
 #[derive(Debug, Clone, PartialEq)]
 pub enum BlockItem {
-    /// Arbitrary statement
-    Stmt(Statement),
+    /// Arbitrary statement, and where it starts
+    Stmt(Statement, Location),
     Decl(Declaration),
 }
 
+/// Return and parameter types of a function.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FunctionType {
+    pub return_type: Type,
+    /// unqualified parameter types, after array and function parameters became pointers
+    pub params: Vec<Type>,
+    /// the parameter list ends with `...`
+    pub variadic: bool,
+    /// declared with a parameter list, otherwise `params` is empty and says nothing
+    pub prototyped: bool,
+}
+
+/// Named function parameter
+#[derive(Debug, Clone, PartialEq)]
+pub struct Param {
+    pub name: String,
+    pub ty: Type,
+    pub location: Location,
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct Function {
     pub name: String,
-    pub params: Vec<String>,
+    pub return_type: Type,
+    pub params: Vec<Param>,
+    /// the parameter list ends with `...`
+    pub variadic: bool,
+    /// declared with a parameter list; `f()` leaves the parameters unspecified
+    pub prototyped: bool,
     pub block_items: Option<Vec<BlockItem>>,
+    pub storage: Option<StorageClass>,
+    /// where the name of the function is
+    pub location: Location,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -0,0 +1,141 @@
+use crate::ast::{BlockItem, Declaration, Expr, Initializer, Statement, Type};
+use std::collections::HashSet;
+
+/// Collects the names of all variables whose address is taken with `&` somewhere
+/// in the function body. Such variables have to live in memory, not in a register.
+pub fn address_taken_variables(items: &[BlockItem]) -> HashSet<String> {
+    let mut names = HashSet::new();
+    for item in items {
+        visit_block_item(item, &mut names);
+    }
+    names
+}
+
+fn visit_block_item(item: &BlockItem, names: &mut HashSet<String>) {
+    match item {
+        BlockItem::Stmt(stmt, _) => visit_stmt(stmt, names),
+        BlockItem::Decl(decl) => visit_decl(decl, names),
+    }
+}
+
+fn visit_decl(decl: &Declaration, names: &mut HashSet<String>) {
+    let Declaration::Declare { ty, init, .. } = decl;
+    if let Type::VarArray(_, len) = ty {
+        visit_expr(len, names);
+    }
+    if let Some(init) = init {
+        visit_initializer(init, names);
+    }
+}
+
+fn visit_initializer(init: &Initializer, names: &mut HashSet<String>) {
+    match init {
+        Initializer::Single(expr) => visit_expr(expr, names),
+        Initializer::List(items) => items.iter().for_each(|i| visit_initializer(i, names)),
+    }
+}
+
+fn visit_stmt(stmt: &Statement, names: &mut HashSet<String>) {
+    match stmt {
+        Statement::Return(expr) | Statement::Expr(expr) => {
+            if let Some(expr) = expr {
+                visit_expr(expr, names);
+            }
+        }
+        Statement::Bingus(expr) => visit_expr(expr, names),
+        Statement::If { cond, then, els } => {
+            visit_expr(cond, names);
+            visit_stmt(then, names);
+            if let Some(els) = els {
+                visit_stmt(els, names);
+            }
+        }
+        Statement::Compound(items) => items.iter().for_each(|i| visit_block_item(i, names)),
+        Statement::For {
+            init,
+            cond,
+            post,
+            body,
+            ..
+        } => {
+            for expr in [init.as_ref(), Some(cond), post.as_ref()]
+                .into_iter()
+                .flatten()
+            {
+                visit_expr(expr, names);
+            }
+            visit_stmt(body, names);
+        }
+        Statement::ForDecl {
+            decl,
+            cond,
+            post,
+            body,
+            ..
+        } => {
+            visit_decl(decl, names);
+            visit_expr(cond, names);
+            if let Some(post) = post {
+                visit_expr(post, names);
+            }
+            visit_stmt(body, names);
+        }
+        Statement::While { cond, body, .. }
+        | Statement::Do { body, cond, .. }
+        | Statement::Switch { cond, body, .. } => {
+            visit_expr(cond, names);
+            visit_stmt(body, names);
+        }
+        Statement::Case { body, .. } | Statement::Default { body, .. } => visit_stmt(body, names),
+        Statement::Break(_) | Statement::Continue(_) => {}
+        Statement::Asm(asm) => {
+            for operand in asm.operands() {
+                // a memory operand refers to the object itself
+                if operand.constraint.contains('m')
+                    && let Expr::Var(name) = &operand.expr
+                {
+                    names.insert(name.clone());
+                }
+                visit_expr(&operand.expr, names);
+            }
+        }
+    }
+}
+
+fn visit_expr(expr: &Expr, names: &mut HashSet<String>) {
+    match expr {
+        Expr::AddrOf(inner) => {
+            if let Expr::Var(name) = inner.as_ref() {
+                names.insert(name.clone());
+            }
+            visit_expr(inner, names);
+        }
+        Expr::Const(_) | Expr::Var(_) => {}
+        Expr::UnOp(_, inner)
+        | Expr::Cast(_, inner)
+        | Expr::Deref(inner)
+        | Expr::VaStart(inner)
+        | Expr::VaArg(inner, _)
+        | Expr::VaEnd(inner)
+        | Expr::Alloca(inner) => visit_expr(inner, names),
+        Expr::BinOp(_, lhs, rhs) | Expr::Assign(lhs, rhs) | Expr::VaCopy(lhs, rhs) => {
+            visit_expr(lhs, names);
+            visit_expr(rhs, names);
+        }
+        Expr::Conditional { cond, then, els } => {
+            visit_expr(cond, names);
+            if let Some(then) = then {
+                visit_expr(then, names);
+            }
+            visit_expr(els, names);
+        }
+        Expr::Block(items) => items.iter().for_each(|i| visit_block_item(i, names)),
+        Expr::FunCall { parameters, .. } | Expr::Builtin(_, parameters) => {
+            parameters.iter().for_each(|p| visit_expr(p, names))
+        }
+        Expr::IndirectCall { callee, parameters } => {
+            visit_expr(callee, names);
+            parameters.iter().for_each(|p| visit_expr(p, names));
+        }
+    }
+}
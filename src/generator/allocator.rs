@@ -1,65 +1,129 @@
-use std::collections::HashMap;
+use crate::ast::Type;
+use std::collections::{HashMap, HashSet};
+
+/// Bytes between x29 and the first local stack slot: the five callee-saved
+/// register pairs (x19–x28) pushed by the function prologue.
+pub const CALLEE_SAVED_AREA: i32 = 80;
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum Variable {
-    Stack(i32),       // offset
-    Register(String), // register name
-    Global(String),   // .data label
+    Stack(i32),     // offset from x29
+    Register(u8),   // callee-saved register number (x19-x28)
+    Global(String), // .data label
+    /// `_Thread_local` object; `defined` if this file defines it, so it is at
+    /// a fixed offset from the thread pointer
+    ThreadLocal {
+        label: String,
+        defined: bool,
+    },
 }
 
 pub struct Allocator {
     next_stack_offset: i32,
-    used_registers: Vec<String>,
-    scopes: Vec<HashMap<String, Variable>>,
+    used_registers: Vec<u8>,
+    scopes: Vec<HashMap<String, (Variable, Type)>>,
+    /// variables whose address is taken, they always live in memory
+    address_taken: HashSet<String>,
 }
 
 impl Allocator {
-    pub fn new(registers: &[&str], global_vars: &HashMap<String, Variable>) -> Self {
+    pub fn new(
+        registers: &[u8],
+        global_vars: &HashMap<String, (Variable, Type)>,
+        address_taken: HashSet<String>,
+    ) -> Self {
         let scopes = vec![global_vars.clone()];
         Self {
-            next_stack_offset: 0,
-            used_registers: registers.iter().map(|s| s.to_string()).collect(),
+            next_stack_offset: -CALLEE_SAVED_AREA,
+            used_registers: registers.to_vec(),
             scopes,
+            address_taken,
         }
     }
 
-    pub fn allocate(&mut self, name: String, size: i32) -> Variable {
-        if size == 4 {
-            if let Some(var) = self.try_allocate_register(name.clone()) {
-                return var;
-            }
+    /// Integers and pointers live in callee-saved registers while they last,
+    /// everything else (and the overflow) goes to the stack.
+    /// Volatile and atomic objects and objects whose address is taken are always on the stack.
+    pub fn allocate(&mut self, name: String, ty: &Type) -> Variable {
+        let qualifiers = ty.qualifiers();
+        if (ty.is_integer() || ty.is_pointer())
+            && !qualifiers.is_volatile
+            && !qualifiers.is_atomic
+            && !self.address_taken.contains(&name)
+            && let Some(var) = self.try_allocate_register(name.clone(), ty)
+        {
+            return var;
         }
-        self.allocate_stack(name, size)
+        self.allocate_stack(name, ty)
     }
 
-    fn try_allocate_register(&mut self, name: String) -> Option<Variable> {
-        if self.used_registers.is_empty() {
-            return None;
-        }
-        let register = self.used_registers.pop().unwrap();
+    fn try_allocate_register(&mut self, name: String, ty: &Type) -> Option<Variable> {
+        let register = self.used_registers.pop()?;
         let var = Variable::Register(register);
-        self.scopes.last_mut().unwrap().insert(name, var.clone());
+        self.scopes
+            .last_mut()
+            .unwrap()
+            .insert(name, (var.clone(), ty.clone()));
         Some(var)
     }
 
-    fn allocate_stack(&mut self, name: String, size: i32) -> Variable {
-        self.next_stack_offset -= size;
+    fn allocate_stack(&mut self, name: String, ty: &Type) -> Variable {
+        let align = ty.alignment();
+        self.next_stack_offset -= ty.size();
+        // round down (away from x29) to the natural alignment of the type
+        self.next_stack_offset = self.next_stack_offset.div_euclid(align) * align;
+
         let var = Variable::Stack(self.next_stack_offset);
-        self.scopes.last_mut().unwrap().insert(name, var.clone());
+        self.scopes
+            .last_mut()
+            .unwrap()
+            .insert(name, (var.clone(), ty.clone()));
+        var
+    }
+
+    /// Reserves `size` bytes of unnamed stack storage and returns the offset
+    /// of its lowest address from x29.
+    pub fn reserve(&mut self, size: i32, align: i32) -> i32 {
+        self.next_stack_offset -= size;
+        self.next_stack_offset = self.next_stack_offset.div_euclid(align) * align;
+        self.next_stack_offset
+    }
+
+    /// Binds `name` in the current scope to storage that is not allocated in the frame:
+    /// a static or thread-local object, or a function.
+    pub fn declare(&mut self, name: String, var: Variable, ty: &Type) -> Variable {
+        self.scopes
+            .last_mut()
+            .unwrap()
+            .insert(name, (var.clone(), ty.clone()));
         var
     }
 
     pub fn get(&self, name: &str) -> Option<&Variable> {
+        self.lookup(name).map(|(var, _)| var)
+    }
+
+    pub fn get_type(&self, name: &str) -> Option<&Type> {
+        self.lookup(name).map(|(_, ty)| ty)
+    }
+
+    /// The file-scope object called `name`, even if a local hides it.
+    pub fn file_scope(&self, name: &str) -> Option<&Variable> {
+        self.scopes[0].get(name).map(|(var, _)| var)
+    }
+
+    fn lookup(&self, name: &str) -> Option<&(Variable, Type)> {
         for scope in self.scopes.iter().rev() {
-            if let Some(var) = scope.get(name) {
-                return Some(var);
+            if let Some(entry) = scope.get(name) {
+                return Some(entry);
             }
         }
         None
     }
 
+    /// Bytes of stack used by locals, not counting the callee-saved area.
     pub fn total_stack_size(&self) -> i32 {
-        self.next_stack_offset.abs()
+        self.next_stack_offset.abs() - CALLEE_SAVED_AREA
     }
 
     pub fn enter_scope(&mut self) {
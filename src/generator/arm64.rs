@@ -2,23 +2,85 @@ use crate::ast::BlockItem::{Decl, Stmt};
 use crate::ast::Declaration::Declare;
 use crate::ast::Expr::{Assign, BinOp, Conditional, FunCall};
 use crate::ast::Statement::Continue;
+use crate::ast::initializer::{InitSlot, flatten_initializer};
 use crate::ast::{
-    BinaryOp, BlockItem, Declaration, Expr, Function, Program, Statement, TopLevel, UnaryOp,
+    AsmStatement, BinaryOp, BlockItem, Builtin, Constant, Declaration, Expr, Function,
+    FunctionType, Initializer, Location, LoopLabel, MemoryOrder, Param, Program, Statement,
+    StorageClass, TopLevel, Type, UnaryOp,
 };
-use crate::generator::allocator::{Allocator, Variable};
+use crate::generator::address_taken::address_taken_variables;
+use crate::generator::allocator::{Allocator, CALLEE_SAVED_AREA, Variable};
 use crate::generator::bingus::is_bingus_used;
-use crate::generator::function_validation::{
-    check_global_name_conflicts, validate_functions_declarations,
-};
 use crate::generator::label::LabelGenerator;
-use crate::generator::stack::simulate_stack_usage;
-use crate::optimizer::evaluate_expr_compile_time::evaluate_compile_time_expr;
+use crate::generator::locals::local_types;
+use crate::generator::stack::{
+    SAVED_SP_SIZE, declares_var_array, items_contain_var_array, loop_saves_sp,
+    simulate_function_stack_usage, uses_dynamic_stack,
+};
 use crate::optimizer::fold_expr::constant_fold;
-use std::collections::{HashMap, HashSet};
+use crate::semantic::constant::evaluate_compile_time_expr;
+use crate::semantic::control_flow::{Cfg, analyze_control_flow};
+use crate::semantic::function_validation::{check_builtin_arity, check_call_arity};
+use crate::semantic::operators::{Comparison, Domain, Operation, operand_type, operation};
+use crate::semantic::{source_name, source_text};
+use std::collections::HashMap;
 use std::error::Error;
 use std::fmt;
 use std::fmt::Write;
 
+/// Register view used for a value of type `ty` in the result register:
+/// `w0` for 32-bit and narrower integers, `x0` for 64-bit integers and pointers,
+/// `s0`/`d0` for floating-point values.
+fn reg_prefix(ty: &Type) -> &'static str {
+    match ty.unqualified() {
+        Type::Float => "s",
+        Type::Double => "d",
+        Type::VaList => "x", // passed around as a single pointer
+        ty if ty.size() == 8 => "x",
+        _ => "w",
+    }
+}
+
+/// Load instruction for `ty`; narrow integers are extended to 32 bits.
+fn load_instr(ty: &Type) -> &'static str {
+    match ty.unqualified() {
+        Type::Char => "ldrsb",
+        Type::UChar => "ldrb",
+        Type::Short => "ldrsh",
+        Type::UShort => "ldrh",
+        _ => "ldr",
+    }
+}
+
+/// Store instruction for `ty`.
+fn store_instr(ty: &Type) -> &'static str {
+    match ty.unqualified() {
+        Type::Char | Type::UChar => "strb",
+        Type::Short | Type::UShort => "strh",
+        _ => "str",
+    }
+}
+
+/// Compute `x29 + offset` into `dst`.
+fn emit_frame_address(output: &mut dyn Write, dst: &str, offset: i32) -> fmt::Result {
+    if (-4095..=0).contains(&offset) {
+        writeln!(output, "sub\t{dst}, x29, #{}", -offset)
+    } else {
+        emit_mov_imm(output, "x", 17, offset as i64)?;
+        writeln!(output, "add\t{dst}, x29, x17")
+    }
+}
+
+/// Memory operand for the frame slot at `offset` from x29.
+/// Load/store offsets are limited, so far slots are addressed through x17.
+fn frame_slot(output: &mut dyn Write, offset: i32) -> Result<String, fmt::Error> {
+    if (-256..=255).contains(&offset) {
+        return Ok(format!("[x29, #{offset:+}]"));
+    }
+    emit_frame_address(output, "x17", offset)?;
+    Ok("[x17]".to_string())
+}
+
 impl Variable {
     pub fn emit_global_variable_address_load_x1(
         label: &str,
@@ -47,45 +109,158 @@ impl Variable {
         }
     }
 
-    pub fn emit_store_in_w0(&self, output: &mut dyn Write, platform: &str) -> fmt::Result {
+    /// Compute the address of a thread-local object into x1.
+    ///
+    /// On Linux, an object defined in this file is at a link-time offset from the
+    /// thread pointer (local-exec); the offset of any other is loaded from the GOT
+    /// (initial-exec). On macOS, the object's TLV descriptor holds a function that
+    /// returns its address; the call preserves all registers but x0, x16, x17 and lr.
+    pub fn emit_thread_local_address_x1(
+        label: &str,
+        defined: bool,
+        output: &mut dyn Write,
+        platform: &str,
+    ) -> fmt::Result {
+        match platform {
+            "macos" => {
+                writeln!(output, "str\tx0, [sp, #-16]!")?;
+                writeln!(output, "adrp\tx0, {label}@TLVPPAGE")?;
+                writeln!(output, "ldr\tx0, [x0, {label}@TLVPPAGEOFF]")?;
+                writeln!(output, "ldr\tx1, [x0]")?;
+                writeln!(output, "blr\tx1")?;
+                writeln!(output, "mov\tx1, x0")?;
+                writeln!(output, "ldr\tx0, [sp], #16")
+            }
+            _ if defined => {
+                writeln!(output, "mrs\tx1, tpidr_el0")?;
+                writeln!(output, "add\tx1, x1, #:tprel_hi12:{label}, lsl #12")?;
+                writeln!(output, "add\tx1, x1, #:tprel_lo12_nc:{label}")
+            }
+            _ => {
+                writeln!(output, "mrs\tx1, tpidr_el0")?;
+                writeln!(output, "adrp\tx17, :gottprel:{label}")?;
+                writeln!(output, "ldr\tx17, [x17, #:gottprel_lo12:{label}]")?;
+                writeln!(output, "add\tx1, x1, x17")
+            }
+        }
+    }
+
+    /// Compute the address of a global or thread-local variable into x1.
+    fn emit_address_x1(&self, output: &mut dyn Write, platform: &str) -> fmt::Result {
+        match self {
+            Variable::Global(label) => {
+                Variable::emit_global_variable_address_load_x1(label, output, platform)
+            }
+            Variable::ThreadLocal { label, defined } => {
+                Variable::emit_thread_local_address_x1(label, *defined, output, platform)
+            }
+            _ => unreachable!("only static objects have a symbol: {self:?}"),
+        }
+    }
+
+    /// Load the variable of type `ty` into w0/x0/s0/d0.
+    pub fn emit_load(&self, ty: &Type, output: &mut dyn Write, platform: &str) -> fmt::Result {
+        let r = reg_prefix(ty);
         match self {
             Variable::Register(reg) => {
-                writeln!(output, "mov\tw0, {}", reg)
+                writeln!(output, "mov\t{r}0, {r}{reg}")
             }
             Variable::Stack(offset) => {
-                writeln!(output, "ldr\tw0, [x29, #{:+}]", offset)
+                let slot = frame_slot(output, *offset)?;
+                writeln!(output, "{}\t{r}0, {slot}", load_instr(ty))
             }
-            Variable::Global(label) => {
-                Variable::emit_global_variable_address_load_x1(label, output, platform)?;
-                writeln!(output, "ldr\tw0, [x1]") // load 32-bit value from label into w0
+            Variable::Global(_) | Variable::ThreadLocal { .. } => {
+                self.emit_address_x1(output, platform)?;
+                writeln!(output, "{}\t{r}0, [x1]", load_instr(ty)) // load value from label
             }
         }
     }
 
-    pub fn emit_store_from_w0(&self, output: &mut dyn Write, platform: &str) -> fmt::Result {
+    /// Store register number `src` (viewed as `ty`, e.g. `w0` or `d2`) into the variable.
+    pub fn emit_store(
+        &self,
+        ty: &Type,
+        src: usize,
+        output: &mut dyn Write,
+        platform: &str,
+    ) -> fmt::Result {
+        let r = reg_prefix(ty);
         match self {
             Variable::Register(reg) => {
-                writeln!(output, "mov\t{}, w0", reg)
+                writeln!(output, "mov\t{r}{reg}, {r}{src}")
             }
             Variable::Stack(offset) => {
-                writeln!(output, "str\tw0, [x29, #{:+}]", offset)
+                let slot = frame_slot(output, *offset)?;
+                writeln!(output, "{}\t{r}{src}, {slot}", store_instr(ty))
             }
-            Variable::Global(label) => {
-                Variable::emit_global_variable_address_load_x1(label, output, platform)?;
-                writeln!(output, "str\tw0, [x1]") // load page address of label into x1
+            Variable::Global(_) | Variable::ThreadLocal { .. } => {
+                self.emit_address_x1(output, platform)?;
+                writeln!(output, "{}\t{r}{src}, [x1]", store_instr(ty)) // store into label
             }
         }
     }
 }
 
+/// Optional architecture extensions the generated code may use.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TargetFeatures {
+    /// ARMv8.1 Large System Extensions: atomic read-modify-write instructions
+    /// (`ldadd`, `swp`, `cas`, ...) instead of exclusive load/store loops
+    pub lse: bool,
+}
+
+impl TargetFeatures {
+    /// The features enabled by `names`, as given on the command line.
+    pub fn from_names(names: &[String]) -> Result<TargetFeatures, String> {
+        let mut features = TargetFeatures::default();
+        for name in names {
+            match name.as_str() {
+                "lse" => features.lse = true,
+                _ => return Err(format!("Unknown target feature: {name} (expected 'lse')")),
+            }
+        }
+        Ok(features)
+    }
+}
+
+/// What the code is generated for.
+#[derive(Debug, Clone)]
+pub struct Target {
+    /// "linux" or "macos"
+    pub platform: String,
+    pub features: TargetFeatures,
+}
+
+/// Type and linkage of a declared function.
+#[derive(Debug, Clone)]
+pub struct Signature {
+    pub ty: FunctionType,
+    /// declared `static`: the symbol is not exported from the object file
+    pub internal: bool,
+}
+
 struct Generator<'a> {
     output: &'a mut dyn Write,
     labels: &'a mut LabelGenerator,
     allocator: Allocator,
+    functions: &'a HashMap<String, Signature>,
+    return_type: Type,
     epilogue: String,
     debug_enabled: bool,
+    /// name of the function being generated, used for static local labels
+    function_name: String,
+    /// data of static locals, emitted after all functions
+    static_data: &'a mut String,
+    /// set while generating a variadic function
+    varargs: Option<VarArgs>,
+    /// frame slot holding the bottom of the stack allocated at run time,
+    /// set if the function has variable-length arrays or calls `__builtin_alloca`
+    dynamic_base: Option<i32>,
+    /// declared types of all block-scope names, including those not in scope yet
+    local_types: HashMap<String, Type>,
 
     platform: String,
+    features: TargetFeatures,
 }
 
 impl Generator<'_> {
@@ -105,57 +280,489 @@ fn function_label_prefix(platform: &str) -> Result<&str, Box<dyn Error>> {
     }
 }
 
-struct Context {
-    break_label: Option<String>,
-    continue_label: Option<String>,
+/// Where the unnamed arguments of a variadic function are found.
+struct VarArgs {
+    /// offset from x29 of the register save area: d0–d7 in 16-byte slots,
+    /// followed by x0–x7 (Linux only, on macOS all unnamed arguments are on the stack)
+    save_area: Option<i32>,
+    /// general-purpose registers taken by named parameters
+    named_gp: usize,
+    /// floating-point registers taken by named parameters
+    named_fp: usize,
+    /// bytes of the caller's argument area taken by named parameters
+    named_stack: i32,
 }
 
-/// Emit *one* arithmetic / logical binary operator.
-/// Assumes:
-///   • right  operand is already in **w0**
-///   • left   operand is already in **w11**
-/// Leaves the result in **w0**.
-fn emit_binop(g: &mut Generator, op: BinaryOp) -> fmt::Result {
+/// Size of the Linux register save area of a variadic function.
+const VA_SAVE_AREA_SIZE: i32 = 8 * 16 + 8 * 8;
+
+/// Materialize an integer immediate in `{prefix}{reg}` (`w` or `x` view).
+/// Small values use a single `mov`, anything else is built with `movz`/`movk`.
+fn emit_mov_imm(output: &mut dyn Write, prefix: &str, reg: usize, value: i64) -> fmt::Result {
+    if (-65536..=65535).contains(&value) {
+        return writeln!(output, "mov\t{prefix}{reg}, #{value}");
+    }
+
+    let bits = if prefix == "w" {
+        value as u32 as u64
+    } else {
+        value as u64
+    };
+    let mut first = true;
+    for shift in (0..if prefix == "w" { 32 } else { 64 }).step_by(16) {
+        let chunk = (bits >> shift) & 0xffff;
+        if chunk == 0 {
+            continue;
+        }
+        let instr = if first { "movz" } else { "movk" };
+        writeln!(output, "{instr}\t{prefix}{reg}, #{chunk}, lsl #{shift}")?;
+        first = false;
+    }
+    Ok(())
+}
+
+fn emit_constant(g: &mut Generator, c: &Constant) -> fmt::Result {
+    let ty = c.ty();
+    match ty {
+        Type::Float => {
+            emit_mov_imm(g.output, "w", 0, c.bits() as i64)?;
+            writeln!(g.output, "fmov\ts0, w0")
+        }
+        Type::Double => {
+            emit_mov_imm(g.output, "x", 0, c.bits() as i64)?;
+            writeln!(g.output, "fmov\td0, x0")
+        }
+        _ => emit_mov_imm(g.output, reg_prefix(&ty), 0, c.as_i128() as i64),
+    }
+}
+
+/// Re-establish the invariant for narrow integers: `w0` holds the value
+/// sign- or zero-extended to 32 bits.
+fn emit_extend_narrow(g: &mut Generator, ty: &Type) -> fmt::Result {
+    match ty.unqualified() {
+        Type::Char => writeln!(g.output, "sxtb\tw0, w0"),
+        Type::UChar => writeln!(g.output, "uxtb\tw0, w0"),
+        Type::Short => writeln!(g.output, "sxth\tw0, w0"),
+        Type::UShort => writeln!(g.output, "uxth\tw0, w0"),
+        _ => Ok(()),
+    }
+}
+
+/// Convert the value in w0/x0/s0/d0 from type `from` to type `to`.
+fn emit_convert(g: &mut Generator, from: &Type, to: &Type) -> fmt::Result {
+    let (from, to) = (from.unqualified(), to.unqualified());
+    if from == to || *to == Type::Void {
+        return Ok(());
+    }
+
+    let (src, dst) = (reg_prefix(from), reg_prefix(to));
+    match (from.is_floating(), to.is_floating()) {
+        // float <-> double
+        (true, true) => writeln!(g.output, "fcvt\t{dst}0, {src}0"),
+        (true, false) => {
+            let instr = if to.is_signed() { "fcvtzs" } else { "fcvtzu" };
+            writeln!(g.output, "{instr}\t{dst}0, {src}0")?;
+            emit_extend_narrow(g, to)
+        }
+        (false, true) => {
+            let instr = if from.is_signed() { "scvtf" } else { "ucvtf" };
+            writeln!(g.output, "{instr}\t{dst}0, {src}0")
+        }
+        // integer / pointer conversions
+        (false, false) => {
+            if to.size() == 8 && from.size() < 8 {
+                // narrow values are already extended to 32 bits, widen the rest of the way
+                if from.is_signed() {
+                    writeln!(g.output, "sxtw\tx0, w0")
+                } else {
+                    writeln!(g.output, "mov\tw0, w0") // writing w0 zeroes the upper half
+                }
+            } else {
+                // truncation is free: the narrower view of x0 is used from now on
+                emit_extend_narrow(g, to)
+            }
+        }
+    }
+}
+
+/// Set the flags by comparing the value of type `ty` in the result register with zero.
+fn emit_compare_zero(g: &mut Generator, ty: &Type) -> Result<(), Box<dyn Error>> {
+    if !ty.is_scalar() {
+        return Err(format!("used {ty} value where scalar is required").into());
+    }
+
+    let r = reg_prefix(ty);
+    if ty.is_floating() {
+        writeln!(g.output, "fcmp\t{r}0, #0.0")?;
+    } else {
+        writeln!(g.output, "cmp\t{r}0, #0")?;
+    }
+    Ok(())
+}
+
+fn is_null_pointer_constant(expr: &Expr) -> bool {
+    matches!(expr, Expr::Const(c) if c.ty().is_integer() && c.is_zero())
+}
+
+/// Pointee types are compatible if they only differ in qualifiers,
+/// or are compatible function types.
+fn same_pointee(a: &Type, b: &Type) -> bool {
+    match (a.unqualified(), b.unqualified()) {
+        (Type::Function(a), Type::Function(b)) => a.compatible(b),
+        (a, b) => a == b,
+    }
+}
+
+fn is_void_pointee(ty: &Type) -> bool {
+    *ty.unqualified() == Type::Void
+}
+
+/// Checks that `expr` of type `from` may be implicitly converted to `to`,
+/// as in assignment, initialization, argument passing and `return`.
+fn check_implicit_conversion(from: &Type, to: &Type, expr: &Expr) -> Result<(), Box<dyn Error>> {
+    let compatible = match (from.unqualified(), to.unqualified()) {
+        (a, b) if a.is_arithmetic() && b.is_arithmetic() => true,
+        (Type::Pointer(a), Type::Pointer(b))
+            if same_pointee(a, b) || is_void_pointee(a) || is_void_pointee(b) =>
+        {
+            if !b.qualifiers().contains(a.qualifiers()) {
+                return Err(format!("conversion from {from} to {to} discards qualifiers").into());
+            }
+            true
+        }
+        (_, Type::Pointer(_)) => is_null_pointer_constant(expr),
+        _ => false,
+    };
+
+    if compatible {
+        Ok(())
+    } else {
+        Err(format!("cannot implicitly convert {from} to {to}").into())
+    }
+}
+
+/// Checks an explicit `(to) expr` cast.
+fn check_cast(from: &Type, to: &Type) -> Result<(), Box<dyn Error>> {
+    let valid = match (from, to.unqualified()) {
+        (_, Type::Void) => true,
+        (Type::Void, _) => false,
+        (_, Type::Array(..) | Type::Function(_)) => false,
+        (a, b) if a.is_pointer() || b.is_pointer() => !a.is_floating() && !b.is_floating(),
+        (a, b) => a.is_arithmetic() && b.is_arithmetic(),
+    };
+
+    if valid {
+        Ok(())
+    } else {
+        Err(format!("invalid cast from {from} to {to}").into())
+    }
+}
+
+/// Computes the type both operands of `op` are converted to before the operation.
+/// Pointer arithmetic is handled separately, see [`pointer_arithmetic_type`].
+fn binop_operand_type(
+    op: BinaryOp,
+    (lhs, lt): (&Expr, &Type),
+    (rhs, rt): (&Expr, &Type),
+) -> Result<Type, Box<dyn Error>> {
     use BinaryOp::*;
+    let invalid = || format!("invalid operands to binary {op} ({lt} and {rt})").into();
+
     match op {
-        Add => writeln!(g.output, "add\tw0, w11, w0"),
-        Sub => writeln!(g.output, "sub\tw0, w11, w0"),
-        Multiply => writeln!(g.output, "mul\tw0, w11, w0"),
-        Divide => writeln!(g.output, "sdiv\tw0, w11, w0"),
-        And => writeln!(g.output, "and\tw0, w11, w0"),
-        Or => writeln!(g.output, "orr\tw0, w11, w0"),
-        Xor => writeln!(g.output, "eor\tw0, w11, w0"),
-        ShiftLeft => writeln!(g.output, "lsl\tw0, w11, w0"),
-        ShiftRight => writeln!(g.output, "lsr\tw0, w11, w0"),
+        Add | Sub | Multiply | Divide if lt.is_arithmetic() && rt.is_arithmetic() => {
+            Ok(operand_type(op, lt, rt))
+        }
+        Modulo | And | Or | Xor | ShiftLeft | ShiftRight if lt.is_integer() && rt.is_integer() => {
+            Ok(operand_type(op, lt, rt))
+        }
 
-        Modulo => {
-            writeln!(g.output, "udiv\tw12, w11, w0")?;
-            writeln!(g.output, "msub\tw0, w12, w0, w11")
+        Equal | NotEqual | Less | LessEqual | Greater | GreaterEqual => {
+            if lt.is_arithmetic() && rt.is_arithmetic() {
+                return Ok(Type::common(lt, rt));
+            }
+            match (lt, rt) {
+                (Type::Pointer(a), Type::Pointer(b))
+                    if same_pointee(a, b) || is_void_pointee(a) || is_void_pointee(b) =>
+                {
+                    Ok(lt.clone())
+                }
+                (Type::Pointer(_), _) if is_null_pointer_constant(rhs) => Ok(lt.clone()),
+                (_, Type::Pointer(_)) if is_null_pointer_constant(lhs) => Ok(rt.clone()),
+                _ => Err(invalid()),
+            }
         }
 
-        Equal => {
-            writeln!(g.output, "cmp\tw11, w0")?;
-            writeln!(g.output, "cset\tw0, eq")
+        LogicalAnd | LogicalOr if lt.is_scalar() && rt.is_scalar() => Ok(Type::Int),
+        _ => Err(invalid()),
+    }
+}
+
+/// Result type of `lhs + rhs` / `lhs - rhs` where at least one side is a pointer:
+/// pointer ± integer is a pointer, pointer − pointer is a `long` element count.
+fn pointer_arithmetic_type(op: BinaryOp, lt: &Type, rt: &Type) -> Result<Type, Box<dyn Error>> {
+    let pointee = |ty: &Type| -> Result<Type, Box<dyn Error>> {
+        match ty {
+            Type::Pointer(inner) if inner.is_function() => {
+                Err(format!("arithmetic on a pointer to the function type {inner}").into())
+            }
+            Type::Pointer(inner) if !is_void_pointee(inner) => Ok(*inner.clone()),
+            _ => Err(format!("arithmetic on a pointer to an incomplete type ({ty})").into()),
         }
-        NotEqual => {
-            writeln!(g.output, "cmp\tw11, w0")?;
-            writeln!(g.output, "cset\tw0, ne")
+    };
+
+    match (op, lt, rt) {
+        (BinaryOp::Add | BinaryOp::Sub, Type::Pointer(_), i) if i.is_integer() => {
+            pointee(lt)?;
+            Ok(lt.clone())
         }
-        Less => {
-            writeln!(g.output, "cmp\tw11, w0")?;
-            writeln!(g.output, "cset\tw0, lt")
+        (BinaryOp::Add, i, Type::Pointer(_)) if i.is_integer() => {
+            pointee(rt)?;
+            Ok(rt.clone())
         }
-        LessEqual => {
-            writeln!(g.output, "cmp\tw11, w0")?;
-            writeln!(g.output, "cset\tw0, le")
+        (BinaryOp::Sub, Type::Pointer(a), Type::Pointer(b)) if same_pointee(a, b) => {
+            pointee(lt)?;
+            Ok(Type::Long)
         }
-        Greater => {
-            writeln!(g.output, "cmp\tw11, w0")?;
-            writeln!(g.output, "cset\tw0, gt")
+        _ => Err(format!("invalid operands to binary {op} ({lt} and {rt})").into()),
+    }
+}
+
+/// Declared type (with qualifiers, arrays not decayed) of the object designated by `expr`.
+fn lvalue_type(g: &Generator, expr: &Expr) -> Result<Type, Box<dyn Error>> {
+    match expr {
+        Expr::Var(name) => match g.allocator.get_type(name) {
+            Some(ty) => Ok(ty.clone()),
+            // a function designator
+            None => match (g.functions.get(name), g.local_types.get(name)) {
+                (Some(signature), _) => Ok(Type::Function(Box::new(signature.ty.clone()))),
+                // declared in a statement expression whose type is needed
+                (None, Some(ty)) => Ok(ty.clone()),
+                (None, None) => Err(format!("variable {name} not found").into()),
+            },
+        },
+        Expr::Deref(inner) => match type_of(g, inner)? {
+            Type::Pointer(pointee) => Ok(*pointee),
+            other => Err(format!("indirection requires pointer operand ({other} invalid)").into()),
+        },
+        _ => Err(format!("expression is not an lvalue: '{}'", source_text(expr)).into()),
+    }
+}
+
+/// Checks that the object designated by `target` may be modified.
+fn check_assignable(target: &Expr, ty: &Type) -> Result<(), Box<dyn Error>> {
+    if ty.is_function() {
+        return Err(format!("function type {ty} is not assignable").into());
+    }
+    if ty.is_array() || ty.is_var_array() {
+        return Err(format!("array type {ty} is not assignable").into());
+    }
+    if ty.qualifiers().is_const {
+        return Err(match target {
+            Expr::Var(name) => format!(
+                "cannot assign to variable '{}' with const-qualified type '{ty}'",
+                source_name(name)
+            ),
+            _ => format!(
+                "cannot assign to read-only location '{}' of type '{ty}'",
+                source_text(target)
+            ),
+        }
+        .into());
+    }
+    Ok(())
+}
+
+/// Computes the type of `expr` in the current scope.
+/// This is the type of its value: arrays have decayed, qualifiers are dropped.
+fn type_of(g: &Generator, expr: &Expr) -> Result<Type, Box<dyn Error>> {
+    use BinaryOp::*;
+    match expr {
+        Expr::Const(c) => Ok(c.ty()),
+        Expr::Var(_) => Ok(lvalue_type(g, expr)?.decay()),
+        Expr::Deref(_) => {
+            let ty = lvalue_type(g, expr)?;
+            if is_void_pointee(&ty) {
+                return Err(
+                    format!("dereferencing a void pointer: '{}'", source_text(expr)).into(),
+                );
+            }
+            Ok(ty.decay())
+        }
+        Expr::AddrOf(inner) => match (inner.as_ref(), lvalue_type(g, inner)?) {
+            (Expr::Var(name), ty) if ty.is_var_array() => Err(format!(
+                "cannot take the address of variable-length array {}",
+                source_name(name)
+            )
+            .into()),
+            (_, ty) => Ok(Type::Pointer(Box::new(ty))),
+        },
+        Expr::Cast(ty, _) => Ok(ty.unqualified().clone()),
+        Expr::UnOp(UnaryOp::Not, _) => Ok(Type::Int),
+        Expr::UnOp(_, inner) => Ok(type_of(g, inner)?.promoted()),
+        BinOp(op, lhs, rhs) => {
+            let (lt, rt) = (type_of(g, lhs)?, type_of(g, rhs)?);
+            match op {
+                Equal | NotEqual | Less | LessEqual | Greater | GreaterEqual | LogicalAnd
+                | LogicalOr => Ok(Type::Int),
+                ShiftLeft | ShiftRight => Ok(lt.promoted()),
+                Add | Sub if lt.is_pointer() || rt.is_pointer() => {
+                    pointer_arithmetic_type(*op, &lt, &rt)
+                }
+                _ => binop_operand_type(*op, (lhs, &lt), (rhs, &rt)),
+            }
+        }
+        Assign(lhs, _) => Ok(lvalue_type(g, lhs)?.unqualified().clone()),
+        Conditional { cond, then, els } => {
+            let then = then.as_deref().unwrap_or(cond);
+            let (tt, et) = (type_of(g, then)?, type_of(g, els)?);
+            conditional_type((then, &tt), (els, &et))
+        }
+        // the value of the last statement, if it is an expression
+        Expr::Block(items) => match items.last() {
+            Some(BlockItem::Stmt(Statement::Expr(Some(value)), _)) => type_of(g, value),
+            _ => Ok(Type::Void),
+        },
+        FunCall { name, .. } => Ok(callee_type(g, &Expr::Var(name.clone()))?.return_type),
+        Expr::IndirectCall { callee, .. } => Ok(callee_type(g, callee)?.return_type),
+        Expr::VaStart(ap) | Expr::VaEnd(ap) => {
+            va_list_is_pointer(g, ap)?;
+            Ok(Type::Void)
+        }
+        Expr::VaCopy(dest, src) => {
+            va_list_is_pointer(g, dest)?;
+            va_list_is_pointer(g, src)?;
+            Ok(Type::Void)
+        }
+        Expr::VaArg(ap, ty) => {
+            va_list_is_pointer(g, ap)?;
+            check_va_arg_type(ty)?;
+            Ok(ty.unqualified().clone())
+        }
+        Expr::Alloca(size) => {
+            let ty = type_of(g, size)?;
+            if !ty.is_integer() {
+                return Err(format!("size of __builtin_alloca has non-integer type '{ty}'").into());
+            }
+            Ok(Type::Pointer(Box::new(Type::Void)))
+        }
+        Expr::Builtin(builtin, args) => {
+            check_builtin_arity(*builtin, args.len())?;
+            if builtin.is_atomic() {
+                return atomic_builtin_type(g, *builtin, args);
+            }
+            let Some(signature) = builtin.function_type() else {
+                overflow_result_type(g, *builtin, args)?;
+                return Ok(Type::Int);
+            };
+            for (arg, param) in args.iter().zip(&signature.params) {
+                check_implicit_conversion(&type_of(g, arg)?, param, arg)?;
+            }
+            Ok(signature.return_type)
+        }
+    }
+}
+
+/// Result type of `cond ? then : els`.
+fn conditional_type(
+    (then, tt): (&Expr, &Type),
+    (els, et): (&Expr, &Type),
+) -> Result<Type, Box<dyn Error>> {
+    if tt.is_arithmetic() && et.is_arithmetic() {
+        return Ok(Type::common(tt, et));
+    }
+    match (tt, et) {
+        (a, b) if a == b => Ok(a.clone()),
+        (Type::Pointer(a), Type::Pointer(b)) if same_pointee(a, b) => {
+            // the result points to a type with the qualifiers of both sides
+            let qualifiers = a.qualifiers().union(b.qualifiers());
+            Ok(Type::Pointer(Box::new(
+                a.unqualified().clone().qualified(qualifiers),
+            )))
+        }
+        (Type::Pointer(a), Type::Pointer(_)) if is_void_pointee(a) => Ok(tt.clone()),
+        (Type::Pointer(_), Type::Pointer(b)) if is_void_pointee(b) => Ok(et.clone()),
+        (Type::Pointer(_), _) if is_null_pointer_constant(els) => Ok(tt.clone()),
+        (_, Type::Pointer(_)) if is_null_pointer_constant(then) => Ok(et.clone()),
+        _ => Err(format!("type mismatch in conditional expression ({tt} and {et})").into()),
+    }
+}
+
+/// Evaluate `expr` and convert the result to `ty` as if by assignment.
+fn generate_expr_as(g: &mut Generator, expr: &Expr, ty: &Type) -> Result<(), Box<dyn Error>> {
+    let from = type_of(g, expr)?;
+    check_implicit_conversion(&from, ty, expr)?;
+    generate_expr(g, expr)?;
+    emit_convert(g, &from, ty)?;
+    Ok(())
+}
+
+/// Emit *one* arithmetic / logical binary operator on operands of type `ty`.
+/// Assumes:
+///   • right  operand is already in **w0** (`x0`, `s0`, `d0` depending on `ty`)
+///   • left   operand is already in **w11** (`x11`, or `s16`/`d16` for floating-point)
+/// Leaves the result in **w0** (`x0`, `s0`, `d0`); comparisons always produce an `int` in w0.
+fn emit_binop(g: &mut Generator, op: BinaryOp, ty: &Type) -> fmt::Result {
+    use Operation::*;
+    let r = reg_prefix(ty);
+    let Some(operation) = operation(op, Domain::of(ty)) else {
+        unreachable!("operand types are checked before emitting {op}")
+    };
+
+    match operation {
+        FloatAdd => writeln!(g.output, "fadd\t{r}0, {r}16, {r}0"),
+        FloatSub => writeln!(g.output, "fsub\t{r}0, {r}16, {r}0"),
+        FloatMultiply => writeln!(g.output, "fmul\t{r}0, {r}16, {r}0"),
+        FloatDivide => writeln!(g.output, "fdiv\t{r}0, {r}16, {r}0"),
+
+        Add => writeln!(g.output, "add\t{r}0, {r}11, {r}0"),
+        Sub => writeln!(g.output, "sub\t{r}0, {r}11, {r}0"),
+        Multiply => writeln!(g.output, "mul\t{r}0, {r}11, {r}0"),
+        SignedDivide => writeln!(g.output, "sdiv\t{r}0, {r}11, {r}0"),
+        UnsignedDivide => writeln!(g.output, "udiv\t{r}0, {r}11, {r}0"),
+        SignedRemainder | UnsignedRemainder => {
+            let div = match operation {
+                SignedRemainder => "sdiv",
+                _ => "udiv",
+            };
+            writeln!(g.output, "{div}\t{r}12, {r}11, {r}0")?;
+            writeln!(g.output, "msub\t{r}0, {r}12, {r}0, {r}11")
+        }
+        And => writeln!(g.output, "and\t{r}0, {r}11, {r}0"),
+        Or => writeln!(g.output, "orr\t{r}0, {r}11, {r}0"),
+        Xor => writeln!(g.output, "eor\t{r}0, {r}11, {r}0"),
+        // the shift instructions use the count modulo the register width
+        ShiftLeft => writeln!(g.output, "lsl\t{r}0, {r}11, {r}0"),
+        ArithmeticShiftRight => writeln!(g.output, "asr\t{r}0, {r}11, {r}0"),
+        LogicalShiftRight => writeln!(g.output, "lsr\t{r}0, {r}11, {r}0"),
+
+        Compare(relation, Domain::Floating) => {
+            // conditions that are false for unordered operands
+            let cond = match relation {
+                Comparison::Equal => "eq",
+                Comparison::NotEqual => "ne",
+                Comparison::Less => "mi",
+                Comparison::LessEqual => "ls",
+                Comparison::Greater => "gt",
+                Comparison::GreaterEqual => "ge",
+            };
+            writeln!(g.output, "fcmp\t{r}16, {r}0")?;
+            writeln!(g.output, "cset\tw0, {cond}")
         }
-        GreaterEqual => {
-            writeln!(g.output, "cmp\tw11, w0")?;
-            writeln!(g.output, "cset\tw0, ge")
+        Compare(relation, domain) => {
+            let cond = match (relation, domain) {
+                (Comparison::Equal, _) => "eq",
+                (Comparison::NotEqual, _) => "ne",
+                (Comparison::Less, Domain::Signed) => "lt",
+                (Comparison::LessEqual, Domain::Signed) => "le",
+                (Comparison::Greater, Domain::Signed) => "gt",
+                (Comparison::GreaterEqual, Domain::Signed) => "ge",
+                (Comparison::Less, _) => "lo",
+                (Comparison::LessEqual, _) => "ls",
+                (Comparison::Greater, _) => "hi",
+                (Comparison::GreaterEqual, _) => "hs",
+            };
+            writeln!(g.output, "cmp\t{r}11, {r}0")?;
+            writeln!(g.output, "cset\tw0, {cond}")
         }
 
         // logical‑and / or are handled earlier in generate_expr
@@ -163,23 +770,109 @@ fn emit_binop(g: &mut Generator, op: BinaryOp) -> fmt::Result {
     }
 }
 
+/// Where an argument of a call is passed.
+enum ArgLocation {
+    /// general-purpose (`x`/`w`) or floating-point (`d`/`s`) register number
+    Register(usize),
+    /// byte offset into the outgoing argument area at `sp`
+    Stack(i32),
+}
+
+/// Assign AAPCS64 locations to arguments of types `params`, of which
+/// the ones past the first `named` are the variadic part of the call.
+/// Returns the locations and the end of the last stack argument.
+///
+/// Integer and pointer arguments use x0…x7, floating-point ones d0…d7.
+/// Linux puts the rest into 8-byte stack slots, while Apple packs them
+/// by their natural size and alignment. Apple passes all variadic
+/// arguments on the stack, in 8-byte slots.
+fn assign_arg_locations(params: &[Type], named: usize, platform: &str) -> (Vec<ArgLocation>, i32) {
+    let (mut next_gp, mut next_fp) = (0, 0);
+    let mut stack = 0;
+    let locations = params
+        .iter()
+        .enumerate()
+        .map(|(i, ty)| {
+            let apple_variadic = platform == "macos" && i >= named;
+            let next = if ty.is_floating() {
+                &mut next_fp
+            } else {
+                &mut next_gp
+            };
+            if *next < 8 && !apple_variadic {
+                *next += 1;
+                return ArgLocation::Register(*next - 1);
+            }
+            let (size, align) = match ty.unqualified() {
+                Type::VaList => (8, 8), // passed as a pointer
+                ty if platform == "macos" && !apple_variadic => (ty.size(), ty.alignment()),
+                _ => (8, 8),
+            };
+            let offset = (stack + align - 1) / align * align;
+            stack = offset + size;
+            ArgLocation::Stack(offset)
+        })
+        .collect();
+    (locations, stack)
+}
+
+/// Type of the function called through `callee`: a function designator or a function pointer.
+fn callee_type(g: &Generator, callee: &Expr) -> Result<FunctionType, Box<dyn Error>> {
+    let ty = type_of(g, callee)?;
+    match ty.callee() {
+        Some(func) => Ok(func.clone()),
+        None => {
+            Err(format!("called object type '{ty}' is not a function or function pointer").into())
+        }
+    }
+}
+
 /// Emit a *complete* function call, including alignment padding,
 /// argument evaluation, the `bl`, and stack clean‑up.
 ///
-///  • `args` are evaluated **left‑to‑right** exactly once each.
-///  • The first 8 results go to  w0…w7, the rest are pushed (right‑to‑left).
-fn emit_fun_call(g: &mut Generator, name: &str, args: &[Expr]) -> Result<(), Box<dyn Error>> {
+///  • `args` are converted to the parameter types of the callee's prototype;
+///    the ones without a parameter undergo the default argument promotions.
+///  • Arguments go where [`assign_arg_locations`] puts them; stack arguments
+///    are stored into an area reserved at `sp` before the registers are filled.
+///  • Register arguments are evaluated into temporaries first, so a nested call
+///    can't clobber argument registers that were already filled.
+///  • A function named by `callee` is called with `bl`, anything else is a
+///    function pointer, evaluated last and called with `blr`.
+fn emit_fun_call(g: &mut Generator, callee: &Expr, args: &[Expr]) -> Result<(), Box<dyn Error>> {
     // On OS X, the stack needs to be 16-byte aligned when the call instruction is issued
 
     // align stack before pushing args
-    // push args (right-to-left)
+    // reserve the argument area and store stack args into it
+    // evaluate register args into temporaries, then pop them into their registers
     // emit bl _func
     // cleanup stack (args + padding)
 
-    let n = args.len();
-    // # stack args = anything beyond the first 8
-    let num_stack_args = n.saturating_sub(8);
-    let arg_stack_size = 16 * num_stack_args;
+    let signature = callee_type(g, callee)?;
+    // an object in scope hides the function of the same name
+    let direct = match callee {
+        Expr::Var(name) if lvalue_type(g, callee)?.is_function() => Some(name),
+        _ => None,
+    };
+    let callee_name = direct.cloned().unwrap_or_else(|| source_text(callee));
+    check_call_arity(&callee_name, &signature, args.len())?;
+
+    let mut arg_types = Vec::with_capacity(args.len());
+    for (i, arg) in args.iter().enumerate() {
+        arg_types.push(match signature.params.get(i) {
+            Some(ty) => ty.clone(),
+            None => default_promoted(&type_of(g, arg)?),
+        });
+    }
+
+    let named = if signature.variadic {
+        signature.params.len()
+    } else {
+        args.len() // calls without a prototype pass everything like named arguments
+    };
+    let (locations, stack_end) = assign_arg_locations(&arg_types, named, &g.platform);
+
+    // sp must stay 16-byte aligned at the call
+    let arg_stack_size = (stack_end + 15) / 16 * 16;
 
     writeln!(g.output, "mov\tx9, sp")?; // x9 will track the future sp
     writeln!(g.output, "sub\tx9, x9, #{arg_stack_size}")?; // simulate: sp - (args + padding marker)
@@ -187,71 +880,138 @@ fn emit_fun_call(g: &mut Generator, name: &str, args: &[Expr]) -> Result<(), Box
     writeln!(g.output, "sub\tsp, sp, x10")?; // subtract misalignment to align
     writeln!(g.output, "str\tx10, [sp, #-16]!")?; // save the padding value (push it)
 
-    for (rev_i, param) in args.iter().rev().enumerate() {
-        generate_expr(g, param)?; // result in w0
+    if arg_stack_size > 0 {
+        emit_sp_adjust(g.output, "sub", arg_stack_size)?;
+    }
 
-        // real register index for this argument
-        let reg = n - 1 - rev_i; // 0 = first param, 1 = second, …
+    let params = args.iter().zip(&arg_types).zip(&locations);
 
-        if reg < 8 {
-            writeln!(g.output, "mov\tw{}, w0", reg)?; // copy into its ABI register
-        } else {
-            // 9‑th and later: go on the stack, highest‑index first
-            writeln!(g.output, "str\tw0, [sp, #-16]!")?;
+    // stack arguments go first; temporaries of their evaluation live below the area
+    for ((arg, ty), l) in params.clone() {
+        if let ArgLocation::Stack(offset) = l {
+            generate_argument(g, arg, ty)?;
+            let (store, r) = (store_instr(ty), reg_prefix(ty));
+            writeln!(g.output, "{store}\t{r}0, [sp, #{offset}]")?;
         }
     }
 
-    let prefix = function_label_prefix(&g.platform)?;
+    let register_args: Vec<_> = params
+        .filter_map(|((arg, ty), l)| match l {
+            ArgLocation::Register(reg) => Some((arg, ty, *reg)),
+            ArgLocation::Stack(_) => None,
+        })
+        .collect();
+
+    for (arg, ty, _) in &register_args {
+        generate_argument(g, arg, ty)?; // result in w0/x0/s0/d0
+        writeln!(g.output, "str\t{}0, [sp, #-16]!", reg_prefix(ty))?;
+    }
+    if direct.is_none() {
+        generate_expr(g, callee)?;
+        writeln!(g.output, "mov\tx16, x0")?; // untouched by the pops below
+    }
+    for (_, ty, reg) in register_args.iter().rev() {
+        writeln!(g.output, "ldr\t{}{reg}, [sp], #16", reg_prefix(ty))?; // into its ABI register
+    }
 
-    writeln!(g.output, "bl\t{prefix}{name}")?;
+    match direct {
+        Some(name) => {
+            let prefix = function_label_prefix(&g.platform)?;
+            writeln!(g.output, "bl\t{prefix}{name}")?;
+        }
+        None => writeln!(g.output, "blr\tx16")?,
+    }
 
-    writeln!(g.output, "add\tsp, sp, #{arg_stack_size}")?; // remove args
+    if arg_stack_size > 0 {
+        emit_sp_adjust(g.output, "add", arg_stack_size)?; // remove args
+    }
 
     writeln!(g.output, "ldr\tx9, [sp], #16")?; // pop off the padding
     writeln!(g.output, "add\tsp, sp, x9")?; // apply padding
 
+    // the callee leaves the upper bits of narrow return values unspecified
+    emit_extend_narrow(g, &signature.return_type)?;
+
     Ok(())
 }
 
 fn generate_expr(g: &mut Generator, expr: &Expr) -> Result<(), Box<dyn Error>> {
     match expr {
-        Expr::Const(n) => {
-            writeln!(g.output, "mov\tw0, #{n}")?;
+        Expr::Const(c) => {
+            emit_constant(g, c)?;
         }
         Expr::Var(name) => {
-            let var = g
-                .allocator
-                .get(name)
-                .ok_or_else(|| format!("variable {name} not found"))?;
-            var.emit_store_in_w0(g.output, &g.platform)?;
+            let ty = lvalue_type(g, expr)?;
+            if ty.is_array() || ty.is_function() {
+                emit_address(g, expr)?; // decays to a pointer to its first element / the function
+            } else if ty.qualifiers().is_atomic {
+                emit_address(g, expr)?;
+                emit_atomic_load(g, &ty.decay(), MemoryOrder::SeqCst)?;
+            } else {
+                let var = g
+                    .allocator
+                    .get(name)
+                    .ok_or_else(|| format!("variable {name} not found"))?;
+                // a variable-length array holds the address of its first element
+                var.emit_load(&ty.decay(), g.output, &g.platform)?;
+            }
+        }
+        Expr::Deref(_) => {
+            let ty = type_of(g, expr)?;
+            emit_address(g, expr)?;
+            let designated = lvalue_type(g, expr)?;
+            if designated.qualifiers().is_atomic {
+                emit_atomic_load(g, &ty, MemoryOrder::SeqCst)?;
+            } else if !designated.is_array() && !designated.is_function() {
+                writeln!(g.output, "{}\t{}0, [x0]", load_instr(&ty), reg_prefix(&ty))?;
+            }
         }
+        Expr::AddrOf(inner) => emit_address(g, inner)?,
         Expr::UnOp(op, inner) => {
+            let ty = type_of(g, inner)?;
+            let promoted = ty.promoted();
+
             generate_expr(g, inner)?; // recursively evaluate into w0
 
             match op {
-                UnaryOp::Neg => writeln!(g.output, "neg\tw0, w0")?,
-                UnaryOp::BitNot => writeln!(g.output, "mvn\tw0, w0")?,
+                UnaryOp::Neg if ty.is_arithmetic() => {
+                    emit_convert(g, &ty, &promoted)?;
+                    let r = reg_prefix(&promoted);
+                    if promoted.is_floating() {
+                        writeln!(g.output, "fneg\t{r}0, {r}0")?
+                    } else {
+                        writeln!(g.output, "neg\t{r}0, {r}0")?
+                    }
+                }
+                UnaryOp::BitNot if ty.is_integer() => {
+                    emit_convert(g, &ty, &promoted)?;
+                    let r = reg_prefix(&promoted);
+                    writeln!(g.output, "mvn\t{r}0, {r}0")?
+                }
                 UnaryOp::Not => {
                     // sets condition flags
-                    writeln!(g.output, "cmp\tw0, #0")?;
+                    emit_compare_zero(g, &ty)?;
                     // clear w0
                     writeln!(g.output, "mov\tw0, #0")?;
                     // set w0 = 1 if w0 was equal to 0
                     writeln!(g.output, "cset\tw0, eq")?;
                 }
+                _ => return Err(format!("invalid operand to unary {op} ({ty})").into()),
             }
         }
         BinOp(BinaryOp::LogicalOr, lhs, rhs) => {
             let true_clause = g.labels.next("or_true");
             let end_clause = g.labels.next("or_end");
 
+            let (lt, rt) = (type_of(g, lhs)?, type_of(g, rhs)?);
+
             generate_expr(g, lhs)?; // result in w0
 
-            writeln!(g.output, "cmp\tw0, #0")?; // check if lhs is true (non-zero)
+            emit_compare_zero(g, &lt)?; // check if lhs is true (non-zero)
             writeln!(g.output, "b.ne\t{true_clause}",)?; // if lhs != 0, short-circuit: result is true
 
             generate_expr(g, rhs)?; // result in w0
-            writeln!(g.output, "cmp\tw0, #0")?; // check if rhs is true (non-zero)
+            emit_compare_zero(g, &rt)?; // check if rhs is true (non-zero)
             writeln!(g.output, "cset\tw0, ne")?; // w0 = 1 if rhs != 0, else 0
             writeln!(g.output, "b\t{end_clause}",)?;
 
@@ -263,13 +1023,15 @@ fn generate_expr(g: &mut Generator, expr: &Expr) -> Result<(), Box<dyn Error>> {
             let false_clause = g.labels.next("and_false");
             let end_clause = g.labels.next("and_end");
 
+            let (lt, rt) = (type_of(g, lhs)?, type_of(g, rhs)?);
+
             generate_expr(g, lhs)?; // result in w0
 
-            writeln!(g.output, "cmp\tw0, #0")?; // check if lhs is false (zero)
+            emit_compare_zero(g, &lt)?; // check if lhs is false (zero)
             writeln!(g.output, "b.eq\t{false_clause}",)?; // if lhs == 0, short-circuit: result is false
 
             generate_expr(g, rhs)?; // result in w0
-            writeln!(g.output, "cmp\tw0, #0")?; // check if rhs is true (non-zero)
+            emit_compare_zero(g, &rt)?; // check if rhs is true (non-zero)
             writeln!(g.output, "cset\tw0, ne")?; // w0 = 1 if rhs != 0, else 0
             writeln!(g.output, "b\t{end_clause}",)?;
 
@@ -280,68 +1042,1234 @@ fn generate_expr(g: &mut Generator, expr: &Expr) -> Result<(), Box<dyn Error>> {
         BinOp(op, lhs, rhs) => {
             // because registers w0–w7 are reserved for the argument list,
             // we must not use any of them as temporaries
-            // use w11
+            // use w11 (d16 for floating-point)
+
+            let (lt, rt) = (type_of(g, lhs)?, type_of(g, rhs)?);
+            if matches!(op, BinaryOp::Add | BinaryOp::Sub) && (lt.is_pointer() || rt.is_pointer()) {
+                return emit_pointer_arithmetic(g, *op, (lhs, &lt), (rhs, &rt));
+            }
+            let ty = binop_operand_type(*op, (lhs, &lt), (rhs, &rt))?;
+            let r = reg_prefix(&ty);
 
             generate_expr(g, lhs)?;
-            writeln!(g.output, "str\tw0, [sp, #-16]!")?; // push lhs (keep 16-byte align)
+            emit_convert(g, &lt, &ty)?;
+            writeln!(g.output, "str\t{r}0, [sp, #-16]!")?; // push lhs (keep 16-byte align)
 
             generate_expr(g, rhs)?;
-            writeln!(g.output, "ldr\tw11, [sp], #16")?; /* lhs → w1 */
+            emit_convert(g, &rt, &ty)?;
+            let tmp = if ty.is_floating() { 16 } else { 11 };
+            writeln!(g.output, "ldr\t{r}{tmp}, [sp], #16")?; /* lhs → w11 */
 
             // w0 - result of evaluating rhs
             // w11 - result of evaluating lhs
 
-            emit_binop(g, *op)?;
+            emit_binop(g, *op, &ty)?;
         }
-        Assign(name, expr) => {
-            let var = {
-                g.allocator
+        Assign(target, expr) => {
+            let declared = lvalue_type(g, target)?;
+            check_assignable(target, &declared)?;
+            let ty = declared.unqualified().clone();
+
+            if declared.qualifiers().is_atomic {
+                emit_atomic_assign(g, target, expr, &ty)?;
+            } else if let Expr::Var(name) = target.as_ref() {
+                let var = g
+                    .allocator
                     .get(name)
                     .cloned()
-                    .ok_or_else(|| format!("assignment to undeclared variable '{}'", name))?
-            };
-            generate_expr(g, expr)?;
-            var.emit_store_from_w0(g.output, &g.platform)?
-        } // op => panic!("op {op} is not supported"),
+                    .ok_or_else(|| format!("assignment to undeclared variable '{}'", name))?;
+                generate_expr_as(g, expr, &ty)?;
+                var.emit_store(&ty, 0, g.output, &g.platform)?
+            } else {
+                emit_address(g, target)?;
+                writeln!(g.output, "str\tx0, [sp, #-16]!")?; // push the address
+                generate_expr_as(g, expr, &ty)?;
+                writeln!(g.output, "ldr\tx1, [sp], #16")?;
+                writeln!(g.output, "{}\t{}0, [x1]", store_instr(&ty), reg_prefix(&ty))?;
+            }
+        }
 
         Conditional { cond, then, els } => {
             let else_label = g.labels.next("_else");
             let post_conditional = g.labels.next("_post_conditional");
 
+            let cond_ty = type_of(g, cond)?;
+            // `cond ?: els` is `cond ? cond : els` with cond evaluated once
+            let then_or_cond = then.as_deref().unwrap_or(cond);
+            let (tt, et) = (type_of(g, then_or_cond)?, type_of(g, els)?);
+            let ty = conditional_type((then_or_cond, &tt), (els, &et))?;
+
             generate_expr(g, cond)?; // evaluate cond (e1)
-            writeln!(g.output, "cmp\tw0, #0")?; // compare e1 cond zero
+            emit_compare_zero(g, &cond_ty)?; // compare e1 cond zero
             writeln!(g.output, "beq\t{else_label}")?; // if e1 == 0 (false), jump to else (e3)
 
-            generate_expr(g, then)?; // evaluate e2
+            if let Some(then) = then {
+                generate_expr(g, then)?; // evaluate e2
+            } // otherwise the value of e1 is still in x0
+            emit_convert(g, &tt, &ty)?;
             writeln!(g.output, "b\t{post_conditional}")?; // skip e3
 
             writeln!(g.output, "{else_label}:")?;
             generate_expr(g, els)?; // evaluate else (e3)
+            emit_convert(g, &et, &ty)?;
 
             writeln!(g.output, "{post_conditional}:")?;
         }
 
-        FunCall { name, parameters } => emit_fun_call(g, name, parameters)?,
+        // a block whose last statement leaves its value in x0
+        Expr::Block(items) => {
+            // the enclosing expression has pushed temporaries, which restoring sp
+            // at the end of a block would lose
+            if items_contain_var_array(items) {
+                return Err(
+                    "variable-length array in a statement expression is not supported".into(),
+                );
+            }
+            g.allocator.enter_scope();
+            for item in items {
+                generate_block_item(g, item)?;
+            }
+            g.allocator.exit_scope();
+        }
+
+        FunCall { name, parameters } => emit_fun_call(g, &Expr::Var(name.clone()), parameters)?,
+        Expr::IndirectCall { callee, parameters } => emit_fun_call(g, callee, parameters)?,
+
+        Expr::Cast(ty, inner) => {
+            let from = type_of(g, inner)?;
+            check_cast(&from, ty)?;
+            generate_expr(g, inner)?;
+            emit_convert(g, &from, ty.unqualified())?;
+        }
+
+        Expr::VaStart(ap) => emit_va_start(g, ap)?,
+        Expr::VaArg(ap, ty) => emit_va_arg(g, ap, ty)?,
+        Expr::VaEnd(_) => {
+            type_of(g, expr)?; // nothing to release
+        }
+        Expr::VaCopy(dest, src) => emit_va_copy(g, dest, src)?,
+        Expr::Alloca(size) => {
+            type_of(g, expr)?;
+            generate_expr_as(g, size, &Type::ULong)?;
+            emit_dynamic_alloc(g)?;
+        }
+        Expr::Builtin(builtin, args) => {
+            type_of(g, expr)?;
+            emit_builtin(g, *builtin, args)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Default argument promotions, applied to arguments that have no parameter:
+/// integer promotion, and `float` becomes `double`.
+fn default_promoted(ty: &Type) -> Type {
+    match ty.unqualified() {
+        Type::Float => Type::Double,
+        ty => ty.promoted(),
+    }
+}
+
+/// Evaluate the argument `arg` passed for a parameter of type `ty`.
+fn generate_argument(g: &mut Generator, arg: &Expr, ty: &Type) -> Result<(), Box<dyn Error>> {
+    if *ty.unqualified() != Type::VaList {
+        return generate_expr_as(g, arg, ty);
+    }
+    emit_va_list_address(g, arg)?;
+    if g.platform == "macos" {
+        writeln!(g.output, "ldr\tx0, [x0]")?; // Apple's va_list is the pointer itself
+    }
+    Ok(())
+}
+
+/// Type a parameter is stored as inside the function. On Linux `va_list` is an
+/// array type, so a `va_list` parameter is a pointer to the caller's object.
+fn parameter_storage_type(ty: &Type, platform: &str) -> Type {
+    match ty.unqualified() {
+        Type::VaList if platform == "linux" => Type::Pointer(Box::new(Type::VaList)),
+        _ => ty.clone(),
+    }
+}
+
+/// Checks that `ap` designates a `va_list`. Returns `true` if it is
+/// a (Linux) `va_list` parameter, which holds a pointer to the object.
+fn va_list_is_pointer(g: &Generator, ap: &Expr) -> Result<bool, Box<dyn Error>> {
+    match type_of(g, ap)? {
+        Type::VaList => Ok(false),
+        Type::Pointer(inner) if *inner.unqualified() == Type::VaList => Ok(true),
+        other => Err(format!("expected 'va_list', found '{other}'").into()),
+    }
+}
+
+/// Variadic arguments undergo the default argument promotions, so only
+/// promoted scalar types can be read back.
+fn check_va_arg_type(ty: &Type) -> Result<(), Box<dyn Error>> {
+    if !ty.is_scalar() {
+        return Err(
+            format!("second argument to 'va_arg' must be a scalar type, not '{ty}'").into(),
+        );
+    }
+    if ty.promoted() != *ty.unqualified() || *ty.unqualified() == Type::Float {
+        return Err(format!("second argument to 'va_arg' is of promotable type '{ty}'").into());
+    }
+    Ok(())
+}
+
+/// Compute the address of the `va_list` object designated by `ap` into x0.
+fn emit_va_list_address(g: &mut Generator, ap: &Expr) -> Result<(), Box<dyn Error>> {
+    if va_list_is_pointer(g, ap)? {
+        generate_expr(g, ap)
+    } else {
+        emit_address(g, ap)
+    }
+}
+
+/// `va_start(ap)`: point `ap` at the first unnamed argument.
+fn emit_va_start(g: &mut Generator, ap: &Expr) -> Result<(), Box<dyn Error>> {
+    let Some(VarArgs {
+        save_area,
+        named_gp,
+        named_fp,
+        named_stack,
+    }) = g.varargs
+    else {
+        return Err("'va_start' used in function with fixed arguments".into());
+    };
+
+    emit_va_list_address(g, ap)?;
+
+    // unnamed arguments passed on the stack follow the named ones, 8-byte aligned
+    let stack = 16 + (named_stack + 7) / 8 * 8;
+    writeln!(g.output, "add\tx9, x29, #{stack}")?;
+    writeln!(g.output, "str\tx9, [x0]")?; // __stack (the whole va_list on macOS)
+
+    if let Some(base) = save_area {
+        emit_frame_address(g.output, "x9", base + VA_SAVE_AREA_SIZE)?;
+        writeln!(g.output, "str\tx9, [x0, #8]")?; // __gr_top
+        emit_frame_address(g.output, "x9", base + 8 * 16)?;
+        writeln!(g.output, "str\tx9, [x0, #16]")?; // __vr_top
+        writeln!(g.output, "mov\tw9, #{}", -8 * (8 - named_gp as i32))?;
+        writeln!(g.output, "str\tw9, [x0, #24]")?; // __gr_offs
+        writeln!(g.output, "mov\tw9, #{}", -16 * (8 - named_fp as i32))?;
+        writeln!(g.output, "str\tw9, [x0, #28]")?; // __vr_offs
+    }
+    Ok(())
+}
+
+/// `va_arg(ap, ty)`: load the next unnamed argument and advance `ap`.
+fn emit_va_arg(g: &mut Generator, ap: &Expr, ty: &Type) -> Result<(), Box<dyn Error>> {
+    check_va_arg_type(ty)?;
+    emit_va_list_address(g, ap)?;
+    writeln!(g.output, "mov\tx1, x0")?;
+
+    let done = g.labels.next("va_arg_done");
+    if g.platform == "linux" {
+        // take the argument from the register save area while registers of its class are left
+        let (offs, top, step) = if ty.is_floating() {
+            (28, 16, 16)
+        } else {
+            (24, 8, 8)
+        };
+        let on_stack = g.labels.next("va_arg_stack");
+        writeln!(g.output, "ldrsw\tx9, [x1, #{offs}]")?;
+        writeln!(g.output, "cmp\tw9, #0")?;
+        writeln!(g.output, "b.ge\t{on_stack}")?;
+        writeln!(g.output, "add\tw10, w9, #{step}")?;
+        writeln!(g.output, "str\tw10, [x1, #{offs}]")?;
+        writeln!(g.output, "ldr\tx10, [x1, #{top}]")?;
+        writeln!(g.output, "add\tx0, x10, x9")?;
+        writeln!(g.output, "b\t{done}")?;
+        writeln!(g.output, "{on_stack}:")?;
+    }
+
+    // every stacked argument occupies an 8-byte slot
+    writeln!(g.output, "ldr\tx0, [x1]")?;
+    writeln!(g.output, "add\tx10, x0, #8")?;
+    writeln!(g.output, "str\tx10, [x1]")?;
+    writeln!(g.output, "{done}:")?;
+    writeln!(g.output, "{}\t{}0, [x0]", load_instr(ty), reg_prefix(ty))?;
+    Ok(())
+}
+
+/// `va_copy(dest, src)`: duplicate the traversal state.
+fn emit_va_copy(g: &mut Generator, dest: &Expr, src: &Expr) -> Result<(), Box<dyn Error>> {
+    emit_va_list_address(g, src)?;
+    writeln!(g.output, "str\tx0, [sp, #-16]!")?;
+    emit_va_list_address(g, dest)?;
+    writeln!(g.output, "ldr\tx1, [sp], #16")?;
+    if g.platform == "linux" {
+        writeln!(g.output, "ldp\tx9, x10, [x1]")?;
+        writeln!(g.output, "stp\tx9, x10, [x0]")?;
+        writeln!(g.output, "ldp\tx9, x10, [x1, #16]")?;
+        writeln!(g.output, "stp\tx9, x10, [x0, #16]")?;
+    } else {
+        writeln!(g.output, "ldr\tx9, [x1]")?;
+        writeln!(g.output, "str\tx9, [x0]")?;
+    }
+    Ok(())
+}
+
+/// Evaluate a call of `builtin` with checked arguments, without calling anything.
+fn emit_builtin(g: &mut Generator, builtin: Builtin, args: &[Expr]) -> Result<(), Box<dyn Error>> {
+    if let Some(op) = builtin.overflow_op() {
+        return emit_overflow_builtin(g, builtin, op, args);
+    }
+    if builtin.is_atomic() {
+        return emit_atomic_builtin(g, builtin, args);
+    }
+    let params = builtin
+        .function_type()
+        .map_or_else(Vec::new, |ty| ty.params);
+    match builtin {
+        Builtin::Unreachable | Builtin::Trap => {
+            writeln!(g.output, "brk\t#0x3e8")?;
+            return Ok(());
+        }
+        Builtin::Expect => {
+            // both operands are evaluated, the expected value is only a hint
+            generate_expr_as(g, &args[0], &params[0])?;
+            writeln!(g.output, "str\tx0, [sp, #-16]!")?;
+            generate_expr_as(g, &args[1], &params[1])?;
+            writeln!(g.output, "ldr\tx0, [sp], #16")?;
+            return Ok(());
+        }
+        _ => generate_expr_as(g, &args[0], &params[0])?,
+    }
+
+    let r = reg_prefix(&params[0]);
+    match builtin {
+        Builtin::Clz | Builtin::ClzLong => writeln!(g.output, "clz\t{r}0, {r}0")?,
+        Builtin::Ctz | Builtin::CtzLong => {
+            writeln!(g.output, "rbit\t{r}0, {r}0")?;
+            writeln!(g.output, "clz\t{r}0, {r}0")?;
+        }
+        Builtin::Popcount | Builtin::PopcountLong => {
+            // count the bits of each byte in a vector register, then add the bytes up
+            let v = if r == "w" { "s" } else { "d" };
+            writeln!(g.output, "fmov\t{v}0, {r}0")?;
+            writeln!(g.output, "cnt\tv0.8b, v0.8b")?;
+            writeln!(g.output, "addv\tb0, v0.8b")?;
+            writeln!(g.output, "fmov\tw0, s0")?;
+        }
+        Builtin::Bswap32 | Builtin::Bswap64 => writeln!(g.output, "rev\t{r}0, {r}0")?,
+        _ => unreachable!("handled above"),
+    }
+    Ok(())
+}
+
+/// Type the result of an overflow-checking builtin is converted to: the pointee of
+/// the third operand, or for the `_p` variants the type of the third operand itself.
+fn overflow_result_type(
+    g: &Generator,
+    builtin: Builtin,
+    args: &[Expr],
+) -> Result<Type, Box<dyn Error>> {
+    let name = builtin.name();
+    for (i, arg) in args[..2].iter().enumerate() {
+        if !type_of(g, arg)?.is_integer() {
+            return Err(format!(
+                "argument {} in call to function '{name}' does not have integral type",
+                i + 1
+            )
+            .into());
+        }
+    }
+
+    let ty = type_of(g, &args[2])?;
+    if !builtin.stores_result() {
+        return match ty.is_integer() {
+            true => Ok(ty),
+            false => Err(format!(
+                "argument 3 in call to function '{name}' does not have integral type"
+            )
+            .into()),
+        };
+    }
+    match &ty {
+        Type::Pointer(pointee) if pointee.unqualified().is_integer() => {
+            if pointee.qualifiers().is_const {
+                return Err(format!(
+                    "argument 3 in call to function '{name}' has pointer to const type ('{ty}')"
+                )
+                .into());
+            }
+            Ok(pointee.unqualified().clone())
+        }
+        _ => Err(format!(
+            "argument 3 in call to function '{name}' does not have pointer to integral type"
+        )
+        .into()),
+    }
+}
+
+/// `__builtin_{add,sub,mul}_overflow[_p]`: the overflow flag in w0, the result is
+/// stored through the third operand unless it only gives the type.
+///
+/// Operands of the result type use the flags of `adds`/`subs` or the high half of
+/// the product. Other combinations compute the exact 128-bit value in x10:x9 and
+/// check that converting it to the result type and back gives the same value.
+fn emit_overflow_builtin(
+    g: &mut Generator,
+    builtin: Builtin,
+    op: BinaryOp,
+    args: &[Expr],
+) -> Result<(), Box<dyn Error>> {
+    let ty = overflow_result_type(g, builtin, args)?;
+    let (ta, tb) = (
+        type_of(g, &args[0])?.promoted(),
+        type_of(g, &args[1])?.promoted(),
+    );
+    let wide = |t: &Type| {
+        if t.is_signed() {
+            Type::Long
+        } else {
+            Type::ULong
+        }
+    };
+
+    let native = ta == ty && tb == ty && ty.size() >= 4;
+    let (ta, tb) = if native {
+        (ty.clone(), ty.clone())
+    } else {
+        (wide(&ta), wide(&tb))
+    };
+    generate_expr_as(g, &args[0], &ta)?;
+    writeln!(g.output, "str\tx0, [sp, #-16]!")?;
+    generate_expr_as(g, &args[1], &tb)?;
+    if builtin.stores_result() {
+        writeln!(g.output, "str\tx0, [sp, #-16]!")?;
+        generate_expr(g, &args[2])?;
+        writeln!(g.output, "mov\tx2, x0")?;
+        writeln!(g.output, "ldr\tx0, [sp], #16")?;
+    }
+    writeln!(g.output, "ldr\tx11, [sp], #16")?;
+
+    if native {
+        emit_native_overflow(g, op, &ty)?;
+    } else {
+        emit_wide_overflow(g, op, ta.is_signed(), tb.is_signed(), &ty)?;
+    }
+
+    if builtin.stores_result() {
+        writeln!(g.output, "{}\t{}0, [x2]", store_instr(&ty), reg_prefix(&ty))?;
+    }
+    writeln!(g.output, "mov\tw0, w1")?;
+    Ok(())
+}
+
+/// `x11 op x0` where both operands and the result have the type `ty`, of 32 or 64 bits.
+/// Leaves the result in x0 and the overflow flag in w1.
+fn emit_native_overflow(g: &mut Generator, op: BinaryOp, ty: &Type) -> fmt::Result {
+    let r = reg_prefix(ty);
+    let signed = ty.is_signed();
+    match op {
+        BinaryOp::Add => {
+            writeln!(g.output, "adds\t{r}0, {r}11, {r}0")?;
+            writeln!(g.output, "cset\tw1, {}", if signed { "vs" } else { "cs" })
+        }
+        BinaryOp::Sub => {
+            // the carry flag is clear on an unsigned borrow
+            writeln!(g.output, "subs\t{r}0, {r}11, {r}0")?;
+            writeln!(g.output, "cset\tw1, {}", if signed { "vs" } else { "cc" })
+        }
+        _ if r == "w" => {
+            // the 64-bit product must survive the conversion back from 32 bits
+            let (mul, extend) = if signed {
+                ("smull", "sxtw")
+            } else {
+                ("umull", "uxtw")
+            };
+            writeln!(g.output, "{mul}\tx0, w11, w0")?;
+            writeln!(g.output, "{extend}\tx9, w0")?;
+            writeln!(g.output, "cmp\tx0, x9")?;
+            writeln!(g.output, "cset\tw1, ne")
+        }
+        _ => {
+            // the high half must be the sign (or zero) extension of the low half
+            writeln!(
+                g.output,
+                "{}\tx9, x11, x0",
+                if signed { "smulh" } else { "umulh" }
+            )?;
+            writeln!(g.output, "mul\tx0, x11, x0")?;
+            if signed {
+                writeln!(g.output, "cmp\tx9, x0, asr #63")?;
+            } else {
+                writeln!(g.output, "cmp\tx9, #0")?;
+            }
+            writeln!(g.output, "cset\tw1, ne")
+        }
+    }
+}
+
+/// `x11 op x0` on 64-bit operands of the given signedness, converted to `ty`.
+/// Leaves the converted result in x0 and the overflow flag in w1.
+fn emit_wide_overflow(
+    g: &mut Generator,
+    op: BinaryOp,
+    a_signed: bool,
+    b_signed: bool,
+    ty: &Type,
+) -> fmt::Result {
+    // high half of `src` extended to 128 bits
+    let high = |signed: bool, dst: &str, src: &str| {
+        if signed {
+            format!("asr\t{dst}, {src}, #63")
+        } else {
+            format!("mov\t{dst}, #0")
+        }
+    };
+    writeln!(g.output, "{}", high(a_signed, "x12", "x11"))?;
+    writeln!(g.output, "{}", high(b_signed, "x13", "x0"))?;
+
+    match op {
+        BinaryOp::Add => {
+            writeln!(g.output, "adds\tx9, x11, x0")?;
+            writeln!(g.output, "adc\tx10, x12, x13")?;
+        }
+        BinaryOp::Sub => {
+            writeln!(g.output, "subs\tx9, x11, x0")?;
+            writeln!(g.output, "sbc\tx10, x12, x13")?;
+        }
+        _ => {
+            // the unsigned product, corrected for each negative signed operand
+            writeln!(g.output, "mul\tx9, x11, x0")?;
+            writeln!(g.output, "umulh\tx10, x11, x0")?;
+            writeln!(g.output, "and\tx12, x12, x0")?;
+            writeln!(g.output, "sub\tx10, x10, x12")?;
+            writeln!(g.output, "and\tx13, x13, x11")?;
+            writeln!(g.output, "sub\tx10, x10, x13")?;
+        }
+    }
+
+    // convert the low half to `ty` and back into x14, with its high half in x15
+    match (ty.size(), ty.is_signed()) {
+        (8, _) => writeln!(g.output, "mov\tx14, x9")?,
+        (4, true) => writeln!(g.output, "sxtw\tx14, w9")?,
+        (4, false) => writeln!(g.output, "mov\tw14, w9")?,
+        (2, true) => writeln!(g.output, "sxth\tx14, w9")?,
+        (2, false) => writeln!(g.output, "and\tx14, x9, #0xffff")?,
+        (_, true) => writeln!(g.output, "sxtb\tx14, w9")?,
+        (_, false) => writeln!(g.output, "and\tx14, x9, #0xff")?,
+    }
+    writeln!(g.output, "{}", high(ty.is_signed(), "x15", "x14"))?;
+
+    writeln!(g.output, "eor\tx9, x9, x14")?;
+    writeln!(g.output, "eor\tx10, x10, x15")?;
+    writeln!(g.output, "orr\tx9, x9, x10")?;
+    writeln!(g.output, "cmp\tx9, #0")?;
+    writeln!(g.output, "cset\tw1, ne")?;
+    writeln!(g.output, "mov\tx0, x14")
+}
+
+/// Compute the address of the object designated by the lvalue `expr` into x0.
+/// Size suffix of the byte and halfword forms of loads and stores (`ldarb`, `stlxrh`, ...).
+fn access_size_suffix(ty: &Type) -> &'static str {
+    match ty.size() {
+        1 => "b",
+        2 => "h",
+        _ => "",
+    }
+}
+
+/// Type of the object the first operand of an `__atomic` builtin points to.
+fn atomic_object_type(
+    g: &Generator,
+    builtin: Builtin,
+    args: &[Expr],
+) -> Result<Type, Box<dyn Error>> {
+    let name = builtin.name();
+    let ty = type_of(g, &args[0])?;
+    let bitwise = matches!(
+        builtin.fetch_op(),
+        Some(BinaryOp::And | BinaryOp::Or | BinaryOp::Xor)
+    );
+    match &ty {
+        Type::Pointer(pointee) if pointee.is_integer() || (pointee.is_pointer() && !bitwise) => {
+            if pointee.qualifiers().is_const && builtin != Builtin::AtomicLoad {
+                return Err(
+                    format!("argument 1 of '{name}' has pointer to const type ('{ty}')").into(),
+                );
+            }
+            Ok(pointee.unqualified().clone())
+        }
+        _ if bitwise => Err(format!(
+            "argument 1 of '{name}' must be a pointer to an integer, found '{ty}'"
+        )
+        .into()),
+        _ => Err(format!(
+            "argument 1 of '{name}' must be a pointer to an integer or pointer, found '{ty}'"
+        )
+        .into()),
+    }
+}
+
+/// Memory orders of an `__atomic` builtin: the order of the operation, and for a
+/// compare-exchange the order of the load when the comparison fails.
+fn atomic_orders(
+    builtin: Builtin,
+    args: &[Expr],
+) -> Result<(MemoryOrder, MemoryOrder), Box<dyn Error>> {
+    let name = builtin.name();
+    let order = |arg: &Expr| -> Result<MemoryOrder, Box<dyn Error>> {
+        let value = evaluate_compile_time_expr(arg)
+            .map_err(|_| format!("memory order argument to '{name}' is not a constant"))?;
+        if !value.ty().is_integer() {
+            return Err(
+                format!("memory order argument to '{name}' does not have integral type").into(),
+            );
+        }
+        MemoryOrder::from_value(value.as_i128()).ok_or_else(|| {
+            format!(
+                "invalid memory model argument {} to '{name}'",
+                value.as_i128()
+            )
+            .into()
+        })
+    };
+    let invalid = |order: MemoryOrder| format!("invalid memory model '{order}' for '{name}'");
+
+    match builtin {
+        Builtin::AtomicLoad => {
+            let order = order(&args[1])?;
+            if matches!(order, MemoryOrder::Release | MemoryOrder::AcqRel) {
+                return Err(invalid(order).into());
+            }
+            Ok((order, order))
+        }
+        Builtin::AtomicStore => {
+            let order = order(&args[2])?;
+            if matches!(
+                order,
+                MemoryOrder::Consume | MemoryOrder::Acquire | MemoryOrder::AcqRel
+            ) {
+                return Err(invalid(order).into());
+            }
+            Ok((order, order))
+        }
+        Builtin::AtomicCompareExchange => {
+            let (success, failure) = (order(&args[4])?, order(&args[5])?);
+            if matches!(failure, MemoryOrder::Release | MemoryOrder::AcqRel) {
+                return Err(
+                    format!("invalid failure memory model '{failure}' for '{name}'").into(),
+                );
+            }
+            if failure > success {
+                return Err(format!(
+                    "failure memory model cannot be stronger than success memory model for '{name}'"
+                )
+                .into());
+            }
+            Ok((success, failure))
+        }
+        _ => {
+            let order = order(&args[2])?;
+            Ok((order, order))
+        }
+    }
+}
+
+/// Type of the value operand of an `__atomic` builtin on an object of type `ty`.
+/// Adding to or subtracting from a pointer moves it by a number of bytes.
+fn atomic_value_type(builtin: Builtin, ty: &Type) -> Type {
+    match builtin.fetch_op() {
+        Some(_) if ty.is_pointer() => Type::Long,
+        _ => ty.clone(),
+    }
+}
+
+/// Checks the operands of an `__atomic` builtin and returns the type of its result.
+fn atomic_builtin_type(
+    g: &Generator,
+    builtin: Builtin,
+    args: &[Expr],
+) -> Result<Type, Box<dyn Error>> {
+    let ty = atomic_object_type(g, builtin, args)?;
+    atomic_orders(builtin, args)?;
+    let value_ty = atomic_value_type(builtin, &ty);
+    match builtin {
+        Builtin::AtomicLoad => Ok(ty),
+        Builtin::AtomicStore => {
+            check_implicit_conversion(&type_of(g, &args[1])?, &value_ty, &args[1])?;
+            Ok(Type::Void)
+        }
+        Builtin::AtomicCompareExchange => {
+            let expected = Type::Pointer(Box::new(ty.clone()));
+            check_implicit_conversion(&type_of(g, &args[1])?, &expected, &args[1])?;
+            check_implicit_conversion(&type_of(g, &args[2])?, &ty, &args[2])?;
+            if !type_of(g, &args[3])?.is_scalar() {
+                return Err(format!(
+                    "argument 4 of '{}' does not have scalar type",
+                    builtin.name()
+                )
+                .into());
+            }
+            Ok(Type::Int)
+        }
+        _ => {
+            check_implicit_conversion(&type_of(g, &args[1])?, &value_ty, &args[1])?;
+            Ok(ty)
+        }
+    }
+}
+
+/// `__atomic_*` builtins. The object's address is evaluated into x1, the value
+/// operand into x2 and the address of the expected value of a compare-exchange into x3.
+fn emit_atomic_builtin(
+    g: &mut Generator,
+    builtin: Builtin,
+    args: &[Expr],
+) -> Result<(), Box<dyn Error>> {
+    let ty = atomic_object_type(g, builtin, args)?;
+    let (order, failure) = atomic_orders(builtin, args)?;
+
+    generate_expr(g, &args[0])?;
+    if builtin == Builtin::AtomicLoad {
+        return Ok(emit_atomic_load(g, &ty, order)?);
+    }
+    writeln!(g.output, "str\tx0, [sp, #-16]!")?;
+
+    if builtin == Builtin::AtomicCompareExchange {
+        generate_expr(g, &args[1])?;
+        writeln!(g.output, "str\tx0, [sp, #-16]!")?;
+        generate_expr_as(g, &args[2], &ty)?;
+        writeln!(g.output, "str\tx0, [sp, #-16]!")?;
+        generate_expr(g, &args[3])?; // a strong compare-exchange never fails spuriously
+        writeln!(g.output, "ldr\tx2, [sp], #16")?;
+        writeln!(g.output, "ldr\tx3, [sp], #16")?;
+        writeln!(g.output, "ldr\tx1, [sp], #16")?;
+        return Ok(emit_atomic_compare_exchange(g, &ty, order, failure)?);
+    }
+
+    generate_expr_as(g, &args[1], &atomic_value_type(builtin, &ty))?;
+    writeln!(g.output, "mov\tx2, x0")?;
+    writeln!(g.output, "ldr\tx1, [sp], #16")?;
+    match builtin {
+        Builtin::AtomicStore => {
+            writeln!(g.output, "mov\tx0, x2")?;
+            emit_atomic_store(g, &ty, order)?;
+        }
+        Builtin::AtomicExchange => emit_atomic_rmw(g, None, &ty, order)?,
+        _ => emit_atomic_rmw(g, builtin.fetch_op(), &ty, order)?,
+    }
+    Ok(())
+}
+
+/// Load the object of type `ty` at the address in x0 into w0/x0.
+fn emit_atomic_load(g: &mut Generator, ty: &Type, order: MemoryOrder) -> fmt::Result {
+    let r = reg_prefix(ty);
+    if !order.acquires() {
+        return writeln!(g.output, "{}\t{r}0, [x0]", load_instr(ty));
+    }
+    writeln!(g.output, "ldar{}\t{r}0, [x0]", access_size_suffix(ty))?;
+    // the acquiring loads zero-extend
+    if ty.is_signed() {
+        emit_extend_narrow(g, ty)?;
+    }
+    Ok(())
+}
+
+/// Store w0/x0 into the object of type `ty` at the address in x1.
+fn emit_atomic_store(g: &mut Generator, ty: &Type, order: MemoryOrder) -> fmt::Result {
+    let r = reg_prefix(ty);
+    if !order.releases() {
+        return writeln!(g.output, "{}\t{r}0, [x1]", store_instr(ty));
+    }
+    writeln!(g.output, "stlr{}\t{r}0, [x1]", access_size_suffix(ty))
+}
+
+/// Acquire/release suffix of an LSE instruction: `ldaddal`, `swpa`, `casl`, ...
+fn lse_order_suffix(acquire: bool, release: bool) -> &'static str {
+    match (acquire, release) {
+        (true, true) => "al",
+        (true, false) => "a",
+        (false, true) => "l",
+        (false, false) => "",
+    }
+}
+
+/// Exclusive load of the object at x1 into w0/x0, acquiring if `acquire` is set.
+/// Narrow values are zero-extended.
+fn emit_load_exclusive(g: &mut Generator, ty: &Type, acquire: bool) -> fmt::Result {
+    let a = if acquire { "a" } else { "" };
+    let size = access_size_suffix(ty);
+    writeln!(g.output, "ld{a}xr{size}\t{}0, [x1]", reg_prefix(ty))
+}
+
+/// Exclusive store of register `src` to the object at x1, releasing if `release` is set.
+/// Branches back to `retry` if the exclusive access was lost.
+fn emit_store_exclusive(
+    g: &mut Generator,
+    ty: &Type,
+    src: u8,
+    release: bool,
+    retry: &str,
+) -> fmt::Result {
+    let l = if release { "l" } else { "" };
+    let size = access_size_suffix(ty);
+    writeln!(
+        g.output,
+        "st{l}xr{size}\tw10, {}{src}, [x1]",
+        reg_prefix(ty)
+    )?;
+    writeln!(g.output, "cbnz\tw10, {retry}")
+}
+
+/// Replace the object of type `ty` at x1 with `old op x2`, or with x2 if `op` is
+/// `None`. Leaves the previous value in w0/x0.
+fn emit_atomic_rmw(
+    g: &mut Generator,
+    op: Option<BinaryOp>,
+    ty: &Type,
+    order: MemoryOrder,
+) -> fmt::Result {
+    let r = reg_prefix(ty);
+    let (acquire, release) = (order.acquires(), order.releases());
+    let size = access_size_suffix(ty);
+
+    if g.features.lse {
+        let suffix = lse_order_suffix(acquire, release);
+        // subtraction adds the negated value, and-ing clears the complemented bits
+        let (instr, src) = match op {
+            None => ("swp", 2),
+            Some(BinaryOp::Add) => ("ldadd", 2),
+            Some(BinaryOp::Sub) => {
+                writeln!(g.output, "neg\t{r}9, {r}2")?;
+                ("ldadd", 9)
+            }
+            Some(BinaryOp::And) => {
+                writeln!(g.output, "mvn\t{r}9, {r}2")?;
+                ("ldclr", 9)
+            }
+            Some(BinaryOp::Or) => ("ldset", 2),
+            Some(BinaryOp::Xor) => ("ldeor", 2),
+            Some(op) => unreachable!("no atomic {op}"),
+        };
+        writeln!(g.output, "{instr}{suffix}{size}\t{r}{src}, {r}0, [x1]")?;
+    } else {
+        let retry = g.labels.next("atomic_retry");
+        writeln!(g.output, "{retry}:")?;
+        emit_load_exclusive(g, ty, acquire)?;
+        let src = match op {
+            None => 2,
+            Some(op) => {
+                let instr = match op {
+                    BinaryOp::Add => "add",
+                    BinaryOp::Sub => "sub",
+                    BinaryOp::And => "and",
+                    BinaryOp::Or => "orr",
+                    BinaryOp::Xor => "eor",
+                    _ => unreachable!("no atomic {op}"),
+                };
+                writeln!(g.output, "{instr}\t{r}9, {r}0, {r}2")?;
+                9
+            }
+        };
+        emit_store_exclusive(g, ty, src, release, &retry)?;
+    }
+
+    if ty.is_signed() {
+        emit_extend_narrow(g, ty)?;
+    }
+    Ok(())
+}
+
+/// Store x2 into the object of type `ty` at x1 if it holds the value at x3, otherwise
+/// copy the object's value to x3. Leaves 1 in w0 if the store happened, else 0.
+fn emit_atomic_compare_exchange(
+    g: &mut Generator,
+    ty: &Type,
+    success: MemoryOrder,
+    failure: MemoryOrder,
+) -> fmt::Result {
+    let r = reg_prefix(ty);
+    let size = access_size_suffix(ty);
+    let acquire = success.acquires() || failure.acquires();
+    let release = success.releases();
+    let done = g.labels.next("cas_done");
+
+    // zero-extended like the value the exclusive load and `cas` give
+    writeln!(g.output, "ldr{size}\t{r}9, [x3]")?;
+    if g.features.lse {
+        let suffix = lse_order_suffix(acquire, release);
+        writeln!(g.output, "mov\t{r}0, {r}9")?;
+        writeln!(g.output, "cas{suffix}{size}\t{r}0, {r}2, [x1]")?;
+        writeln!(g.output, "cmp\t{r}0, {r}9")?;
+        writeln!(g.output, "cset\tw10, eq")?;
+        writeln!(g.output, "b.eq\t{done}")?;
+        writeln!(g.output, "str{size}\t{r}0, [x3]")?;
+    } else {
+        let retry = g.labels.next("cas_retry");
+        let fail = g.labels.next("cas_fail");
+        writeln!(g.output, "{retry}:")?;
+        emit_load_exclusive(g, ty, acquire)?;
+        writeln!(g.output, "cmp\t{r}0, {r}9")?;
+        writeln!(g.output, "b.ne\t{fail}")?;
+        emit_store_exclusive(g, ty, 2, release, &retry)?;
+        writeln!(g.output, "mov\tw10, #1")?;
+        writeln!(g.output, "b\t{done}")?;
+        writeln!(g.output, "{fail}:")?;
+        writeln!(g.output, "clrex")?;
+        writeln!(g.output, "str{size}\t{r}0, [x3]")?;
+        writeln!(g.output, "mov\tw10, #0")?;
+    }
+    writeln!(g.output, "{done}:")?;
+    writeln!(g.output, "mov\tw0, w10")
+}
+
+/// Assignment to an `_Atomic` object of type `ty`: a sequentially consistent store.
+/// The `x = x op y` form compound assignments are parsed into updates the object
+/// with a single atomic read-modify-write.
+fn emit_atomic_assign(
+    g: &mut Generator,
+    target: &Expr,
+    value: &Expr,
+    ty: &Type,
+) -> Result<(), Box<dyn Error>> {
+    use BinaryOp::*;
+    if let BinOp(op, lhs, rhs) = value
+        && lhs.as_ref() == target
+        && matches!(
+            op,
+            Add | Sub | Multiply | Divide | Modulo | And | Or | Xor | ShiftLeft | ShiftRight
+        )
+    {
+        return emit_atomic_update(g, target, value, *op, rhs, ty);
+    }
+
+    emit_address(g, target)?;
+    writeln!(g.output, "str\tx0, [sp, #-16]!")?; // push the address
+    generate_expr_as(g, value, ty)?;
+    writeln!(g.output, "ldr\tx1, [sp], #16")?;
+    emit_atomic_store(g, ty, MemoryOrder::SeqCst)?;
+    Ok(())
+}
+
+/// `target op= rhs` on an `_Atomic` object of type `ty`, where `value` is `target op rhs`.
+/// The new value is computed between an exclusive load and store, retried until no
+/// other write intervened; with LSE, additions and bitwise operations are one instruction.
+/// Leaves the new value in the result register.
+fn emit_atomic_update(
+    g: &mut Generator,
+    target: &Expr,
+    value: &Expr,
+    op: BinaryOp,
+    rhs: &Expr,
+    ty: &Type,
+) -> Result<(), Box<dyn Error>> {
+    check_implicit_conversion(&type_of(g, value)?, ty, value)?;
+    let rt = type_of(g, rhs)?;
+
+    emit_address(g, target)?;
+    writeln!(g.output, "str\tx0, [sp, #-16]!")?; // push the address
+    generate_expr(g, rhs)?;
+    let operand_ty = match ty {
+        Type::Pointer(pointee) => {
+            emit_scaled_index(g, &rt, pointee.size())?;
+            Type::Long
+        }
+        _ => {
+            let operand_ty = binop_operand_type(op, (target, ty), (rhs, &rt))?;
+            emit_convert(g, &rt, &operand_ty)?;
+            operand_ty
+        }
+    };
+    // the right operand waits in x2 while the object is updated
+    let r = reg_prefix(&operand_ty);
+    let x = if r == "d" { "x" } else { "w" };
+    if operand_ty.is_floating() {
+        writeln!(g.output, "fmov\t{x}2, {r}0")?;
+    } else {
+        writeln!(g.output, "mov\tx2, x0")?;
+    }
+    writeln!(g.output, "ldr\tx1, [sp], #16")?;
+
+    let lse_op = matches!(op, BinaryOp::Add | BinaryOp::Sub)
+        || (ty.is_integer() && matches!(op, BinaryOp::And | BinaryOp::Or | BinaryOp::Xor));
+    if g.features.lse && lse_op && !operand_ty.is_floating() {
+        emit_atomic_rmw(g, Some(op), ty, MemoryOrder::SeqCst)?;
+        // the instruction gives the previous value, compute the new one again
+        let rt = reg_prefix(ty);
+        let instr = match op {
+            BinaryOp::Add => "add",
+            BinaryOp::Sub => "sub",
+            BinaryOp::And => "and",
+            BinaryOp::Or => "orr",
+            _ => "eor",
+        };
+        writeln!(g.output, "{instr}\t{rt}0, {rt}0, {rt}2")?;
+        emit_extend_narrow(g, ty)?;
+        return Ok(());
+    }
+
+    let retry = g.labels.next("atomic_retry");
+    writeln!(g.output, "{retry}:")?;
+    emit_load_exclusive(g, ty, true)?;
+    if ty.is_signed() {
+        emit_extend_narrow(g, ty)?;
+    }
+    emit_convert(g, ty, &operand_ty)?;
+    if operand_ty.is_floating() {
+        writeln!(g.output, "fmov\t{r}16, {r}0")?;
+        writeln!(g.output, "fmov\t{r}0, {x}2")?;
+    } else {
+        writeln!(g.output, "mov\tx11, x0")?;
+        writeln!(g.output, "mov\tx0, x2")?;
+    }
+    emit_binop(g, op, &operand_ty)?;
+    emit_convert(g, &operand_ty, ty)?;
+    emit_store_exclusive(g, ty, 0, true, &retry)?;
+    Ok(())
+}
+
+fn emit_address(g: &mut Generator, expr: &Expr) -> Result<(), Box<dyn Error>> {
+    match expr {
+        Expr::Var(name) if g.allocator.get(name).is_none() && g.functions.contains_key(name) => {
+            let label = format!("{}{name}", function_label_prefix(&g.platform)?);
+            Variable::emit_global_variable_address_load_x1(&label, g.output, &g.platform)?;
+            writeln!(g.output, "mov\tx0, x1")?;
+        }
+        Expr::Var(name) => {
+            if lvalue_type(g, expr)?.is_var_array() {
+                return Err(format!(
+                    "cannot take the address of variable-length array {}",
+                    source_name(name)
+                )
+                .into());
+            }
+            let var = g
+                .allocator
+                .get(name)
+                .cloned()
+                .ok_or_else(|| format!("variable {name} not found"))?;
+            match var {
+                Variable::Stack(offset) => emit_frame_address(g.output, "x0", offset)?,
+                Variable::Global(_) | Variable::ThreadLocal { .. } => {
+                    var.emit_address_x1(g.output, &g.platform)?;
+                    writeln!(g.output, "mov\tx0, x1")?;
+                }
+                Variable::Register(_) => {
+                    return Err(
+                        format!("cannot take the address of register variable {name}").into(),
+                    );
+                }
+            }
+        }
+        Expr::Deref(inner) => {
+            lvalue_type(g, expr)?; // operand must be a pointer
+            generate_expr(g, inner)?; // the pointer value is the address
+        }
+        _ => {
+            return Err(format!(
+                "cannot take the address of an rvalue: '{}'",
+                source_text(expr)
+            )
+            .into());
+        }
+    }
+    Ok(())
+}
+
+/// Convert an integer operand of pointer arithmetic in w0/x0 to a byte offset in x0.
+fn emit_scaled_index(g: &mut Generator, ty: &Type, element_size: i32) -> fmt::Result {
+    if !ty.is_integer() {
+        return Ok(());
+    }
+    emit_convert(g, ty, &Type::Long)?;
+    if element_size != 1 {
+        emit_mov_imm(g.output, "x", 12, element_size as i64)?;
+        writeln!(g.output, "mul\tx0, x0, x12")?;
+    }
+    Ok(())
+}
+
+/// Emit `lhs + rhs` or `lhs - rhs` where at least one operand is a pointer.
+/// Integer operands are scaled by the size of the pointee; the difference of two
+/// pointers is divided by it.
+fn emit_pointer_arithmetic(
+    g: &mut Generator,
+    op: BinaryOp,
+    (lhs, lt): (&Expr, &Type),
+    (rhs, rt): (&Expr, &Type),
+) -> Result<(), Box<dyn Error>> {
+    let result = pointer_arithmetic_type(op, lt, rt)?;
+    let element_size = match (lt, rt) {
+        (Type::Pointer(pointee), _) | (_, Type::Pointer(pointee)) => pointee.size(),
+        _ => unreachable!("one operand is a pointer"),
+    };
+
+    generate_expr(g, lhs)?;
+    emit_scaled_index(g, lt, element_size)?;
+    writeln!(g.output, "str\tx0, [sp, #-16]!")?; // push lhs
+
+    generate_expr(g, rhs)?;
+    emit_scaled_index(g, rt, element_size)?;
+    writeln!(g.output, "ldr\tx11, [sp], #16")?; // lhs → x11
+
+    match op {
+        BinaryOp::Add => writeln!(g.output, "add\tx0, x11, x0")?,
+        _ => writeln!(g.output, "sub\tx0, x11, x0")?,
+    }
+
+    // pointer - pointer counts elements, not bytes
+    if !result.is_pointer() && element_size != 1 {
+        emit_mov_imm(g.output, "x", 12, element_size as i64)?;
+        writeln!(g.output, "sdiv\tx0, x0, x12")?;
+    }
+    Ok(())
+}
+
+/// Put the zero value of the scalar type `ty` in the result register.
+fn emit_zero(g: &mut Generator, ty: &Type) -> fmt::Result {
+    match ty.unqualified() {
+        Type::Float => writeln!(g.output, "fmov\ts0, wzr"),
+        Type::Double => writeln!(g.output, "fmov\td0, xzr"),
+        _ => writeln!(g.output, "mov\t{}0, #0", reg_prefix(ty)),
+    }
+}
+
+/// Evaluate a controlling expression and set the flags by comparing it with zero.
+fn emit_condition(g: &mut Generator, cond: &Expr) -> Result<(), Box<dyn Error>> {
+    let ty = type_of(g, cond)?;
+    generate_expr(g, cond)?;
+    emit_compare_zero(g, &ty)
+}
+
+/// The labels `break` and `continue` jump to in the loop numbered `label`.
+fn loop_labels(label: LoopLabel) -> Result<(String, String), Box<dyn Error>> {
+    let n = label.ok_or("loop without a label, the program was not analyzed")?;
+    Ok((format!("_loop_{n}_break"), format!("_loop_{n}_continue")))
+}
+
+/// The label of the `case` or `default` at `location` in the `switch` numbered `label`.
+fn case_label(label: LoopLabel, location: Location) -> Result<String, Box<dyn Error>> {
+    let n = label.ok_or("case without a label, the program was not analyzed")?;
+    Ok(format!("_switch_{n}_{}_{}", location.line, location.column))
+}
+
+/// The `case` and `default` statements of a `switch` with the body `stmt`, not those
+/// of the `switch` statements nested in it.
+fn switch_labels<'a>(stmt: &'a Statement, labels: &mut Vec<&'a Statement>) {
+    match stmt {
+        Statement::Case { body, .. } | Statement::Default { body, .. } => {
+            labels.push(stmt);
+            switch_labels(body, labels);
+        }
+        Statement::Compound(items) => {
+            for item in items {
+                if let Stmt(stmt, _) = item {
+                    switch_labels(stmt, labels);
+                }
+            }
+        }
+        Statement::If { then, els, .. } => {
+            switch_labels(then, labels);
+            if let Some(els) = els {
+                switch_labels(els, labels);
+            }
+        }
+        Statement::For { body, .. }
+        | Statement::ForDecl { body, .. }
+        | Statement::While { body, .. }
+        | Statement::Do { body, .. } => switch_labels(body, labels),
+        _ => {}
+    }
+}
+
+/// Compare the value of `cond` with each `case` of `body` and jump to the first that
+/// matches, else to `default`, else past the body. `break` leaves it like a loop.
+fn generate_switch(
+    g: &mut Generator,
+    cond: &Expr,
+    body: &Statement,
+    label: LoopLabel,
+) -> Result<(), Box<dyn Error>> {
+    let ty = type_of(g, cond)?.promoted();
+    if !ty.is_integer() {
+        return Err("switch quantity not an integer".into());
+    }
+    let (finish, _) = loop_labels(label)?;
+    let saved_sp = emit_loop_save_sp(g, body)?;
+    generate_expr_as(g, cond, &ty)?;
+
+    let mut labels = Vec::new();
+    switch_labels(body, &mut labels);
+    let prefix = reg_prefix(&ty);
+    let mut default = None;
+    // the values matched so far, as ranges in the promoted type
+    let mut ranges: Vec<(i128, i128)> = Vec::new();
+    for stmt in labels {
+        let (value, last, location) = match stmt {
+            Statement::Case {
+                value,
+                last,
+                location,
+                ..
+            } => (value, last.unwrap_or(*value), *location),
+            Statement::Default { location, .. } => {
+                if default.is_some() {
+                    return Err(format!("{location}: multiple default labels in one switch").into());
+                }
+                default = Some(case_label(label, *location)?);
+                continue;
+            }
+            _ => unreachable!("switch_labels only collects labels"),
+        };
+        let low = value.convert(&ty).expect("integer type").as_i128();
+        let high = last.convert(&ty).expect("integer type").as_i128();
+        // an empty range matches nothing
+        if low > high {
+            continue;
+        }
+        if ranges.iter().any(|&(l, h)| low <= h && l <= high) {
+            return Err(format!("{location}: duplicate case value").into());
+        }
+        ranges.push((low, high));
+
+        let target = case_label(label, location)?;
+        emit_mov_imm(g.output, prefix, 9, low as i64)?;
+        if low == high {
+            writeln!(g.output, "cmp\t{prefix}0, {prefix}9")?;
+            writeln!(g.output, "b.eq\t{target}")?;
+        } else {
+            // value - low <= high - low, compared unsigned, is in the range
+            writeln!(g.output, "sub\t{prefix}9, {prefix}0, {prefix}9")?;
+            emit_mov_imm(g.output, prefix, 10, (high - low) as i64)?;
+            writeln!(g.output, "cmp\t{prefix}9, {prefix}10")?;
+            writeln!(g.output, "b.ls\t{target}")?;
+        }
     }
+    writeln!(g.output, "b\t{}", default.as_ref().unwrap_or(&finish))?;
 
+    generate_statement_in_new_scope(g, body)?;
+
+    writeln!(g.output, "{}:", finish)?;
+    emit_loop_restore_sp(g, saved_sp)?;
     Ok(())
 }
 
-fn generate_stmt(
-    ctx: &mut Context,
-    g: &mut Generator,
-    stmt: &Statement,
-) -> Result<(), Box<dyn Error>> {
+fn generate_stmt(g: &mut Generator, stmt: &Statement) -> Result<(), Box<dyn Error>> {
     match stmt {
         Statement::Expr(Some(e)) => generate_expr(g, e),
         Statement::Expr(None) => Ok(()),
 
         Statement::Return(r) => {
-            generate_expr(g, r)?;
+            let return_type = g.return_type.clone();
+            match (r, &return_type) {
+                (Some(_), Type::Void) => {
+                    return Err("`return` with a value in function returning void".into());
+                }
+                (Some(r), _) => generate_expr_as(g, r, &return_type)?,
+                (None, Type::Void) => {}
+                (None, _) => {
+                    return Err("`return` without a value in function returning non-void".into());
+                }
+            }
             writeln!(g.output, "b\t{}", g.epilogue).map_err(Into::into)
         }
         Statement::Bingus(expr) => {
-            generate_expr(g, expr)?;
+            generate_expr_as(g, expr, &Type::Int)?;
             writeln!(g.output, "bl\tbingus")?;
             Ok(())
         }
@@ -349,16 +2277,15 @@ fn generate_stmt(
             let else_label = g.labels.next("_else");
             let post_conditional = g.labels.next("_post_conditional");
 
-            generate_expr(g, cond)?; // evaluate cond (e1)
-            writeln!(g.output, "cmp\tw0, #0")?; // compare e1 cond zero
+            emit_condition(g, cond)?; // evaluate cond (e1) and compare it with zero
             writeln!(g.output, "beq\t{}", else_label)?; // if e1 == 0 (false), jump to else (e3)
 
-            generate_stmt(ctx, g, then)?; // evaluate e2
+            generate_stmt(g, then)?; // evaluate e2
             writeln!(g.output, "b\t{}", post_conditional)?; // skip e3
 
             writeln!(g.output, "{}:", else_label)?;
             if let Some(els) = els {
-                generate_stmt(ctx, g, els)?; // evaluate else (e3)
+                generate_stmt(g, els)?; // evaluate else (e3)
             }
             // if els is None, it would just go to post_conditional
             // TODO: do not emit else_label if els.is_none()
@@ -366,50 +2293,44 @@ fn generate_stmt(
             writeln!(g.output, "{}:", post_conditional)?;
             Ok(())
         }
-        Statement::Compound(block_items) => generate_block(ctx, g, block_items, None),
+        Statement::Compound(block_items) => generate_block(g, block_items),
 
-        Statement::While { cond, body } => {
-            let start = g.labels.next("_while");
-            let continue_label = g.labels.next("_while_continue");
-            let finish = g.labels.next("_while_end");
+        Statement::While { cond, body, label } => {
+            // `continue` goes back to the condition
+            let (finish, start) = loop_labels(*label)?;
+            let saved_sp = emit_loop_save_sp(g, body)?;
 
             writeln!(g.output, "{}:", start)?;
-            generate_expr(g, cond)?; // evaluate cond
-
-            writeln!(g.output, "cmp\tw0, #0")?; // compare cond with zero
+            emit_loop_restore_sp(g, saved_sp)?;
+            emit_condition(g, cond)?; // evaluate cond and compare it with zero
             writeln!(g.output, "beq\t{}", finish)?; // if cond == 0 (false), jump to finish
 
-            ctx.break_label = Some(finish.clone());
-            ctx.continue_label = Some(continue_label.clone());
-
-            writeln!(g.output, "{}:", continue_label)?;
-            generate_statement_in_new_scope(ctx, g, body)?; // evaluate body
+            generate_statement_in_new_scope(g, body)?; // evaluate body
 
             writeln!(g.output, "b\t{}", start)?; // jump back to start
 
             writeln!(g.output, "{}:", finish)?;
+            emit_loop_restore_sp(g, saved_sp)?;
             Ok(())
         }
 
-        Statement::Do { cond, body } => {
+        Statement::Do { cond, body, label } => {
             let start = g.labels.next("_do_while");
-            let continue_label = g.labels.next("_do_while_continue");
-            let finish = g.labels.next("_do_while_end");
+            let (finish, continue_label) = loop_labels(*label)?;
+            let saved_sp = emit_loop_save_sp(g, body)?;
 
             writeln!(g.output, "{}:", start)?;
+            emit_loop_restore_sp(g, saved_sp)?;
+            generate_statement_in_new_scope(g, body)?; // evaluate body
 
-            ctx.break_label = Some(finish.clone());
-            ctx.continue_label = Some(continue_label.clone());
-
+            // `continue` skips the rest of the body, not the condition
             writeln!(g.output, "{}:", continue_label)?;
-            generate_statement_in_new_scope(ctx, g, body)?; // evaluate body
-
-            generate_expr(g, cond)?; // evaluate cond
-            writeln!(g.output, "cmp\tw0, #0")?; // compare cond with zero
+            emit_condition(g, cond)?; // evaluate cond and compare it with zero
             writeln!(g.output, "beq\t{}", finish)?; // if cond == 0 (false), jump to finish
             writeln!(g.output, "b\t{}", start)?; // jump back to start
 
             writeln!(g.output, "{}:", finish)?;
+            emit_loop_restore_sp(g, saved_sp)?;
             Ok(())
         }
 
@@ -418,32 +2339,31 @@ fn generate_stmt(
             cond,
             post,
             body,
+            label,
         } => {
             let start = g.labels.next("_for");
-            let continue_label = g.labels.next("_for_continue");
-            let finish = g.labels.next("_for_end");
+            let (finish, continue_label) = loop_labels(*label)?;
+            let saved_sp = emit_loop_save_sp(g, body)?;
 
             if let Some(init) = init {
                 generate_expr(g, init)?;
             }
 
             writeln!(g.output, "{}:", start)?;
-            generate_expr(g, cond)?; // evaluate cond
-
-            writeln!(g.output, "cmp\tw0, #0")?; // compare cond with zero
+            emit_condition(g, cond)?; // evaluate cond and compare it with zero
             writeln!(g.output, "beq\t{}", finish)?; // if cond == 0 (false), jump to finish
 
-            ctx.break_label = Some(finish.clone());
-            ctx.continue_label = Some(continue_label.clone());
-            generate_statement_in_new_scope(ctx, g, body)?; // evaluate body
+            generate_statement_in_new_scope(g, body)?; // evaluate body
 
             writeln!(g.output, "{}:", continue_label)?;
+            emit_loop_restore_sp(g, saved_sp)?;
             if let Some(post) = post {
                 generate_expr(g, post)?; // evaluate post expression
             }
             writeln!(g.output, "b\t{}", start)?; // jump back to start
 
             writeln!(g.output, "{}:", finish)?;
+            emit_loop_restore_sp(g, saved_sp)?;
             Ok(())
         }
         Statement::ForDecl {
@@ -451,174 +2371,674 @@ fn generate_stmt(
             cond,
             post,
             body,
+            label,
         } => {
             g.allocator.enter_scope();
             generate_declaration(g, decl)?; // alloc ‘i’ inside *this* scope
 
             let start = g.labels.next("_for_decl");
-            let continue_label = g.labels.next("_for_decl_continue");
-            let finish = g.labels.next("_for_decl_end");
+            let (finish, continue_label) = loop_labels(*label)?;
+            let saved_sp = emit_loop_save_sp(g, body)?;
 
             writeln!(g.output, "{}:", start)?;
-            generate_expr(g, cond)?;
-            writeln!(g.output, "cmp\tw0, #0")?;
+            emit_condition(g, cond)?;
             writeln!(g.output, "beq\t{}", finish)?;
 
-            ctx.break_label = Some(finish.clone());
-            ctx.continue_label = Some(continue_label.clone());
-            generate_statement_in_new_scope(ctx, g, body)?;
+            generate_statement_in_new_scope(g, body)?;
 
             writeln!(g.output, "{}:", continue_label)?;
+            emit_loop_restore_sp(g, saved_sp)?;
             if let Some(post) = post {
                 generate_expr(g, post)?;
             }
             writeln!(g.output, "b\t{}", start)?;
             writeln!(g.output, "{}:", finish)?;
+            emit_loop_restore_sp(g, saved_sp)?;
 
             g.allocator.exit_scope();
             Ok(())
         }
 
-        Statement::Break => {
-            let label = ctx
-                .break_label
-                .as_deref()
-                .ok_or("`break` used outside of loop")?;
-            writeln!(g.output, "b\t{}", label)?;
+        Statement::Switch { cond, body, label } => generate_switch(g, cond, body, *label),
+
+        Statement::Case {
+            body,
+            location,
+            label,
+            ..
+        }
+        | Statement::Default {
+            body,
+            location,
+            label,
+        } => {
+            writeln!(g.output, "{}:", case_label(*label, *location)?)?;
+            generate_stmt(g, body)
+        }
+
+        Statement::Break(label) => {
+            let (finish, _) = loop_labels(*label)?;
+            writeln!(g.output, "b\t{}", finish)?;
             Ok(())
         }
 
-        Continue => {
-            let label = ctx
-                .continue_label
-                .as_deref()
-                .ok_or("`continue` used outside of loop")?;
-            writeln!(g.output, "b\t{}", label)?;
+        Continue(label) => {
+            let (_, continue_label) = loop_labels(*label)?;
+            writeln!(g.output, "b\t{}", continue_label)?;
             Ok(())
         }
+
+        Statement::Asm(asm) => emit_asm(g, asm),
+    }
+}
+
+/// Temporaries that asm operands are loaded into; they hold nothing between statements.
+const ASM_SCRATCH_REGISTERS: [u8; 7] = [9, 10, 11, 12, 13, 14, 15];
+
+/// Where an asm operand is while the template runs.
+#[derive(Clone, Copy)]
+enum AsmBinding {
+    /// a general register: a temporary, or the register a variable lives in
+    Register(u8),
+    /// memory at the address held by a register
+    Memory(u8),
+    /// `i` / `n`: a constant
+    Immediate(i128),
+}
+
+/// How a register output gets back to its object after the template ran.
+enum AsmWriteBack {
+    /// the variable lives in the bound register already
+    None,
+    Variable(Variable),
+    /// through the address held by a register
+    Address(u8),
+}
+
+/// Emit an asm statement:
+///  • every operand value and address is computed first and pushed, then popped
+///    into its temporary, so evaluating one operand can't clobber another;
+///  • integer variables that live in a register are bound to it directly;
+///  • clobbered callee-saved registers hold variables (or the caller's values)
+///    and are saved around the template;
+///  • register outputs are stored to their objects afterwards.
+fn emit_asm(g: &mut Generator, asm: &AsmStatement) -> Result<(), Box<dyn Error>> {
+    if asm.basic {
+        writeln!(g.output, "{}", asm.template)?;
+        return Ok(());
+    }
+
+    let clobbered = asm_clobbered_registers(&asm.clobbers)?;
+    let mut scratch = ASM_SCRATCH_REGISTERS
+        .iter()
+        .copied()
+        .filter(|r| !clobbered.contains(&format!("x{r}")));
+    let mut next_scratch = || -> Result<u8, Box<dyn Error>> {
+        scratch
+            .next()
+            .ok_or_else(|| "asm statement has more operands than free registers".into())
+    };
+
+    let mut bindings = Vec::new();
+    let mut write_backs = Vec::new();
+    let mut pushed = Vec::new(); // registers to pop the pushed values into, in push order
+    let mut loads = Vec::new(); // `+` outputs read through their address after the pops
+
+    for output in &asm.outputs {
+        let ty = lvalue_type(g, &output.expr)?;
+        check_assignable(&output.expr, &ty)?;
+        let read = output.constraint.starts_with('+');
+
+        if !output.constraint.contains('r') {
+            check_asm_constraint(&output.constraint[1..], "m&")?;
+            let reg = next_scratch()?;
+            emit_address(g, &output.expr)?;
+            writeln!(g.output, "str\tx0, [sp, #-16]!")?;
+            pushed.push(reg);
+            bindings.push(AsmBinding::Memory(reg));
+            write_backs.push(AsmWriteBack::None);
+            continue;
+        }
+        check_asm_constraint(&output.constraint[1..], "r&")?;
+        check_asm_register_type(&ty)?;
+
+        let variable = match &output.expr {
+            Expr::Var(name) => g.allocator.get(name).cloned(),
+            _ => None,
+        };
+        let (reg, write_back) = match variable {
+            Some(Variable::Register(reg)) => {
+                (asm_variable_register(reg, &clobbered)?, AsmWriteBack::None)
+            }
+            Some(var) => {
+                let reg = next_scratch()?;
+                if read {
+                    generate_expr(g, &output.expr)?;
+                    writeln!(g.output, "str\tx0, [sp, #-16]!")?;
+                    pushed.push(reg);
+                }
+                (reg, AsmWriteBack::Variable(var))
+            }
+            None => {
+                let (reg, address) = (next_scratch()?, next_scratch()?);
+                emit_address(g, &output.expr)?;
+                writeln!(g.output, "str\tx0, [sp, #-16]!")?;
+                pushed.push(address);
+                if read {
+                    loads.push((reg, address, ty.unqualified().clone()));
+                }
+                (reg, AsmWriteBack::Address(address))
+            }
+        };
+        bindings.push(AsmBinding::Register(reg));
+        write_backs.push(write_back);
+    }
+
+    for input in &asm.inputs {
+        let constraint = input.constraint.as_str();
+        if let Ok(index) = constraint.parse::<usize>() {
+            // shares the register of an output
+            let Some(AsmBinding::Register(reg)) = bindings.get(index).copied() else {
+                return Err(format!(
+                    "matching constraint references invalid operand number {index}"
+                )
+                .into());
+            };
+            let ty = lvalue_type(g, &asm.outputs[index].expr)?;
+            generate_expr_as(g, &input.expr, ty.unqualified())?;
+            writeln!(g.output, "str\tx0, [sp, #-16]!")?;
+            pushed.push(reg);
+            bindings.push(AsmBinding::Register(reg));
+        } else if constraint.contains('r') {
+            check_asm_constraint(constraint, "r")?;
+            check_asm_register_type(&type_of(g, &input.expr)?)?;
+            let variable = match &input.expr {
+                Expr::Var(name) => g.allocator.get(name).cloned(),
+                _ => None,
+            };
+            if let Some(Variable::Register(reg)) = variable {
+                bindings.push(AsmBinding::Register(asm_variable_register(
+                    reg, &clobbered,
+                )?));
+                continue;
+            }
+            let reg = next_scratch()?;
+            generate_expr(g, &input.expr)?;
+            writeln!(g.output, "str\tx0, [sp, #-16]!")?;
+            pushed.push(reg);
+            bindings.push(AsmBinding::Register(reg));
+        } else if constraint.contains('m') {
+            check_asm_constraint(constraint, "m")?;
+            let reg = next_scratch()?;
+            emit_address(g, &input.expr)?;
+            writeln!(g.output, "str\tx0, [sp, #-16]!")?;
+            pushed.push(reg);
+            bindings.push(AsmBinding::Memory(reg));
+        } else {
+            check_asm_constraint(constraint, "in")?;
+            let value = evaluate_compile_time_expr(&input.expr)
+                .map_err(|_| format!("impossible constraint in 'asm': {input}"))?;
+            if !value.ty().is_integer() {
+                return Err(format!("impossible constraint in 'asm': {input}").into());
+            }
+            bindings.push(AsmBinding::Immediate(value.as_i128()));
+        }
+    }
+
+    for reg in pushed.iter().rev() {
+        writeln!(g.output, "ldr\tx{reg}, [sp], #16")?;
+    }
+    for (reg, address, ty) in &loads {
+        let r = reg_prefix(ty);
+        writeln!(g.output, "{}\t{r}{reg}, [x{address}]", load_instr(ty))?;
+    }
+
+    let saved: Vec<&String> = clobbered.iter().filter(|r| is_callee_saved(r)).collect();
+    for reg in &saved {
+        writeln!(g.output, "str\t{reg}, [sp, #-16]!")?;
+    }
+
+    let names: Vec<Option<&str>> = asm.operands().map(|o| o.name.as_deref()).collect();
+    writeln!(
+        g.output,
+        "{}",
+        substitute_asm_operands(&asm.template, &bindings, &names)?
+    )?;
+
+    for reg in saved.iter().rev() {
+        writeln!(g.output, "ldr\t{reg}, [sp], #16")?;
+    }
+
+    for ((output, binding), write_back) in asm.outputs.iter().zip(&bindings).zip(&write_backs) {
+        let AsmBinding::Register(reg) = *binding else {
+            continue;
+        };
+        let ty = lvalue_type(g, &output.expr)?.unqualified().clone();
+        match write_back {
+            AsmWriteBack::None => {}
+            AsmWriteBack::Variable(var) => {
+                var.emit_store(&ty, reg as usize, g.output, &g.platform)?
+            }
+            AsmWriteBack::Address(address) => {
+                writeln!(
+                    g.output,
+                    "{}\t{}{reg}, [x{address}]",
+                    store_instr(&ty),
+                    reg_prefix(&ty)
+                )?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Checks that an asm constraint, without its `=` or `+`, only has letters from `allowed`.
+fn check_asm_constraint(constraint: &str, allowed: &str) -> Result<(), Box<dyn Error>> {
+    match constraint.chars().find(|c| !allowed.contains(*c)) {
+        Some(c) => Err(format!("unsupported constraint '{c}' in asm").into()),
+        None if constraint.is_empty() => Err("asm operand has an empty constraint".into()),
+        None => Ok(()),
+    }
+}
+
+fn check_asm_register_type(ty: &Type) -> Result<(), Box<dyn Error>> {
+    if ty.is_integer() || ty.is_pointer() {
+        return Ok(());
+    }
+    Err(format!("asm operand of type {ty} can't be held in a general register").into())
+}
+
+/// The register of a variable used as an asm operand, which the template must not clobber.
+fn asm_variable_register(reg: u8, clobbered: &[String]) -> Result<u8, Box<dyn Error>> {
+    if clobbered.contains(&format!("x{reg}")) {
+        return Err(format!("asm operand in x{reg} conflicts with the clobber list").into());
+    }
+    Ok(reg)
+}
+
+/// Normalized names (`x19`, `d8`) of the registers in an asm clobber list.
+fn asm_clobbered_registers(clobbers: &[String]) -> Result<Vec<String>, Box<dyn Error>> {
+    let mut registers = Vec::new();
+    for clobber in clobbers {
+        let name = clobber.as_str();
+        if name == "memory" || name == "cc" {
+            continue; // nothing is kept in registers or flags across statements
+        }
+        let register = match name.split_at_checked(1) {
+            _ if name == "lr" => "x30".to_string(),
+            Some(("x" | "w", n)) if n.parse::<u8>().is_ok_and(|n| n <= 30) => format!("x{n}"),
+            Some(("v" | "q" | "d" | "s", n)) if n.parse::<u8>().is_ok_and(|n| n <= 31) => {
+                format!("d{n}")
+            }
+            _ if name == "sp" => {
+                return Err("the stack pointer can't be clobbered by asm".into());
+            }
+            _ => return Err(format!("unknown register name '{name}' in asm").into()),
+        };
+        if register == "x29" {
+            return Err("the frame pointer can't be clobbered by asm".into());
+        }
+        registers.push(register);
+    }
+    Ok(registers)
+}
+
+/// x19–x28 and the low halves of v8–v15 must survive the template; x30 is
+/// saved by the prologue already.
+fn is_callee_saved(register: &str) -> bool {
+    match register.split_at(1) {
+        ("x", n) => (19..=28).contains(&n.parse::<u8>().unwrap_or(0)),
+        ("d", n) => (8..=15).contains(&n.parse::<u8>().unwrap_or(0)),
+        _ => false,
+    }
+}
+
+/// Replace the operand references in an asm template: `%0` and `%x0` by the x
+/// register, `%w0` by the w register, `%[name]` by the named operand and `%%` by `%`.
+fn substitute_asm_operands(
+    template: &str,
+    bindings: &[AsmBinding],
+    names: &[Option<&str>],
+) -> Result<String, Box<dyn Error>> {
+    let mut text = String::new();
+    let mut chars = template.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != '%' {
+            text.push(c);
+            continue;
+        }
+        if chars.peek() == Some(&'%') {
+            chars.next();
+            text.push('%');
+            continue;
+        }
+
+        let modifier = chars.next_if(|c| *c == 'w' || *c == 'x');
+        let index = if chars.next_if_eq(&'[').is_some() {
+            let name: String = chars.by_ref().take_while(|c| *c != ']').collect();
+            names
+                .iter()
+                .position(|n| *n == Some(name.as_str()))
+                .ok_or_else(|| format!("undefined named operand '{name}' in asm"))?
+        } else {
+            let mut digits = String::new();
+            while let Some(d) = chars.next_if(char::is_ascii_digit) {
+                digits.push(d);
+            }
+            digits
+                .parse::<usize>()
+                .map_err(|_| "operand number missing after %-letter in asm")?
+        };
+
+        match bindings.get(index) {
+            Some(AsmBinding::Register(reg)) => match modifier {
+                Some('w') => write!(text, "w{reg}")?,
+                _ => write!(text, "x{reg}")?,
+            },
+            Some(AsmBinding::Memory(reg)) => write!(text, "[x{reg}]")?,
+            Some(AsmBinding::Immediate(value)) => write!(text, "{value}")?,
+            None => return Err(format!("operand number {index} out of range in asm").into()),
+        }
     }
+    Ok(text)
 }
 
 fn generate_declaration(g: &mut Generator, decl: &Declaration) -> Result<(), Box<dyn Error>> {
     match decl {
-        Declare(name, expr) => {
-            let var = g.allocator.allocate(name.clone(), 4);
+        Declare {
+            name,
+            ty,
+            init,
+            storage: Some(StorageClass::Static),
+            thread_local,
+            ..
+        } => {
+            // a static local lives in the data section and keeps its value across calls;
+            // its resolved name is unique in the program
+            let prefix = function_label_prefix(&g.platform)?;
+            let label = format!("{prefix}{}.{name}", g.function_name);
+            let object = StaticObject {
+                label: &label,
+                name: source_name(name),
+                ty,
+                init: init.as_ref(),
+                global: false,
+                thread_local: *thread_local,
+            };
+            emit_static_object(g.static_data, &g.platform, g.functions, &object)?;
+            let var = match thread_local {
+                true => Variable::ThreadLocal {
+                    label,
+                    defined: true,
+                },
+                false => Variable::Global(label),
+            };
+            let var = g.allocator.declare(name.clone(), var, ty);
+            g.debug(format!("static var {var:?} allocated"));
+            Ok(())
+        }
+        // a function or extern object: the name refers to the file-scope symbol
+        Declare {
+            name,
+            ty,
+            thread_local,
+            ..
+        } if decl.has_linkage() => {
+            let label = format!("{}{name}", function_label_prefix(&g.platform)?);
+            let var = match (g.allocator.file_scope(name), thread_local) {
+                (Some(var @ Variable::ThreadLocal { .. }), true) => var.clone(),
+                (Some(Variable::ThreadLocal { .. }), false) => {
+                    return Err(format!(
+                        "non-thread-local declaration of '{name}' follows thread-local declaration"
+                    )
+                    .into());
+                }
+                (Some(_), true) => {
+                    return Err(format!(
+                        "thread-local declaration of '{name}' follows non-thread-local declaration"
+                    )
+                    .into());
+                }
+                (None, true) => Variable::ThreadLocal {
+                    label,
+                    defined: false,
+                },
+                (_, false) => Variable::Global(label),
+            };
+            let var = g.allocator.declare(name.clone(), var, ty);
+            g.debug(format!("{name} bound to {var:?}"));
+            Ok(())
+        }
+        Declare {
+            name,
+            ty: ty @ Type::VarArray(elem, len),
+            ..
+        } => {
+            let len_ty = type_of(g, len)?;
+            if !len_ty.is_integer() {
+                return Err(format!(
+                    "size of array '{}' has non-integer type '{len_ty}'",
+                    source_name(name)
+                )
+                .into());
+            }
+            // the length is evaluated before the array is in scope
+            generate_expr_as(g, len, &Type::ULong)?;
+            emit_mov_imm(g.output, "x", 9, elem.size() as i64)?;
+            writeln!(g.output, "mul\tx0, x0, x9")?;
+            emit_dynamic_alloc(g)?;
+
+            let var = g.allocator.allocate(name.clone(), ty);
+            g.debug(format!("variable-length array {var:?} allocated"));
+            var.emit_store(&ty.decay(), 0, g.output, &g.platform)?;
+            Ok(())
+        }
+        // an automatic object
+        Declare { name, ty, init, .. } => {
+            let var = g.allocator.allocate(name.clone(), ty);
             g.debug(format!("var {var:?} allocated"));
-            if let Some(expr) = expr {
-                generate_expr(g, expr)?;
-                var.emit_store_from_w0(g.output, &g.platform)?;
+            let Some(init) = init else {
+                return Ok(());
+            };
+
+            // arrays are stored element by element, missing elements are zeroed
+            for slot in flatten_initializer(ty, Some(init))? {
+                match slot.value {
+                    Some(expr) => generate_expr_as(g, expr, &slot.ty)?,
+                    None => emit_zero(g, &slot.ty)?,
+                }
+                let target = match &var {
+                    Variable::Stack(offset) => Variable::Stack(offset + slot.offset),
+                    other => other.clone(),
+                };
+                target.emit_store(&slot.ty, 0, g.output, &g.platform)?;
             }
             Ok(())
         }
     }
 }
 
-fn generate_block_item(
-    ctx: &mut Context,
-    g: &mut Generator,
-    block_item: &BlockItem,
-) -> Result<(), Box<dyn Error>> {
+fn generate_block_item(g: &mut Generator, block_item: &BlockItem) -> Result<(), Box<dyn Error>> {
     match block_item {
-        Stmt(stmt) => generate_stmt(ctx, g, stmt),
+        Stmt(stmt, _) => generate_stmt(g, stmt),
         Decl(decl) => generate_declaration(g, decl),
     }
 }
 
 fn generate_statement_in_new_scope(
-    ctx: &mut Context,
     g: &mut Generator,
     stmt: &Statement,
 ) -> Result<(), Box<dyn Error>> {
     g.allocator.enter_scope();
 
-    generate_stmt(ctx, g, stmt)?;
+    generate_stmt(g, stmt)?;
 
     g.allocator.exit_scope();
 
     Ok(())
 }
 
-fn generate_block(
-    ctx: &mut Context,
-    g: &mut Generator,
-    items: &[BlockItem],
-    outer_scope: Option<&HashSet<String>>,
-) -> Result<(), Box<dyn Error>> {
+fn generate_block(g: &mut Generator, items: &[BlockItem]) -> Result<(), Box<dyn Error>> {
     g.allocator.enter_scope();
 
-    let mut current_scope = HashSet::new();
-    if let Some(outer) = outer_scope {
-        current_scope.extend(outer.clone());
-    }
+    // variable-length arrays of the block are released when it ends
+    let saved_sp = if declares_var_array(items) {
+        let offset = g.allocator.reserve(SAVED_SP_SIZE, SAVED_SP_SIZE);
+        emit_save_sp(g, offset)?;
+        Some(offset)
+    } else {
+        None
+    };
 
     for item in items {
-        match item {
-            Decl(Declare(name, _)) => {
-                if !current_scope.insert(name.clone()) {
-                    return Err(format!("variable {} redeclared in same block", name).into());
-                }
-                generate_block_item(ctx, g, item)?;
-            }
-            Stmt(stmt) => {
-                generate_stmt(ctx, g, stmt)?;
-            }
-        }
+        generate_block_item(g, item)?;
     }
 
+    if let Some(offset) = saved_sp {
+        emit_restore_sp(g, offset)?;
+    }
     g.allocator.exit_scope();
 
     Ok(())
 }
 
-fn ends_with_return(item: &BlockItem) -> bool {
-    match item {
-        Stmt(stmt) => stmt_ends_with_return(stmt),
-        Decl(Declare(_, _)) => false,
+/// `sp = sp {instr} bytes`; sizes beyond the 12-bit immediate go through x16.
+/// Allocate x0 bytes, rounded up to keep sp aligned, below everything allocated at run time
+/// so far and leave their address in x0. Temporaries that the enclosing expression has
+/// pushed sit below that space, so they move down along with sp.
+fn emit_dynamic_alloc(g: &mut Generator) -> Result<(), Box<dyn Error>> {
+    let base = g
+        .dynamic_base
+        .ok_or("stack allocation in a function without a dynamic frame")?;
+    let copy = g.labels.next("_alloc_copy");
+    let done = g.labels.next("_alloc_done");
+
+    writeln!(g.output, "add\tx0, x0, #15")?;
+    writeln!(g.output, "and\tx0, x0, #-16")?;
+    let slot = frame_slot(g.output, base)?;
+    writeln!(g.output, "ldr\tx9, {slot}")?; // x9 = bottom of the allocations so far
+    writeln!(g.output, "sub\tx10, x9, x0")?; // x10 = bottom of the new space
+    writeln!(g.output, "mov\tx12, sp")?; // x12 = temporaries to move, up to x9
+    writeln!(g.output, "sub\tx13, x9, x12")?;
+    writeln!(g.output, "sub\tx13, x10, x13")?; // x13 = where they go
+    writeln!(g.output, "mov\tsp, x13")?;
+
+    writeln!(g.output, "{copy}:")?;
+    writeln!(g.output, "cmp\tx12, x9")?;
+    writeln!(g.output, "beq\t{done}")?;
+    writeln!(g.output, "ldp\tx14, x15, [x12], #16")?;
+    writeln!(g.output, "stp\tx14, x15, [x13], #16")?;
+    writeln!(g.output, "b\t{copy}")?;
+    writeln!(g.output, "{done}:")?;
+
+    let slot = frame_slot(g.output, base)?;
+    writeln!(g.output, "str\tx10, {slot}")?;
+    writeln!(g.output, "mov\tx0, x10")?;
+    Ok(())
+}
+
+/// Save sp to the frame slot at `offset`, between statements, where nothing is pushed.
+fn emit_save_sp(g: &mut Generator, offset: i32) -> fmt::Result {
+    writeln!(g.output, "mov\tx16, sp")?;
+    let slot = frame_slot(g.output, offset)?;
+    writeln!(g.output, "str\tx16, {slot}")
+}
+
+/// Release what was allocated at run time since [`emit_save_sp`] saved sp at `offset`.
+fn emit_restore_sp(g: &mut Generator, offset: i32) -> Result<(), Box<dyn Error>> {
+    let base = g
+        .dynamic_base
+        .ok_or("stack restore in a function without a dynamic frame")?;
+    let slot = frame_slot(g.output, offset)?;
+    writeln!(g.output, "ldr\tx16, {slot}")?;
+    writeln!(g.output, "mov\tsp, x16")?;
+    let slot = frame_slot(g.output, base)?;
+    writeln!(g.output, "str\tx16, {slot}")?;
+    Ok(())
+}
+
+/// A loop whose body declares variable-length arrays, or may jump out of a statement
+/// expression, saves sp before it starts. However an iteration ends, by `continue`,
+/// `break` or falling through, the arrays and any temporaries are released. So does
+/// a `switch` when it's left.
+fn emit_loop_save_sp(g: &mut Generator, body: &Statement) -> Result<Option<i32>, Box<dyn Error>> {
+    if !loop_saves_sp(body) {
+        return Ok(None);
     }
+    let offset = g.allocator.reserve(SAVED_SP_SIZE, SAVED_SP_SIZE);
+    emit_save_sp(g, offset)?;
+    Ok(Some(offset))
 }
 
-fn stmt_ends_with_return(stmt: &Statement) -> bool {
-    match stmt {
-        Statement::Return(_) => true,
-        Statement::If {
-            cond: _,
-            then,
-            els: Some(else_),
-        } => stmt_ends_with_return(then) && stmt_ends_with_return(else_),
-        Statement::Compound(items) => items
-            .iter()
-            .rev()
-            .find(|item| matches!(item, Stmt(_)))
-            .is_some_and(ends_with_return),
-        _ => false,
+fn emit_loop_restore_sp(g: &mut Generator, saved_sp: Option<i32>) -> Result<(), Box<dyn Error>> {
+    match saved_sp {
+        Some(offset) => emit_restore_sp(g, offset),
+        None => Ok(()),
+    }
+}
+
+fn emit_sp_adjust(output: &mut dyn Write, instr: &str, bytes: i32) -> fmt::Result {
+    if bytes <= 4095 {
+        return writeln!(output, "{instr}\tsp, sp, #{bytes}");
     }
+    emit_mov_imm(output, "x", 16, bytes as i64)?;
+    writeln!(output, "{instr}\tsp, sp, x16")
 }
 
 /// Generates
 pub fn generate_function(
     function: &Function,
     labels: &mut LabelGenerator,
-    platform: &str,
+    target: &Target,
     debug: bool,
-    global_vars: &HashMap<String, Variable>,
+    global_vars: &HashMap<String, (Variable, Type)>,
+    functions: &HashMap<String, Signature>,
+    static_data: &mut String,
 ) -> Result<String, Box<dyn Error>> {
     if function.block_items.is_none() {
         return Err("cannot generate function declaration".into());
     }
+    let platform = target.platform.as_str();
 
     let mut output = String::new();
 
     let prefix = function_label_prefix(platform)?;
 
-    let free_use_registers = &[
-        "w19", "w20", "w21", "w22", "w23", "w24", "w25", "w26", "w27", "w28",
-    ];
+    // callee-saved x19-x28, handed out from the back
+    let free_use_registers: Vec<u8> = (19..=28).collect();
 
-    let mut dry_allocator = Allocator::new(free_use_registers, global_vars);
+    let block_items = function.block_items.as_ref().unwrap();
+    let address_taken = address_taken_variables(block_items);
+    let params: Vec<Param> = function
+        .params
+        .iter()
+        .map(|p| Param {
+            name: p.name.clone(),
+            ty: parameter_storage_type(&p.ty, platform),
+            location: p.location,
+        })
+        .collect();
+
+    let mut dry_allocator = Allocator::new(&free_use_registers, global_vars, address_taken.clone());
+    let mut allocator = Allocator::new(&free_use_registers, global_vars, address_taken);
     let mut max_stack = 0;
 
-    let block_items = function.block_items.as_ref().unwrap();
-    simulate_stack_usage(block_items, &mut dry_allocator, &mut max_stack);
+    // the register save area is the first thing in the frame, before any parameter
+    let save_area = if function.variadic && platform == "linux" {
+        dry_allocator.reserve(VA_SAVE_AREA_SIZE, 16);
+        Some(allocator.reserve(VA_SAVE_AREA_SIZE, 16))
+    } else {
+        None
+    };
+
+    let dynamic_base = if uses_dynamic_stack(block_items) {
+        dry_allocator.reserve(SAVED_SP_SIZE, SAVED_SP_SIZE);
+        Some(allocator.reserve(SAVED_SP_SIZE, SAVED_SP_SIZE))
+    } else {
+        None
+    };
+
+    simulate_function_stack_usage(&params, block_items, &mut dry_allocator, &mut max_stack);
 
     let stack_size = ((max_stack + 15) / 16) * 16; // alignment
 
@@ -626,7 +3046,12 @@ pub fn generate_function(
         println!("stack size: {stack_size}");
     }
 
-    writeln!(output, ".global {}{}", prefix, function.name)?;
+    if !functions[&function.name].internal {
+        writeln!(output, ".global {}{}", prefix, function.name)?;
+    }
+    if platform == "linux" {
+        writeln!(output, ".type\t{}, @function", function.name)?;
+    }
     writeln!(output, "{}{}:", prefix, function.name)?;
 
     // ---------- function prologue ----------
@@ -651,7 +3076,25 @@ pub fn generate_function(
     }
 
     if stack_size > 0 {
-        writeln!(output, "sub\tsp, sp, #{}", stack_size)?;
+        emit_sp_adjust(&mut output, "sub", stack_size)?;
+    }
+
+    if let Some(base) = save_area {
+        // spill every argument register, va_arg picks the unnamed ones from here
+        emit_frame_address(&mut output, "x9", base)?;
+        for i in 0..8 {
+            writeln!(output, "str\td{i}, [x9, #{}]", 16 * i)?;
+        }
+        for i in (0..8).step_by(2) {
+            writeln!(output, "stp\tx{i}, x{}, [x9, #{}]", i + 1, 128 + 8 * i)?;
+        }
+    }
+
+    if let Some(base) = dynamic_base {
+        // nothing is allocated yet, the bottom is the fixed frame
+        writeln!(output, "mov\tx16, sp")?;
+        let slot = frame_slot(&mut output, base)?;
+        writeln!(output, "str\tx16, {slot}")?;
     }
 
     let epilogue = labels.next("func_epilogue");
@@ -660,70 +3103,86 @@ pub fn generate_function(
     let mut generator = Generator {
         output: &mut output,
         labels,
-        allocator: Allocator::new(free_use_registers, global_vars),
+        allocator,
+        functions,
+        return_type: function.return_type.clone(),
         epilogue: epilogue.clone(),
         debug_enabled: debug,
+        function_name: function.name.clone(),
+        static_data,
+        varargs: None,
+        dynamic_base,
+        local_types: local_types(block_items),
         platform: platform.to_string(),
+        features: target.features,
     };
 
-    let saw_return = block_items
-        .iter()
-        .rev()
-        .find(|item| matches!(item, Stmt(_)))
-        .is_some_and(ends_with_return);
-
-    let mut ctx = Context {
-        break_label: None,
-        continue_label: None,
-    };
-
-    // assign incoming parameters to allocator and move them from w0–w7 into locals
-    for (i, param) in function.params.iter().enumerate() {
-        if i >= 8 {
-            return Err("more than 8 parameters not supported".into());
-        }
-
-        let var = generator.allocator.allocate(param.clone(), 4);
-        generator.debug(format!("param {param} -> {var:?}"));
-
-        match var {
-            Variable::Register(reg) => {
-                writeln!(generator.output, "mov\t{}, w{}", reg, i)?;
-            }
-            Variable::Stack(offset) => {
-                writeln!(generator.output, "str\tw{}, [x29, #{:+}]", i, offset)?;
-            }
-            Variable::Global(label) => {
-                Variable::emit_global_variable_address_load_x1(&label, generator.output, platform)?;
-                writeln!(generator.output, "str\tw{}, [x1]", i)?;
+    let falls_off_end = analyze_control_flow(&Cfg::build(function)).falls_off_end;
+
+    // assign incoming parameters to allocator and move them from x0–x7 / d0–d7
+    // or the caller's argument area (right above the frame record) into locals
+    let param_types: Vec<Type> = params.iter().map(|p| p.ty.clone()).collect();
+    let (locations, named_stack) = assign_arg_locations(&param_types, params.len(), platform);
+    for (param, location) in params.iter().zip(&locations) {
+        let var = generator.allocator.allocate(param.name.clone(), &param.ty);
+        generator.debug(format!("param {} -> {var:?}", param.name));
+
+        let reg = match *location {
+            ArgLocation::Register(reg) => reg,
+            ArgLocation::Stack(offset) => {
+                // x16/d16 are free scratch registers at this point
+                let (load, r) = (load_instr(&param.ty), reg_prefix(&param.ty));
+                let slot = frame_slot(generator.output, 16 + offset)?;
+                writeln!(generator.output, "{load}\t{r}16, {slot}")?;
+                var.emit_store(&param.ty, 16, generator.output, platform)?;
+                continue;
             }
+        };
+
+        // narrow arguments arrive with unspecified upper bits
+        if param.ty.size() < 4 && param.ty.is_integer() {
+            let extend = match param.ty.unqualified() {
+                Type::Char => "sxtb",
+                Type::UChar => "uxtb",
+                Type::Short => "sxth",
+                _ => "uxth",
+            };
+            writeln!(generator.output, "{extend}\tw{reg}, w{reg}")?;
         }
+
+        var.emit_store(&param.ty, reg, generator.output, platform)?;
     }
 
-    let mut top_scope_names = HashSet::new();
-    for param in &function.params {
-        if !top_scope_names.insert(param.clone()) {
-            return Err(format!("duplicate parameter '{}'", param).into());
-        }
+    if function.variadic {
+        // named parameters past the first eight of a class don't take registers
+        let registers = |floating: bool| {
+            let named = param_types.iter().filter(|ty| ty.is_floating() == floating);
+            named.count().min(8)
+        };
+        generator.varargs = Some(VarArgs {
+            save_area,
+            named_gp: registers(false),
+            named_fp: registers(true),
+            named_stack,
+        });
     }
 
-    generate_block(
-        &mut ctx,
-        &mut generator,
-        block_items,
-        Some(&top_scope_names),
-    )?;
+    generate_block(&mut generator, block_items)?;
 
-    // emit default return if none provided
-    if !saw_return {
+    // emit default return if control can get here without one
+    if falls_off_end {
         writeln!(generator.output, "mov\tw0, #0")?;
         // fallthrough to epilogue
     }
 
     // function epilogue
     writeln!(output, "{}:", epilogue)?;
-    if stack_size > 0 {
-        writeln!(output, "add\tsp, sp, #{}", stack_size)?;
+    if dynamic_base.is_some() {
+        // release everything allocated at run time along with the fixed frame
+        emit_frame_address(&mut output, "x16", -CALLEE_SAVED_AREA)?;
+        writeln!(output, "mov\tsp, x16")?;
+    } else if stack_size > 0 {
+        emit_sp_adjust(&mut output, "add", stack_size)?;
     }
 
     // restore x27-x28 … x19-x20 (reverse order!)
@@ -733,50 +3192,376 @@ pub fn generate_function(
 
     writeln!(output, "ldp\tx29, x30, [sp], #16")?;
     writeln!(output, "ret")?;
+    if platform == "linux" {
+        writeln!(output, ".size\t{0}, .-{0}", function.name)?;
+    }
 
     Ok(output)
 }
 
-pub fn generate(program: &Program, platform: &str, debug: bool) -> Result<String, Box<dyn Error>> {
-    let program = constant_fold(program);
+/// Assembler directive that emits one object of type `ty`.
+fn data_directive(ty: &Type) -> &'static str {
+    match ty.size() {
+        1 => ".byte",
+        2 => ".hword",
+        4 => ".word",
+        _ => ".quad",
+    }
+}
+
+/// Section directive for static data; `read_only` for const objects,
+/// `zero` for objects without any non-zero byte. Read-only objects holding
+/// addresses are patched by the dynamic linker, so they can't live in a text segment.
+fn data_section(platform: &str, read_only: bool, zero: bool, relocated: bool) -> &'static str {
+    match (platform, read_only, zero) {
+        ("macos", true, _) if relocated => ".section __DATA,__const",
+        ("macos", true, _) => ".section __TEXT,__const",
+        (_, true, _) if relocated => ".section .data.rel.ro,\"aw\"",
+        (_, true, _) => ".section .rodata",
+        (_, false, true) => ".bss",
+        (_, false, false) => ".data",
+    }
+}
+
+/// One scalar part of a static object.
+#[derive(Debug, PartialEq)]
+enum StaticValue {
+    /// bits emitted by [`data_directive`]
+    Bits(i128),
+    /// address of a function symbol, resolved by the linker
+    Address(String),
+}
+
+/// Function designated by a static initializer of a function pointer: `f` or `&f`.
+fn static_function_address<'e>(
+    expr: Option<&'e Expr>,
+    functions: &HashMap<String, Signature>,
+) -> Option<&'e str> {
+    let designator = match expr? {
+        Expr::AddrOf(inner) => inner.as_ref(),
+        other => other,
+    };
+    match designator {
+        Expr::Var(f) if functions.contains_key(f) => Some(f),
+        _ => None,
+    }
+}
+
+/// Value of one scalar part of a static object.
+fn static_slot_value(
+    name: &str,
+    slot: &InitSlot,
+    functions: &HashMap<String, Signature>,
+    platform: &str,
+) -> Result<StaticValue, Box<dyn Error>> {
+    if let Some(f) = static_function_address(slot.value, functions) {
+        let ty = Type::Function(Box::new(functions[f].ty.clone()));
+        return match slot.ty.unqualified() {
+            Type::Pointer(pointee) if same_pointee(pointee, &ty) => {
+                let prefix = function_label_prefix(platform)?;
+                Ok(StaticValue::Address(format!("{prefix}{f}")))
+            }
+            _ => Err(format!(
+                "cannot initialize {name} of type '{}' with function '{f}'",
+                slot.ty
+            )
+            .into()),
+        };
+    }
+
+    // a pointer may be initialized with null or an integer cast to a pointer type
+    let (expr, cast_to_pointer) = match slot.value {
+        Some(Expr::Cast(ty, inner)) if ty.is_pointer() && slot.ty.is_pointer() => {
+            (Some(inner.as_ref()), true)
+        }
+        other => (other, false),
+    };
+    let val = match expr {
+        Some(expr) => evaluate_compile_time_expr(expr)
+            .map_err(|e| format!("initializer of {name} is not constant: {e}"))?,
+        None => Constant::Int(0),
+    };
+
+    if slot.ty.is_pointer() {
+        if !val.ty().is_integer() || !(val.is_zero() || cast_to_pointer) {
+            return Err(format!("initializer of pointer {name} is not constant").into());
+        }
+        return Ok(StaticValue::Bits(
+            val.convert(&Type::ULong).unwrap().as_i128(),
+        ));
+    }
+
+    let val = val
+        .convert(&slot.ty)
+        .ok_or_else(|| format!("invalid initializer for {name}"))?;
+    Ok(StaticValue::Bits(if slot.ty.is_floating() {
+        val.bits() as i128
+    } else {
+        val.as_i128()
+    }))
+}
+
+/// A file-scope object, merged from all of its declarations.
+struct FileScopeObject<'a> {
+    ty: Type,
+    init: Option<&'a Initializer>,
+    /// `false` if the object is only declared `extern` in this file
+    defined: bool,
+    /// declared `static`: the symbol is not exported from the object file
+    internal: bool,
+    /// declared `_Thread_local`
+    thread_local: bool,
+}
+
+/// An object with static or thread storage duration, emitted at `label`.
+struct StaticObject<'a> {
+    label: &'a str,
+    /// C name, for error messages
+    name: &'a str,
+    ty: &'a Type,
+    init: Option<&'a Initializer>,
+    /// exports the symbol to other object files
+    global: bool,
+    /// every thread has its own copy, initialized from the emitted data
+    thread_local: bool,
+}
+
+/// Emit the storage of a static object in its own section.
+fn emit_static_object(
+    output: &mut dyn Write,
+    platform: &str,
+    functions: &HashMap<String, Signature>,
+    object: &StaticObject,
+) -> Result<(), Box<dyn Error>> {
+    let StaticObject {
+        label,
+        name,
+        ty,
+        init,
+        global,
+        thread_local,
+    } = *object;
+    let slots = flatten_initializer(ty, init)?;
+    let values = slots
+        .iter()
+        .map(|slot| static_slot_value(name, slot, functions, platform))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    // const objects can never be written, so they go to a read-only section;
+    // objects that are all zeros take no space in the file
+    let read_only = ty.qualifiers().is_const;
+    let zero = values.iter().all(|value| *value == StaticValue::Bits(0));
+    let relocated = values
+        .iter()
+        .any(|value| matches!(value, StaticValue::Address(_)));
+    let (size, align_log2) = (ty.size(), ty.alignment().trailing_zeros());
+
+    if global {
+        writeln!(output, ".global\t{label}")?;
+    }
+    if thread_local && platform == "macos" {
+        return emit_thread_local_descriptor(output, label, ty, &slots, values);
+    }
+    if platform == "macos" && zero && !read_only {
+        // Mach-O zero-fill sections are reserved with a single directive that defines the symbol
+        writeln!(output, ".zerofill __DATA,__bss,{label},{size},{align_log2}")?;
+        return Ok(());
+    }
+
+    // the initial value of every thread's copy of a thread-local object
+    let section = match (thread_local, zero) {
+        (true, true) => ".section .tbss,\"awT\",@nobits",
+        (true, false) => ".section .tdata,\"awT\",@progbits",
+        (false, _) => data_section(platform, read_only, zero, relocated),
+    };
+    writeln!(output, "{section}")?;
+    writeln!(output, ".p2align {align_log2}")?;
+    if platform == "linux" {
+        writeln!(output, ".type\t{label}, @object")?;
+        writeln!(output, ".size\t{label}, {size}")?;
+    }
+    writeln!(output, "{label}:")?;
+    emit_static_values(output, ty, &slots, values)
+}
+
+/// Emit a Mach-O thread-local object: a template for the copy of each thread,
+/// and at `label` the TLV descriptor whose accessor returns the current thread's copy.
+fn emit_thread_local_descriptor(
+    output: &mut dyn Write,
+    label: &str,
+    ty: &Type,
+    slots: &[InitSlot],
+    values: Vec<StaticValue>,
+) -> Result<(), Box<dyn Error>> {
+    let (size, align_log2) = (ty.size(), ty.alignment().trailing_zeros());
+    let init = format!("{label}$tlv$init");
+    if values.iter().all(|value| *value == StaticValue::Bits(0)) {
+        writeln!(output, ".tbss {init}, {size}, {align_log2}")?;
+    } else {
+        writeln!(output, ".section __DATA,__thread_data,thread_local_regular")?;
+        writeln!(output, ".p2align {align_log2}")?;
+        writeln!(output, "{init}:")?;
+        emit_static_values(output, ty, slots, values)?;
+    }
 
-    validate_functions_declarations(&program)?;
-    check_global_name_conflicts(&program)?;
+    writeln!(
+        output,
+        ".section __DATA,__thread_vars,thread_local_variables"
+    )?;
+    writeln!(output, ".p2align 3")?;
+    writeln!(output, "{label}:")?;
+    writeln!(output, "\t.quad\t__tlv_bootstrap")?;
+    writeln!(output, "\t.quad\t0")?;
+    writeln!(output, "\t.quad\t{init}")?;
+    Ok(())
+}
+
+/// Emit the bytes of a static object of type `ty` from the values of its parts.
+fn emit_static_values(
+    output: &mut dyn Write,
+    ty: &Type,
+    slots: &[InitSlot],
+    values: Vec<StaticValue>,
+) -> Result<(), Box<dyn Error>> {
+    if values.iter().all(|value| *value == StaticValue::Bits(0)) {
+        writeln!(output, "\t.zero\t{}", ty.size())?;
+        return Ok(());
+    }
+    let mut emitted = 0;
+    for (slot, value) in slots.iter().zip(values) {
+        if slot.offset > emitted {
+            writeln!(output, "\t.zero\t{}", slot.offset - emitted)?;
+        }
+        match value {
+            StaticValue::Bits(bits) => writeln!(output, "\t{}\t{bits}", data_directive(&slot.ty))?,
+            StaticValue::Address(symbol) => writeln!(output, "\t.quad\t{symbol}")?,
+        }
+        emitted = slot.offset + slot.ty.size();
+    }
+    Ok(())
+}
+
+/// Generates the assembly of a program that passed [`crate::semantic::analyze`].
+pub fn generate(program: &Program, target: &Target, debug: bool) -> Result<String, Box<dyn Error>> {
+    let platform = target.platform.as_str();
+    let program = constant_fold(program);
 
     let mut output = String::new();
     let mut labels = LabelGenerator::new();
 
-    let mut global_vars_all: HashMap<String, Variable> = HashMap::new();
-    let mut global_vars_definitions: HashMap<String, &Option<Expr>> = HashMap::new();
+    let mut functions: HashMap<String, Signature> = HashMap::new();
+    for item in &program.toplevel_items {
+        if let TopLevel::Function(f) = item {
+            let signature = functions
+                .entry(f.name.clone())
+                .or_insert_with(|| Signature {
+                    ty: f.function_type(),
+                    internal: f.storage == Some(StorageClass::Static),
+                });
+            // the first prototype provides the parameters
+            if f.prototyped && !signature.ty.prototyped {
+                signature.ty = f.function_type();
+            }
+        }
+    }
+
+    let prefix = function_label_prefix(platform)?;
+    let mut globals: HashMap<&String, FileScopeObject> = HashMap::new();
+    let mut globals_order = Vec::new();
 
-    // first loop: generate global data
-    let mut any_globals = false;
+    // first loop: merge all declarations of each file-scope object
     for item in &program.toplevel_items {
-        if let TopLevel::GlobalVariable(Declare(name, expr)) = item {
-            if !any_globals {
-                writeln!(output, ".data")?;
-                any_globals = true;
-            }
-            if let Some(prev) = global_vars_definitions.get(name) {
-                if prev.is_some() {
-                    return Err(format!("global variable {name} defined twice").into());
-                }
+        if let TopLevel::GlobalVariable(Declare {
+            name,
+            ty,
+            init,
+            storage,
+            thread_local,
+            ..
+        }) = item
+        {
+            let is_static = *storage == Some(StorageClass::Static);
+            let defines = init.is_some() || *storage != Some(StorageClass::Extern);
+
+            let Some(prev) = globals.get_mut(name) else {
+                globals_order.push(name);
+                globals.insert(
+                    name,
+                    FileScopeObject {
+                        ty: ty.clone(),
+                        init: init.as_ref(),
+                        defined: defines,
+                        internal: is_static,
+                        thread_local: *thread_local,
+                    },
+                );
+                continue;
+            };
+
+            if prev.thread_local != *thread_local {
+                let (this, previous) = match thread_local {
+                    true => ("thread-local", "non-thread-local"),
+                    false => ("non-thread-local", "thread-local"),
+                };
+                return Err(format!(
+                    "{this} declaration of '{name}' follows {previous} declaration"
+                )
+                .into());
             }
 
-            let mut val = 0;
-            if let Some(expr) = expr {
-                val = evaluate_compile_time_expr(expr)?;
+            if prev.init.is_some() && init.is_some() {
+                return Err(format!("global variable {name} defined twice").into());
+            }
+            if prev.ty != *ty {
+                return Err(format!(
+                    "conflicting types for global variable {name} ({} vs {ty})",
+                    prev.ty
+                )
+                .into());
+            }
+            // `extern` keeps the linkage of an earlier declaration, anything else must agree with it
+            if is_static && !prev.internal {
+                return Err(format!(
+                    "static declaration of '{name}' follows non-static declaration"
+                )
+                .into());
+            }
+            if storage.is_none() && prev.internal {
+                return Err(format!(
+                    "non-static declaration of '{name}' follows static declaration"
+                )
+                .into());
             }
 
-            let prefix = function_label_prefix(platform)?;
-            let label = labels.next(&format!("{prefix}global_{name}"));
-            writeln!(output, ".global\t{label}")?;
-            writeln!(output, "{label}:")?;
-            writeln!(output, "\t.word\t{val}")?;
+            prev.defined |= defines;
+            prev.init = prev.init.or(init.as_ref());
+        }
+    }
 
-            global_vars_all.insert(name.clone(), Variable::Global(label));
-            global_vars_definitions.insert(name.clone(), expr);
+    // emit each defined object once, under its C symbol name
+    let mut global_vars_all: HashMap<String, (Variable, Type)> = HashMap::new();
+    for name in globals_order {
+        let object = &globals[name];
+        let label = format!("{prefix}{name}");
+        if object.defined {
+            let object = StaticObject {
+                label: &label,
+                name,
+                ty: &object.ty,
+                init: object.init,
+                global: !object.internal,
+                thread_local: object.thread_local,
+            };
+            emit_static_object(&mut output, platform, &functions, &object)?;
         }
+        let var = match object.thread_local {
+            true => Variable::ThreadLocal {
+                label,
+                defined: object.defined,
+            },
+            false => Variable::Global(label),
+        };
+        global_vars_all.insert(name.clone(), (var, object.ty.clone()));
     }
 
     // emit all functions in .text
@@ -806,10 +3591,11 @@ pub fn generate(program: &Program, platform: &str, debug: bool) -> Result<String
     }
 
     let mut seen_globals = HashMap::new();
+    let mut static_data = String::new();
 
     for item in &program.toplevel_items {
         match item {
-            TopLevel::GlobalVariable(Declare(name, _)) => {
+            TopLevel::GlobalVariable(Declare { name, .. }) => {
                 // populate in declaration order
                 seen_globals.insert(name.clone(), global_vars_all.get(name).unwrap().clone());
             }
@@ -817,12 +3603,26 @@ pub fn generate(program: &Program, platform: &str, debug: bool) -> Result<String
                 if function.block_items.is_none() {
                     continue;
                 }
-                output +=
-                    &generate_function(function, &mut labels, platform, debug, &seen_globals)?;
+                output += &generate_function(
+                    function,
+                    &mut labels,
+                    target,
+                    debug,
+                    &seen_globals,
+                    &functions,
+                    &mut static_data,
+                )?;
                 output.push('\n');
             }
         }
     }
 
+    output += &static_data;
+
+    if platform == "linux" {
+        // marks the stack as non-executable, otherwise the linker assumes it has to be
+        writeln!(output, ".section .note.GNU-stack,\"\",@progbits")?;
+    }
+
     Ok(output)
 }
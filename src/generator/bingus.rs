@@ -3,16 +3,24 @@ use crate::ast::{BlockItem, Statement};
 
 pub fn is_bingus_used(block_item: &BlockItem) -> bool {
     match block_item {
-        Stmt(Statement::Bingus(_)) => true,
+        Stmt(stmt, _) => is_bingus_used_in(stmt),
+        _ => false,
+    }
+}
+
+fn is_bingus_used_in(stmt: &Statement) -> bool {
+    match stmt {
+        Statement::Bingus(_) => true,
 
-        Stmt(
-            Statement::For { body, .. }
-            | Statement::ForDecl { body, .. }
-            | Statement::While { body, .. }
-            | Statement::Do { body, .. },
-        ) => is_bingus_used(&Stmt(*body.clone())),
+        Statement::For { body, .. }
+        | Statement::ForDecl { body, .. }
+        | Statement::While { body, .. }
+        | Statement::Do { body, .. }
+        | Statement::Switch { body, .. }
+        | Statement::Case { body, .. }
+        | Statement::Default { body, .. } => is_bingus_used_in(body),
 
-        Stmt(Statement::Compound(block_items)) => block_items.iter().any(is_bingus_used),
+        Statement::Compound(block_items) => block_items.iter().any(is_bingus_used),
 
         _ => false,
     }
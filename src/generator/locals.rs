@@ -0,0 +1,106 @@
+use crate::ast::{BlockItem, Declaration, Expr, Initializer, Statement, Type};
+use std::collections::HashMap;
+
+/// Collects the declared type of every block-scope name in the function body.
+///
+/// Names are unique within a function once identifiers are resolved. The type of a
+/// statement expression is that of its last statement, which may use names declared
+/// in it; they aren't in the allocator's scope until its code is generated.
+pub fn local_types(items: &[BlockItem]) -> HashMap<String, Type> {
+    let mut types = HashMap::new();
+    visit_items(items, &mut types);
+    types
+}
+
+fn visit_items(items: &[BlockItem], types: &mut HashMap<String, Type>) {
+    for item in items {
+        match item {
+            BlockItem::Stmt(stmt, _) => visit_stmt(stmt, types),
+            BlockItem::Decl(decl) => visit_decl(decl, types),
+        }
+    }
+}
+
+fn visit_decl(decl: &Declaration, types: &mut HashMap<String, Type>) {
+    let Declaration::Declare { name, ty, init, .. } = decl;
+    if let Type::VarArray(_, len) = ty {
+        visit_expr(len, types);
+    }
+    types.insert(name.clone(), ty.clone());
+    if let Some(init) = init {
+        visit_initializer(init, types);
+    }
+}
+
+fn visit_initializer(init: &Initializer, types: &mut HashMap<String, Type>) {
+    match init {
+        Initializer::Single(expr) => visit_expr(expr, types),
+        Initializer::List(items) => items.iter().for_each(|i| visit_initializer(i, types)),
+    }
+}
+
+fn visit_stmt(stmt: &Statement, types: &mut HashMap<String, Type>) {
+    match stmt {
+        Statement::Return(expr) | Statement::Expr(expr) => {
+            expr.iter().for_each(|expr| visit_expr(expr, types));
+        }
+        Statement::Bingus(expr) => visit_expr(expr, types),
+        Statement::If { cond, then, els } => {
+            visit_expr(cond, types);
+            visit_stmt(then, types);
+            if let Some(els) = els {
+                visit_stmt(els, types);
+            }
+        }
+        Statement::Compound(items) => visit_items(items, types),
+        Statement::For {
+            init,
+            cond,
+            post,
+            body,
+            ..
+        } => {
+            for expr in [init.as_ref(), Some(cond), post.as_ref()]
+                .into_iter()
+                .flatten()
+            {
+                visit_expr(expr, types);
+            }
+            visit_stmt(body, types);
+        }
+        Statement::ForDecl {
+            decl,
+            cond,
+            post,
+            body,
+            ..
+        } => {
+            visit_decl(decl, types);
+            visit_expr(cond, types);
+            post.iter().for_each(|post| visit_expr(post, types));
+            visit_stmt(body, types);
+        }
+        Statement::While { cond, body, .. }
+        | Statement::Do { body, cond, .. }
+        | Statement::Switch { cond, body, .. } => {
+            visit_expr(cond, types);
+            visit_stmt(body, types);
+        }
+        Statement::Case { body, .. } | Statement::Default { body, .. } => visit_stmt(body, types),
+        Statement::Break(_) | Statement::Continue(_) => {}
+        Statement::Asm(asm) => asm
+            .operands()
+            .for_each(|operand| visit_expr(&operand.expr, types)),
+    }
+}
+
+/// Only statement expressions declare anything in an expression.
+fn visit_expr(expr: &Expr, types: &mut HashMap<String, Type>) {
+    match expr {
+        Expr::Block(items) => visit_items(items, types),
+        _ => expr
+            .operands()
+            .into_iter()
+            .for_each(|operand| visit_expr(operand, types)),
+    }
+}
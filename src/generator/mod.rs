@@ -1,6 +1,7 @@
+mod address_taken;
 mod allocator;
 pub mod arm64;
 mod bingus;
-pub mod function_validation;
 mod label;
+mod locals;
 mod stack;
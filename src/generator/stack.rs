@@ -1,32 +1,415 @@
 use crate::ast::Declaration::Declare;
-use crate::ast::{BlockItem, Statement};
+use crate::ast::{BlockItem, Declaration, Expr, Initializer, Param, Statement, Type};
 use crate::generator::allocator::Allocator;
 
+/// Size of a frame slot that saves sp, see [`declares_var_array`] and [`loop_saves_sp`].
+pub const SAVED_SP_SIZE: i32 = 8;
+
+/// Dry-runs the allocator over a function the same way code generation does
+/// (parameters first, then every declaration in the order its code is generated)
+/// and records the peak stack usage in `max`.
+pub fn simulate_function_stack_usage(
+    params: &[Param],
+    items: &[BlockItem],
+    allocator: &mut Allocator,
+    max: &mut i32,
+) {
+    for param in params {
+        allocator.allocate(param.name.clone(), &param.ty);
+        *max = (*max).max(allocator.total_stack_size());
+    }
+    simulate_stack_usage(items, allocator, max);
+}
+
 pub fn simulate_stack_usage(items: &[BlockItem], allocator: &mut Allocator, max: &mut i32) {
     allocator.enter_scope();
+    if declares_var_array(items) {
+        allocator.reserve(SAVED_SP_SIZE, SAVED_SP_SIZE);
+    }
 
     for item in items {
         match item {
-            BlockItem::Decl(Declare(name, _)) => {
-                allocator.allocate(name.clone(), 4);
-                *max = (*max).max(allocator.total_stack_size());
-            }
-            BlockItem::Stmt(stmt) => simulate_stmt_stack(stmt, allocator, max),
+            // objects with static storage and functions take no stack space
+            BlockItem::Decl(Declare {
+                storage: Some(_), ..
+            }) => {}
+            BlockItem::Decl(Declare { ty, .. }) if ty.is_function() => {}
+            BlockItem::Decl(decl) => simulate_decl_stack(decl, allocator, max),
+            BlockItem::Stmt(stmt, _) => simulate_stmt_stack(stmt, allocator, max),
         }
     }
 
     allocator.exit_scope();
 }
 
+/// The length of a variable-length array is evaluated before the array is allocated,
+/// the initializer after the object.
+fn simulate_decl_stack(decl: &Declaration, allocator: &mut Allocator, max: &mut i32) {
+    let Declare { name, ty, init, .. } = decl;
+    if let Type::VarArray(_, len) = ty {
+        simulate_expr_stack(len, allocator, max);
+    }
+    allocator.allocate(name.clone(), ty);
+    *max = (*max).max(allocator.total_stack_size());
+    if let Some(init) = init {
+        simulate_initializer_stack(init, allocator, max);
+    }
+}
+
+fn simulate_initializer_stack(init: &Initializer, allocator: &mut Allocator, max: &mut i32) {
+    match init {
+        Initializer::Single(expr) => simulate_expr_stack(expr, allocator, max),
+        Initializer::List(items) => {
+            (items.iter()).for_each(|item| simulate_initializer_stack(item, allocator, max))
+        }
+    }
+}
+
+/// Only statement expressions declare anything in an expression.
+fn simulate_expr_stack(expr: &Expr, allocator: &mut Allocator, max: &mut i32) {
+    match expr {
+        Expr::Block(items) => simulate_stack_usage(items, allocator, max),
+        _ => (expr.operands().into_iter())
+            .for_each(|operand| simulate_expr_stack(operand, allocator, max)),
+    }
+}
+
+fn simulate_expr_stack_opt(expr: Option<&Expr>, allocator: &mut Allocator, max: &mut i32) {
+    if let Some(expr) = expr {
+        simulate_expr_stack(expr, allocator, max);
+    }
+}
+
 fn simulate_stmt_stack(stmt: &Statement, allocator: &mut Allocator, max: &mut i32) {
     match stmt {
-        Statement::If { cond: _, then, els } => {
+        Statement::If { cond, then, els } => {
+            simulate_expr_stack(cond, allocator, max);
             simulate_stmt_stack(then, allocator, max);
             if let Some(els) = els {
                 simulate_stmt_stack(els, allocator, max);
             }
         }
         Statement::Compound(items) => simulate_stack_usage(items, allocator, max),
-        _ => {}
+        Statement::For {
+            init,
+            cond,
+            post,
+            body,
+            ..
+        } => {
+            if loop_saves_sp(body) {
+                allocator.reserve(SAVED_SP_SIZE, SAVED_SP_SIZE);
+            }
+            simulate_expr_stack_opt(init.as_ref(), allocator, max);
+            simulate_expr_stack(cond, allocator, max);
+            simulate_stmt_stack(body, allocator, max);
+            simulate_expr_stack_opt(post.as_ref(), allocator, max);
+        }
+        Statement::While { cond, body, .. } => {
+            if loop_saves_sp(body) {
+                allocator.reserve(SAVED_SP_SIZE, SAVED_SP_SIZE);
+            }
+            simulate_expr_stack(cond, allocator, max);
+            simulate_stmt_stack(body, allocator, max);
+        }
+        Statement::Do { body, cond, .. } => {
+            if loop_saves_sp(body) {
+                allocator.reserve(SAVED_SP_SIZE, SAVED_SP_SIZE);
+            }
+            simulate_stmt_stack(body, allocator, max);
+            simulate_expr_stack(cond, allocator, max);
+        }
+        Statement::Switch { cond, body, .. } => {
+            if loop_saves_sp(body) {
+                allocator.reserve(SAVED_SP_SIZE, SAVED_SP_SIZE);
+            }
+            simulate_expr_stack(cond, allocator, max);
+            simulate_stmt_stack(body, allocator, max);
+        }
+        Statement::Case { body, .. } | Statement::Default { body, .. } => {
+            simulate_stmt_stack(body, allocator, max)
+        }
+        Statement::ForDecl {
+            decl,
+            cond,
+            post,
+            body,
+            ..
+        } => {
+            simulate_decl_stack(decl, allocator, max);
+            if loop_saves_sp(body) {
+                allocator.reserve(SAVED_SP_SIZE, SAVED_SP_SIZE);
+            }
+            *max = (*max).max(allocator.total_stack_size());
+            simulate_expr_stack(cond, allocator, max);
+            simulate_stmt_stack(body, allocator, max);
+            simulate_expr_stack_opt(post.as_ref(), allocator, max);
+        }
+        Statement::Return(expr) | Statement::Expr(expr) => {
+            simulate_expr_stack_opt(expr.as_ref(), allocator, max);
+        }
+        Statement::Bingus(expr) => simulate_expr_stack(expr, allocator, max),
+        Statement::Asm(asm) => {
+            (asm.operands()).for_each(|operand| simulate_expr_stack(&operand.expr, allocator, max))
+        }
+        Statement::Break(_) | Statement::Continue(_) => {}
+    }
+}
+
+/// `true` if the block declares a variable-length array itself, not in a nested block.
+/// Such a block saves sp on entry and restores it on exit, which frees the arrays.
+pub fn declares_var_array(items: &[BlockItem]) -> bool {
+    items
+        .iter()
+        .any(|item| matches!(item, BlockItem::Decl(Declare { ty, .. }) if ty.is_var_array()))
+}
+
+/// `true` if a variable-length array is declared anywhere in `stmt`. A loop with
+/// such a body restores sp when it continues or exits, however the body is left.
+pub fn contains_var_array(stmt: &Statement) -> bool {
+    match stmt {
+        Statement::Compound(items) => items_contain_var_array(items),
+        Statement::If { then, els, .. } => {
+            contains_var_array(then) || els.as_deref().is_some_and(contains_var_array)
+        }
+        Statement::For { body, .. }
+        | Statement::ForDecl { body, .. }
+        | Statement::While { body, .. }
+        | Statement::Do { body, .. }
+        | Statement::Switch { body, .. }
+        | Statement::Case { body, .. }
+        | Statement::Default { body, .. } => contains_var_array(body),
+        _ => false,
+    }
+}
+
+/// `true` if a variable-length array is declared anywhere in the block.
+pub fn items_contain_var_array(items: &[BlockItem]) -> bool {
+    items.iter().any(|item| match item {
+        BlockItem::Decl(Declare { ty, .. }) => ty.is_var_array(),
+        BlockItem::Stmt(stmt, _) => contains_var_array(stmt),
+    })
+}
+
+/// `true` if a loop with the body `body` saves sp before it starts and restores it when
+/// it continues or exits: the body declares variable-length arrays, or a `break` or
+/// `continue` in a statement expression may leave temporaries of the expression around
+/// it on the stack.
+pub fn loop_saves_sp(body: &Statement) -> bool {
+    contains_var_array(body) || stmt_jumps_from_expr(body)
+}
+
+/// `true` if a statement expression in `stmt` contains a `break` or a `continue`.
+fn stmt_jumps_from_expr(stmt: &Statement) -> bool {
+    match stmt {
+        Statement::Return(expr) | Statement::Expr(expr) => expr.as_ref().is_some_and(jumps_from),
+        Statement::Bingus(expr) => jumps_from(expr),
+        Statement::If { cond, then, els } => {
+            jumps_from(cond)
+                || stmt_jumps_from_expr(then)
+                || els.as_deref().is_some_and(stmt_jumps_from_expr)
+        }
+        Statement::Compound(items) => items.iter().any(|item| match item {
+            BlockItem::Decl(Declare { init, .. }) => init
+                .as_ref()
+                .is_some_and(|init| initializer_exprs(init).into_iter().any(jumps_from)),
+            BlockItem::Stmt(stmt, _) => stmt_jumps_from_expr(stmt),
+        }),
+        Statement::For {
+            init,
+            cond,
+            post,
+            body,
+            ..
+        } => {
+            [init.as_ref(), Some(cond), post.as_ref()]
+                .into_iter()
+                .flatten()
+                .any(jumps_from)
+                || stmt_jumps_from_expr(body)
+        }
+        Statement::ForDecl {
+            decl: Declare { init, .. },
+            cond,
+            post,
+            body,
+            ..
+        } => {
+            init.as_ref()
+                .is_some_and(|init| initializer_exprs(init).into_iter().any(jumps_from))
+                || jumps_from(cond)
+                || post.as_ref().is_some_and(jumps_from)
+                || stmt_jumps_from_expr(body)
+        }
+        Statement::While { cond, body, .. }
+        | Statement::Do { body, cond, .. }
+        | Statement::Switch { cond, body, .. } => jumps_from(cond) || stmt_jumps_from_expr(body),
+        Statement::Case { body, .. } | Statement::Default { body, .. } => {
+            stmt_jumps_from_expr(body)
+        }
+        Statement::Break(_) | Statement::Continue(_) => false,
+        Statement::Asm(asm) => asm.operands().any(|operand| jumps_from(&operand.expr)),
+    }
+}
+
+/// `true` if `expr` has a statement expression with a `break` or a `continue` in it.
+fn jumps_from(expr: &Expr) -> bool {
+    match expr {
+        Expr::Block(items) => items.iter().any(jumps),
+        _ => expr.operands().into_iter().any(jumps_from),
+    }
+}
+
+/// `true` if the item is or contains a `break` or a `continue` statement, whatever it leaves.
+fn jumps(item: &BlockItem) -> bool {
+    let BlockItem::Stmt(stmt, _) = item else {
+        return false;
+    };
+    stmt_jumps(stmt)
+}
+
+fn stmt_jumps(stmt: &Statement) -> bool {
+    match stmt {
+        Statement::Break(_) | Statement::Continue(_) => true,
+        Statement::If { then, els, .. } => {
+            stmt_jumps(then) || els.as_deref().is_some_and(stmt_jumps)
+        }
+        Statement::Compound(items) => items.iter().any(jumps),
+        Statement::For { body, .. }
+        | Statement::ForDecl { body, .. }
+        | Statement::While { body, .. }
+        | Statement::Do { body, .. }
+        | Statement::Switch { body, .. }
+        | Statement::Case { body, .. }
+        | Statement::Default { body, .. } => stmt_jumps(body),
+        _ => false,
+    }
+}
+
+fn initializer_exprs(init: &Initializer) -> Vec<&Expr> {
+    match init {
+        Initializer::Single(expr) => vec![expr],
+        Initializer::List(items) => items.iter().flat_map(initializer_exprs).collect(),
+    }
+}
+
+/// `true` if the function allocates stack at run time, with variable-length arrays
+/// or `__builtin_alloca`. Its frame then keeps the bottom of that space in a slot,
+/// and the epilogue restores sp from the frame pointer, which a `return` from a
+/// statement expression needs as well: the temporaries of the enclosing expression
+/// are still pushed. So does a `break` or `continue` from one, see [`loop_saves_sp`].
+pub fn uses_dynamic_stack(items: &[BlockItem]) -> bool {
+    items.iter().any(|item| match item {
+        BlockItem::Decl(decl) => decl_allocates(decl),
+        BlockItem::Stmt(stmt, _) => stmt_allocates(stmt),
+    })
+}
+
+fn decl_allocates(decl: &Declaration) -> bool {
+    let Declare { ty, init, .. } = decl;
+    ty.is_var_array() || init.as_ref().is_some_and(initializer_allocates)
+}
+
+fn stmt_allocates(stmt: &Statement) -> bool {
+    match stmt {
+        Statement::Return(expr) | Statement::Expr(expr) => expr.as_ref().is_some_and(allocates),
+        Statement::Bingus(expr) => allocates(expr),
+        Statement::If { cond, then, els } => {
+            allocates(cond) || stmt_allocates(then) || els.as_deref().is_some_and(stmt_allocates)
+        }
+        Statement::Compound(items) => uses_dynamic_stack(items),
+        Statement::For {
+            init,
+            cond,
+            post,
+            body,
+            ..
+        } => {
+            init.as_ref().is_some_and(allocates)
+                || allocates(cond)
+                || post.as_ref().is_some_and(allocates)
+                || stmt_allocates(body)
+        }
+        Statement::ForDecl {
+            decl,
+            cond,
+            post,
+            body,
+            ..
+        } => {
+            decl_allocates(decl)
+                || allocates(cond)
+                || post.as_ref().is_some_and(allocates)
+                || stmt_allocates(body)
+        }
+        Statement::While { cond, body, .. }
+        | Statement::Do { body, cond, .. }
+        | Statement::Switch { cond, body, .. } => allocates(cond) || stmt_allocates(body),
+        Statement::Case { body, .. } | Statement::Default { body, .. } => stmt_allocates(body),
+        Statement::Break(_) | Statement::Continue(_) => false,
+        Statement::Asm(asm) => asm.operands().any(|operand| allocates(&operand.expr)),
+    }
+}
+
+/// `true` if the item is or contains a `return` statement.
+fn returns(item: &BlockItem) -> bool {
+    let BlockItem::Stmt(stmt, _) = item else {
+        return false;
+    };
+    stmt_returns(stmt)
+}
+
+fn stmt_returns(stmt: &Statement) -> bool {
+    match stmt {
+        Statement::Return(_) => true,
+        Statement::If { then, els, .. } => {
+            stmt_returns(then) || els.as_deref().is_some_and(stmt_returns)
+        }
+        Statement::Compound(items) => items.iter().any(returns),
+        Statement::For { body, .. }
+        | Statement::ForDecl { body, .. }
+        | Statement::While { body, .. }
+        | Statement::Do { body, .. }
+        | Statement::Switch { body, .. }
+        | Statement::Case { body, .. }
+        | Statement::Default { body, .. } => stmt_returns(body),
+        _ => false,
+    }
+}
+
+fn initializer_allocates(init: &Initializer) -> bool {
+    match init {
+        Initializer::Single(expr) => allocates(expr),
+        Initializer::List(items) => items.iter().any(initializer_allocates),
+    }
+}
+
+/// `true` if evaluating `expr` calls `__builtin_alloca`.
+fn allocates(expr: &Expr) -> bool {
+    match expr {
+        Expr::Alloca(_) => true,
+        Expr::Const(_) | Expr::Var(_) => false,
+        Expr::UnOp(_, inner)
+        | Expr::Cast(_, inner)
+        | Expr::Deref(inner)
+        | Expr::AddrOf(inner)
+        | Expr::VaStart(inner)
+        | Expr::VaArg(inner, _)
+        | Expr::VaEnd(inner) => allocates(inner),
+        Expr::BinOp(_, lhs, rhs) | Expr::Assign(lhs, rhs) | Expr::VaCopy(lhs, rhs) => {
+            allocates(lhs) || allocates(rhs)
+        }
+        Expr::Conditional { cond, then, els } => {
+            allocates(cond) || then.as_deref().is_some_and(allocates) || allocates(els)
+        }
+        Expr::Block(items) => {
+            uses_dynamic_stack(items) || items.iter().any(|item| returns(item) || jumps(item))
+        }
+        Expr::FunCall { parameters, .. } | Expr::Builtin(_, parameters) => {
+            parameters.iter().any(allocates)
+        }
+        Expr::IndirectCall { callee, parameters } => {
+            allocates(callee) || parameters.iter().any(allocates)
+        }
     }
 }
@@ -1,4 +1,4 @@
-use crate::lexer::Token;
+use crate::lexer::{Location, Token};
 use std::fmt;
 
 impl fmt::Display for Token {
@@ -6,3 +6,9 @@ impl fmt::Display for Token {
         format!("{:?}", self).fmt(f)
     }
 }
+
+impl fmt::Display for Location {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.line, self.column)
+    }
+}
@@ -1,14 +1,42 @@
-use crate::lexer::Token;
+use crate::lexer::{Location, Token, Tokens};
 use std::iter::Peekable;
 use std::str::Chars;
 
+/// The characters of the source that are left, and where the next one is.
+#[derive(Clone)]
+struct Cursor<'a> {
+    chars: Peekable<Chars<'a>>,
+    location: Location,
+}
+
+impl Cursor<'_> {
+    fn peek(&mut self) -> Option<&char> {
+        self.chars.peek()
+    }
+}
+
+impl Iterator for Cursor<'_> {
+    type Item = char;
+
+    fn next(&mut self) -> Option<char> {
+        let ch = self.chars.next()?;
+        if ch == '\n' {
+            self.location.line += 1;
+            self.location.column = 1;
+        } else {
+            self.location.column += 1;
+        }
+        Some(ch)
+    }
+}
+
 /// Returns `true` for a valid identifier character (Unicode letter or digit, or `_`).
 fn is_identifier_char(c: char) -> bool {
     c.is_alphanumeric() || c == '_'
 }
 
 /// Consume characters from `chars` while `condition` is true and return them as a `String`.
-fn consume_until<F>(chars: &mut Peekable<Chars>, condition: F) -> String
+fn consume_until<F>(chars: &mut Cursor, condition: F) -> String
 where
     F: Fn(char) -> bool,
 {
@@ -26,7 +54,8 @@ where
 }
 
 /// Detect and skip either a `//` line-comment **or** a `/* ... */` block comment.
-fn skip_comment_if_present(chars: &mut Peekable<Chars>) -> Result<bool, String> {
+/// Line comments are recorded in `tokens`.
+fn skip_comment_if_present(chars: &mut Cursor, tokens: &mut Tokens) -> Result<bool, String> {
     if chars.peek() != Some(&'/') {
         return Ok(false);
     }
@@ -37,6 +66,7 @@ fn skip_comment_if_present(chars: &mut Peekable<Chars>) -> Result<bool, String>
         match next_ch {
             '/' => {
                 // Line comment
+                tokens.line_comments.push(chars.location);
                 chars.next(); // consume '/'
                 chars.next(); // consume second '/'
                 while let Some(&ch) = chars.peek() {
@@ -72,32 +102,151 @@ fn skip_comment_if_present(chars: &mut Peekable<Chars>) -> Result<bool, String>
     Ok(false)
 }
 
+/// Integer literal types in the order they are tried for a value, per C11 6.4.4.1.
+#[derive(Clone, Copy)]
+enum IntLiteralKind {
+    Int,
+    UInt,
+    Long,
+    ULong,
+}
+
+/// Consume a preprocessing number (digits, letters, `.` and exponent signs) as raw text.
+fn consume_number(chars: &mut Cursor) -> String {
+    let mut text = String::new();
+
+    while let Some(&ch) = chars.peek() {
+        let is_hex = text.starts_with("0x") || text.starts_with("0X");
+        let exponent = if is_hex { ['p', 'P'] } else { ['e', 'E'] };
+        let is_exponent_sign = (ch == '+' || ch == '-') && text.ends_with(exponent);
+        if !(ch.is_ascii_alphanumeric() || ch == '_' || ch == '.' || is_exponent_sign) {
+            break;
+        }
+        text.push(ch);
+        chars.next();
+    }
+
+    text
+}
+
+/// Turn the text of a numeric literal into a typed literal token.
+fn parse_number(text: &str) -> Result<Token, String> {
+    let lower = text.to_ascii_lowercase();
+    let is_hex = lower.starts_with("0x");
+
+    let is_floating = if is_hex {
+        lower.contains('.') || lower.contains('p')
+    } else {
+        lower.contains('.') || lower.contains('e')
+    };
+
+    if is_floating {
+        if is_hex {
+            return Err(format!(
+                "Hexadecimal floating constants are not supported: {text}"
+            ));
+        }
+        return if let Some(body) = lower.strip_suffix('f') {
+            let value: f32 = body.parse().map_err(|_| format!("Invalid float: {text}"))?;
+            Ok(Token::FloatLiteral(value))
+        } else {
+            let body = lower.strip_suffix('l').unwrap_or(&lower);
+            let value: f64 = body
+                .parse()
+                .map_err(|_| format!("Invalid double: {text}"))?;
+            Ok(Token::DoubleLiteral(value))
+        };
+    }
+
+    let digits = lower.trim_end_matches(['u', 'l']);
+    let suffix = &lower[digits.len()..];
+    let (unsigned, long) = match suffix {
+        "" => (false, false),
+        "u" => (true, false),
+        "l" | "ll" => (false, true),
+        "ul" | "lu" | "ull" | "llu" => (true, true),
+        _ => return Err(format!("Invalid integer suffix: {text}")),
+    };
+
+    let (radix, digits) = if let Some(hex) = digits.strip_prefix("0x") {
+        (16, hex)
+    } else if digits.len() > 1 && digits.starts_with('0') {
+        (8, &digits[1..])
+    } else {
+        (10, digits)
+    };
+    let value =
+        u64::from_str_radix(digits, radix).map_err(|_| format!("Invalid integer: {text}"))?;
+
+    use IntLiteralKind::*;
+    let candidates: &[IntLiteralKind] = match (unsigned, long, radix == 10) {
+        (false, false, true) => &[Int, Long],
+        (false, false, false) => &[Int, UInt, Long, ULong],
+        (true, false, _) => &[UInt, ULong],
+        (false, true, true) => &[Long],
+        (false, true, false) => &[Long, ULong],
+        (true, true, _) => &[ULong],
+    };
+
+    for kind in candidates {
+        let token = match kind {
+            Int => i32::try_from(value).ok().map(Token::IntLiteral),
+            UInt => u32::try_from(value).ok().map(Token::UIntLiteral),
+            Long => i64::try_from(value).ok().map(Token::LongLiteral),
+            ULong => Some(Token::ULongLiteral(value)),
+        };
+        if let Some(token) = token {
+            return Ok(token);
+        }
+    }
+
+    Err(format!("Integer literal is too large: {text}"))
+}
+
+/// Resolve the escape sequence after a `\\` in a character or string literal.
+fn parse_escape(chars: &mut Cursor) -> Result<char, String> {
+    match chars.next() {
+        Some('n') => Ok('\n'),
+        Some('t') => Ok('\t'),
+        Some('r') => Ok('\r'),
+        Some('\'') => Ok('\''),
+        Some('\"') => Ok('\"'),
+        Some('\\') => Ok('\\'),
+        Some(other) => Err(format!("Unknown escape sequence: \\{}", other)),
+        None => Err("Incomplete escape sequence".into()),
+    }
+}
+
 /// Longest-match operator scanner.
 /// Returns `Some(Token)` if an operator starts at the cursor, otherwise `None`.
-fn match_operator(chars: &mut Peekable<Chars>) -> Option<Token> {
+fn match_operator(chars: &mut Cursor) -> Option<Token> {
     const MAX_OP_LEN: usize = 3;
 
     // try the longest slice first, then shorter ones
     for len in (1..=MAX_OP_LEN).rev() {
         let candidate: String = chars.clone().take(len).collect();
-        if candidate.len() == len {
-            if let Ok(tok) = Token::try_from(candidate.as_str()) {
-                // consume the matched chars from the real iterator
-                for _ in 0..len {
-                    chars.next();
-                }
-                return Some(tok);
+        if candidate.len() == len
+            && let Ok(tok) = Token::try_from(candidate.as_str())
+        {
+            // consume the matched chars from the real iterator
+            for _ in 0..len {
+                chars.next();
             }
+            return Some(tok);
         }
     }
     None
 }
 
-pub fn lex(input: &str) -> Result<Vec<Token>, String> {
-    let mut tokens: Vec<Token> = Vec::new();
+pub fn lex(input: &str) -> Result<Tokens, String> {
+    let mut tokens = Tokens::default();
 
-    let mut chars = input.chars().peekable();
+    let mut chars = Cursor {
+        chars: input.chars().peekable(),
+        location: Location::START,
+    };
     while let Some(&ch) = chars.peek() {
+        let location = chars.location;
         if ch.is_whitespace() {
             chars.next();
             continue;
@@ -106,25 +255,71 @@ pub fn lex(input: &str) -> Result<Vec<Token>, String> {
         if ch.is_alphabetic() || ch == '_' {
             let ident = consume_until(&mut chars, is_identifier_char);
             match ident.as_str() {
-                "bingus" => tokens.push(Token::KeywordBingus),
-                "int" => tokens.push(Token::KeywordInt),
-                "return" => tokens.push(Token::KeywordReturn),
-                "if" => tokens.push(Token::KeywordIf),
-                "else" => tokens.push(Token::KeywordElse),
-                "while" => tokens.push(Token::KeywordWhile),
-                "for" => tokens.push(Token::KeywordFor),
-                "do" => tokens.push(Token::KeywordDo),
-                "break" => tokens.push(Token::KeywordBreak),
-                "continue" => tokens.push(Token::KeywordContinue),
-                _ => tokens.push(Token::Identifier(ident)),
+                "bingus" => tokens.push(Token::KeywordBingus, location),
+                "int" => tokens.push(Token::KeywordInt, location),
+                "char" => tokens.push(Token::KeywordChar, location),
+                "short" => tokens.push(Token::KeywordShort, location),
+                "long" => tokens.push(Token::KeywordLong, location),
+                "signed" => tokens.push(Token::KeywordSigned, location),
+                "unsigned" => tokens.push(Token::KeywordUnsigned, location),
+                "float" => tokens.push(Token::KeywordFloat, location),
+                "double" => tokens.push(Token::KeywordDouble, location),
+                "void" => tokens.push(Token::KeywordVoid, location),
+                "const" => tokens.push(Token::KeywordConst, location),
+                "volatile" | "__volatile__" => tokens.push(Token::KeywordVolatile, location),
+                "_Atomic" => tokens.push(Token::KeywordAtomic, location),
+                "static" => tokens.push(Token::KeywordStatic, location),
+                "extern" => tokens.push(Token::KeywordExtern, location),
+                "_Thread_local" => {
+                    tokens.thread_local_keywords.push(location);
+                    tokens.push(Token::KeywordThreadLocal, location);
+                }
+                "__thread" => tokens.push(Token::KeywordThreadLocal, location),
+                "va_list" | "__builtin_va_list" => tokens.push(Token::KeywordVaList, location),
+                "va_start" | "__builtin_va_start" => tokens.push(Token::KeywordVaStart, location),
+                "va_arg" | "__builtin_va_arg" => tokens.push(Token::KeywordVaArg, location),
+                "va_end" | "__builtin_va_end" => tokens.push(Token::KeywordVaEnd, location),
+                "va_copy" | "__builtin_va_copy" => tokens.push(Token::KeywordVaCopy, location),
+                "__builtin_alloca" => tokens.push(Token::KeywordAlloca, location),
+                "asm" => {
+                    tokens.plain_asm.push(location);
+                    tokens.push(Token::KeywordAsm, location);
+                }
+                "__asm__" | "__asm" => tokens.push(Token::KeywordAsm, location),
+                "_Static_assert" => tokens.push(Token::KeywordStaticAssert, location),
+                "__typeof__" | "__typeof" => tokens.push(Token::KeywordTypeof, location),
+                "__extension__" => tokens.push(Token::KeywordExtension, location),
+                "return" => tokens.push(Token::KeywordReturn, location),
+                "if" => tokens.push(Token::KeywordIf, location),
+                "else" => tokens.push(Token::KeywordElse, location),
+                "while" => tokens.push(Token::KeywordWhile, location),
+                "for" => tokens.push(Token::KeywordFor, location),
+                "do" => tokens.push(Token::KeywordDo, location),
+                "break" => tokens.push(Token::KeywordBreak, location),
+                "continue" => tokens.push(Token::KeywordContinue, location),
+                "switch" => tokens.push(Token::KeywordSwitch, location),
+                "case" => tokens.push(Token::KeywordCase, location),
+                "default" => tokens.push(Token::KeywordDefault, location),
+                // memory orders of the `__atomic` builtins, predefined as macros by GCC and Clang
+                "__ATOMIC_RELAXED" => tokens.push(Token::IntLiteral(0), location),
+                "__ATOMIC_CONSUME" => tokens.push(Token::IntLiteral(1), location),
+                "__ATOMIC_ACQUIRE" => tokens.push(Token::IntLiteral(2), location),
+                "__ATOMIC_RELEASE" => tokens.push(Token::IntLiteral(3), location),
+                "__ATOMIC_ACQ_REL" => tokens.push(Token::IntLiteral(4), location),
+                "__ATOMIC_SEQ_CST" => tokens.push(Token::IntLiteral(5), location),
+                _ => tokens.push(Token::Identifier(ident), location),
             }
             continue;
         }
 
-        if ch.is_ascii_digit() {
-            let num = consume_until(&mut chars, |c: char| c.is_ascii_digit());
-            let value: i32 = num.parse().map_err(|_| "Invalid integer")?;
-            tokens.push(Token::IntLiteral(value));
+        let starts_fraction = ch == '.' && {
+            let mut lookahead = chars.clone();
+            lookahead.next();
+            lookahead.peek().is_some_and(char::is_ascii_digit)
+        };
+        if ch.is_ascii_digit() || starts_fraction {
+            let num = consume_number(&mut chars);
+            tokens.push(parse_number(&num)?, location);
             continue;
         }
 
@@ -133,22 +328,13 @@ pub fn lex(input: &str) -> Result<Vec<Token>, String> {
             chars.next(); // consume opening '
 
             let ch = match chars.next() {
-                Some('\\') => match chars.next() {
-                    Some('n') => '\n',
-                    Some('t') => '\t',
-                    Some('r') => '\r',
-                    Some('\'') => '\'',
-                    Some('\"') => '\"',
-                    Some('\\') => '\\',
-                    Some(other) => return Err(format!("Unknown escape sequence: \\{}", other)),
-                    None => return Err("Incomplete escape sequence".into()),
-                },
+                Some('\\') => parse_escape(&mut chars)?,
                 Some(c) => c,
                 None => return Err("Unexpected end after opening character literal".into()),
             };
 
             match chars.next() {
-                Some('\'') => tokens.push(Token::CharLiteral(ch)),
+                Some('\'') => tokens.push(Token::CharLiteral(ch), location),
                 Some(other) => return Err(format!("Expected closing ', found '{}'", other)),
                 None => return Err("Unterminated character literal".into()),
             }
@@ -156,12 +342,30 @@ pub fn lex(input: &str) -> Result<Vec<Token>, String> {
             continue;
         }
 
-        if skip_comment_if_present(&mut chars)? {
+        if ch == '"' {
+            // string literal
+            chars.next(); // consume opening "
+
+            let mut text = String::new();
+            loop {
+                match chars.next() {
+                    Some('"') => break,
+                    Some('\\') => text.push(parse_escape(&mut chars)?),
+                    Some('\n') | None => return Err("Unterminated string literal".into()),
+                    Some(c) => text.push(c),
+                }
+            }
+            tokens.push(Token::StringLiteral(text), location);
+
+            continue;
+        }
+
+        if skip_comment_if_present(&mut chars, &mut tokens)? {
             continue;
         }
 
         if let Some(tok) = match_operator(&mut chars) {
-            tokens.push(tok);
+            tokens.push(tok, location);
         } else {
             return Err(format!("Unrecognized character '{}'", ch));
         }
@@ -181,7 +385,7 @@ mod tests {
         let tokens = lex(code).unwrap();
 
         assert_eq!(
-            &tokens,
+            &tokens[..],
             &[
                 Token::KeywordInt,
                 Token::Identifier("main".into()),
@@ -202,7 +406,7 @@ mod tests {
         let tokens = lex(code).unwrap();
 
         assert_eq!(
-            &tokens,
+            &tokens[..],
             &[
                 Token::KeywordInt,
                 Token::Identifier("main".into()),
@@ -230,7 +434,7 @@ mod tests {
         let tokens = lex(code).unwrap();
 
         assert_eq!(
-            &tokens,
+            &tokens[..],
             &[
                 Token::KeywordInt,
                 Token::Identifier("_main_123".into()),
@@ -245,13 +449,35 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_lexer_typed_number_literals() {
+        let code = "1 2147483648 0xffffffff 7u 7l 7ul 010 1.5 1e3 .25f";
+        let tokens = lex(code).unwrap();
+
+        assert_eq!(
+            &tokens[..],
+            &[
+                Token::IntLiteral(1),
+                Token::LongLiteral(2147483648),
+                Token::UIntLiteral(0xffffffff),
+                Token::UIntLiteral(7),
+                Token::LongLiteral(7),
+                Token::ULongLiteral(7),
+                Token::IntLiteral(8),
+                Token::DoubleLiteral(1.5),
+                Token::DoubleLiteral(1000.0),
+                Token::FloatLiteral(0.25),
+            ]
+        );
+    }
+
     #[test]
     fn test_lexer_char_literal() {
         let code = "return 'a'; return '\\n';";
         let tokens = lex(code).unwrap();
 
         assert_eq!(
-            &tokens,
+            &tokens[..],
             &[
                 Token::KeywordReturn,
                 Token::CharLiteral('a'),
@@ -262,4 +488,250 @@ mod tests {
             ]
         );
     }
+
+    #[test]
+    fn test_lexer_qualifiers_and_brackets() {
+        let code = "const volatile int a[2];";
+        let tokens = lex(code).unwrap();
+
+        assert_eq!(
+            &tokens[..],
+            &[
+                Token::KeywordConst,
+                Token::KeywordVolatile,
+                Token::KeywordInt,
+                Token::Identifier("a".into()),
+                Token::LBracket,
+                Token::IntLiteral(2),
+                Token::RBracket,
+                Token::Semicolon,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_lexer_variadic_parameters() {
+        let code = "int f(int n, ...) { __builtin_va_list ap; va_start(ap, n); }";
+        let tokens = lex(code).unwrap();
+
+        assert_eq!(
+            &tokens[..],
+            &[
+                Token::KeywordInt,
+                Token::Identifier("f".into()),
+                Token::LParen,
+                Token::KeywordInt,
+                Token::Identifier("n".into()),
+                Token::Comma,
+                Token::Ellipsis,
+                Token::RParen,
+                Token::LBrace,
+                Token::KeywordVaList,
+                Token::Identifier("ap".into()),
+                Token::Semicolon,
+                Token::KeywordVaStart,
+                Token::LParen,
+                Token::Identifier("ap".into()),
+                Token::Comma,
+                Token::Identifier("n".into()),
+                Token::RParen,
+                Token::Semicolon,
+                Token::RBrace,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_lexer_alloca() {
+        let code = "p = __builtin_alloca(n);";
+        let tokens = lex(code).unwrap();
+
+        assert_eq!(
+            &tokens[..],
+            &[
+                Token::Identifier("p".into()),
+                Token::Equal,
+                Token::KeywordAlloca,
+                Token::LParen,
+                Token::Identifier("n".into()),
+                Token::RParen,
+                Token::Semicolon,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_lexer_atomic() {
+        let code = "_Atomic(long) n; __atomic_load_n(&n, __ATOMIC_ACQUIRE);";
+        let tokens = lex(code).unwrap();
+
+        assert_eq!(
+            &tokens[..],
+            &[
+                Token::KeywordAtomic,
+                Token::LParen,
+                Token::KeywordLong,
+                Token::RParen,
+                Token::Identifier("n".into()),
+                Token::Semicolon,
+                Token::Identifier("__atomic_load_n".into()),
+                Token::LParen,
+                Token::And,
+                Token::Identifier("n".into()),
+                Token::Comma,
+                Token::IntLiteral(2),
+                Token::RParen,
+                Token::Semicolon,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_lexer_thread_local() {
+        let code = "static _Thread_local int a; __thread long b;";
+        let tokens = lex(code).unwrap();
+
+        assert_eq!(
+            &tokens[..],
+            &[
+                Token::KeywordStatic,
+                Token::KeywordThreadLocal,
+                Token::KeywordInt,
+                Token::Identifier("a".into()),
+                Token::Semicolon,
+                Token::KeywordThreadLocal,
+                Token::KeywordLong,
+                Token::Identifier("b".into()),
+                Token::Semicolon,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_lexer_asm_string_literals() {
+        let code = r#"__asm__ __volatile__("dmb ish\n\t" "\"%%\"" ::: "memory");"#;
+        let tokens = lex(code).unwrap();
+
+        assert_eq!(
+            &tokens[..],
+            &[
+                Token::KeywordAsm,
+                Token::KeywordVolatile,
+                Token::LParen,
+                Token::StringLiteral("dmb ish\n\t".into()),
+                Token::StringLiteral("\"%%\"".into()),
+                Token::Colon,
+                Token::Colon,
+                Token::Colon,
+                Token::StringLiteral("memory".into()),
+                Token::RParen,
+                Token::Semicolon,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_lexer_static_assert() {
+        let code = r#"_Static_assert(1, "one");"#;
+        let tokens = lex(code).unwrap();
+
+        assert_eq!(
+            &tokens[..],
+            &[
+                Token::KeywordStaticAssert,
+                Token::LParen,
+                Token::IntLiteral(1),
+                Token::Comma,
+                Token::StringLiteral("one".into()),
+                Token::RParen,
+                Token::Semicolon,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_lexer_gnu_keywords() {
+        let code = "__extension__ __typeof__(x) y = ({ x ?: 1; });";
+        let tokens = lex(code).unwrap();
+
+        assert_eq!(
+            &tokens[..],
+            &[
+                Token::KeywordExtension,
+                Token::KeywordTypeof,
+                Token::LParen,
+                Token::Identifier("x".into()),
+                Token::RParen,
+                Token::Identifier("y".into()),
+                Token::Equal,
+                Token::LParen,
+                Token::LBrace,
+                Token::Identifier("x".into()),
+                Token::QuestionMark,
+                Token::Colon,
+                Token::IntLiteral(1),
+                Token::Semicolon,
+                Token::RBrace,
+                Token::RParen,
+                Token::Semicolon,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_lexer_unterminated_string_literal() {
+        assert!(lex("asm(\"nop);").is_err());
+    }
+
+    #[test]
+    fn test_lexer_locations() {
+        let code = "int x;\n/* a\n comment */  x = 'a';\n\tx++; // done";
+        let tokens = lex(code).unwrap();
+
+        let locations: Vec<_> = (0..tokens.len())
+            .map(|pos| {
+                let Location { line, column } = tokens.location(pos);
+                (line, column)
+            })
+            .collect();
+        assert_eq!(
+            locations,
+            [
+                (1, 1),
+                (1, 5),
+                (1, 6),
+                (3, 14),
+                (3, 16),
+                (3, 18),
+                (3, 21),
+                (4, 2),
+                (4, 3),
+                (4, 5),
+            ]
+        );
+        // past the end, the last token
+        assert_eq!(tokens.location(100), Location { line: 4, column: 5 });
+        assert_eq!(tokens.line_comments, [Location { line: 4, column: 7 }]);
+    }
+
+    #[test]
+    fn test_lexer_keyword_spellings() {
+        let code = "_Thread_local int a;
+__thread int b;
+void f(void) { asm(\"\"); __asm__(\"\"); }";
+        let tokens = lex(code).unwrap();
+        assert_eq!(
+            tokens.thread_local_keywords,
+            [Location { line: 1, column: 1 }]
+        );
+        assert_eq!(
+            tokens.plain_asm,
+            [Location {
+                line: 3,
+                column: 16
+            }]
+        );
+        assert_eq!(tokens[0], Token::KeywordThreadLocal);
+        assert_eq!(tokens[4], Token::KeywordThreadLocal);
+    }
 }
@@ -1,12 +1,62 @@
+use std::ops::Deref;
+
 /// Token type, emitted by the lexer.
 /// Semantic meaning is further documented in the [AST] section,
 /// unless it's not; some unambiguous tokens are explained here anyway.
 ///
 /// [AST]: crate::ast::types
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq)]
 pub enum Token {
     /// Literal "int"
     KeywordInt,
+    /// Literal "char"
+    KeywordChar,
+    /// Literal "short"
+    KeywordShort,
+    /// Literal "long"
+    KeywordLong,
+    /// Literal "signed"
+    KeywordSigned,
+    /// Literal "unsigned"
+    KeywordUnsigned,
+    /// Literal "float"
+    KeywordFloat,
+    /// Literal "double"
+    KeywordDouble,
+    /// Literal "void"
+    KeywordVoid,
+    /// Literal "const"
+    KeywordConst,
+    /// Literal "volatile"
+    KeywordVolatile,
+    /// Literal "_Atomic"
+    KeywordAtomic,
+    /// Literal "static"
+    KeywordStatic,
+    /// Literal "extern"
+    KeywordExtern,
+    /// Literal "_Thread_local" / "__thread"
+    KeywordThreadLocal,
+    /// Literal "va_list" / "__builtin_va_list"
+    KeywordVaList,
+    /// Literal "va_start" / "__builtin_va_start"
+    KeywordVaStart,
+    /// Literal "va_arg" / "__builtin_va_arg"
+    KeywordVaArg,
+    /// Literal "va_end" / "__builtin_va_end"
+    KeywordVaEnd,
+    /// Literal "va_copy" / "__builtin_va_copy"
+    KeywordVaCopy,
+    /// Literal "__builtin_alloca"
+    KeywordAlloca,
+    /// Literal "asm" / "__asm__" / "__asm"
+    KeywordAsm,
+    /// Literal "_Static_assert"
+    KeywordStaticAssert,
+    /// Literal "__typeof__" / "__typeof"
+    KeywordTypeof,
+    /// Literal "__extension__"
+    KeywordExtension,
     /// Literal "return"
     KeywordReturn,
     /// Literal "bingus"
@@ -25,13 +75,31 @@ pub enum Token {
     KeywordBreak,
     /// Literal "continue"
     KeywordContinue,
+    /// Literal "switch"
+    KeywordSwitch,
+    /// Literal "case"
+    KeywordCase,
+    /// Literal "default"
+    KeywordDefault,
 
     /// Identifier, such as "main"
     Identifier(String),
     /// An `int` literal, such as 123
     IntLiteral(i32),
+    /// An `unsigned int` literal, such as 123u or 0xffffffff
+    UIntLiteral(u32),
+    /// A `long` literal, such as 123l or 2147483648
+    LongLiteral(i64),
+    /// An `unsigned long` literal, such as 123ul
+    ULongLiteral(u64),
+    /// A `float` literal, such as 1.5f
+    FloatLiteral(f32),
+    /// A `double` literal, such as 1.5 or 1e-3
+    DoubleLiteral(f64),
     /// A character literal, such as 'k', '\n'
     CharLiteral(char),
+    /// A string literal with its escapes resolved, such as "dmb ish\n"; only used by `asm`
+    StringLiteral(String),
 
     /// Literal "("
     LParen,
@@ -41,6 +109,10 @@ pub enum Token {
     LBrace,
     /// Literal "}"
     RBrace,
+    /// Literal "["
+    LBracket,
+    /// Literal "]"
+    RBracket,
     /// Literal ";"
     Semicolon,
 
@@ -121,6 +193,8 @@ pub enum Token {
 
     /// Literal ","
     Comma,
+    /// Literal "..." (variadic parameter list, GNU case range)
+    Ellipsis,
 
     /// Literal "++".
     /// Unary increment operator
@@ -134,7 +208,7 @@ pub enum Token {
 
     /// Literal "?" (part of the ternary expression)
     QuestionMark,
-    /// Literal ":" (part of the ternary expression)
+    /// Literal ":" (part of the ternary expression, ends a `case` or `default` label)
     Colon,
 }
 
@@ -143,6 +217,7 @@ impl TryFrom<&str> for Token {
 
     fn try_from(s: &str) -> Result<Self, Self::Error> {
         Ok(match s {
+            "..." => Token::Ellipsis,
             ">>=" => Token::ShiftRightEqual,
             "<<=" => Token::ShiftLeftEqual,
             "++" => Token::PlusPlus,
@@ -178,6 +253,8 @@ impl TryFrom<&str> for Token {
             ")" => Token::RParen,
             "{" => Token::LBrace,
             "}" => Token::RBrace,
+            "[" => Token::LBracket,
+            "]" => Token::RBracket,
             "%" => Token::Modulo,
             "&" => Token::And,
             "|" => Token::Or,
@@ -188,3 +265,52 @@ impl TryFrom<&str> for Token {
         })
     }
 }
+
+/// Position of a character in the source file, both counted from 1.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Location {
+    pub line: usize,
+    pub column: usize,
+}
+
+impl Location {
+    pub const START: Location = Location { line: 1, column: 1 };
+}
+
+/// Tokens of a source file and where each of them starts.
+/// Dereferences to the tokens, which is all most of the parser looks at.
+#[derive(Clone, Debug, Default)]
+pub struct Tokens {
+    tokens: Vec<Token>,
+    locations: Vec<Location>,
+    /// where each `//` comment starts, which not every standard allows
+    pub line_comments: Vec<Location>,
+    /// where `asm` is spelled that way, a keyword only in the GNU standards unlike `__asm__`
+    pub plain_asm: Vec<Location>,
+    /// where `_Thread_local` is spelled that way, which C11 added, unlike GNU `__thread`
+    pub thread_local_keywords: Vec<Location>,
+}
+
+impl Tokens {
+    pub fn push(&mut self, token: Token, location: Location) {
+        self.tokens.push(token);
+        self.locations.push(location);
+    }
+
+    /// Where the token at `pos` starts; past the end, where the last token starts.
+    pub fn location(&self, pos: usize) -> Location {
+        let last = self.locations.len().saturating_sub(1);
+        self.locations
+            .get(pos.min(last))
+            .copied()
+            .unwrap_or(Location::START)
+    }
+}
+
+impl Deref for Tokens {
+    type Target = [Token];
+
+    fn deref(&self) -> &[Token] {
+        &self.tokens
+    }
+}
@@ -1,13 +1,17 @@
 use crate::lexer::lex;
 use crate::parser::parse;
+use crate::semantic::analyze;
+use crate::semantic::standard::{Standard, check_standard};
+use crate::semantic::warnings::{WarningOptions, check_warnings};
 use clap::Parser;
-use generator::arm64::generate;
+use generator::arm64::{Target, TargetFeatures, generate};
 
 mod ast;
 mod generator;
 mod lexer;
 mod optimizer;
 mod parser;
+mod semantic;
 
 /// Simple program to greet a person
 #[derive(Parser, Debug)]
@@ -24,12 +28,42 @@ struct Args {
     #[arg(short, long, help = "target platform", default_value_t = std::env::consts::OS.to_string())]
     platform: String,
 
+    #[arg(
+        long = "target-feature",
+        value_name = "FEATURE",
+        help = "enable an optional target feature: lse (ARMv8.1 atomics)"
+    )]
+    target_features: Vec<String>,
+
+    #[arg(
+        short = 'W',
+        value_name = "WARNING",
+        help = "enable a warning, or a group with all or extra; no-<warning> disables one, error makes them errors"
+    )]
+    warnings: Vec<String>,
+
+    #[arg(
+        long = "std",
+        value_name = "STANDARD",
+        default_value = "gnu11",
+        help = "language standard: c89, c99, c11 or gnu11"
+    )]
+    standard: String,
+
+    #[arg(long, help = "warn about features outside the selected standard")]
+    pedantic: bool,
+
     #[arg(long, help = "debug mode")]
     debug: bool,
 }
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let args = Args::parse();
+    // GCC spells these with a single dash
+    let args = Args::parse_from(std::env::args().map(|arg| match arg.as_str() {
+        "-pedantic" => "--pedantic".to_string(),
+        _ if arg.starts_with("-std=") => format!("-{arg}"),
+        _ => arg,
+    }));
 
     if args.arch != "aarch64" {
         eprintln!("Only arm64 is supported. Found: {}", args.arch);
@@ -44,18 +78,64 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         std::process::exit(1);
     }
 
+    let features = TargetFeatures::from_names(&args.target_features).unwrap_or_else(|e| {
+        eprintln!("{e}");
+        std::process::exit(1);
+    });
+
+    let standard = Standard::from_name(&args.standard).unwrap_or_else(|e| {
+        eprintln!("{e}");
+        std::process::exit(1);
+    });
+
+    let mut warning_flags = args.warnings.clone();
+    if args.pedantic {
+        warning_flags.insert(0, "pedantic".to_string());
+    }
+    let warnings = WarningOptions::from_flags(&warning_flags).unwrap_or_else(|e| {
+        eprintln!("{e}");
+        std::process::exit(1);
+    });
+
     let input = std::fs::read_to_string(&args.input_file)?;
     let tokens = lex(&input).expect("Lexer failed");
     if args.debug {
-        println!("parsed tokens {:?}", tokens);
+        println!("parsed tokens {:?}", &tokens[..]);
     }
 
-    let program = parse(&tokens).expect("Parser failed");
+    let program = parse(&tokens).unwrap_or_else(|e| {
+        eprintln!("{}:{e}", args.input_file);
+        std::process::exit(1);
+    });
     if args.debug {
         println!("program: {}", program);
     }
 
-    let asm = generate(&program, &args.platform, args.debug)?;
+    let mut diagnostics =
+        check_standard(&tokens, &program, standard, &warnings).unwrap_or_else(|e| {
+            eprintln!("{}:{e}", args.input_file);
+            std::process::exit(1);
+        });
+
+    let program = analyze(&program)?;
+    if args.debug {
+        println!("resolved program: {}", program);
+    }
+
+    diagnostics.extend(check_warnings(&program, &warnings));
+    diagnostics.sort_by_key(|d| d.location);
+    for diagnostic in &diagnostics {
+        eprintln!("{}", diagnostic.render(&args.input_file, warnings.werror));
+    }
+    if warnings.werror && !diagnostics.is_empty() {
+        std::process::exit(1);
+    }
+
+    let target = Target {
+        platform: args.platform,
+        features,
+    };
+    let asm = generate(&program, &target, args.debug)?;
 
     let asm_path = args.input_file.replace(".c", ".s");
     if args.debug {
@@ -1,44 +1,62 @@
 use crate::ast::*;
-use crate::optimizer::evaluate_expr_compile_time::evaluate_compile_time_expr;
+use crate::semantic::constant::evaluate_compile_time_expr;
 
 /// Fold as much as possible in an `Expr`.
+///
+/// A constant whose value is undefined, such as a signed overflow, is left for run
+/// time; the warnings pass reports it, and only integer constant expressions such as
+/// array sizes and static initializers reject it.
 fn fold_expr(e: &Expr) -> Expr {
     // try constant‑evaluate first
     if let Ok(value) = evaluate_compile_time_expr(e) {
         return Expr::Const(value);
     }
 
+    let fold = |e: &Expr| Box::new(fold_expr(e));
+    let fold_all = |args: &[Expr]| args.iter().map(fold_expr).collect();
     // otherwise recurse and rebuild only the affected branches
     match e {
-        Expr::UnOp(op, inner) => Expr::UnOp(*op, Box::new(fold_expr(inner))),
-        Expr::BinOp(op, lhs, rhs) => {
-            Expr::BinOp(*op, Box::new(fold_expr(lhs)), Box::new(fold_expr(rhs)))
-        }
-        Expr::Assign(name, rhs) => Expr::Assign(name.clone(), Box::new(fold_expr(rhs))),
+        Expr::UnOp(op, inner) => Expr::UnOp(*op, fold(inner)),
+        Expr::BinOp(op, lhs, rhs) => Expr::BinOp(*op, fold(lhs), fold(rhs)),
+        Expr::Assign(lhs, rhs) => Expr::Assign(fold(lhs), fold(rhs)),
         Expr::Conditional { cond, then, els } => Expr::Conditional {
-            cond: Box::new(fold_expr(cond)),
-            then: Box::new(fold_expr(then)),
-            els: Box::new(fold_expr(els)),
+            cond: fold(cond),
+            then: then.as_deref().map(fold),
+            els: fold(els),
         },
         Expr::FunCall { name, parameters } => Expr::FunCall {
             name: name.clone(),
-            parameters: parameters.iter().map(fold_expr).collect(),
+            parameters: fold_all(parameters),
+        },
+        Expr::IndirectCall { callee, parameters } => Expr::IndirectCall {
+            callee: fold(callee),
+            parameters: fold_all(parameters),
         },
+        Expr::Cast(ty, inner) => Expr::Cast(ty.clone(), fold(inner)),
+        Expr::Deref(inner) => Expr::Deref(fold(inner)),
+        Expr::AddrOf(inner) => Expr::AddrOf(fold(inner)),
+        Expr::VaStart(ap) => Expr::VaStart(fold(ap)),
+        Expr::VaArg(ap, ty) => Expr::VaArg(fold(ap), ty.clone()),
+        Expr::VaEnd(ap) => Expr::VaEnd(fold(ap)),
+        Expr::Alloca(size) => Expr::Alloca(fold(size)),
+        Expr::Builtin(builtin, args) => Expr::Builtin(*builtin, fold_all(args)),
+        Expr::VaCopy(dest, src) => Expr::VaCopy(fold(dest), fold(src)),
+        Expr::Block(items) => Expr::Block(items.iter().map(fold_block_item).collect()),
         Expr::Var(_) | Expr::Const(_) => e.clone(),
     }
 }
 
 /// Fold inside a `Statement`.
 fn fold_stmt(s: &Statement) -> Statement {
+    let optional = |e: &Option<Expr>| e.as_ref().map(fold_expr);
     match s {
-        Statement::Return(e) => Statement::Return(fold_expr(e)),
-        Statement::Expr(Some(e)) => Statement::Expr(Some(fold_expr(e))),
-        Statement::Expr(None) => s.clone(),
+        Statement::Return(e) => Statement::Return(optional(e)),
+        Statement::Expr(e) => Statement::Expr(optional(e)),
 
         Statement::If { cond, then, els } => Statement::If {
             cond: fold_expr(cond),
             then: Box::new(fold_stmt(then)),
-            els: els.as_ref().map(|st| Box::new(fold_stmt(st))),
+            els: els.as_deref().map(|st| Box::new(fold_stmt(st))),
         },
 
         Statement::Compound(items) => {
@@ -52,11 +70,13 @@ fn fold_stmt(s: &Statement) -> Statement {
             cond,
             post,
             body,
+            label,
         } => Statement::For {
-            init: init.as_ref().map(fold_expr),
+            init: optional(init),
             cond: fold_expr(cond),
-            post: post.as_ref().map(fold_expr),
+            post: optional(post),
             body: Box::new(fold_stmt(body)),
+            label: *label,
         },
 
         Statement::ForDecl {
@@ -64,59 +84,128 @@ fn fold_stmt(s: &Statement) -> Statement {
             cond,
             post,
             body,
+            label,
         } => Statement::ForDecl {
             decl: fold_decl(decl),
             cond: fold_expr(cond),
-            post: post.as_ref().map(fold_expr),
+            post: optional(post),
             body: Box::new(fold_stmt(body)),
+            label: *label,
         },
 
-        Statement::While { cond, body } => Statement::While {
+        Statement::While { cond, body, label } => Statement::While {
             cond: fold_expr(cond),
             body: Box::new(fold_stmt(body)),
+            label: *label,
         },
 
-        Statement::Do { body, cond } => Statement::Do {
+        Statement::Do { body, cond, label } => Statement::Do {
             body: Box::new(fold_stmt(body)),
             cond: fold_expr(cond),
+            label: *label,
+        },
+
+        Statement::Switch { cond, body, label } => Statement::Switch {
+            cond: fold_expr(cond),
+            body: Box::new(fold_stmt(body)),
+            label: *label,
+        },
+
+        Statement::Case {
+            value,
+            last,
+            body,
+            location,
+            label,
+        } => Statement::Case {
+            value: *value,
+            last: *last,
+            body: Box::new(fold_stmt(body)),
+            location: *location,
+            label: *label,
+        },
+
+        Statement::Default {
+            body,
+            location,
+            label,
+        } => Statement::Default {
+            body: Box::new(fold_stmt(body)),
+            location: *location,
+            label: *label,
         },
 
         // simple control‑flow terminals
-        Statement::Break | Statement::Continue => s.clone(),
+        Statement::Break(_) | Statement::Continue(_) => s.clone(),
+
+        // outputs are lvalues, only the inputs can be folded
+        Statement::Asm(asm) => Statement::Asm(AsmStatement {
+            inputs: asm
+                .inputs
+                .iter()
+                .map(|input| AsmOperand {
+                    expr: fold_expr(&input.expr),
+                    ..input.clone()
+                })
+                .collect(),
+            ..asm.clone()
+        }),
+    }
+}
+
+fn fold_initializer(init: &Initializer) -> Initializer {
+    match init {
+        Initializer::Single(e) => Initializer::Single(fold_expr(e)),
+        Initializer::List(items) => Initializer::List(items.iter().map(fold_initializer).collect()),
     }
 }
 
 fn fold_decl(d: &Declaration) -> Declaration {
     match d {
-        Declaration::Declare(name, Some(init)) => {
-            Declaration::Declare(name.clone(), Some(fold_expr(init)))
-        }
+        Declaration::Declare {
+            name,
+            ty,
+            init: Some(init),
+            storage,
+            thread_local,
+            location,
+        } => Declaration::Declare {
+            name: name.clone(),
+            ty: ty.clone(),
+            init: Some(fold_initializer(init)),
+            storage: *storage,
+            thread_local: *thread_local,
+            location: *location,
+        },
         _ => d.clone(),
     }
 }
 
 fn fold_block_item(item: &BlockItem) -> BlockItem {
     match item {
-        BlockItem::Stmt(st) => BlockItem::Stmt(fold_stmt(st)),
+        BlockItem::Stmt(st, location) => BlockItem::Stmt(fold_stmt(st), *location),
         BlockItem::Decl(dec) => BlockItem::Decl(fold_decl(dec)),
     }
 }
 
 /// Fold inside a `Function`.
 fn fold_function(f: &Function) -> Function {
-    let new_body = f
-        .block_items
-        .as_ref()
-        .map(|items| items.iter().map(fold_block_item).collect());
+    let new_body =
+        (f.block_items.as_ref()).map(|items| items.iter().map(fold_block_item).collect());
 
     Function {
         name: f.name.clone(),
+        return_type: f.return_type.clone(),
         params: f.params.clone(),
+        variadic: f.variadic,
+        prototyped: f.prototyped,
         block_items: new_body,
+        storage: f.storage,
+        location: f.location,
     }
 }
 
-/// Fold as much as possible in program
+/// Fold as much as possible in program.
 pub fn constant_fold(program: &Program) -> Program {
     let items = program
         .toplevel_items
@@ -1,2 +1 @@
-pub mod evaluate_expr_compile_time;
 pub mod fold_expr;
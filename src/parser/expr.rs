@@ -1,12 +1,23 @@
 use crate::ast::BlockItem::Decl;
 use crate::ast::Declaration::Declare;
 use crate::ast::Expr::{Assign, BinOp, Const, Var};
-use crate::ast::{BinaryOp, BlockItem, Declaration, Expr, Statement, UnaryOp};
-use crate::lexer::Token;
+use crate::ast::initializer::array_length;
+use crate::ast::{
+    AsmOperand, AsmStatement, BinaryOp, BlockItem, Builtin, Constant, Declaration, Expr,
+    Initializer, Location, Statement, StorageClass, Type, UnaryOp,
+};
+use crate::lexer::{Token, Tokens};
 use crate::parser::parse::{expect, expect_ident};
+use crate::parser::types::{
+    Declarator, Derivation, StorageSpecifiers, is_declaration_specifier, is_type_specifier,
+    parse_declaration_specifiers, parse_declarator, parse_type_name,
+};
+use crate::semantic::constant::{ConstError, evaluate_compile_time_expr};
 
 // From highest to lowest precedence (tighter binding first):
-// parse_factor             – literals, variables, parentheses, unary operators (-, ~, !)
+// parse_postfix            – subscripts a[i]
+// parse_factor             – literals, variables, parentheses, statement expressions, casts,
+//                            unary operators (-, ~, !, *, &)
 // parse_term               – *, /, %
 // parse_additive_exp       – +, -
 // parse_shift_exp          – <<, >>
@@ -17,15 +28,15 @@ use crate::parser::parse::{expect, expect_ident};
 // parse_bitwise_or_exp     – |
 // parse_logical_and_exp    – &&
 // parse_logical_or_exp     – ||
-// parse_conditional_expr   – e1 ? e2 : e3
+// parse_conditional_expr   – e1 ? e2 : e3, e1 ?: e3
 // parse_expr               – assignment (=, +=, -=, etc.), ++, --
 // parse_statements         – return, if, expression, block, etc.
 
 // helper
 fn parse_binary_chain(
-    tokens: &[Token],
+    tokens: &Tokens,
     pos: &mut usize,
-    parse_operand: fn(&[Token], &mut usize) -> Result<Expr, String>,
+    parse_operand: fn(&Tokens, &mut usize) -> Result<Expr, String>,
     match_op: fn(&Token) -> Option<BinaryOp>,
 ) -> Result<Expr, String> {
     let mut left = parse_operand(tokens, pos)?;
@@ -43,16 +54,57 @@ fn parse_binary_chain(
     Ok(left)
 }
 
-fn parse_factor(tokens: &[Token], pos: &mut usize) -> Result<Expr, String> {
+fn parse_factor(tokens: &Tokens, pos: &mut usize) -> Result<Expr, String> {
     match tokens.get(*pos) {
         Some(Token::IntLiteral(n)) => {
             *pos += 1;
-            Ok(Const(*n))
+            Ok(Const(Constant::Int(*n)))
+        }
+        Some(Token::UIntLiteral(n)) => {
+            *pos += 1;
+            Ok(Const(Constant::UInt(*n)))
+        }
+        Some(Token::LongLiteral(n)) => {
+            *pos += 1;
+            Ok(Const(Constant::Long(*n)))
+        }
+        Some(Token::ULongLiteral(n)) => {
+            *pos += 1;
+            Ok(Const(Constant::ULong(*n)))
+        }
+        Some(Token::FloatLiteral(n)) => {
+            *pos += 1;
+            Ok(Const(Constant::Float(*n)))
+        }
+        Some(Token::DoubleLiteral(n)) => {
+            *pos += 1;
+            Ok(Const(Constant::Double(*n)))
         }
 
         Some(Token::CharLiteral(ch)) => {
             *pos += 1;
-            Ok(Const(*ch as i32))
+            Ok(Const(Constant::Int(*ch as i32)))
+        }
+
+        // cast: `(type) factor`
+        Some(Token::LParen) if is_type_specifier(tokens.get(*pos + 1)) => {
+            *pos += 1;
+            let ty = parse_type_name(tokens, pos)?;
+            expect(tokens, pos, &Token::RParen)?;
+            let inner = parse_factor(tokens, pos)?;
+            Ok(Expr::Cast(ty, Box::new(inner)))
+        }
+
+        // GNU statement expression: `({ items })`
+        Some(Token::LParen) if tokens.get(*pos + 1) == Some(&Token::LBrace) => {
+            *pos += 2;
+            let mut block_items = Vec::new();
+            while tokens.get(*pos) != Some(&Token::RBrace) {
+                block_items.extend(parse_block_items(tokens, pos)?);
+            }
+            expect(tokens, pos, &Token::RBrace)?;
+            expect(tokens, pos, &Token::RParen)?;
+            parse_postfix(tokens, pos, Expr::Block(block_items))
         }
 
         Some(Token::LParen) => {
@@ -60,12 +112,30 @@ fn parse_factor(tokens: &[Token], pos: &mut usize) -> Result<Expr, String> {
             let expr = parse_expr(tokens, pos)?;
             if tokens.get(*pos) == Some(&Token::RParen) {
                 *pos += 1;
-                Ok(expr)
+                parse_postfix(tokens, pos, expr)
             } else {
                 Err("expected ')'".to_string())
             }
         }
 
+        Some(Token::Asterisk) => {
+            *pos += 1;
+            let inner = parse_factor(tokens, pos)?;
+            Ok(Expr::Deref(Box::new(inner)))
+        }
+
+        // only silences pedantic warnings in GCC
+        Some(Token::KeywordExtension) => {
+            *pos += 1;
+            parse_factor(tokens, pos)
+        }
+
+        Some(Token::And) => {
+            *pos += 1;
+            let inner = parse_factor(tokens, pos)?;
+            Ok(Expr::AddrOf(Box::new(inner)))
+        }
+
         Some(Token::Minus | Token::Tilde | Token::Bang) => {
             let op = match tokens[*pos] {
                 Token::Minus => UnaryOp::Neg,
@@ -78,6 +148,21 @@ fn parse_factor(tokens: &[Token], pos: &mut usize) -> Result<Expr, String> {
             Ok(Expr::UnOp(op, Box::new(inner)))
         }
 
+        Some(
+            Token::KeywordVaStart
+            | Token::KeywordVaArg
+            | Token::KeywordVaEnd
+            | Token::KeywordVaCopy,
+        ) => parse_va_builtin(tokens, pos),
+
+        Some(Token::KeywordAlloca) => {
+            *pos += 1;
+            expect(tokens, pos, &Token::LParen)?;
+            let size = parse_expr(tokens, pos)?;
+            expect(tokens, pos, &Token::RParen)?;
+            parse_postfix(tokens, pos, Expr::Alloca(Box::new(size)))
+        }
+
         Some(Token::Identifier(name)) => {
             *pos += 1;
 
@@ -85,36 +170,109 @@ fn parse_factor(tokens: &[Token], pos: &mut usize) -> Result<Expr, String> {
                 // function
                 *pos += 1; // consume '('
 
-                let mut args = Vec::new();
+                let args = parse_arguments(tokens, pos)?;
+                let call = match Builtin::from_name(name) {
+                    Some(builtin) => Expr::Builtin(builtin, args),
+                    None => Expr::FunCall {
+                        name: name.clone(),
+                        parameters: args,
+                    },
+                };
+                return parse_postfix(tokens, pos, call);
+            }
 
-                if tokens.get(*pos) != Some(&Token::RParen) {
-                    loop {
-                        let arg = parse_expr(tokens, pos)?;
-                        args.push(arg);
+            parse_postfix(tokens, pos, Var(name.clone()))
+        }
 
-                        if tokens.get(*pos) == Some(&Token::Comma) {
-                            *pos += 1;
-                        } else {
-                            break;
-                        }
-                    }
-                }
+        Some(Token::StringLiteral(_)) => {
+            Err("string literals are only supported in asm statements".to_string())
+        }
 
-                expect(tokens, pos, &Token::RParen)?;
-                return Ok(Expr::FunCall {
-                    name: name.clone(),
-                    parameters: args,
-                });
+        other => Err(format!("expected factor, found {:?}", other)),
+    }
+}
+
+/// Parse `va_start(ap, last)`, `va_arg(ap, type)`, `va_end(ap)` or `va_copy(dest, src)`.
+fn parse_va_builtin(tokens: &Tokens, pos: &mut usize) -> Result<Expr, String> {
+    let builtin = tokens[*pos].clone();
+    *pos += 1;
+    expect(tokens, pos, &Token::LParen)?;
+    let ap = Box::new(parse_expr(tokens, pos)?);
+
+    let expr = match builtin {
+        Token::KeywordVaStart => {
+            // the last named parameter is only a hint, the frame layout is known anyway
+            if tokens.get(*pos) == Some(&Token::Comma) {
+                *pos += 1;
+                expect_ident(tokens, pos)?;
             }
+            Expr::VaStart(ap)
+        }
+        Token::KeywordVaArg => {
+            expect(tokens, pos, &Token::Comma)?;
+            Expr::VaArg(ap, parse_type_name(tokens, pos)?)
+        }
+        Token::KeywordVaEnd => Expr::VaEnd(ap),
+        _ => {
+            expect(tokens, pos, &Token::Comma)?;
+            Expr::VaCopy(ap, Box::new(parse_expr(tokens, pos)?))
+        }
+    };
 
-            Ok(Var(name.clone()))
+    expect(tokens, pos, &Token::RParen)?;
+    Ok(expr)
+}
+
+/// Parse a call's argument list after the opening `(`, up to and including the `)`.
+fn parse_arguments(tokens: &Tokens, pos: &mut usize) -> Result<Vec<Expr>, String> {
+    let mut args = Vec::new();
+
+    if tokens.get(*pos) != Some(&Token::RParen) {
+        loop {
+            let arg = parse_expr(tokens, pos)?;
+            args.push(arg);
+
+            if tokens.get(*pos) == Some(&Token::Comma) {
+                *pos += 1;
+            } else {
+                break;
+            }
         }
+    }
 
-        other => Err(format!("expected factor, found {:?}", other)),
+    expect(tokens, pos, &Token::RParen)?;
+    Ok(args)
+}
+
+/// Parse any number of subscripts and calls after a primary expression:
+/// `a[i][j]` is `*(*(a + i) + j)`, `handlers[i](x)` calls through the pointer `handlers[i]`.
+fn parse_postfix(tokens: &Tokens, pos: &mut usize, mut expr: Expr) -> Result<Expr, String> {
+    loop {
+        match tokens.get(*pos) {
+            Some(Token::LBracket) => {
+                *pos += 1;
+                let index = parse_expr(tokens, pos)?;
+                expect(tokens, pos, &Token::RBracket)?;
+                expr = Expr::Deref(Box::new(BinOp(
+                    BinaryOp::Add,
+                    Box::new(expr),
+                    Box::new(index),
+                )));
+            }
+            Some(Token::LParen) => {
+                *pos += 1;
+                let parameters = parse_arguments(tokens, pos)?;
+                expr = Expr::IndirectCall {
+                    callee: Box::new(expr),
+                    parameters,
+                };
+            }
+            _ => return Ok(expr),
+        }
     }
 }
 
-fn parse_term(tokens: &[Token], pos: &mut usize) -> Result<Expr, String> {
+fn parse_term(tokens: &Tokens, pos: &mut usize) -> Result<Expr, String> {
     let mut expr = parse_factor(tokens, pos)?;
 
     while let Some(op_token) = tokens.get(*pos) {
@@ -133,7 +291,7 @@ fn parse_term(tokens: &[Token], pos: &mut usize) -> Result<Expr, String> {
     Ok(expr)
 }
 
-fn parse_additive_exp(tokens: &[Token], pos: &mut usize) -> Result<Expr, String> {
+fn parse_additive_exp(tokens: &Tokens, pos: &mut usize) -> Result<Expr, String> {
     parse_binary_chain(tokens, pos, parse_term, |tok| match tok {
         Token::Plus => Some(BinaryOp::Add),
         Token::Minus => Some(BinaryOp::Sub),
@@ -141,7 +299,7 @@ fn parse_additive_exp(tokens: &[Token], pos: &mut usize) -> Result<Expr, String>
     })
 }
 
-fn parse_shift_exp(tokens: &[Token], pos: &mut usize) -> Result<Expr, String> {
+fn parse_shift_exp(tokens: &Tokens, pos: &mut usize) -> Result<Expr, String> {
     parse_binary_chain(tokens, pos, parse_additive_exp, |tok| match tok {
         Token::ShiftRight => Some(BinaryOp::ShiftRight),
         Token::ShiftLeft => Some(BinaryOp::ShiftLeft),
@@ -149,7 +307,7 @@ fn parse_shift_exp(tokens: &[Token], pos: &mut usize) -> Result<Expr, String> {
     })
 }
 
-fn parse_relational_exp(tokens: &[Token], pos: &mut usize) -> Result<Expr, String> {
+fn parse_relational_exp(tokens: &Tokens, pos: &mut usize) -> Result<Expr, String> {
     parse_binary_chain(tokens, pos, parse_shift_exp, |tok| match tok {
         Token::Less => Some(BinaryOp::Less),
         Token::LessEqual => Some(BinaryOp::LessEqual),
@@ -159,7 +317,7 @@ fn parse_relational_exp(tokens: &[Token], pos: &mut usize) -> Result<Expr, Strin
     })
 }
 
-fn parse_equality_exp(tokens: &[Token], pos: &mut usize) -> Result<Expr, String> {
+fn parse_equality_exp(tokens: &Tokens, pos: &mut usize) -> Result<Expr, String> {
     parse_binary_chain(tokens, pos, parse_relational_exp, |tok| match tok {
         Token::EqualEqual => Some(BinaryOp::Equal),
         Token::BangEqual => Some(BinaryOp::NotEqual),
@@ -167,52 +325,57 @@ fn parse_equality_exp(tokens: &[Token], pos: &mut usize) -> Result<Expr, String>
     })
 }
 
-fn parse_bitwise_and_exp(tokens: &[Token], pos: &mut usize) -> Result<Expr, String> {
+fn parse_bitwise_and_exp(tokens: &Tokens, pos: &mut usize) -> Result<Expr, String> {
     parse_binary_chain(tokens, pos, parse_equality_exp, |tok| match tok {
         Token::And => Some(BinaryOp::And),
         _ => None,
     })
 }
 
-fn parse_bitwise_xor_exp(tokens: &[Token], pos: &mut usize) -> Result<Expr, String> {
+fn parse_bitwise_xor_exp(tokens: &Tokens, pos: &mut usize) -> Result<Expr, String> {
     parse_binary_chain(tokens, pos, parse_bitwise_and_exp, |tok| match tok {
         Token::Xor => Some(BinaryOp::Xor),
         _ => None,
     })
 }
 
-fn parse_bitwise_or_exp(tokens: &[Token], pos: &mut usize) -> Result<Expr, String> {
+fn parse_bitwise_or_exp(tokens: &Tokens, pos: &mut usize) -> Result<Expr, String> {
     parse_binary_chain(tokens, pos, parse_bitwise_xor_exp, |tok| match tok {
         Token::Or => Some(BinaryOp::Or),
         _ => None,
     })
 }
 
-fn parse_logical_and_exp(tokens: &[Token], pos: &mut usize) -> Result<Expr, String> {
+fn parse_logical_and_exp(tokens: &Tokens, pos: &mut usize) -> Result<Expr, String> {
     parse_binary_chain(tokens, pos, parse_bitwise_or_exp, |tok| match tok {
         Token::AndAnd => Some(BinaryOp::LogicalAnd),
         _ => None,
     })
 }
 
-fn parse_logical_or_exp(tokens: &[Token], pos: &mut usize) -> Result<Expr, String> {
+fn parse_logical_or_exp(tokens: &Tokens, pos: &mut usize) -> Result<Expr, String> {
     parse_binary_chain(tokens, pos, parse_logical_and_exp, |tok| match tok {
         Token::OrOr => Some(BinaryOp::LogicalOr),
         _ => None,
     })
 }
 
-fn parse_conditional_expr(tokens: &[Token], pos: &mut usize) -> Result<Expr, String> {
+fn parse_conditional_expr(tokens: &Tokens, pos: &mut usize) -> Result<Expr, String> {
     let condition = parse_logical_or_exp(tokens, pos)?;
 
     if tokens.get(*pos) == Some(&Token::QuestionMark) {
         *pos += 1;
-        let then_expr = parse_expr(tokens, pos)?;
+        // GNU `cond ?: else` leaves out the middle operand
+        let then_expr = if tokens.get(*pos) == Some(&Token::Colon) {
+            None
+        } else {
+            Some(Box::new(parse_expr(tokens, pos)?))
+        };
         expect(tokens, pos, &Token::Colon)?;
         let else_expr = parse_conditional_expr(tokens, pos)?; // right-associative
         Ok(Expr::Conditional {
             cond: Box::new(condition),
-            then: Box::new(then_expr),
+            then: then_expr,
             els: Box::new(else_expr),
         })
     } else {
@@ -220,17 +383,21 @@ fn parse_conditional_expr(tokens: &[Token], pos: &mut usize) -> Result<Expr, Str
     }
 }
 
-fn assign_bin_op(op: BinaryOp, var_name: &str, expr: Expr) -> Expr {
+fn assign_bin_op(op: BinaryOp, target: Expr, expr: Expr) -> Expr {
     Assign(
-        var_name.to_string(),
-        Box::new(BinOp(
-            op,
-            Box::new(Var(var_name.to_string())),
-            Box::new(expr),
-        )),
+        Box::new(target.clone()),
+        Box::new(BinOp(op, Box::new(target), Box::new(expr))),
     )
 }
 
+/// Only variables and dereferences designate objects that can be assigned to.
+fn expect_lvalue(expr: Expr) -> Result<Expr, String> {
+    match expr {
+        Var(_) | Expr::Deref(_) => Ok(expr),
+        other => Err(format!("expression is not assignable: {other}")),
+    }
+}
+
 fn token_to_binop(token: &Token) -> Option<BinaryOp> {
     match token {
         Token::PlusEqual => Some(BinaryOp::Add),
@@ -247,51 +414,177 @@ fn token_to_binop(token: &Token) -> Option<BinaryOp> {
     }
 }
 
-pub fn parse_expr(tokens: &[Token], pos: &mut usize) -> Result<Expr, String> {
+pub fn parse_expr(tokens: &Tokens, pos: &mut usize) -> Result<Expr, String> {
     if let Some(Token::Identifier(name)) = tokens.get(*pos) {
         match tokens.get(*pos + 1) {
-            Some(&Token::Equal) => {
-                *pos += 2;
-                let rhs = parse_expr(tokens, pos)?;
-                return Ok(Assign(name.to_string(), Box::new(rhs)));
-            }
             Some(&Token::PlusPlus) => {
                 *pos += 2;
-                return Ok(assign_bin_op(BinaryOp::Add, name, Const(1)));
+                let target = Var(name.to_string());
+                return Ok(assign_bin_op(
+                    BinaryOp::Add,
+                    target,
+                    Const(Constant::Int(1)),
+                ));
             }
             Some(&Token::MinusMinus) => {
                 *pos += 2;
-                return Ok(assign_bin_op(BinaryOp::Sub, name, Const(1)));
+                let target = Var(name.to_string());
+                return Ok(assign_bin_op(
+                    BinaryOp::Sub,
+                    target,
+                    Const(Constant::Int(1)),
+                ));
             }
+            _ => {}
+        }
+    }
 
-            Some(token) => {
-                if let Some(bin_op) = token_to_binop(token) {
-                    *pos += 2;
-                    let rhs = parse_expr(tokens, pos)?;
-                    return Ok(assign_bin_op(bin_op, name, rhs));
-                }
-            }
+    let lhs = parse_conditional_expr(tokens, pos)?;
 
-            _ => {}
+    match tokens.get(*pos) {
+        Some(&Token::Equal) => {
+            *pos += 1;
+            let target = expect_lvalue(lhs)?;
+            let rhs = parse_expr(tokens, pos)?;
+            Ok(Assign(Box::new(target), Box::new(rhs)))
         }
+        Some(token) if token_to_binop(token).is_some() => {
+            let bin_op = token_to_binop(token).unwrap();
+            *pos += 1;
+            let target = expect_lvalue(lhs)?;
+            let rhs = parse_expr(tokens, pos)?;
+            Ok(assign_bin_op(bin_op, target, rhs))
+        }
+        _ => Ok(lhs),
     }
+}
 
-    parse_conditional_expr(tokens, pos)
+/// Parse an initializer: an expression or a braced, possibly nested, list.
+pub fn parse_initializer(tokens: &Tokens, pos: &mut usize) -> Result<Initializer, String> {
+    if tokens.get(*pos) != Some(&Token::LBrace) {
+        return Ok(Initializer::Single(parse_expr(tokens, pos)?));
+    }
+
+    *pos += 1;
+    let mut items = Vec::new();
+    while tokens.get(*pos) != Some(&Token::RBrace) {
+        items.push(parse_initializer(tokens, pos)?);
+        if tokens.get(*pos) == Some(&Token::Comma) {
+            *pos += 1; // also allows a trailing comma
+        } else {
+            break;
+        }
+    }
+    expect(tokens, pos, &Token::RBrace)?;
+    Ok(Initializer::List(items))
 }
 
-fn parse_declaration(tokens: &[Token], pos: &mut usize) -> Result<Declaration, String> {
-    let name = expect_ident(tokens, pos)?;
-    let expr = if tokens.get(*pos) == Some(&Token::Equal) {
+/// Parse the optional initializer of an object declared by `declarator`
+/// on top of the specifier type `base`.
+pub fn parse_declarator_rest(
+    tokens: &Tokens,
+    pos: &mut usize,
+    declarator: Declarator,
+    base: Type,
+    storage: StorageSpecifiers,
+) -> Result<Declaration, String> {
+    let Some(name) = declarator.name.clone() else {
+        return Err(format!("expected identifier, found {:?}", tokens.get(*pos)));
+    };
+    if let Some(Derivation::Function { .. }) = declarator.outermost() {
+        if storage.thread_local {
+            return Err(format!("function '{name}' declared '_Thread_local'"));
+        }
+        if storage.class == Some(StorageClass::Static) {
+            return Err(format!(
+                "invalid storage class for block-scope function '{name}'"
+            ));
+        }
+        if tokens.get(*pos) == Some(&Token::Equal) {
+            return Err(format!("function '{name}' is initialized like a variable"));
+        }
+    }
+    if base.unqualified() == &Type::Void && declarator.derivations.is_empty() {
+        return Err(format!("variable '{name}' declared void"));
+    }
+
+    let init = if tokens.get(*pos) == Some(&Token::Equal) {
         *pos += 1;
-        Some(parse_expr(tokens, pos)?)
+        Some(parse_initializer(tokens, pos)?)
     } else {
         None
     };
-    Ok(Declare(name, expr))
+
+    let first_len = match (declarator.outermost(), &init) {
+        (Some(Derivation::Array(None)), Some(Initializer::List(items))) => {
+            Some(array_length(&declarator.inner_type(base.clone())?, items)?)
+        }
+        (Some(Derivation::Array(None)), _) => {
+            return Err(format!("array size missing in '{name}'"));
+        }
+        _ => None,
+    };
+    let ty = declarator.apply(base, first_len)?;
+    if ty.is_var_array() {
+        if storage.class.is_some() || storage.thread_local {
+            return Err(format!("storage size of '{name}' isn't constant"));
+        }
+        if init.is_some() {
+            return Err(format!(
+                "variable-sized object '{name}' may not be initialized"
+            ));
+        }
+    }
+
+    Ok(Declare {
+        name,
+        ty,
+        init,
+        storage: storage.class,
+        thread_local: storage.thread_local,
+        location: declarator.location,
+    })
 }
 
-fn parse_for_declaration(tokens: &[Token], pos: &mut usize) -> Result<Declaration, String> {
-    let decl = parse_declaration(tokens, pos)?;
+/// Parse one declarator of a declaration list: `*name[N] [= init]`.
+fn parse_declaration(
+    tokens: &Tokens,
+    pos: &mut usize,
+    base: &Type,
+    storage: StorageSpecifiers,
+) -> Result<Declaration, String> {
+    let declarator = parse_declarator(tokens, pos)?;
+    parse_declarator_rest(tokens, pos, declarator, base.clone(), storage)
+}
+
+fn parse_for_declaration(tokens: &Tokens, pos: &mut usize) -> Result<Declaration, String> {
+    let (storage, base) = parse_declaration_specifiers(tokens, pos)?;
+    let decl = parse_declaration(tokens, pos, &base, storage)?;
+    let Declare { name, .. } = &decl;
+    if let Some(storage) = storage.class {
+        return Err(format!(
+            "declaration of {storage} variable '{name}' in 'for' loop initial declaration"
+        ));
+    }
+    if storage.thread_local {
+        return Err(format!(
+            "declaration of _Thread_local variable '{name}' in 'for' loop initial declaration"
+        ));
+    }
+    if let Declare { name, ty, .. } = &decl
+        && ty.is_function()
+    {
+        return Err(format!(
+            "declaration of non-variable '{name}' in 'for' loop initial declaration"
+        ));
+    }
+    if let Declare { name, ty, .. } = &decl
+        && ty.is_var_array()
+    {
+        return Err(format!(
+            "variable-length array '{name}' in 'for' loop initial declaration is not supported"
+        ));
+    }
 
     match tokens.get(*pos) {
         Some(Token::Semicolon) => {
@@ -304,11 +597,34 @@ fn parse_for_declaration(tokens: &[Token], pos: &mut usize) -> Result<Declaratio
     }
 }
 
-fn parse_declaration_list(tokens: &[Token], pos: &mut usize) -> Result<Vec<BlockItem>, String> {
+fn parse_declaration_list(
+    tokens: &Tokens,
+    pos: &mut usize,
+    base: &Type,
+    storage: StorageSpecifiers,
+) -> Result<Vec<BlockItem>, String> {
     let mut decls = Vec::new();
 
     loop {
-        let decl = parse_declaration(tokens, pos)?;
+        let decl = parse_declaration(tokens, pos, base, storage)?;
+        if let Declare { name, ty, .. } = &decl
+            && storage.thread_local
+            && storage.class.is_none()
+            && !ty.is_function()
+        {
+            return Err(format!(
+                "'_Thread_local' variable '{name}' at block scope must be 'static' or 'extern'"
+            ));
+        }
+        if let Declare {
+            name,
+            init: Some(_),
+            storage: Some(StorageClass::Extern),
+            ..
+        } = &decl
+        {
+            return Err(format!("'{name}' has both 'extern' and initializer"));
+        }
         decls.push(Decl(decl));
 
         match tokens.get(*pos) {
@@ -332,17 +648,17 @@ fn parse_declaration_list(tokens: &[Token], pos: &mut usize) -> Result<Vec<Block
 }
 
 /// Used in `do { ... } while ( <expr> )`
-fn parse_expr_in_parens(tokens: &[Token], pos: &mut usize) -> Result<Expr, String> {
+fn parse_expr_in_parens(tokens: &Tokens, pos: &mut usize) -> Result<Expr, String> {
     let e = parse_expr(tokens, pos)?;
     expect(tokens, pos, &Token::RParen)?;
     Ok(e)
 }
 
-fn parse_for_cond(tokens: &[Token], pos: &mut usize) -> Result<Expr, String> {
+fn parse_for_cond(tokens: &Tokens, pos: &mut usize) -> Result<Expr, String> {
     match tokens.get(*pos) {
         Some(Token::Semicolon) => {
             *pos += 1;
-            Ok(Const(1))
+            Ok(Const(Constant::Int(1)))
         }
         _ => {
             let e = parse_expr(tokens, pos)?;
@@ -352,13 +668,179 @@ fn parse_for_cond(tokens: &[Token], pos: &mut usize) -> Result<Expr, String> {
     }
 }
 
-pub fn parse_statement(tokens: &[Token], pos: &mut usize) -> Result<Statement, String> {
+/// Parse `asm [volatile] ("template" [: outputs [: inputs [: clobbers]]]);`
+fn parse_asm(tokens: &Tokens, pos: &mut usize) -> Result<Statement, String> {
+    *pos += 1;
+    let volatile = tokens.get(*pos) == Some(&Token::KeywordVolatile);
+    if volatile {
+        *pos += 1;
+    }
+    expect(tokens, pos, &Token::LParen)?;
+
+    let mut asm = AsmStatement {
+        template: parse_string(tokens, pos)?,
+        outputs: Vec::new(),
+        inputs: Vec::new(),
+        clobbers: Vec::new(),
+        volatile,
+        basic: true,
+    };
+    for section in 0..3 {
+        if tokens.get(*pos) != Some(&Token::Colon) {
+            break;
+        }
+        *pos += 1;
+        asm.basic = false;
+        match section {
+            0 => asm.outputs = parse_asm_operands(tokens, pos, true)?,
+            1 => asm.inputs = parse_asm_operands(tokens, pos, false)?,
+            _ => {
+                while let Some(Token::StringLiteral(_)) = tokens.get(*pos) {
+                    asm.clobbers.push(parse_string(tokens, pos)?);
+                    if tokens.get(*pos) != Some(&Token::Comma) {
+                        break;
+                    }
+                    *pos += 1;
+                }
+            }
+        }
+    }
+
+    expect(tokens, pos, &Token::RParen)?;
+    expect(tokens, pos, &Token::Semicolon)?;
+    Ok(Statement::Asm(asm))
+}
+
+/// Parse `_Static_assert(expr, "message");` and check the assertion: `expr` must be an
+/// integer constant expression that isn't zero. A failure is reported at the keyword.
+pub fn parse_static_assert(tokens: &Tokens, pos: &mut usize) -> Result<(), String> {
+    let location = tokens.location(*pos);
+    *pos += 1;
+    expect(tokens, pos, &Token::LParen)?;
+    let cond = parse_conditional_expr(tokens, pos)?;
+    expect(tokens, pos, &Token::Comma)?;
+    let message = parse_string(tokens, pos)?;
+    expect(tokens, pos, &Token::RParen)?;
+    expect(tokens, pos, &Token::Semicolon)?;
+
+    let value = match evaluate_compile_time_expr(&cond) {
+        Ok(value) if value.ty().is_integer() => value,
+        Ok(_) | Err(ConstError::NotConstant(_)) => {
+            return Err(format!(
+                "{location}: expression in static assertion is not an integer constant expression"
+            ));
+        }
+        Err(e @ ConstError::Undefined(..)) => return Err(format!("{location}: {e}")),
+    };
+    if value.is_zero() {
+        return Err(format!("{location}: static assertion failed: {message}"));
+    }
+    Ok(())
+}
+
+/// Parse the value of the `case` label at `location`, an integer constant expression.
+fn parse_case_value(
+    tokens: &Tokens,
+    pos: &mut usize,
+    location: Location,
+) -> Result<Constant, String> {
+    let expr = parse_conditional_expr(tokens, pos)?;
+    match evaluate_compile_time_expr(&expr) {
+        Ok(value) if value.ty().is_integer() => Ok(value),
+        Ok(_) | Err(ConstError::NotConstant(_)) => Err(format!(
+            "{location}: case label does not reduce to an integer constant"
+        )),
+        Err(e @ ConstError::Undefined(..)) => Err(format!("{location}: {e}")),
+    }
+}
+
+/// Parse one or more adjacent string literals, which are concatenated.
+fn parse_string(tokens: &Tokens, pos: &mut usize) -> Result<String, String> {
+    let mut text = String::new();
+    let start = *pos;
+    while let Some(Token::StringLiteral(part)) = tokens.get(*pos) {
+        text.push_str(part);
+        *pos += 1;
+    }
+    if *pos == start {
+        return Err(format!(
+            "expected string literal, found {}",
+            tokens
+                .get(*pos)
+                .map_or_else(|| "EOF".to_string(), std::string::ToString::to_string)
+        ));
+    }
+    Ok(text)
+}
+
+/// Parse the comma-separated `[name] "constraint" (expr)` operands of one asm section.
+fn parse_asm_operands(
+    tokens: &Tokens,
+    pos: &mut usize,
+    outputs: bool,
+) -> Result<Vec<AsmOperand>, String> {
+    let mut operands = Vec::new();
+    while matches!(
+        tokens.get(*pos),
+        Some(Token::StringLiteral(_) | Token::LBracket)
+    ) {
+        let name = if tokens.get(*pos) == Some(&Token::LBracket) {
+            *pos += 1;
+            let name = expect_ident(tokens, pos)?;
+            expect(tokens, pos, &Token::RBracket)?;
+            Some(name)
+        } else {
+            None
+        };
+
+        let constraint = parse_string(tokens, pos)?;
+        match (outputs, constraint.starts_with(['=', '+'])) {
+            (true, false) => {
+                return Err(format!(
+                    "output operand constraint lacks '=': \"{constraint}\""
+                ));
+            }
+            (false, true) => {
+                return Err(format!(
+                    "input operand constraint contains '{}': \"{constraint}\"",
+                    &constraint[..1]
+                ));
+            }
+            _ => {}
+        }
+
+        expect(tokens, pos, &Token::LParen)?;
+        let expr = parse_expr(tokens, pos)?;
+        expect(tokens, pos, &Token::RParen)?;
+        operands.push(AsmOperand {
+            name,
+            constraint,
+            expr,
+        });
+
+        if tokens.get(*pos) != Some(&Token::Comma) {
+            break;
+        }
+        *pos += 1;
+    }
+    Ok(operands)
+}
+
+pub fn parse_statement(tokens: &Tokens, pos: &mut usize) -> Result<Statement, String> {
     match tokens.get(*pos) {
         Some(Token::KeywordReturn) => {
             *pos += 1;
+            if tokens.get(*pos) == Some(&Token::Semicolon) {
+                *pos += 1;
+                return Ok(Statement::Return(None));
+            }
             let expr = parse_expr(tokens, pos)?;
             expect(tokens, pos, &Token::Semicolon)?;
-            Ok(Statement::Return(expr))
+            Ok(Statement::Return(Some(expr)))
+        }
+        Some(Token::KeywordAsm) => parse_asm(tokens, pos),
+        Some(Token::KeywordStaticAssert) => {
+            Err("a static assertion is a declaration, not a statement".into())
         }
         Some(Token::KeywordBingus) => {
             *pos += 1;
@@ -393,8 +875,7 @@ pub fn parse_statement(tokens: &[Token], pos: &mut usize) -> Result<Statement, S
             expect(tokens, pos, &Token::LParen)?;
 
             // ForDecl case
-            if tokens.get(*pos) == Some(&Token::KeywordInt) {
-                *pos += 1;
+            if is_declaration_specifier(tokens.get(*pos)) {
                 let decl = parse_for_declaration(tokens, pos)?; // this consumes semicolon
 
                 let cond = parse_for_cond(tokens, pos)?;
@@ -413,6 +894,7 @@ pub fn parse_statement(tokens: &[Token], pos: &mut usize) -> Result<Statement, S
                     cond,
                     post,
                     body,
+                    label: None,
                 })
             } else {
                 // For (expression-based)
@@ -441,6 +923,7 @@ pub fn parse_statement(tokens: &[Token], pos: &mut usize) -> Result<Statement, S
                     cond,
                     post,
                     body,
+                    label: None,
                 })
             }
         }
@@ -449,7 +932,7 @@ pub fn parse_statement(tokens: &[Token], pos: &mut usize) -> Result<Statement, S
             expect(tokens, pos, &Token::LParen)?;
 
             let cond = if tokens.get(*pos) == Some(&Token::RParen) {
-                Const(1)
+                Const(Constant::Int(1))
             } else {
                 parse_expr(tokens, pos)?
             };
@@ -457,7 +940,11 @@ pub fn parse_statement(tokens: &[Token], pos: &mut usize) -> Result<Statement, S
             expect(tokens, pos, &Token::RParen)?;
 
             let body = Box::new(parse_statement(tokens, pos)?);
-            Ok(Statement::While { cond, body })
+            Ok(Statement::While {
+                cond,
+                body,
+                label: None,
+            })
         }
 
         Some(Token::KeywordDo) => {
@@ -470,17 +957,64 @@ pub fn parse_statement(tokens: &[Token], pos: &mut usize) -> Result<Statement, S
             let cond = parse_expr_in_parens(tokens, pos)?;
             expect(tokens, pos, &Token::Semicolon)?;
 
-            Ok(Statement::Do { body, cond })
+            Ok(Statement::Do {
+                body,
+                cond,
+                label: None,
+            })
+        }
+        Some(Token::KeywordSwitch) => {
+            *pos += 1;
+            expect(tokens, pos, &Token::LParen)?;
+            let cond = parse_expr_in_parens(tokens, pos)?;
+            let body = Box::new(parse_statement(tokens, pos)?);
+            Ok(Statement::Switch {
+                cond,
+                body,
+                label: None,
+            })
+        }
+        Some(Token::KeywordCase) => {
+            let location = tokens.location(*pos);
+            *pos += 1;
+            let value = parse_case_value(tokens, pos, location)?;
+            // GNU `case value ... last:`
+            let last = if tokens.get(*pos) == Some(&Token::Ellipsis) {
+                *pos += 1;
+                Some(parse_case_value(tokens, pos, location)?)
+            } else {
+                None
+            };
+            expect(tokens, pos, &Token::Colon)?;
+            let body = Box::new(parse_statement(tokens, pos)?);
+            Ok(Statement::Case {
+                value,
+                last,
+                body,
+                location,
+                label: None,
+            })
+        }
+        Some(Token::KeywordDefault) => {
+            let location = tokens.location(*pos);
+            *pos += 1;
+            expect(tokens, pos, &Token::Colon)?;
+            let body = Box::new(parse_statement(tokens, pos)?);
+            Ok(Statement::Default {
+                body,
+                location,
+                label: None,
+            })
         }
         Some(Token::KeywordContinue) => {
             *pos += 1;
             expect(tokens, pos, &Token::Semicolon)?;
-            Ok(Statement::Continue)
+            Ok(Statement::Continue(None))
         }
         Some(Token::KeywordBreak) => {
             *pos += 1;
             expect(tokens, pos, &Token::Semicolon)?;
-            Ok(Statement::Break)
+            Ok(Statement::Break(None))
         }
         Some(Token::LBrace) => {
             // begin compound block
@@ -506,17 +1040,26 @@ pub fn parse_statement(tokens: &[Token], pos: &mut usize) -> Result<Statement, S
     }
 }
 
-pub fn parse_block_items(tokens: &[Token], pos: &mut usize) -> Result<Vec<BlockItem>, String> {
+pub fn parse_block_items(tokens: &Tokens, pos: &mut usize) -> Result<Vec<BlockItem>, String> {
     match tokens.get(*pos) {
-        Some(Token::KeywordInt) => {
+        Some(Token::KeywordStaticAssert) => {
+            parse_static_assert(tokens, pos)?;
+            Ok(Vec::new())
+        }
+        Some(Token::KeywordExtension) => {
             *pos += 1;
-            let decls = parse_declaration_list(tokens, pos)?;
+            parse_block_items(tokens, pos)
+        }
+        token if is_declaration_specifier(token) => {
+            let (storage, base) = parse_declaration_specifiers(tokens, pos)?;
+            let decls = parse_declaration_list(tokens, pos, &base, storage)?;
             Ok(decls)
         }
 
         _ => {
+            let location = tokens.location(*pos);
             let stmt = parse_statement(tokens, pos)?;
-            Ok(vec![BlockItem::Stmt(stmt)])
+            Ok(vec![BlockItem::Stmt(stmt, location)])
         }
     }
 }
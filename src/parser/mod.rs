@@ -1,4 +1,5 @@
 mod expr;
 pub mod parse;
+mod types;
 
 pub use parse::parse;
@@ -1,8 +1,10 @@
-use crate::ast::{Declaration, Function, Program, TopLevel};
-use crate::lexer::Token;
-use crate::parser::expr::{parse_block_items, parse_expr};
+use crate::ast::Declaration::Declare;
+use crate::ast::{Function, Program, TopLevel};
+use crate::lexer::{Token, Tokens};
+use crate::parser::expr::{parse_block_items, parse_declarator_rest, parse_static_assert};
+use crate::parser::types::{Derivation, parse_declaration_specifiers, parse_declarator};
 
-pub fn expect(tokens: &[Token], pos: &mut usize, expected: &Token) -> Result<(), String> {
+pub fn expect(tokens: &Tokens, pos: &mut usize, expected: &Token) -> Result<(), String> {
     if tokens.get(*pos) == Some(expected) {
         *pos += 1;
         Ok(())
@@ -17,7 +19,7 @@ pub fn expect(tokens: &[Token], pos: &mut usize, expected: &Token) -> Result<(),
     }
 }
 
-pub fn expect_ident(tokens: &[Token], pos: &mut usize) -> Result<String, String> {
+pub fn expect_ident(tokens: &Tokens, pos: &mut usize) -> Result<String, String> {
     match tokens.get(*pos) {
         Some(Token::Identifier(name)) => {
             *pos += 1;
@@ -27,58 +29,51 @@ pub fn expect_ident(tokens: &[Token], pos: &mut usize) -> Result<String, String>
     }
 }
 
-pub fn parse(tokens: &[Token]) -> Result<Program, String> {
+pub fn parse(tokens: &Tokens) -> Result<Program, String> {
     let mut pos = 0;
     let mut functions = Vec::new();
 
     while pos < tokens.len() {
-        expect(tokens, &mut pos, &Token::KeywordInt)?;
-        let name = expect_ident(tokens, &mut pos)?;
-
-        match tokens.get(pos) {
-            // global var declaration
-            Some(Token::Semicolon) => {
-                pos += 1;
-                functions.push(TopLevel::GlobalVariable(Declaration::Declare(name, None)));
-            }
-
-            // global var declaration and definition
-            Some(Token::Equal) => {
-                pos += 1;
-
-                let expr = parse_expr(tokens, &mut pos)?;
-                expect(tokens, &mut pos, &Token::Semicolon)?;
-
-                functions.push(TopLevel::GlobalVariable(Declaration::Declare(
-                    name,
-                    Some(expr),
-                )));
-            }
+        if tokens.get(pos) == Some(&Token::KeywordStaticAssert) {
+            parse_static_assert(tokens, &mut pos)?;
+            continue;
+        }
+        if tokens.get(pos) == Some(&Token::KeywordExtension) {
+            pos += 1;
+            continue;
+        }
+        let (storage, base) = parse_declaration_specifiers(tokens, &mut pos)?;
+        let declarator = parse_declarator(tokens, &mut pos)?;
 
+        match (&declarator.name, declarator.outermost()) {
             // function
-            Some(Token::LParen) => {
-                pos += 1;
-
-                // parse parameter list: [ "int" <id> { "," "int" <id> } ]
-                let mut params = Vec::new();
-                if tokens.get(pos) != Some(&Token::RParen) {
-                    loop {
-                        expect(tokens, &mut pos, &Token::KeywordInt)?;
-                        let param = expect_ident(tokens, &mut pos)?;
-                        params.push(param);
-
-                        if tokens.get(pos) == Some(&Token::Comma) {
-                            pos += 1;
-                        } else {
-                            break;
-                        }
-                    }
+            (
+                Some(name),
+                Some(Derivation::Function {
+                    params,
+                    variadic,
+                    prototyped,
+                }),
+            ) => {
+                let (name, params, variadic, prototyped) =
+                    (name.clone(), params.clone(), *variadic, *prototyped);
+                let location = declarator.location;
+                if storage.thread_local {
+                    return Err(format!("function '{name}' declared '_Thread_local'"));
+                }
+                let return_type = declarator.inner_type(base)?;
+                if return_type.is_array() || return_type.is_function() {
+                    return Err(format!("function '{name}' cannot return {return_type}"));
                 }
-                expect(tokens, &mut pos, &Token::RParen)?;
 
                 // expect either `{` (definition) or `;` (declaration)
                 let body = match tokens.get(pos) {
                     Some(Token::LBrace) => {
+                        if params.iter().any(|p| p.name.is_empty()) {
+                            return Err(format!(
+                                "parameter name omitted in definition of '{name}'"
+                            ));
+                        }
                         pos += 1;
                         let mut block_items = Vec::new();
                         while tokens.get(pos) != Some(&Token::RBrace) {
@@ -97,12 +92,26 @@ pub fn parse(tokens: &[Token]) -> Result<Program, String> {
 
                 functions.push(TopLevel::Function(Function {
                     name,
+                    return_type: return_type.unqualified().clone(), // qualifiers on a return value are meaningless
                     params,
+                    variadic,
+                    prototyped,
                     block_items: body,
+                    storage: storage.class,
+                    location,
                 }));
             }
 
-            other => return Err(format!("Expected '(' or ';' or '=', found {:?}", other)),
+            // global var declaration, possibly with a definition
+            _ => {
+                let decl = parse_declarator_rest(tokens, &mut pos, declarator, base, storage)?;
+                let Declare { name, ty, .. } = &decl;
+                if ty.is_var_array() {
+                    return Err(format!("variably modified '{name}' at file scope"));
+                }
+                expect(tokens, &mut pos, &Token::Semicolon)?;
+                functions.push(TopLevel::GlobalVariable(decl));
+            }
         }
     }
 
@@ -0,0 +1,546 @@
+use crate::ast::{Expr, FunctionType, Param, Qualifiers, StorageClass, Type};
+use crate::lexer::{Location, Token, Tokens};
+use crate::parser::expr::parse_expr;
+use crate::parser::parse::expect;
+use crate::semantic::constant::{ConstError, evaluate_compile_time_expr};
+
+/// Returns `true` if the token can start a list of type specifiers and qualifiers.
+pub fn is_type_specifier(token: Option<&Token>) -> bool {
+    matches!(
+        token,
+        Some(
+            Token::KeywordInt
+                | Token::KeywordChar
+                | Token::KeywordShort
+                | Token::KeywordLong
+                | Token::KeywordSigned
+                | Token::KeywordUnsigned
+                | Token::KeywordFloat
+                | Token::KeywordDouble
+                | Token::KeywordVoid
+                | Token::KeywordVaList
+                | Token::KeywordConst
+                | Token::KeywordVolatile
+                | Token::KeywordAtomic
+                | Token::KeywordTypeof
+        )
+    )
+}
+
+/// Returns `true` if the token can start a declaration: a type specifier,
+/// a qualifier or a storage-class specifier.
+pub fn is_declaration_specifier(token: Option<&Token>) -> bool {
+    is_type_specifier(token)
+        || matches!(
+            token,
+            Some(Token::KeywordStatic | Token::KeywordExtern | Token::KeywordThreadLocal)
+        )
+}
+
+/// Storage-class specifiers of a declaration: `static` or `extern`,
+/// optionally combined with `_Thread_local`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct StorageSpecifiers {
+    pub class: Option<StorageClass>,
+    pub thread_local: bool,
+}
+
+/// Consume any number of `const` / `volatile` / `_Atomic` keywords.
+/// `_Atomic` followed by `(` is the type specifier, not a qualifier.
+fn parse_qualifiers(tokens: &Tokens, pos: &mut usize) -> Qualifiers {
+    let mut qualifiers = Qualifiers::default();
+    loop {
+        match tokens.get(*pos) {
+            Some(Token::KeywordConst) => qualifiers.is_const = true,
+            Some(Token::KeywordVolatile) => qualifiers.is_volatile = true,
+            Some(Token::KeywordAtomic) if tokens.get(*pos + 1) != Some(&Token::LParen) => {
+                qualifiers.is_atomic = true
+            }
+            _ => return qualifiers,
+        }
+        *pos += 1;
+    }
+}
+
+/// How many times each specifier keyword occurred in a declaration.
+#[derive(Default)]
+struct Specifiers {
+    void: u8,
+    char: u8,
+    short: u8,
+    int: u8,
+    long: u8,
+    signed: u8,
+    unsigned: u8,
+    float: u8,
+    double: u8,
+    va_list: u8,
+}
+
+impl Specifiers {
+    /// Number of specifier keywords seen.
+    fn count(&self) -> u8 {
+        self.void
+            + self.char
+            + self.short
+            + self.int
+            + self.long
+            + self.signed
+            + self.unsigned
+            + self.float
+            + self.double
+            + self.va_list
+    }
+
+    fn resolve(&self) -> Result<Type, String> {
+        let invalid = || "invalid combination of type specifiers".to_string();
+
+        if self.va_list > 0 {
+            let others = self.count() - self.va_list;
+            return if self.va_list == 1 && others == 0 {
+                Ok(Type::VaList)
+            } else {
+                Err(invalid())
+            };
+        }
+
+        if self.int > 1 || self.signed + self.unsigned > 1 {
+            return Err(invalid());
+        }
+
+        let unsigned = self.unsigned > 0;
+        let plain = self.int + self.signed + self.unsigned == 0;
+        let pick = |signed: Type, unsigned_ty: Type| if unsigned { unsigned_ty } else { signed };
+
+        let ty = match (
+            self.void,
+            self.float,
+            self.double,
+            self.char,
+            self.short,
+            self.long,
+        ) {
+            (1, 0, 0, 0, 0, 0) if plain => Type::Void,
+            (0, 1, 0, 0, 0, 0) if plain => Type::Float,
+            (0, 0, 1, 0, 0, 0) if plain => Type::Double,
+            (0, 0, 0, 1, 0, 0) if self.int == 0 => pick(Type::Char, Type::UChar),
+            (0, 0, 0, 0, 1, 0) => pick(Type::Short, Type::UShort),
+            (0, 0, 0, 0, 0, 1 | 2) => pick(Type::Long, Type::ULong),
+            (0, 0, 0, 0, 0, 0) => pick(Type::Int, Type::UInt),
+            _ => return Err(invalid()),
+        };
+
+        Ok(ty)
+    }
+}
+
+/// Parse a list of type specifiers and qualifiers in any order, e.g. `unsigned long const int`.
+pub fn parse_type_specifiers(tokens: &Tokens, pos: &mut usize) -> Result<Type, String> {
+    parse_specifiers(tokens, pos, None)
+}
+
+/// Parse the specifiers of a declaration: like [`parse_type_specifiers`], but
+/// storage-class specifiers may appear among them (`static const int`): at most
+/// one of `static` and `extern`, and `_Thread_local`.
+pub fn parse_declaration_specifiers(
+    tokens: &Tokens,
+    pos: &mut usize,
+) -> Result<(StorageSpecifiers, Type), String> {
+    let mut storage = StorageSpecifiers::default();
+    let ty = parse_specifiers(tokens, pos, Some(&mut storage))?;
+    Ok((storage, ty))
+}
+
+fn parse_specifiers(
+    tokens: &Tokens,
+    pos: &mut usize,
+    mut storage: Option<&mut StorageSpecifiers>,
+) -> Result<Type, String> {
+    let mut specifiers = Specifiers::default();
+    let mut qualifiers = Qualifiers::default();
+    let mut any_specifier = false;
+    // the type named by an `_Atomic(type-name)` specifier
+    let mut atomic_type = None;
+    // the type named by a `__typeof__(type-name)` or `__typeof__(expr)` specifier
+    let mut typeof_type = None;
+
+    while let Some(token) = tokens.get(*pos) {
+        let counter = match token {
+            Token::KeywordAtomic if tokens.get(*pos + 1) == Some(&Token::LParen) => {
+                *pos += 2;
+                let ty = parse_type_name(tokens, pos)?;
+                expect(tokens, pos, &Token::RParen)?;
+                if ty.is_array() || ty.is_function() || !ty.qualifiers().is_empty() {
+                    return Err(format!("_Atomic cannot be applied to type '{ty}'"));
+                }
+                if atomic_type.replace(ty).is_some() {
+                    return Err("invalid combination of type specifiers".into());
+                }
+                any_specifier = true;
+                continue;
+            }
+            Token::KeywordTypeof => {
+                *pos += 1;
+                expect(tokens, pos, &Token::LParen)?;
+                let ty = if is_type_specifier(tokens.get(*pos)) {
+                    parse_type_name(tokens, pos)?
+                } else {
+                    Type::Typeof(Box::new(parse_expr(tokens, pos)?))
+                };
+                expect(tokens, pos, &Token::RParen)?;
+                if typeof_type.replace(ty).is_some() {
+                    return Err("invalid combination of type specifiers".into());
+                }
+                any_specifier = true;
+                continue;
+            }
+            Token::KeywordConst | Token::KeywordVolatile | Token::KeywordAtomic => {
+                qualifiers = qualifiers.union(parse_qualifiers(tokens, pos));
+                continue;
+            }
+            Token::KeywordStatic | Token::KeywordExtern => {
+                let Some(storage) = storage.as_deref_mut() else {
+                    break;
+                };
+                if storage.class.is_some() {
+                    return Err("multiple storage classes in declaration specifiers".into());
+                }
+                storage.class = Some(if token == &Token::KeywordStatic {
+                    StorageClass::Static
+                } else {
+                    StorageClass::Extern
+                });
+                *pos += 1;
+                continue;
+            }
+            Token::KeywordThreadLocal => {
+                let Some(storage) = storage.as_deref_mut() else {
+                    break;
+                };
+                if storage.thread_local {
+                    return Err("duplicate '_Thread_local' declaration specifier".into());
+                }
+                storage.thread_local = true;
+                *pos += 1;
+                continue;
+            }
+            Token::KeywordVoid => &mut specifiers.void,
+            Token::KeywordChar => &mut specifiers.char,
+            Token::KeywordShort => &mut specifiers.short,
+            Token::KeywordInt => &mut specifiers.int,
+            Token::KeywordLong => &mut specifiers.long,
+            Token::KeywordSigned => &mut specifiers.signed,
+            Token::KeywordUnsigned => &mut specifiers.unsigned,
+            Token::KeywordFloat => &mut specifiers.float,
+            Token::KeywordDouble => &mut specifiers.double,
+            Token::KeywordVaList => &mut specifiers.va_list,
+            _ => break,
+        };
+        *counter += 1;
+        *pos += 1;
+        any_specifier = true;
+    }
+
+    if !any_specifier {
+        return Err(format!(
+            "expected type specifier, found {:?}",
+            tokens.get(*pos)
+        ));
+    }
+
+    let ty = match (atomic_type, typeof_type) {
+        (Some(_), Some(_)) => return Err("invalid combination of type specifiers".into()),
+        (Some(_), None) | (None, Some(_)) if specifiers.count() > 0 => {
+            return Err("invalid combination of type specifiers".into());
+        }
+        (Some(ty), None) => {
+            qualifiers.is_atomic = true;
+            ty
+        }
+        (None, Some(ty)) => ty,
+        (None, None) => specifiers.resolve()?,
+    };
+    if qualifiers.is_atomic && !ty.is_integer() && !ty.is_pointer() {
+        return Err(format!(
+            "_Atomic is only supported on integer and pointer types, found '{ty}'"
+        ));
+    }
+    Ok(ty.qualified(qualifiers))
+}
+
+/// Parse the `[N][M]...` suffix of a declarator. Only the first dimension may be
+/// left empty (`None`); it is then completed from the initializer. A dimension
+/// that is not a constant expression makes a variable-length array.
+fn parse_array_dimensions(tokens: &Tokens, pos: &mut usize) -> Result<Vec<Derivation>, String> {
+    let mut dims = Vec::new();
+    while tokens.get(*pos) == Some(&Token::LBracket) {
+        *pos += 1;
+        if tokens.get(*pos) == Some(&Token::RBracket) {
+            *pos += 1;
+            if !dims.is_empty() {
+                return Err("array has incomplete element type".into());
+            }
+            dims.push(Derivation::Array(None));
+            continue;
+        }
+
+        let location = tokens.location(*pos);
+        let size_expr = parse_expr(tokens, pos)?;
+        expect(tokens, pos, &Token::RBracket)?;
+        let size = match evaluate_compile_time_expr(&size_expr) {
+            Ok(size) => size,
+            Err(ConstError::NotConstant(_)) => {
+                dims.push(Derivation::VarArray(size_expr));
+                continue;
+            }
+            Err(e @ ConstError::Undefined(..)) => return Err(format!("{location}: {e}")),
+        };
+        if !size.ty().is_integer() {
+            return Err(format!(
+                "{location}: size of array has non-integer type {}",
+                size.ty()
+            ));
+        }
+        if size.as_i128() <= 0 {
+            return Err(format!(
+                "{location}: array size must be positive, got {}",
+                size.as_i128()
+            ));
+        }
+        dims.push(Derivation::Array(Some(size.as_i128() as usize)));
+    }
+    Ok(dims)
+}
+
+/// One step from the declaration specifiers towards the declared type.
+pub enum Derivation {
+    /// `*`, with the qualifiers that follow it
+    Pointer(Qualifiers),
+    /// `[N]`, `None` for `[]`
+    Array(Option<usize>),
+    /// `[expr]` with a length only known at run time
+    VarArray(Expr),
+    /// `(params)`
+    Function {
+        params: Vec<Param>,
+        variadic: bool,
+        prototyped: bool,
+    },
+}
+
+/// A parsed declarator, such as `*name[3]` or `(*handler)(int)`.
+pub struct Declarator {
+    /// `None` for an abstract declarator, as in a type name or an unnamed parameter
+    pub name: Option<String>,
+    /// innermost first: `int *(*x)[3]` is int → pointer → array of 3 → pointer
+    pub derivations: Vec<Derivation>,
+    /// where the name is, or where an abstract declarator ends
+    pub location: Location,
+}
+
+impl Declarator {
+    /// The last step, which determines what kind of entity is declared.
+    pub fn outermost(&self) -> Option<&Derivation> {
+        self.derivations.last()
+    }
+
+    /// Type derived by all steps but the outermost one: the element type of
+    /// an array declarator or the return type of a function declarator.
+    pub fn inner_type(&self, base: Type) -> Result<Type, String> {
+        let inner = self
+            .derivations
+            .split_last()
+            .map_or(&[][..], |(_, inner)| inner);
+        derive(base, inner, None)
+    }
+
+    /// Derive the declared type from `base`. An outermost `[]` gets `first_len`
+    /// elements if given; anywhere else an empty dimension is an error.
+    pub fn apply(&self, base: Type, first_len: Option<usize>) -> Result<Type, String> {
+        derive(base, &self.derivations, first_len)
+    }
+
+    /// Type of the declarator as a parameter: arrays become pointers to their
+    /// first element and functions pointers to the function.
+    pub fn parameter_type(&self, base: Type) -> Result<Type, String> {
+        let Some((last, inner)) = self.derivations.split_last() else {
+            return Ok(base);
+        };
+        match last {
+            Derivation::Array(_) | Derivation::VarArray(_) => {
+                Ok(Type::Pointer(Box::new(derive(base, inner, None)?)))
+            }
+            Derivation::Function { .. } => Ok(Type::Pointer(Box::new(self.apply(base, None)?))),
+            Derivation::Pointer(_) => self.apply(base, None),
+        }
+    }
+}
+
+fn derive(
+    base: Type,
+    derivations: &[Derivation],
+    first_len: Option<usize>,
+) -> Result<Type, String> {
+    let mut ty = base;
+    for (i, derivation) in derivations.iter().enumerate() {
+        ty = match derivation {
+            Derivation::Pointer(qualifiers) => Type::Pointer(Box::new(ty)).qualified(*qualifiers),
+            Derivation::Array(len) => {
+                if ty.is_function() {
+                    return Err(format!("declaration of array of functions ({ty})"));
+                }
+                if ty.unqualified() == &Type::Void {
+                    return Err("declaration of array of voids".into());
+                }
+                let outermost = i + 1 == derivations.len();
+                let len = match (len, first_len) {
+                    (Some(len), _) => *len,
+                    (None, Some(first_len)) if outermost => first_len,
+                    _ => return Err("array has incomplete element type".into()),
+                };
+                Type::Array(Box::new(ty), len)
+            }
+            Derivation::VarArray(len) => {
+                if ty.is_function() {
+                    return Err(format!("declaration of array of functions ({ty})"));
+                }
+                if ty.unqualified() == &Type::Void {
+                    return Err("declaration of array of voids".into());
+                }
+                // the length is only kept for the object that is being declared
+                if i + 1 != derivations.len() {
+                    return Err(format!(
+                        "variable length array of {ty} is only supported as the declared object"
+                    ));
+                }
+                Type::VarArray(Box::new(ty), Box::new(len.clone()))
+            }
+            Derivation::Function {
+                params,
+                variadic,
+                prototyped,
+            } => {
+                if ty.is_array() || ty.is_function() {
+                    return Err(format!("function cannot return {ty}"));
+                }
+                Type::Function(Box::new(FunctionType {
+                    return_type: ty.unqualified().clone(), // qualifiers on a return value are meaningless
+                    params: params.iter().map(|p| p.ty.unqualified().clone()).collect(),
+                    variadic: *variadic,
+                    prototyped: *prototyped,
+                }))
+            }
+        };
+    }
+    Ok(ty)
+}
+
+/// Parse a declarator: pointers, then a name or a parenthesized declarator,
+/// then any number of `[N]` and `(params)` suffixes. The name may be absent.
+pub fn parse_declarator(tokens: &Tokens, pos: &mut usize) -> Result<Declarator, String> {
+    let mut pointers = Vec::new();
+    while tokens.get(*pos) == Some(&Token::Asterisk) {
+        *pos += 1;
+        pointers.push(Derivation::Pointer(parse_qualifiers(tokens, pos)));
+    }
+
+    let (name, inner, location) = match (tokens.get(*pos), tokens.get(*pos + 1)) {
+        (Some(Token::Identifier(name)), _) => {
+            *pos += 1;
+            (Some(name.clone()), Vec::new(), tokens.location(*pos - 1))
+        }
+        // `(` starts a nested declarator, unless it's the parameter list of an abstract one
+        (Some(Token::LParen), Some(Token::Asterisk | Token::LParen | Token::Identifier(_))) => {
+            *pos += 1;
+            let inner = parse_declarator(tokens, pos)?;
+            expect(tokens, pos, &Token::RParen)?;
+            (inner.name, inner.derivations, inner.location)
+        }
+        _ => (None, Vec::new(), tokens.location(*pos)),
+    };
+
+    let mut suffixes = Vec::new();
+    loop {
+        match tokens.get(*pos) {
+            Some(Token::LBracket) => {
+                suffixes.extend(parse_array_dimensions(tokens, pos)?);
+            }
+            Some(Token::LParen) => {
+                *pos += 1;
+                let prototyped = tokens.get(*pos) != Some(&Token::RParen);
+                let (params, variadic) = parse_params(tokens, pos)?;
+                expect(tokens, pos, &Token::RParen)?;
+                suffixes.push(Derivation::Function {
+                    params,
+                    variadic,
+                    prototyped,
+                });
+            }
+            _ => break,
+        }
+    }
+
+    // the suffixes bind tighter than the pointers, the nested declarator applies last
+    let mut derivations = pointers;
+    derivations.extend(suffixes.into_iter().rev());
+    derivations.extend(inner);
+    Ok(Declarator {
+        name,
+        derivations,
+        location,
+    })
+}
+
+/// Parse a function parameter list after the opening `(`: `void` or
+/// `type declarator {, type declarator} [, ...]`. Returns the parameters and whether the list is variadic.
+/// Unnamed parameters get an empty name.
+fn parse_params(tokens: &Tokens, pos: &mut usize) -> Result<(Vec<Param>, bool), String> {
+    let mut params = Vec::new();
+
+    if tokens.get(*pos) == Some(&Token::KeywordVoid) && tokens.get(*pos + 1) == Some(&Token::RParen)
+    {
+        *pos += 1;
+        return Ok((params, false));
+    }
+    if tokens.get(*pos) == Some(&Token::Ellipsis) {
+        return Err("ISO C requires a named parameter before '...'".into());
+    }
+
+    if tokens.get(*pos) != Some(&Token::RParen) {
+        loop {
+            let base = parse_type_specifiers(tokens, pos)?;
+            let declarator = parse_declarator(tokens, pos)?;
+            let ty = declarator.parameter_type(base)?;
+            if ty.unqualified() == &Type::Void {
+                return Err("parameter cannot have type void".into());
+            }
+            params.push(Param {
+                name: declarator.name.unwrap_or_default(),
+                ty,
+                location: declarator.location,
+            });
+
+            if tokens.get(*pos) == Some(&Token::Comma) {
+                *pos += 1;
+            } else {
+                break;
+            }
+            if tokens.get(*pos) == Some(&Token::Ellipsis) {
+                *pos += 1;
+                return Ok((params, true));
+            }
+        }
+    }
+
+    Ok((params, false))
+}
+
+/// Parse a type name as used in casts: specifiers followed by an abstract declarator.
+pub fn parse_type_name(tokens: &Tokens, pos: &mut usize) -> Result<Type, String> {
+    let base = parse_type_specifiers(tokens, pos)?;
+    let declarator = parse_declarator(tokens, pos)?;
+    if let Some(name) = declarator.name {
+        return Err(format!("unexpected identifier '{name}' in type name"));
+    }
+    declarator.apply(base, None)
+}
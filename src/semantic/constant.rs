@@ -0,0 +1,391 @@
+use crate::ast::Expr::{
+    AddrOf, Assign, BinOp, Cast, Conditional, Const, Deref, FunCall, UnOp, VaArg, VaCopy, VaEnd,
+    VaStart, Var,
+};
+use crate::ast::{BinaryOp, Builtin, Constant, Expr, Type, UnaryOp};
+use crate::semantic::operators::{Comparison, Domain, Operation, operand_type, operation};
+use std::cmp::Ordering;
+use std::error::Error;
+use std::fmt;
+
+/// Why an expression has no value at compile time.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConstError {
+    /// not a constant expression, such as a read of a variable; it is computed at run time
+    NotConstant(String),
+    /// a constant expression without a defined value, such as a signed integer overflow
+    Undefined(Undefined, String),
+}
+
+/// Why the value of a constant expression is undefined.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Undefined {
+    /// a division or a remainder by zero
+    ZeroDivisor,
+    /// a result out of the range of its type, or a shift by more than its width
+    Overflow,
+}
+
+impl fmt::Display for ConstError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConstError::NotConstant(msg) | ConstError::Undefined(_, msg) => write!(f, "{msg}"),
+        }
+    }
+}
+
+impl Error for ConstError {}
+
+fn not_constant(msg: impl Into<String>) -> ConstError {
+    ConstError::NotConstant(msg.into())
+}
+
+const OVERFLOW: (Undefined, &str) = (
+    Undefined::Overflow,
+    "integer overflow in constant expression",
+);
+
+/// A computed value, with the reason it is undefined in C if it is.
+/// Outside the evaluated operands of `&&`, `||` and `?:` the reason is ignored.
+type Outcome = (Constant, Option<(Undefined, &'static str)>);
+
+fn convert(value: Constant, ty: &Type) -> Result<Constant, ConstError> {
+    value
+        .convert(ty)
+        .ok_or_else(|| not_constant(format!("conversion to {ty} is not a constant expression")))
+}
+
+/// `value` wrapped to the width of the integer type `ty`, and whether that changed a signed value.
+fn wrap(value: i128, ty: &Type) -> Outcome {
+    let wrapped = Constant::ULong(value as u64)
+        .convert(ty)
+        .expect("integer type");
+    let overflow = ty.is_signed() && wrapped.as_i128() != value;
+    (wrapped, overflow.then_some(OVERFLOW))
+}
+
+/// `l op r` as [`operation`] defines it, for operands that aren't `&&` or `||`.
+fn evaluate_binary(op: BinaryOp, l: Constant, r: Constant) -> Result<Outcome, ConstError> {
+    use Operation::*;
+    let ty = operand_type(op, &l.ty(), &r.ty());
+    let invalid = || not_constant(format!("invalid operands to binary {op}"));
+    let operation = operation(op, Domain::of(&ty)).ok_or_else(invalid)?;
+    if matches!(
+        operation,
+        ShiftLeft | ArithmeticShiftRight | LogicalShiftRight
+    ) {
+        if !r.ty().is_integer() {
+            return Err(invalid());
+        }
+        return Ok(evaluate_shift(
+            operation,
+            convert(l, &ty)?,
+            r.as_i128(),
+            &ty,
+        ));
+    }
+    let (l, r) = (convert(l, &ty)?, convert(r, &ty)?);
+
+    if let Compare(relation, _) = operation {
+        // the converted values compare exactly as f64 if floating, as i128 otherwise
+        let ordering = match ty.is_floating() {
+            true => as_f64(l).partial_cmp(&as_f64(r)),
+            false => Some(l.as_i128().cmp(&r.as_i128())),
+        };
+        let holds = match relation {
+            Comparison::Equal => ordering == Some(Ordering::Equal),
+            Comparison::NotEqual => ordering != Some(Ordering::Equal),
+            Comparison::Less => ordering == Some(Ordering::Less),
+            Comparison::LessEqual => matches!(ordering, Some(Ordering::Less | Ordering::Equal)),
+            Comparison::Greater => ordering == Some(Ordering::Greater),
+            Comparison::GreaterEqual => {
+                matches!(ordering, Some(Ordering::Greater | Ordering::Equal))
+            }
+        };
+        return Ok((Constant::Int(holds as i32), None));
+    }
+
+    match (l, r) {
+        (Constant::Float(l), Constant::Float(r)) => {
+            let value = float_arithmetic(operation, l as f64, r as f64) as f32;
+            return Ok((Constant::Float(value), None));
+        }
+        (Constant::Double(l), Constant::Double(r)) => {
+            let value = float_arithmetic(operation, l, r);
+            return Ok((Constant::Double(value), None));
+        }
+        _ => {}
+    }
+
+    // unsigned values are non-negative here, so the exact result is the same for both
+    // signednesses; wrapping it to the type is what tells them apart
+    let (l, r) = (l.as_i128(), r.as_i128());
+    let exact = match operation {
+        Add => l.checked_add(r),
+        Sub => l.checked_sub(r),
+        Multiply => l.checked_mul(r),
+        And => Some(l & r),
+        Or => Some(l | r),
+        Xor => Some(l ^ r),
+        SignedDivide | UnsignedDivide | SignedRemainder | UnsignedRemainder if r == 0 => {
+            let zero = Constant::Int(0).convert(&ty).expect("integer type");
+            let reason = match operation {
+                SignedDivide | UnsignedDivide => "division by zero in constant expression",
+                _ => "modulo by zero in constant expression",
+            };
+            return Ok((zero, Some((Undefined::ZeroDivisor, reason))));
+        }
+        // both truncate toward zero
+        SignedDivide | UnsignedDivide => Some(l / r),
+        // `MIN % -1` is undefined like `MIN / -1`, whose quotient doesn't fit
+        SignedRemainder | UnsignedRemainder => {
+            let (value, _) = wrap(l % r, &ty);
+            let (_, overflow) = wrap(l / r, &ty);
+            return Ok((value, overflow));
+        }
+        _ => unreachable!("{operation:?} is not integer arithmetic"),
+    };
+    // only the product of two large unsigned longs doesn't fit in 128 bits
+    Ok(wrap(exact.unwrap_or_else(|| l.wrapping_mul(r)), &ty))
+}
+
+fn as_f64(value: Constant) -> f64 {
+    match value {
+        Constant::Float(v) => v as f64,
+        Constant::Double(v) => v,
+        _ => value.as_i128() as f64,
+    }
+}
+
+/// Operations on `f32` operands are done in `f64` and rounded back, which gives the same
+/// result: a double holds every sum, difference, product and rounded quotient exactly enough.
+fn float_arithmetic(operation: Operation, l: f64, r: f64) -> f64 {
+    match operation {
+        Operation::FloatAdd => l + r,
+        Operation::FloatSub => l - r,
+        Operation::FloatMultiply => l * r,
+        Operation::FloatDivide => l / r,
+        _ => unreachable!("{operation:?} is not floating-point arithmetic"),
+    }
+}
+
+/// A shift of `l`, which has the promoted type `ty`, by `amount`.
+fn evaluate_shift(operation: Operation, l: Constant, amount: i128, ty: &Type) -> Outcome {
+    let width = ty.size() as i128 * 8;
+    let l = l.as_i128();
+    // what the shift instructions do with the count
+    let masked = (amount & (width - 1)) as u32;
+    let exact = match operation {
+        Operation::ShiftLeft => l << masked,
+        Operation::ArithmeticShiftRight => l >> masked,
+        // the bits of the value as an unsigned number
+        _ => (l & ((1 << width) - 1)) >> masked,
+    };
+    let (value, _) = wrap(exact, ty);
+
+    let fits = -(1 << (width - 1)) <= exact && exact < 1 << width;
+    let reason = if !(0..width).contains(&amount) {
+        Some((
+            Undefined::Overflow,
+            "shift count out of range in constant expression",
+        ))
+    } else if ty.is_signed() && !fits {
+        Some(OVERFLOW)
+    } else {
+        None
+    };
+    (value, reason)
+}
+
+/// Value of a builtin called with constant arguments, computed like the instructions
+/// it lowers to: the count of leading or trailing zeros of 0 is the width of the operand.
+fn evaluate_builtin(
+    builtin: Builtin,
+    args: &[Expr],
+    evaluated: bool,
+) -> Result<Constant, ConstError> {
+    if let Some(op) = builtin.overflow_op() {
+        if builtin.stores_result() || args.len() != 3 {
+            return Err(not_constant(format!(
+                "{} is not a constant expression",
+                builtin.name()
+            )));
+        }
+        let (a, b) = (
+            evaluate(&args[0], evaluated)?,
+            evaluate(&args[1], evaluated)?,
+        );
+        // only the type of the third operand matters
+        let ty = evaluate(&args[2], false)?.ty();
+        let (_, overflow) = evaluate_overflow(op, a, b, &ty)?;
+        return Ok(Constant::Int(overflow as i32));
+    }
+
+    let params = builtin
+        .function_type()
+        .map_or_else(Vec::new, |ty| ty.params);
+    if args.len() != params.len() || params.is_empty() {
+        return Err(not_constant(format!(
+            "{} is not a constant expression",
+            builtin.name()
+        )));
+    }
+    let mut values = Vec::with_capacity(args.len());
+    for (arg, ty) in args.iter().zip(&params) {
+        values.push(convert(evaluate(arg, evaluated)?, ty)?);
+    }
+
+    Ok(match (builtin, values[0]) {
+        (Builtin::Clz, Constant::UInt(n)) => Constant::Int(n.leading_zeros() as i32),
+        (Builtin::ClzLong, Constant::ULong(n)) => Constant::Int(n.leading_zeros() as i32),
+        (Builtin::Ctz, Constant::UInt(n)) => Constant::Int(n.trailing_zeros() as i32),
+        (Builtin::CtzLong, Constant::ULong(n)) => Constant::Int(n.trailing_zeros() as i32),
+        (Builtin::Popcount, Constant::UInt(n)) => Constant::Int(n.count_ones() as i32),
+        (Builtin::PopcountLong, Constant::ULong(n)) => Constant::Int(n.count_ones() as i32),
+        (Builtin::Bswap32, Constant::UInt(n)) => Constant::UInt(n.swap_bytes()),
+        (Builtin::Bswap64, Constant::ULong(n)) => Constant::ULong(n.swap_bytes()),
+        (Builtin::Expect, value) => value,
+        _ => unreachable!("arguments are converted to the parameter types"),
+    })
+}
+
+/// `a op b` computed exactly and converted to the integer type `ty`, and whether
+/// that conversion changed the value; the semantics of the overflow-checking builtins.
+fn evaluate_overflow(
+    op: BinaryOp,
+    a: Constant,
+    b: Constant,
+    ty: &Type,
+) -> Result<(Constant, bool), ConstError> {
+    if !a.ty().is_integer() || !b.ty().is_integer() || !ty.is_integer() {
+        return Err(not_constant(format!(
+            "invalid operands to overflow-checked {op}"
+        )));
+    }
+
+    let (a, b) = (a.as_i128(), b.as_i128());
+    let exact = match op {
+        BinaryOp::Add => Some(a + b),
+        BinaryOp::Sub => Some(a - b),
+        BinaryOp::Multiply => a.checked_mul(b),
+        _ => unreachable!("not an overflow-checked operator: {op}"),
+    };
+    let wrapped = exact.unwrap_or_else(|| a.wrapping_mul(b));
+    let value = convert(Constant::ULong(wrapped as u64), ty)?;
+    Ok((value, exact != Some(value.as_i128())))
+}
+
+/// Value of a constant expression, computed with the semantics of C on this target.
+///
+/// Array sizes, static initializers, builtin arguments and the constant folder all
+/// use it. An operation whose result is undefined in C, including a conversion of a
+/// floating value out of the range of an integer type, is [`ConstError::Undefined`]
+/// unless it is in an operand of `&&`, `||` or `?:` that is never evaluated. Only
+/// integer constant expressions reject it; elsewhere it's left for run time.
+pub fn evaluate_compile_time_expr(expr: &Expr) -> Result<Constant, ConstError> {
+    evaluate(expr, true)
+}
+
+/// `value`, or the reason it's undefined if `expr` is `evaluated`.
+fn checked((value, undefined): Outcome, evaluated: bool) -> Result<Constant, ConstError> {
+    match undefined {
+        Some((kind, reason)) if evaluated => Err(ConstError::Undefined(kind, reason.into())),
+        _ => Ok(value),
+    }
+}
+
+fn evaluate(expr: &Expr, evaluated: bool) -> Result<Constant, ConstError> {
+    match expr {
+        Const(c) => Ok(*c),
+
+        Var(name) => Err(not_constant(format!(
+            "variables not supported in compile-time evaluation: {name}"
+        ))),
+
+        Cast(ty, inner) => {
+            let value = evaluate(inner, evaluated)?;
+            let converted = convert(value, ty)?;
+            // a floating value whose integer part doesn't fit in the type
+            let out_of_range = value.ty().is_floating()
+                && ty.is_integer()
+                && converted.as_i128() != value.as_i128();
+            let reason = out_of_range.then_some((
+                Undefined::Overflow,
+                "floating value out of range in conversion",
+            ));
+            checked((converted, reason), evaluated)
+        }
+
+        UnOp(op, inner) => {
+            let value = evaluate(inner, evaluated)?;
+            let ty = value.ty().promoted();
+            let outcome = match (op, convert(value, &ty)?) {
+                (UnaryOp::Not, _) => (Constant::Int(value.is_zero() as i32), None),
+                (UnaryOp::Neg, Constant::Float(n)) => (Constant::Float(-n), None),
+                (UnaryOp::Neg, Constant::Double(n)) => (Constant::Double(-n), None),
+                (UnaryOp::Neg, n) => wrap(-n.as_i128(), &ty),
+                (UnaryOp::BitNot, n) if ty.is_integer() => wrap(!n.as_i128(), &ty),
+                (UnaryOp::BitNot, _) => return Err(not_constant("invalid operand to unary ~")),
+            };
+            checked(outcome, evaluated)
+        }
+
+        BinOp(op @ (BinaryOp::LogicalAnd | BinaryOp::LogicalOr), lhs, rhs) => {
+            let l_val = evaluate(lhs, evaluated)?;
+            // the right operand is evaluated only if the left one doesn't decide
+            let decided = l_val.is_zero() == (*op == BinaryOp::LogicalAnd);
+            let r_val = evaluate(rhs, evaluated && !decided)?;
+            let truth = match decided {
+                true => *op == BinaryOp::LogicalOr,
+                false => !r_val.is_zero(),
+            };
+            Ok(Constant::Int(truth as i32))
+        }
+
+        BinOp(op, lhs, rhs) => {
+            let l_val = evaluate(lhs, evaluated)?;
+            let r_val = evaluate(rhs, evaluated)?;
+            checked(evaluate_binary(*op, l_val, r_val)?, evaluated)
+        }
+
+        Assign(_, _) => Err(not_constant(
+            "assignment is not allowed in compile-time expressions",
+        )),
+
+        Conditional { cond, then, els } => {
+            let cond_val = evaluate(cond, evaluated)?;
+            let then_val = match then {
+                Some(then) => evaluate(then, evaluated && !cond_val.is_zero())?,
+                None => cond_val,
+            };
+            let els_val = evaluate(els, evaluated && cond_val.is_zero())?;
+
+            // the result has the common type of both branches, whichever is taken
+            let ty = Type::common(&then_val.ty(), &els_val.ty());
+            convert(
+                if cond_val.is_zero() {
+                    els_val
+                } else {
+                    then_val
+                },
+                &ty,
+            )
+        }
+
+        FunCall { .. } | Expr::IndirectCall { .. } => Err(not_constant(
+            "function calls are not allowed in compile-time expressions",
+        )),
+
+        // memory accesses are never constant (and must not be folded away if volatile)
+        Deref(_) | AddrOf(_) => Err(not_constant("memory access in compile-time expression")),
+
+        VaStart(_) | VaArg(..) | VaEnd(_) | VaCopy(..) => Err(not_constant(
+            "variadic argument access in compile-time expression",
+        )),
+        Expr::Alloca(_) => Err(not_constant("stack allocation in compile-time expression")),
+        Expr::Builtin(builtin, args) => evaluate_builtin(*builtin, args, evaluated),
+        Expr::Block(_) => Err(not_constant(
+            "statement expressions are not allowed in compile-time expressions",
+        )),
+    }
+}
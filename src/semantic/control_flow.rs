@@ -0,0 +1,522 @@
+use crate::ast::*;
+use crate::semantic::constant::evaluate_compile_time_expr;
+use std::collections::HashSet;
+
+/// What the control-flow graph of a function definition says about it.
+#[derive(Debug, Default)]
+pub struct ControlFlow {
+    /// control can reach the closing `}` without a `return`
+    pub falls_off_end: bool,
+    /// the first statement or declaration that can never run in each block that can,
+    /// such as the one after a `return`
+    pub unreachable: Vec<Location>,
+    /// loops that, once entered, are never left by a `break`, a `return` or their condition
+    pub endless_loops: Vec<Location>,
+}
+
+/// A point between statements; the edges are the ways control can go on from it.
+pub type Node = usize;
+
+/// Where `return` goes, the only node without successors.
+const EXIT: Node = 0;
+
+/// Something a point of the graph does with a block-scope object, by symbol.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Access {
+    /// the declaration of an automatic scalar without an initializer is reached,
+    /// and the object holds no value yet
+    Declare(String),
+    /// the object is assigned, or its address escapes and it may be assigned through it
+    Write(String),
+    /// like `Write`, but only on some of the ways through the expression, such as in
+    /// the right operand of `&&`
+    MaybeWrite(String),
+    /// the value of the object is used by the statement at the location
+    Read(String, Location),
+}
+
+/// A loop, to check once the graph is complete.
+struct Loop {
+    location: Location,
+    /// the first point in the loop
+    entry: Node,
+    /// the point after the loop
+    after: Node,
+}
+
+/// The control-flow graph of a function definition.
+///
+/// A condition that is a constant expression only ever goes one way, so `while (1)`
+/// exits only through `break` or `return`, and nothing after it is reachable otherwise.
+/// Neither is anything after `__builtin_unreachable()` or `__builtin_trap()`.
+pub struct Cfg {
+    /// where control can go on from each point
+    pub successors: Vec<Vec<Node>>,
+    /// what each point does, in order, before control goes on
+    pub accesses: Vec<Vec<Access>>,
+    pub start: Node,
+    /// the closing `}`, from which control returns
+    pub end: Node,
+    /// entry point of each block and the point before each of its items
+    blocks: Vec<(Node, Vec<(Node, Location)>)>,
+    loops: Vec<Loop>,
+}
+
+/// A `switch` whose body is being built.
+struct Switch {
+    /// where the value has been computed and control goes to one of the labels
+    dispatch: Node,
+    has_default: bool,
+}
+
+struct Builder {
+    cfg: Cfg,
+    /// `break` and `continue` targets of the enclosing loops and `switch` statements,
+    /// innermost last
+    targets: Vec<(Node, Node)>,
+    /// the enclosing `switch` statements, innermost last
+    switches: Vec<Switch>,
+    /// where the statement being built starts
+    location: Location,
+}
+
+impl Cfg {
+    /// Builds the graph of `f`, which must have a body.
+    pub fn build(f: &Function) -> Cfg {
+        let items = f.block_items.as_deref().unwrap_or_default();
+        let mut builder = Builder {
+            cfg: Cfg {
+                successors: Vec::new(),
+                accesses: Vec::new(),
+                start: EXIT,
+                end: EXIT,
+                blocks: Vec::new(),
+                loops: Vec::new(),
+            },
+            targets: Vec::new(),
+            switches: Vec::new(),
+            location: f.location,
+        };
+        builder.node();
+        let start = builder.node();
+        let end = builder.block(items, start);
+        builder.edge(end, EXIT);
+        Cfg {
+            start,
+            end,
+            ..builder.cfg
+        }
+    }
+
+    pub fn reachable_from(&self, start: Node) -> HashSet<Node> {
+        let mut seen = HashSet::from([start]);
+        let mut stack = vec![start];
+        while let Some(node) = stack.pop() {
+            for &next in &self.successors[node] {
+                if seen.insert(next) {
+                    stack.push(next);
+                }
+            }
+        }
+        seen
+    }
+}
+
+/// Finds where control can go and where it can't in the graph of a function.
+pub fn analyze_control_flow(cfg: &Cfg) -> ControlFlow {
+    let reachable = cfg.reachable_from(cfg.start);
+    let mut flow = ControlFlow {
+        falls_off_end: reachable.contains(&cfg.end),
+        ..ControlFlow::default()
+    };
+
+    for (entry, items) in &cfg.blocks {
+        // the body of a `switch` is only entered through its labels
+        let runs =
+            reachable.contains(entry) || items.iter().any(|(node, _)| reachable.contains(node));
+        if !runs {
+            continue;
+        }
+        let first_dead = items.iter().find(|(node, _)| !reachable.contains(node));
+        if let Some((_, location)) = first_dead {
+            flow.unreachable.push(*location);
+        }
+    }
+
+    // inner loops come first; a loop around an endless one is only reported for that one
+    let mut endless_entries = Vec::new();
+    for l in &cfg.loops {
+        if !reachable.contains(&l.entry) {
+            continue;
+        }
+        let inside = cfg.reachable_from(l.entry);
+        let leaves = inside.contains(&l.after) || inside.contains(&EXIT);
+        if !leaves && !endless_entries.iter().any(|entry| inside.contains(entry)) {
+            endless_entries.push(l.entry);
+            flow.endless_loops.push(l.location);
+        }
+    }
+    flow.unreachable.sort();
+    flow
+}
+
+/// `Some` if `cond` is a constant expression, which decides a branch at compile time.
+fn constant_truth(cond: &Expr) -> Option<bool> {
+    evaluate_compile_time_expr(cond).ok().map(|c| !c.is_zero())
+}
+
+impl Builder {
+    fn node(&mut self) -> Node {
+        self.cfg.successors.push(Vec::new());
+        self.cfg.accesses.push(Vec::new());
+        self.cfg.successors.len() - 1
+    }
+
+    fn edge(&mut self, from: Node, to: Node) {
+        self.cfg.successors[from].push(to);
+    }
+
+    /// Evaluates `cond` from `at` and goes to `on_true` or `on_false`. The operands of
+    /// `&&` and `||` are conditions of their own, and a constant only goes one way.
+    fn condition(&mut self, cond: &Expr, at: Node, on_true: Node, on_false: Node) {
+        match cond {
+            Expr::BinOp(BinaryOp::LogicalAnd, lhs, rhs) => {
+                let rhs_entry = self.node();
+                self.condition(lhs, at, rhs_entry, on_false);
+                self.condition(rhs, rhs_entry, on_true, on_false);
+            }
+            Expr::BinOp(BinaryOp::LogicalOr, lhs, rhs) => {
+                let rhs_entry = self.node();
+                self.condition(lhs, at, on_true, rhs_entry);
+                self.condition(rhs, rhs_entry, on_true, on_false);
+            }
+            Expr::UnOp(UnaryOp::Not, inner) => self.condition(inner, at, on_false, on_true),
+            _ => {
+                let at = self.expr(cond, at, true);
+                let truth = constant_truth(cond);
+                if truth != Some(false) {
+                    self.edge(at, on_true);
+                }
+                if truth != Some(true) {
+                    self.edge(at, on_false);
+                }
+            }
+        }
+    }
+
+    /// Adds the items of a block entered at `entry`; returns the point after them.
+    fn block(&mut self, items: &[BlockItem], entry: Node) -> Node {
+        let index = self.cfg.blocks.len();
+        self.cfg.blocks.push((entry, Vec::new()));
+        let mut at = entry;
+        for item in items {
+            match item {
+                BlockItem::Stmt(stmt, location) => {
+                    self.location = *location;
+                    // an empty statement is no code that could be reported
+                    if *stmt == Statement::Expr(None) {
+                        continue;
+                    }
+                    // a labeled statement can be reached from its `switch` as well
+                    let (entry, stmt) = self.labels(stmt, at);
+                    self.cfg.blocks[index].1.push((entry, *location));
+                    at = self.stmt(stmt, entry);
+                }
+                BlockItem::Decl(decl) => {
+                    let Declaration::Declare { location, .. } = decl;
+                    self.cfg.blocks[index].1.push((at, *location));
+                    at = self.declaration(decl, at);
+                }
+            }
+        }
+        at
+    }
+
+    /// Adds `decl`, which runs from `at`; returns the point after it.
+    fn declaration(&mut self, decl: &Declaration, at: Node) -> Node {
+        let Declaration::Declare {
+            name,
+            ty,
+            init,
+            storage,
+            location,
+            ..
+        } = decl;
+        self.location = *location;
+        let at = match ty {
+            Type::VarArray(_, len) => self.expr(len, at, true),
+            _ => at,
+        };
+        match init {
+            Some(init) => self.initializer(init, at),
+            None if storage.is_none() && ty.is_scalar() => {
+                self.cfg.accesses[at].push(Access::Declare(name.clone()));
+                at
+            }
+            None => at,
+        }
+    }
+
+    fn initializer(&mut self, init: &Initializer, at: Node) -> Node {
+        match init {
+            Initializer::Single(e) => self.expr(e, at, true),
+            Initializer::List(items) => (items.iter()).fold(at, |at, i| self.initializer(i, at)),
+        }
+    }
+
+    /// Adds `stmt`, which runs from `at`; returns the point after it.
+    fn stmt(&mut self, stmt: &Statement, at: Node) -> Node {
+        match stmt {
+            // neither comes back
+            Statement::Expr(Some(e @ Expr::Builtin(Builtin::Unreachable | Builtin::Trap, _))) => {
+                self.expr(e, at, true);
+                self.node()
+            }
+            Statement::Expr(Some(e)) | Statement::Bingus(e) => self.expr(e, at, true),
+            Statement::Expr(None) => at,
+            Statement::Asm(asm) => self.asm(asm, at),
+            Statement::Return(e) => {
+                let at = (e.iter()).fold(at, |at, e| self.expr(e, at, true));
+                self.jump(at, EXIT)
+            }
+            Statement::Break(_) => match self.targets.last() {
+                Some(&(after, _)) => self.jump(at, after),
+                None => at, // rejected by the semantic pass
+            },
+            Statement::Continue(_) => match self.targets.last() {
+                Some(&(_, next)) => self.jump(at, next),
+                None => at,
+            },
+            Statement::Case { .. } | Statement::Default { .. } => {
+                let (entry, stmt) = self.labels(stmt, at);
+                self.stmt(stmt, entry)
+            }
+            // the body is only entered through its labels
+            Statement::Switch { cond, body, .. } => {
+                let location = self.location;
+                let dispatch = self.expr(cond, at, true);
+                let (entry, after) = (self.node(), self.node());
+                // `continue` goes to the enclosing loop; without one it's rejected
+                let next = self.targets.last().map_or(after, |&(_, next)| next);
+                self.switches.push(Switch {
+                    dispatch,
+                    has_default: false,
+                });
+                self.targets.push((after, next));
+                let body_end = self.stmt(body, entry);
+                self.targets.pop();
+                let switch = self.switches.pop().expect("pushed above");
+                self.location = location;
+                self.edge(body_end, after);
+                if !switch.has_default {
+                    self.edge(dispatch, after);
+                }
+                after
+            }
+            Statement::Compound(items) => {
+                let location = self.location;
+                let after = self.block(items, at);
+                self.location = location;
+                after
+            }
+            Statement::If { cond, then, els } => {
+                let (then_entry, else_entry, join) = (self.node(), self.node(), self.node());
+                let location = self.location;
+                self.condition(cond, at, then_entry, else_entry);
+                self.location = location;
+                let then_end = self.stmt(then, then_entry);
+                let else_end = match els {
+                    Some(els) => self.stmt(els, else_entry),
+                    None => else_entry,
+                };
+                self.edge(then_end, join);
+                self.edge(else_end, join);
+                join
+            }
+            Statement::While { cond, body, .. } => self.test_first_loop(at, cond, body, None),
+            Statement::For {
+                init,
+                cond,
+                post,
+                body,
+                ..
+            } => {
+                let at = (init.iter()).fold(at, |at, e| self.expr(e, at, true));
+                self.test_first_loop(at, cond, body, post.as_ref())
+            }
+            Statement::ForDecl {
+                decl,
+                cond,
+                post,
+                body,
+                ..
+            } => {
+                let location = self.location;
+                let at = self.declaration(decl, at);
+                self.location = location;
+                self.test_first_loop(at, cond, body, post.as_ref())
+            }
+            Statement::Do { body, cond, .. } => {
+                let location = self.location;
+                let (entry, next, after) = (self.node(), self.node(), self.node());
+                self.edge(at, entry);
+                let body_end = self.loop_body(body, entry, (after, next));
+                self.location = location;
+                self.edge(body_end, next);
+                self.condition(cond, next, entry, after);
+                self.location = location;
+                self.cfg.loops.push(Loop {
+                    location,
+                    entry,
+                    after,
+                });
+                after
+            }
+        }
+    }
+
+    /// Adds the `case` and `default` labels of `stmt`, which runs from `at` or from the
+    /// dispatch of the innermost `switch`; returns the point where the statement they
+    /// label starts, and that statement.
+    fn labels<'a>(&mut self, stmt: &'a Statement, at: Node) -> (Node, &'a Statement) {
+        if !matches!(stmt, Statement::Case { .. } | Statement::Default { .. }) {
+            return (at, stmt);
+        }
+        let entry = self.node();
+        self.edge(at, entry);
+        let mut stmt = stmt;
+        while let Statement::Case { body, .. } | Statement::Default { body, .. } = stmt {
+            // outside a `switch` it's rejected by the semantic pass
+            if let Some(switch) = self.switches.last_mut() {
+                switch.has_default |= matches!(stmt, Statement::Default { .. });
+                let dispatch = switch.dispatch;
+                self.edge(dispatch, entry);
+            }
+            stmt = body;
+        }
+        (entry, stmt)
+    }
+
+    /// Control goes from `at` to `to` and no further: what follows is only reachable
+    /// some other way.
+    fn jump(&mut self, at: Node, to: Node) -> Node {
+        self.edge(at, to);
+        self.node()
+    }
+
+    /// A `while` or `for` loop, which tests `cond` before each iteration; a `for` loop
+    /// evaluates `post` after each, which is where `continue` goes.
+    fn test_first_loop(
+        &mut self,
+        at: Node,
+        cond: &Expr,
+        body: &Statement,
+        post: Option<&Expr>,
+    ) -> Node {
+        let location = self.location;
+        let (test, entry, next, after) = (self.node(), self.node(), self.node(), self.node());
+        self.edge(at, test);
+        self.condition(cond, test, entry, after);
+        self.location = location;
+        let body_end = self.loop_body(body, entry, (after, next));
+        self.location = location;
+        self.edge(body_end, next);
+        let post_end = (post.iter()).fold(next, |at, e| self.expr(e, at, true));
+        self.location = location;
+        self.edge(post_end, test);
+        self.cfg.loops.push(Loop {
+            location,
+            entry: test,
+            after,
+        });
+        after
+    }
+
+    fn loop_body(&mut self, body: &Statement, entry: Node, targets: (Node, Node)) -> Node {
+        self.targets.push(targets);
+        let end = self.stmt(body, entry);
+        self.targets.pop();
+        end
+    }
+
+    /// Outputs are written after the inputs are read; `+` outputs are read as well.
+    fn asm(&mut self, asm: &AsmStatement, at: Node) -> Node {
+        let mut at = at;
+        for operand in &asm.outputs {
+            if operand.constraint.starts_with('+') {
+                at = self.expr(&operand.expr, at, true);
+            }
+        }
+        for operand in &asm.inputs {
+            at = self.expr(&operand.expr, at, true);
+        }
+        for operand in &asm.outputs {
+            at = self.write(&operand.expr, at, true);
+        }
+        at
+    }
+
+    /// Records what evaluating `e` from `at` does, in order, and returns the point where
+    /// it's done, which is only another one after a statement expression. If `e` isn't
+    /// `certain` to be evaluated, its assignments may not happen.
+    fn expr(&mut self, e: &Expr, at: Node, certain: bool) -> Node {
+        match e {
+            Expr::Var(name) => {
+                let read = Access::Read(name.clone(), self.location);
+                self.cfg.accesses[at].push(read);
+                at
+            }
+            Expr::Const(_) => at,
+            Expr::Assign(lhs, rhs) => {
+                let at = self.expr(rhs, at, certain);
+                self.write(lhs, at, certain)
+            }
+            Expr::AddrOf(inner) => self.write(inner, at, certain),
+            Expr::BinOp(BinaryOp::LogicalAnd | BinaryOp::LogicalOr, lhs, rhs) => {
+                let at = self.expr(lhs, at, certain);
+                self.expr(rhs, at, false)
+            }
+            Expr::Conditional { cond, then, els } => {
+                let at = self.expr(cond, at, certain);
+                let at = match then {
+                    Some(then) => self.expr(then, at, false),
+                    None => at,
+                };
+                self.expr(els, at, false)
+            }
+            // the items are a block of their own; if they may not run, control can go
+            // around them
+            Expr::Block(items) if certain => {
+                let location = self.location;
+                let end = self.block(items, at);
+                self.location = location;
+                end
+            }
+            Expr::Block(_) => {
+                let (entry, after) = (self.node(), self.node());
+                self.edge(at, entry);
+                self.edge(at, after);
+                let end = self.expr(e, entry, true);
+                self.edge(end, after);
+                after
+            }
+            _ => (e.operands().into_iter()).fold(at, |at, operand| self.expr(operand, at, certain)),
+        }
+    }
+
+    /// `lvalue` gets a value, or a pointer to it that may be used to give it one.
+    fn write(&mut self, lvalue: &Expr, at: Node, certain: bool) -> Node {
+        match lvalue {
+            Expr::Var(name) => {
+                let write = match certain {
+                    true => Access::Write(name.clone()),
+                    false => Access::MaybeWrite(name.clone()),
+                };
+                self.cfg.accesses[at].push(write);
+                at
+            }
+            // the address of an element or a member
+            _ => self.expr(lvalue, at, certain),
+        }
+    }
+}
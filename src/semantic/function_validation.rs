@@ -0,0 +1,568 @@
+use crate::ast::{
+    BinaryOp, BlockItem, Builtin, Declaration, Expr, Function, FunctionType, Initializer, Program,
+    Statement, StorageClass, TopLevel, Type,
+};
+use crate::semantic::source_text;
+use std::collections::HashMap;
+use std::fmt;
+
+enum FuncKind {
+    Decl(FunctionType),
+    Def(FunctionType),
+}
+
+impl FuncKind {
+    fn function_type(&self) -> &FunctionType {
+        match self {
+            FuncKind::Decl(ty) | FuncKind::Def(ty) => ty,
+        }
+    }
+}
+
+/// Number of arguments a call has to supply.
+#[derive(Clone, Copy, PartialEq)]
+enum Arity {
+    /// declared without a prototype, any number goes
+    Unspecified,
+    Exact(usize),
+    /// variadic, the named parameters and possibly more
+    AtLeast(usize),
+}
+
+impl Arity {
+    fn of(ty: &FunctionType) -> Arity {
+        match (ty.prototyped, ty.variadic) {
+            (false, _) => Arity::Unspecified,
+            (true, false) => Arity::Exact(ty.params.len()),
+            (true, true) => Arity::AtLeast(ty.params.len()),
+        }
+    }
+
+    fn accepts(self, args: usize) -> bool {
+        match self {
+            Arity::Unspecified => true,
+            Arity::Exact(n) => args == n,
+            Arity::AtLeast(n) => args >= n,
+        }
+    }
+}
+
+impl fmt::Display for Arity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Arity::Unspecified => write!(f, "unspecified"),
+            Arity::Exact(n) => write!(f, "{n}"),
+            Arity::AtLeast(n) => write!(f, "at least {n}"),
+        }
+    }
+}
+
+/// Checks that a call to `callee`, of type `ty`, passes an acceptable number of arguments.
+pub fn check_call_arity(callee: &str, ty: &FunctionType, args: usize) -> Result<(), String> {
+    check_arity(callee, Arity::of(ty), args)
+}
+
+/// Checks that a call to `builtin` passes the number of arguments it takes.
+pub fn check_builtin_arity(builtin: Builtin, args: usize) -> Result<(), String> {
+    check_arity(builtin.name(), Arity::Exact(builtin.arity()), args)
+}
+
+fn check_arity(callee: &str, arity: Arity, args: usize) -> Result<(), String> {
+    if arity.accepts(args) {
+        return Ok(());
+    }
+    Err(format!(
+        "function call to `{}` has wrong number of arguments: expected {}, got {}",
+        callee, arity, args
+    ))
+}
+
+/// Validates the semantic correctness of function declarations, definitions, and calls.
+///
+/// This pass checks for the following errors:
+/// - Multiple definitions of the same function.
+/// - Inconsistent parameter counts across declarations and definitions.
+/// - Inconsistent return or parameter types across declarations and definitions.
+///   A declaration without a prototype is completed by a later one with it.
+/// - A `static` declaration following one with external linkage.
+/// - Block-scope function and `extern` declarations that conflict with
+///   file-scope declarations or with each other.
+/// - Function calls with the wrong number of arguments, directly or through
+///   a function pointer whose type is known from declarations.
+/// - Calls to undefined functions.
+///   Should be called after parsing and before code generation.
+pub fn validate_functions_declarations(program: &Program) -> Result<(), String> {
+    let mut function_map: HashMap<String, FuncKind> = HashMap::new();
+    let mut first_seen: HashMap<&String, &Function> = HashMap::new();
+    let mut first_prototype: HashMap<&String, &Function> = HashMap::new();
+
+    for item in &program.toplevel_items {
+        match item {
+            TopLevel::Function(func) => {
+                let first = *first_seen.entry(&func.name).or_insert(func);
+                if first.return_type != func.return_type {
+                    return Err(format!("conflicting types for function {}", func.name));
+                }
+                if func.prototyped {
+                    let proto = *first_prototype.entry(&func.name).or_insert(func);
+                    let param_types_differ = proto
+                        .params
+                        .iter()
+                        .zip(&func.params)
+                        .any(|(a, b)| a.ty.unqualified() != b.ty.unqualified());
+                    if param_types_differ || proto.variadic != func.variadic {
+                        return Err(format!("conflicting types for function {}", func.name));
+                    }
+                }
+                if func.storage == Some(StorageClass::Static)
+                    && first.storage != Some(StorageClass::Static)
+                {
+                    return Err(format!(
+                        "static declaration of '{}' follows non-static declaration",
+                        func.name
+                    ));
+                }
+
+                let ty = func.function_type();
+                let (existing, defined) = match function_map.get(&func.name) {
+                    Some(FuncKind::Def(_)) if func.block_items.is_some() => {
+                        return Err(format!("function {} defined multiple times", func.name));
+                    }
+                    Some(kind @ FuncKind::Decl(_)) => (Some(kind.function_type()), false),
+                    Some(kind @ FuncKind::Def(_)) => (Some(kind.function_type()), true),
+                    None => (None, false),
+                };
+
+                // the first prototype fixes the parameters
+                let ty = match existing {
+                    Some(existing) if existing.prototyped && ty.prototyped => {
+                        let (existing_arity, arity) = (Arity::of(existing), Arity::of(&ty));
+                        if existing_arity != arity {
+                            return Err(format!(
+                                "function {} declared/defined with inconsistent parameter counts ({} vs {})",
+                                func.name, existing_arity, arity
+                            ));
+                        }
+                        existing.clone()
+                    }
+                    Some(existing) if existing.prototyped => existing.clone(),
+                    _ => ty,
+                };
+                let kind = if defined || func.block_items.is_some() {
+                    FuncKind::Def(ty)
+                } else {
+                    FuncKind::Decl(ty)
+                };
+                function_map.insert(func.name.clone(), kind);
+            }
+            TopLevel::GlobalVariable(_) => {}
+        }
+    }
+
+    // file-scope objects are visible in every function body
+    let mut globals = HashMap::new();
+    for item in &program.toplevel_items {
+        if let TopLevel::GlobalVariable(Declaration::Declare { name, ty, .. }) = item {
+            globals.insert(name.clone(), ty.clone());
+        }
+    }
+    check_block_scope_linkage(program, &function_map, &globals)?;
+
+    // Second pass: check function calls
+    for item in &program.toplevel_items {
+        match item {
+            TopLevel::Function(func) => {
+                if let Some(body) = &func.block_items {
+                    let params = (func.params.iter())
+                        .map(|p| (p.name.clone(), p.ty.clone()))
+                        .collect();
+                    let mut scopes = Scopes {
+                        objects: vec![globals.clone(), params],
+                        functions: &function_map,
+                    };
+                    validate_function_body(body, &mut scopes)?;
+                }
+            }
+            TopLevel::GlobalVariable(_) => {}
+        }
+    }
+
+    Ok(())
+}
+
+/// Collects the block-scope declarations of functions and `extern` objects in `items`.
+fn collect_linked_declarations<'a>(items: &'a [BlockItem], out: &mut Vec<&'a Declaration>) {
+    fn visit_stmt<'a>(stmt: &'a Statement, out: &mut Vec<&'a Declaration>) {
+        match stmt {
+            Statement::Compound(items) => collect_linked_declarations(items, out),
+            Statement::If { then, els, .. } => {
+                visit_stmt(then, out);
+                if let Some(els) = els {
+                    visit_stmt(els, out);
+                }
+            }
+            Statement::For { body, .. }
+            | Statement::ForDecl { body, .. }
+            | Statement::While { body, .. }
+            | Statement::Do { body, .. }
+            | Statement::Switch { body, .. }
+            | Statement::Case { body, .. }
+            | Statement::Default { body, .. } => visit_stmt(body, out),
+            _ => {}
+        }
+    }
+
+    for item in items {
+        match item {
+            BlockItem::Decl(decl) if decl.has_linkage() => out.push(decl),
+            BlockItem::Decl(_) => {}
+            BlockItem::Stmt(stmt, _) => visit_stmt(stmt, out),
+        }
+    }
+}
+
+/// Block-scope declarations of functions and `extern` objects name the same entity as
+/// the file-scope declarations, so their types must agree with them and with each other.
+fn check_block_scope_linkage(
+    program: &Program,
+    functions: &HashMap<String, FuncKind>,
+    globals: &HashMap<String, Type>,
+) -> Result<(), String> {
+    let mut decls = Vec::new();
+    for item in &program.toplevel_items {
+        if let TopLevel::Function(Function {
+            block_items: Some(body),
+            ..
+        }) = item
+        {
+            collect_linked_declarations(body, &mut decls);
+        }
+    }
+
+    // entities declared only at block scope, by their first declaration
+    let mut block_functions: HashMap<&String, &FunctionType> = HashMap::new();
+    let mut block_objects: HashMap<&String, &Type> = HashMap::new();
+
+    for decl in decls {
+        let Declaration::Declare { name, ty, .. } = decl;
+        match ty {
+            Type::Function(func) => {
+                if globals.contains_key(name) || block_objects.contains_key(name) {
+                    return Err(format!("'{name}' redeclared as different kind of symbol"));
+                }
+                let existing = match functions.get(name) {
+                    Some(kind) => kind.function_type(),
+                    None => block_functions.entry(name).or_insert(func),
+                };
+                if !existing.compatible(func) {
+                    return Err(format!("conflicting types for function {name}"));
+                }
+            }
+            _ => {
+                if functions.contains_key(name) || block_functions.contains_key(name) {
+                    return Err(format!("'{name}' redeclared as different kind of symbol"));
+                }
+                let existing = match globals.get(name) {
+                    Some(existing) => existing,
+                    None => block_objects.entry(name).or_insert(ty),
+                };
+                if existing != ty {
+                    return Err(format!(
+                        "conflicting types for '{name}': '{ty}' vs '{existing}'"
+                    ));
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Names visible inside a function body.
+struct Scopes<'a> {
+    /// declared type of each object, or of a function declared at block scope,
+    /// innermost scope last
+    objects: Vec<HashMap<String, Type>>,
+    functions: &'a HashMap<String, FuncKind>,
+}
+
+impl Scopes<'_> {
+    fn object(&self, name: &str) -> Option<&Type> {
+        self.objects.iter().rev().find_map(|scope| scope.get(name))
+    }
+
+    fn declare(&mut self, decl: &Declaration) {
+        let Declaration::Declare { name, ty, .. } = decl;
+        if let Some(scope) = self.objects.last_mut() {
+            scope.insert(name.clone(), ty.clone());
+        }
+    }
+
+    /// Type of the value of `expr`, as far as it can be told from declarations alone.
+    /// Only the forms that can produce a function pointer are followed.
+    fn value_type(&self, expr: &Expr) -> Option<Type> {
+        match expr {
+            Expr::Var(name) => match self.object(name) {
+                Some(ty) => Some(ty.decay()),
+                None => (self.functions.get(name))
+                    .map(|kind| Type::Function(Box::new(kind.function_type().clone())).decay()),
+            },
+            Expr::Deref(inner) => match self.value_type(inner)? {
+                Type::Pointer(pointee) => Some(pointee.decay()),
+                _ => None,
+            },
+            Expr::AddrOf(inner) => match inner.as_ref() {
+                Expr::Deref(pointer) => self.value_type(pointer),
+                _ => self.value_type(inner).filter(|ty| ty.callee().is_some()),
+            },
+            Expr::BinOp(BinaryOp::Add | BinaryOp::Sub, lhs, rhs) => {
+                let lt = self.value_type(lhs);
+                if lt.as_ref().is_some_and(Type::is_pointer) {
+                    return lt;
+                }
+                self.value_type(rhs).filter(Type::is_pointer)
+            }
+            Expr::Assign(target, _) => self.value_type(target),
+            Expr::Cast(ty, _) => Some(ty.unqualified().clone()),
+            Expr::FunCall { name, .. } => {
+                let callee = Expr::Var(name.clone());
+                Some(self.value_type(&callee)?.callee()?.return_type.clone())
+            }
+            Expr::IndirectCall { callee, .. } => {
+                Some(self.value_type(callee)?.callee()?.return_type.clone())
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Validates the function body to ensure all function calls are semantically correct.
+///
+/// Checks every expression in the given block for:
+/// - Calls to functions that have not been declared or defined.
+/// - Calls with an incorrect number of arguments (arity mismatch).
+/// - Calls of objects that are not functions or function pointers.
+///
+/// This function is called once per function that has a body (i.e., not just a declaration).
+fn validate_function_body(block_items: &[BlockItem], scopes: &mut Scopes) -> Result<(), String> {
+    fn check_expr(expr: &Expr, scopes: &mut Scopes) -> Result<(), String> {
+        match expr {
+            Expr::FunCall { name, parameters } => {
+                match scopes.object(name) {
+                    // an object in scope hides a function of the same name
+                    Some(ty) => match ty.decay().callee() {
+                        Some(func) => check_call_arity(name, func, parameters.len())?,
+                        None => {
+                            return Err(format!(
+                                "called object '{name}' is not a function or function pointer"
+                            ));
+                        }
+                    },
+                    None => match scopes.functions.get(name) {
+                        Some(kind) => {
+                            check_call_arity(name, kind.function_type(), parameters.len())?
+                        }
+                        None => {
+                            return Err(format!("call to undefined function `{}`", name));
+                        }
+                    },
+                }
+                for arg in parameters {
+                    check_expr(arg, scopes)?; // recurse
+                }
+            }
+            Expr::IndirectCall { callee, parameters } => {
+                if let Some(ty) = scopes.value_type(callee) {
+                    let Some(func) = ty.callee() else {
+                        return Err(format!(
+                            "called object type '{ty}' is not a function or function pointer"
+                        ));
+                    };
+                    check_call_arity(&source_text(callee), func, parameters.len())?;
+                }
+                check_expr(callee, scopes)?;
+                for arg in parameters {
+                    check_expr(arg, scopes)?;
+                }
+            }
+            Expr::Builtin(builtin, args) => {
+                check_builtin_arity(*builtin, args.len())?;
+                for arg in args {
+                    check_expr(arg, scopes)?;
+                }
+            }
+
+            Expr::UnOp(_, e)
+            | Expr::Cast(_, e)
+            | Expr::Deref(e)
+            | Expr::AddrOf(e)
+            | Expr::VaStart(e)
+            | Expr::VaArg(e, _)
+            | Expr::VaEnd(e)
+            | Expr::Alloca(e) => check_expr(e, scopes)?,
+            Expr::Assign(l, r) | Expr::BinOp(_, l, r) | Expr::VaCopy(l, r) => {
+                check_expr(l, scopes)?;
+                check_expr(r, scopes)?;
+            }
+            Expr::Conditional { cond, then, els } => {
+                check_expr(cond, scopes)?;
+                if let Some(then) = then {
+                    check_expr(then, scopes)?;
+                }
+                check_expr(els, scopes)?;
+            }
+            Expr::Block(items) => {
+                scopes.objects.push(HashMap::new());
+                let result = check_items(items, scopes);
+                scopes.objects.pop();
+                result?;
+            }
+            Expr::Const(_) | Expr::Var(_) => {}
+        }
+        Ok(())
+    }
+
+    fn check_initializer(init: &Initializer, scopes: &mut Scopes) -> Result<(), String> {
+        match init {
+            Initializer::Single(e) => check_expr(e, scopes),
+            Initializer::List(items) => items
+                .iter()
+                .try_for_each(|item| check_initializer(item, scopes)),
+        }
+    }
+
+    fn check_declaration(decl: &Declaration, scopes: &mut Scopes) -> Result<(), String> {
+        let Declaration::Declare { init, .. } = decl;
+        scopes.declare(decl); // in scope in its own initializer
+        if let Some(init) = init {
+            check_initializer(init, scopes)?;
+        }
+        Ok(())
+    }
+
+    fn check_items(items: &[BlockItem], scopes: &mut Scopes) -> Result<(), String> {
+        for item in items {
+            match item {
+                BlockItem::Stmt(s, _) => check_stmt(s, scopes)?,
+                BlockItem::Decl(d) => check_declaration(d, scopes)?,
+            }
+        }
+        Ok(())
+    }
+
+    fn check_stmt(stmt: &Statement, scopes: &mut Scopes) -> Result<(), String> {
+        match stmt {
+            Statement::Expr(Some(e)) => check_expr(e, scopes),
+            Statement::Expr(None) => Ok(()),
+            Statement::Return(Some(e)) => check_expr(e, scopes),
+            Statement::Return(None) => Ok(()),
+            Statement::If { cond, then, els } => {
+                check_expr(cond, scopes)?;
+                check_stmt(then, scopes)?;
+                if let Some(els) = els {
+                    check_stmt(els, scopes)?;
+                }
+                Ok(())
+            }
+            Statement::While { cond, body, .. }
+            | Statement::Do { cond, body, .. }
+            | Statement::Switch { cond, body, .. } => {
+                check_expr(cond, scopes)?;
+                check_stmt(body, scopes)
+            }
+            Statement::Case { body, .. } | Statement::Default { body, .. } => {
+                check_stmt(body, scopes)
+            }
+            Statement::For {
+                init,
+                cond,
+                post,
+                body,
+                ..
+            } => {
+                if let Some(init) = init {
+                    check_expr(init, scopes)?;
+                }
+                check_expr(cond, scopes)?;
+                if let Some(post) = post {
+                    check_expr(post, scopes)?;
+                }
+                check_stmt(body, scopes)
+            }
+            Statement::ForDecl {
+                decl,
+                cond,
+                post,
+                body,
+                ..
+            } => {
+                scopes.objects.push(HashMap::new());
+                check_declaration(decl, scopes)?;
+                check_expr(cond, scopes)?;
+                if let Some(post) = post {
+                    check_expr(post, scopes)?;
+                }
+                let result = check_stmt(body, scopes);
+                scopes.objects.pop();
+                result
+            }
+            Statement::Compound(items) => {
+                scopes.objects.push(HashMap::new());
+                let result = check_items(items, scopes);
+                scopes.objects.pop();
+                result
+            }
+            Statement::Break(_) | Statement::Continue(_) | Statement::Bingus(_) => Ok(()),
+            Statement::Asm(asm) => {
+                for operand in asm.operands() {
+                    check_expr(&operand.expr, scopes)?;
+                }
+                Ok(())
+            }
+        }
+    }
+
+    // the outermost block shares the scope of the parameters
+    check_items(block_items, scopes)
+}
+
+pub fn check_global_name_conflicts(program: &Program) -> Result<(), String> {
+    let mut function_names: HashMap<&String, bool> = HashMap::new(); // func name -> has_definition
+    let mut global_var_names: HashMap<&String, &Option<Initializer>> = HashMap::new();
+
+    for item in &program.toplevel_items {
+        match item {
+            TopLevel::Function(func) => {
+                let has_definition = func.block_items.is_some();
+
+                if let Some(&defined) = function_names.get(&func.name) {
+                    if defined && has_definition {
+                        return Err(format!("function '{}' defined twice", func.name));
+                    }
+                } else {
+                    function_names.insert(&func.name, has_definition);
+                }
+            }
+            TopLevel::GlobalVariable(Declaration::Declare { name, init, .. }) => {
+                if let Some(&var) = global_var_names.get(name)
+                    && var.is_some()
+                {
+                    return Err(format!("Duplicate global variable definition: '{}'", name));
+                }
+
+                global_var_names.insert(name, init);
+            }
+        }
+    }
+
+    // Check for conflicts
+    for (name, _) in global_var_names {
+        if function_names.contains_key(name) {
+            return Err(format!(
+                "Name conflict: '{}' are used as both a global variable and a function",
+                name
+            ));
+        }
+    }
+
+    Ok(())
+}
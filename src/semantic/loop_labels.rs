@@ -0,0 +1,207 @@
+use crate::ast::{
+    BlockItem, Declaration, Expr, Function, Initializer, LoopLabel, Program, Statement, TopLevel,
+    Type,
+};
+
+/// The numbers of the statements that a `break`, `continue`, `case` or `default`
+/// would refer to, if there are any.
+#[derive(Debug, Clone, Copy, Default)]
+struct Enclosing {
+    /// the innermost loop or `switch`, which `break` leaves
+    breakable: Option<usize>,
+    /// the innermost loop, whose next iteration `continue` goes to
+    looping: Option<usize>,
+    /// the innermost `switch`, unless there is a statement expression in between,
+    /// which can't be jumped into
+    switch: Option<usize>,
+}
+
+/// Numbers every loop and `switch` of `program` and points each `break`, `continue`,
+/// `case` and `default` at the innermost one around it that it refers to, so code
+/// generation never has to track them.
+pub fn label_loops(program: &Program) -> Result<Program, String> {
+    let mut program = program.clone();
+    let mut loops = 0;
+    for item in &mut program.toplevel_items {
+        if let TopLevel::Function(Function {
+            block_items: Some(items),
+            ..
+        }) = item
+        {
+            label_items(items, Enclosing::default(), &mut loops)?;
+        }
+    }
+    Ok(program)
+}
+
+fn label_items(
+    items: &mut [BlockItem],
+    current: Enclosing,
+    loops: &mut usize,
+) -> Result<(), String> {
+    for item in items {
+        match item {
+            BlockItem::Stmt(stmt, _) => label_stmt(stmt, current, loops)?,
+            BlockItem::Decl(decl) => label_decl(decl, current, loops)?,
+        }
+    }
+    Ok(())
+}
+
+fn label_decl(decl: &mut Declaration, current: Enclosing, loops: &mut usize) -> Result<(), String> {
+    let Declaration::Declare { ty, init, .. } = decl;
+    if let Type::VarArray(_, len) = ty {
+        label_expr(len, current, loops)?;
+    }
+    match init {
+        Some(init) => label_initializer(init, current, loops),
+        None => Ok(()),
+    }
+}
+
+fn label_initializer(
+    init: &mut Initializer,
+    current: Enclosing,
+    loops: &mut usize,
+) -> Result<(), String> {
+    match init {
+        Initializer::Single(expr) => label_expr(expr, current, loops),
+        Initializer::List(items) => items
+            .iter_mut()
+            .try_for_each(|item| label_initializer(item, current, loops)),
+    }
+}
+
+/// Labels the loops in the statement expressions of `expr`. A `break` or `continue` in
+/// one leaves the loop around the expression, like a statement there would.
+fn label_expr(expr: &mut Expr, current: Enclosing, loops: &mut usize) -> Result<(), String> {
+    if let Expr::Block(items) = expr {
+        let current = Enclosing {
+            switch: None,
+            ..current
+        };
+        return label_items(items, current, loops);
+    }
+    (expr.operands_mut().into_iter()).try_for_each(|operand| label_expr(operand, current, loops))
+}
+
+/// Points `label` at `target`, or fails with `error` if there is none.
+fn point_at(label: &mut LoopLabel, target: Option<usize>, error: String) -> Result<(), String> {
+    match target {
+        Some(n) => {
+            *label = Some(n);
+            Ok(())
+        }
+        None => Err(error),
+    }
+}
+
+/// The loop numbered `n`, in the `switch` the loop is in.
+fn in_loop(current: Enclosing, n: usize) -> Enclosing {
+    Enclosing {
+        breakable: Some(n),
+        looping: Some(n),
+        ..current
+    }
+}
+
+/// Labels the loops in `stmt`; `current` is what `stmt` is in.
+fn label_stmt(stmt: &mut Statement, current: Enclosing, loops: &mut usize) -> Result<(), String> {
+    match stmt {
+        Statement::For {
+            init,
+            cond,
+            post,
+            body,
+            label,
+        } => {
+            for expr in [init.as_mut(), Some(cond), post.as_mut()]
+                .into_iter()
+                .flatten()
+            {
+                label_expr(expr, current, loops)?;
+            }
+            *loops += 1;
+            *label = Some(*loops);
+            label_stmt(body, in_loop(current, *loops), loops)
+        }
+        Statement::ForDecl {
+            decl,
+            cond,
+            post,
+            body,
+            label,
+        } => {
+            label_decl(decl, current, loops)?;
+            label_expr(cond, current, loops)?;
+            if let Some(post) = post {
+                label_expr(post, current, loops)?;
+            }
+            *loops += 1;
+            *label = Some(*loops);
+            label_stmt(body, in_loop(current, *loops), loops)
+        }
+        Statement::While { cond, body, label } | Statement::Do { body, cond, label } => {
+            label_expr(cond, current, loops)?;
+            *loops += 1;
+            *label = Some(*loops);
+            label_stmt(body, in_loop(current, *loops), loops)
+        }
+        Statement::Switch { cond, body, label } => {
+            label_expr(cond, current, loops)?;
+            *loops += 1;
+            *label = Some(*loops);
+            let current = Enclosing {
+                breakable: Some(*loops),
+                switch: Some(*loops),
+                ..current
+            };
+            label_stmt(body, current, loops)
+        }
+        Statement::Case {
+            body,
+            location,
+            label,
+            ..
+        } => {
+            let error = format!("{location}: `case` label not within a switch statement");
+            point_at(label, current.switch, error)?;
+            label_stmt(body, current, loops)
+        }
+        Statement::Default {
+            body,
+            location,
+            label,
+        } => {
+            let error = format!("{location}: `default` label not within a switch statement");
+            point_at(label, current.switch, error)?;
+            label_stmt(body, current, loops)
+        }
+        Statement::Break(label) => point_at(
+            label,
+            current.breakable,
+            "`break` used outside of loop or switch".to_string(),
+        ),
+        Statement::Continue(label) => point_at(
+            label,
+            current.looping,
+            "`continue` used outside of loop".to_string(),
+        ),
+        Statement::If { cond, then, els } => {
+            label_expr(cond, current, loops)?;
+            label_stmt(then, current, loops)?;
+            match els {
+                Some(els) => label_stmt(els, current, loops),
+                None => Ok(()),
+            }
+        }
+        Statement::Compound(items) => label_items(items, current, loops),
+        Statement::Return(Some(expr)) | Statement::Expr(Some(expr)) | Statement::Bingus(expr) => {
+            label_expr(expr, current, loops)
+        }
+        Statement::Return(None) | Statement::Expr(None) => Ok(()),
+        Statement::Asm(asm) => (asm.outputs.iter_mut())
+            .chain(asm.inputs.iter_mut())
+            .try_for_each(|operand| label_expr(&mut operand.expr, current, loops)),
+    }
+}
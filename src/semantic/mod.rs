@@ -0,0 +1,31 @@
+pub mod constant;
+pub mod control_flow;
+pub mod function_validation;
+mod loop_labels;
+pub mod operators;
+mod resolve;
+pub mod standard;
+mod typeof_expansion;
+pub mod uninitialized;
+pub mod warnings;
+
+pub use resolve::{source_name, source_text};
+
+use crate::ast::Program;
+use function_validation::{check_global_name_conflicts, validate_functions_declarations};
+use loop_labels::label_loops;
+use resolve::resolve_identifiers;
+use typeof_expansion::expand_typeof;
+
+/// Checks a parsed program and resolves its identifiers, before any code is generated.
+///
+/// In the returned program every block-scope object has a name of its own, so later
+/// passes can look names up without caring about scopes and shadowing, every
+/// `break` and `continue` names the loop it belongs to, and no type is a `__typeof__`.
+pub fn analyze(program: &Program) -> Result<Program, String> {
+    let program = expand_typeof(program)?;
+    validate_functions_declarations(&program)?;
+    check_global_name_conflicts(&program)?;
+    let program = resolve_identifiers(&program)?;
+    label_loops(&program)
+}
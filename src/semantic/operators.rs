@@ -0,0 +1,122 @@
+use crate::ast::{BinaryOp, Type};
+
+/// How the operands of a binary operator are interpreted after the usual conversions.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Domain {
+    Signed,
+    Unsigned,
+    Floating,
+}
+
+impl Domain {
+    pub fn of(ty: &Type) -> Domain {
+        if ty.is_floating() {
+            Domain::Floating
+        } else if ty.is_signed() {
+            Domain::Signed
+        } else {
+            Domain::Unsigned
+        }
+    }
+}
+
+/// Relation tested by a comparison operator.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Comparison {
+    Equal,
+    NotEqual,
+    Less,
+    LessEqual,
+    Greater,
+    GreaterEqual,
+}
+
+/// What a binary operator computes on operands of a given domain, all of the same
+/// type `T` of N bits. The constant evaluator and the code generator both lower
+/// operators through [`operation`], so a folded expression has the value the
+/// program would compute at run time.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Operation {
+    /// the low N bits of the exact sum; undefined for signed `T` if that changes the value
+    Add,
+    /// the low N bits of the exact difference, undefined like `Add`
+    Sub,
+    /// the low N bits of the exact product, undefined like `Add`
+    Multiply,
+    /// quotient truncated toward zero; undefined when dividing by zero or `MIN / -1`
+    SignedDivide,
+    /// quotient of the values as unsigned numbers; undefined when dividing by zero
+    UnsignedDivide,
+    /// `a - (a / b) * b`, which has the sign of the dividend
+    SignedRemainder,
+    /// remainder of the values as unsigned numbers
+    UnsignedRemainder,
+    And,
+    Or,
+    Xor,
+    /// the low N bits of `a * 2^b`; undefined for signed `T` if bits other than the sign
+    /// bit are lost. A count outside `0..N` is undefined; at run time only its low bits count.
+    ShiftLeft,
+    /// `a / 2^b` rounded toward minus infinity: copies of the sign bit are shifted in
+    ArithmeticShiftRight,
+    /// zeros are shifted in
+    LogicalShiftRight,
+    /// IEEE 754 operations, rounded to nearest
+    FloatAdd,
+    FloatSub,
+    FloatMultiply,
+    FloatDivide,
+    /// an `int` that is 1 if the relation holds, comparing the values of the domain;
+    /// every relation but `!=` is false if a floating operand is a NaN
+    Compare(Comparison, Domain),
+    /// `&&` and `||`: an `int`, and the right operand runs only if the left one doesn't decide
+    LogicalAnd,
+    LogicalOr,
+}
+
+/// The semantics of `op` on operands converted to a type of `domain`: the common type of
+/// both operands, or the promoted left operand of a shift. `None` if C doesn't define `op`
+/// on that domain, such as `%` or `<<` on floating-point values.
+pub fn operation(op: BinaryOp, domain: Domain) -> Option<Operation> {
+    use Domain::*;
+    let compare = |relation| Some(Operation::Compare(relation, domain));
+    match (op, domain) {
+        (BinaryOp::LogicalAnd, _) => Some(Operation::LogicalAnd),
+        (BinaryOp::LogicalOr, _) => Some(Operation::LogicalOr),
+        (BinaryOp::Equal, _) => compare(Comparison::Equal),
+        (BinaryOp::NotEqual, _) => compare(Comparison::NotEqual),
+        (BinaryOp::Less, _) => compare(Comparison::Less),
+        (BinaryOp::LessEqual, _) => compare(Comparison::LessEqual),
+        (BinaryOp::Greater, _) => compare(Comparison::Greater),
+        (BinaryOp::GreaterEqual, _) => compare(Comparison::GreaterEqual),
+
+        (BinaryOp::Add, Floating) => Some(Operation::FloatAdd),
+        (BinaryOp::Sub, Floating) => Some(Operation::FloatSub),
+        (BinaryOp::Multiply, Floating) => Some(Operation::FloatMultiply),
+        (BinaryOp::Divide, Floating) => Some(Operation::FloatDivide),
+        (_, Floating) => None,
+
+        (BinaryOp::Add, _) => Some(Operation::Add),
+        (BinaryOp::Sub, _) => Some(Operation::Sub),
+        (BinaryOp::Multiply, _) => Some(Operation::Multiply),
+        (BinaryOp::Divide, Signed) => Some(Operation::SignedDivide),
+        (BinaryOp::Divide, _) => Some(Operation::UnsignedDivide),
+        (BinaryOp::Modulo, Signed) => Some(Operation::SignedRemainder),
+        (BinaryOp::Modulo, _) => Some(Operation::UnsignedRemainder),
+        (BinaryOp::And, _) => Some(Operation::And),
+        (BinaryOp::Or, _) => Some(Operation::Or),
+        (BinaryOp::Xor, _) => Some(Operation::Xor),
+        (BinaryOp::ShiftLeft, _) => Some(Operation::ShiftLeft),
+        (BinaryOp::ShiftRight, Signed) => Some(Operation::ArithmeticShiftRight),
+        (BinaryOp::ShiftRight, _) => Some(Operation::LogicalShiftRight),
+    }
+}
+
+/// Type both operands of `op` are converted to before it's applied: the promoted left
+/// operand for a shift, the common type otherwise.
+pub fn operand_type(op: BinaryOp, lhs: &Type, rhs: &Type) -> Type {
+    match op {
+        BinaryOp::ShiftLeft | BinaryOp::ShiftRight => lhs.promoted(),
+        _ => Type::common(lhs, rhs),
+    }
+}
@@ -0,0 +1,435 @@
+use crate::ast::*;
+use std::collections::{HashMap, HashSet};
+
+/// What a name declared in a scope refers to.
+struct Binding {
+    /// the name every use is rewritten to
+    symbol: String,
+    /// a function or an `extern` object, which may be declared again in the same scope
+    linkage: bool,
+}
+
+/// Names visible at a point of the program, innermost scope last.
+struct Resolver {
+    scopes: Vec<HashMap<String, Binding>>,
+    /// functions can be called before they are declared
+    functions: HashSet<String>,
+    /// number of block-scope objects renamed so far
+    renamed: usize,
+}
+
+/// The name an identifier had in the source, before it was resolved.
+pub fn source_name(symbol: &str) -> &str {
+    symbol.split('.').next().unwrap_or(symbol)
+}
+
+/// `expr` written as C with the names the source declares, for diagnostics.
+/// Operands other than names, constants and calls are parenthesized.
+pub fn source_text(expr: &Expr) -> String {
+    let operand = |e: &Expr| match e {
+        Expr::Var(_) | Expr::Const(_) | Expr::FunCall { .. } | Expr::Builtin(..) => source_text(e),
+        _ => format!("({})", source_text(e)),
+    };
+    let list = |args: &[Expr]| args.iter().map(source_text).collect::<Vec<_>>().join(", ");
+    match expr {
+        Expr::Const(c) => match c {
+            Constant::UInt(n) => format!("{n}u"),
+            Constant::Long(n) => format!("{n}l"),
+            Constant::ULong(n) => format!("{n}ul"),
+            Constant::Float(n) => format!("{n:?}f"),
+            Constant::Double(n) => format!("{n:?}"),
+            _ => c.as_i128().to_string(),
+        },
+        Expr::Var(name) => source_name(name).to_string(),
+        Expr::UnOp(op, inner) => format!("{op}{}", operand(inner)),
+        Expr::BinOp(op, lhs, rhs) => format!("{} {op} {}", operand(lhs), operand(rhs)),
+        Expr::Assign(lhs, rhs) => format!("{} = {}", operand(lhs), operand(rhs)),
+        Expr::Conditional { cond, then, els } => match then {
+            Some(then) => format!("{} ? {} : {}", operand(cond), operand(then), operand(els)),
+            None => format!("{} ?: {}", operand(cond), operand(els)),
+        },
+        Expr::FunCall { name, parameters } => {
+            format!("{}({})", source_name(name), list(parameters))
+        }
+        Expr::IndirectCall { callee, parameters } => {
+            format!("{}({})", operand(callee), list(parameters))
+        }
+        Expr::Cast(ty, inner) => format!("({ty}){}", operand(inner)),
+        Expr::Deref(inner) => format!("*{}", operand(inner)),
+        Expr::AddrOf(inner) => format!("&{}", operand(inner)),
+        Expr::VaStart(ap) => format!("va_start({})", source_text(ap)),
+        Expr::VaArg(ap, ty) => format!("va_arg({}, {ty})", source_text(ap)),
+        Expr::VaEnd(ap) => format!("va_end({})", source_text(ap)),
+        Expr::VaCopy(dest, src) => format!("va_copy({}, {})", source_text(dest), source_text(src)),
+        Expr::Alloca(size) => format!("__builtin_alloca({})", source_text(size)),
+        Expr::Builtin(builtin, args) => format!("{}({})", builtin.name(), list(args)),
+        Expr::Block(_) => "({...})".to_string(),
+    }
+}
+
+/// Rewrites every identifier of `program` to the symbol it refers to.
+///
+/// Parameters and block-scope objects without linkage are renamed to `name.N`, unique in
+/// the program; functions, file-scope objects and `extern` declarations keep their name.
+/// Reports uses of undeclared variables and redeclarations in the same scope.
+pub fn resolve_identifiers(program: &Program) -> Result<Program, String> {
+    let functions = (program.toplevel_items.iter())
+        .filter_map(|item| match item {
+            TopLevel::Function(f) => Some(f.name.clone()),
+            TopLevel::GlobalVariable(_) => None,
+        })
+        .collect();
+    let mut resolver = Resolver {
+        scopes: vec![HashMap::new()],
+        functions,
+        renamed: 0,
+    };
+
+    let mut toplevel_items = Vec::with_capacity(program.toplevel_items.len());
+    for item in &program.toplevel_items {
+        toplevel_items.push(match item {
+            TopLevel::Function(f) => TopLevel::Function(resolver.resolve_function(f)?),
+            TopLevel::GlobalVariable(decl) => {
+                // a file-scope object is visible from its first declaration on
+                let Declaration::Declare { name, .. } = decl;
+                resolver.scopes[0].insert(
+                    name.clone(),
+                    Binding {
+                        symbol: name.clone(),
+                        linkage: true,
+                    },
+                );
+                TopLevel::GlobalVariable(decl.clone())
+            }
+        });
+    }
+    Ok(Program { toplevel_items })
+}
+
+impl Resolver {
+    fn lookup(&self, name: &str) -> Option<&Binding> {
+        self.scopes.iter().rev().find_map(|scope| scope.get(name))
+    }
+
+    /// Binds `name` in the innermost scope and returns the symbol it now stands for.
+    fn declare(&mut self, name: &str, linkage: bool) -> Result<String, String> {
+        let symbol = match linkage {
+            true => name.to_string(),
+            false => {
+                self.renamed += 1;
+                format!("{name}.{}", self.renamed)
+            }
+        };
+        let scope = self.scopes.last_mut().unwrap();
+        if let Some(prev) = scope.get(name)
+            && !(prev.linkage && linkage)
+        {
+            return Err(format!("variable {name} redeclared in same block"));
+        }
+        let binding = Binding {
+            symbol: symbol.clone(),
+            linkage,
+        };
+        scope.insert(name.to_string(), binding);
+        Ok(symbol)
+    }
+
+    fn resolve_function(&mut self, f: &Function) -> Result<Function, String> {
+        let Some(items) = &f.block_items else {
+            return Ok(f.clone());
+        };
+
+        // the outermost block shares the scope of the parameters
+        self.scopes.push(HashMap::new());
+        let mut params = Vec::with_capacity(f.params.len());
+        for param in &f.params {
+            if self.scopes.last().unwrap().contains_key(&param.name) {
+                return Err(format!("duplicate parameter '{}'", param.name));
+            }
+            let ty = self.resolve_type(&param.ty)?;
+            params.push(Param {
+                name: self.declare(&param.name, false)?,
+                ty,
+                location: param.location,
+            });
+        }
+        let items = self.resolve_items(items)?;
+        self.scopes.pop();
+
+        Ok(Function {
+            params,
+            block_items: Some(items),
+            ..f.clone()
+        })
+    }
+
+    fn resolve_items(&mut self, items: &[BlockItem]) -> Result<Vec<BlockItem>, String> {
+        items
+            .iter()
+            .map(|item| match item {
+                BlockItem::Stmt(stmt, location) => {
+                    Ok(BlockItem::Stmt(self.resolve_stmt(stmt)?, *location))
+                }
+                BlockItem::Decl(decl) => Ok(BlockItem::Decl(self.resolve_declaration(decl)?)),
+            })
+            .collect()
+    }
+
+    fn resolve_declaration(&mut self, decl: &Declaration) -> Result<Declaration, String> {
+        let Declaration::Declare {
+            name,
+            ty,
+            init,
+            storage,
+            thread_local,
+            location,
+        } = decl;
+        // the length of a variable-length array is evaluated before the array is in scope
+        let ty = self.resolve_type(ty)?;
+        // the name is in scope in its own initializer
+        let name = self.declare(name, decl.has_linkage())?;
+        let init = match init {
+            Some(init) => Some(self.resolve_initializer(init)?),
+            None => None,
+        };
+        Ok(Declaration::Declare {
+            name,
+            ty,
+            init,
+            storage: *storage,
+            thread_local: *thread_local,
+            location: *location,
+        })
+    }
+
+    /// Resolves the expressions a type contains: lengths of variable-length arrays
+    /// and operands of `__typeof__`.
+    fn resolve_type(&mut self, ty: &Type) -> Result<Type, String> {
+        Ok(match ty {
+            Type::Pointer(inner) => Type::Pointer(Box::new(self.resolve_type(inner)?)),
+            Type::Array(elem, n) => Type::Array(Box::new(self.resolve_type(elem)?), *n),
+            Type::VarArray(elem, len) => Type::VarArray(
+                Box::new(self.resolve_type(elem)?),
+                Box::new(self.resolve_expr(len)?),
+            ),
+            Type::Function(func) => Type::Function(Box::new(FunctionType {
+                return_type: self.resolve_type(&func.return_type)?,
+                params: (func.params.iter())
+                    .map(|param| self.resolve_type(param))
+                    .collect::<Result<_, _>>()?,
+                ..(**func).clone()
+            })),
+            Type::Qualified(q, inner) => Type::Qualified(*q, Box::new(self.resolve_type(inner)?)),
+            Type::Typeof(e) => Type::Typeof(Box::new(self.resolve_expr(e)?)),
+            _ => ty.clone(),
+        })
+    }
+
+    fn resolve_initializer(&mut self, init: &Initializer) -> Result<Initializer, String> {
+        Ok(match init {
+            Initializer::Single(e) => Initializer::Single(self.resolve_expr(e)?),
+            Initializer::List(items) => Initializer::List(
+                (items.iter())
+                    .map(|item| self.resolve_initializer(item))
+                    .collect::<Result<_, _>>()?,
+            ),
+        })
+    }
+
+    /// Resolves `stmt` in a scope of its own, which ends with it.
+    fn resolve_scoped(&mut self, stmt: &Statement) -> Result<Statement, String> {
+        self.scopes.push(HashMap::new());
+        let resolved = self.resolve_stmt(stmt);
+        self.scopes.pop();
+        resolved
+    }
+
+    fn resolve_stmt(&mut self, stmt: &Statement) -> Result<Statement, String> {
+        let optional = |resolver: &mut Self, e: &Option<Expr>| match e {
+            Some(e) => resolver.resolve_expr(e).map(Some),
+            None => Ok(None),
+        };
+        Ok(match stmt {
+            Statement::Return(e) => Statement::Return(optional(self, e)?),
+            Statement::Expr(e) => Statement::Expr(optional(self, e)?),
+            Statement::If { cond, then, els } => Statement::If {
+                cond: self.resolve_expr(cond)?,
+                then: Box::new(self.resolve_scoped(then)?),
+                els: match els {
+                    Some(els) => Some(Box::new(self.resolve_scoped(els)?)),
+                    None => None,
+                },
+            },
+            Statement::Compound(items) => {
+                self.scopes.push(HashMap::new());
+                let items = self.resolve_items(items);
+                self.scopes.pop();
+                Statement::Compound(items?)
+            }
+            Statement::Bingus(e) => Statement::Bingus(self.resolve_expr(e)?),
+            Statement::For {
+                init,
+                cond,
+                post,
+                body,
+                label,
+            } => Statement::For {
+                init: optional(self, init)?,
+                cond: self.resolve_expr(cond)?,
+                post: optional(self, post)?,
+                body: Box::new(self.resolve_scoped(body)?),
+                label: *label,
+            },
+            Statement::ForDecl {
+                decl,
+                cond,
+                post,
+                body,
+                label,
+            } => {
+                // the declaration is in a scope around the body
+                self.scopes.push(HashMap::new());
+                let resolved = self.resolve_for_decl(decl, cond, post, body, *label);
+                self.scopes.pop();
+                resolved?
+            }
+            Statement::While { cond, body, label } => Statement::While {
+                cond: self.resolve_expr(cond)?,
+                body: Box::new(self.resolve_scoped(body)?),
+                label: *label,
+            },
+            Statement::Do { body, cond, label } => Statement::Do {
+                body: Box::new(self.resolve_scoped(body)?),
+                cond: self.resolve_expr(cond)?,
+                label: *label,
+            },
+            Statement::Switch { cond, body, label } => Statement::Switch {
+                cond: self.resolve_expr(cond)?,
+                body: Box::new(self.resolve_scoped(body)?),
+                label: *label,
+            },
+            Statement::Case {
+                value,
+                last,
+                body,
+                location,
+                label,
+            } => Statement::Case {
+                value: *value,
+                last: *last,
+                body: Box::new(self.resolve_stmt(body)?),
+                location: *location,
+                label: *label,
+            },
+            Statement::Default {
+                body,
+                location,
+                label,
+            } => Statement::Default {
+                body: Box::new(self.resolve_stmt(body)?),
+                location: *location,
+                label: *label,
+            },
+            Statement::Break(_) | Statement::Continue(_) => stmt.clone(),
+            Statement::Asm(asm) => {
+                let mut operands = |operands: &[AsmOperand]| {
+                    (operands.iter())
+                        .map(|operand| {
+                            Ok(AsmOperand {
+                                expr: self.resolve_expr(&operand.expr)?,
+                                ..operand.clone()
+                            })
+                        })
+                        .collect::<Result<_, String>>()
+                };
+                Statement::Asm(AsmStatement {
+                    outputs: operands(&asm.outputs)?,
+                    inputs: operands(&asm.inputs)?,
+                    ..asm.clone()
+                })
+            }
+        })
+    }
+
+    fn resolve_for_decl(
+        &mut self,
+        decl: &Declaration,
+        cond: &Expr,
+        post: &Option<Expr>,
+        body: &Statement,
+        label: LoopLabel,
+    ) -> Result<Statement, String> {
+        Ok(Statement::ForDecl {
+            decl: self.resolve_declaration(decl)?,
+            cond: self.resolve_expr(cond)?,
+            post: match post {
+                Some(post) => Some(self.resolve_expr(post)?),
+                None => None,
+            },
+            body: Box::new(self.resolve_scoped(body)?),
+            label,
+        })
+    }
+
+    fn resolve_boxed(&mut self, e: &Expr) -> Result<Box<Expr>, String> {
+        self.resolve_expr(e).map(Box::new)
+    }
+
+    fn resolve_all(&mut self, args: &[Expr]) -> Result<Vec<Expr>, String> {
+        (args.iter()).map(|arg| self.resolve_expr(arg)).collect()
+    }
+
+    fn resolve_expr(&mut self, e: &Expr) -> Result<Expr, String> {
+        Ok(match e {
+            Expr::Var(name) => match self.lookup(name) {
+                Some(binding) => Expr::Var(binding.symbol.clone()),
+                // a function designator
+                None if self.functions.contains(name) => e.clone(),
+                None => return Err(format!("variable {name} not found")),
+            },
+            // an object in scope hides the function of the same name
+            Expr::FunCall { name, parameters } => Expr::FunCall {
+                name: self.lookup(name).map_or(name, |b| &b.symbol).clone(),
+                parameters: self.resolve_all(parameters)?,
+            },
+            Expr::Const(_) => e.clone(),
+            Expr::UnOp(op, inner) => Expr::UnOp(*op, self.resolve_boxed(inner)?),
+            Expr::BinOp(op, lhs, rhs) => {
+                Expr::BinOp(*op, self.resolve_boxed(lhs)?, self.resolve_boxed(rhs)?)
+            }
+            Expr::Assign(lhs, rhs) => {
+                Expr::Assign(self.resolve_boxed(lhs)?, self.resolve_boxed(rhs)?)
+            }
+            Expr::Conditional { cond, then, els } => Expr::Conditional {
+                cond: self.resolve_boxed(cond)?,
+                then: match then {
+                    Some(then) => Some(self.resolve_boxed(then)?),
+                    None => None,
+                },
+                els: self.resolve_boxed(els)?,
+            },
+            Expr::IndirectCall { callee, parameters } => Expr::IndirectCall {
+                callee: self.resolve_boxed(callee)?,
+                parameters: self.resolve_all(parameters)?,
+            },
+            Expr::Cast(ty, inner) => Expr::Cast(self.resolve_type(ty)?, self.resolve_boxed(inner)?),
+            Expr::Deref(inner) => Expr::Deref(self.resolve_boxed(inner)?),
+            Expr::AddrOf(inner) => Expr::AddrOf(self.resolve_boxed(inner)?),
+            Expr::VaStart(ap) => Expr::VaStart(self.resolve_boxed(ap)?),
+            Expr::VaArg(ap, ty) => Expr::VaArg(self.resolve_boxed(ap)?, self.resolve_type(ty)?),
+            Expr::VaEnd(ap) => Expr::VaEnd(self.resolve_boxed(ap)?),
+            Expr::VaCopy(dest, src) => {
+                Expr::VaCopy(self.resolve_boxed(dest)?, self.resolve_boxed(src)?)
+            }
+            Expr::Alloca(size) => Expr::Alloca(self.resolve_boxed(size)?),
+            Expr::Builtin(builtin, args) => Expr::Builtin(*builtin, self.resolve_all(args)?),
+            // a block of its own
+            Expr::Block(items) => {
+                self.scopes.push(HashMap::new());
+                let items = self.resolve_items(items);
+                self.scopes.pop();
+                Expr::Block(items?)
+            }
+        })
+    }
+}
@@ -0,0 +1,381 @@
+use std::ops::RangeInclusive;
+
+use crate::ast::*;
+use crate::lexer::{Token, Tokens};
+use crate::semantic::warnings::{Diagnostic, Warning, WarningOptions};
+
+/// Language standard selected with `-std=`: a revision of ISO C, or one with GNU extensions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Standard {
+    C89,
+    C99,
+    C11,
+    Gnu11,
+}
+
+impl Standard {
+    pub fn from_name(name: &str) -> Result<Standard, String> {
+        match name {
+            "c89" | "c90" => Ok(Standard::C89),
+            "c99" => Ok(Standard::C99),
+            "c11" => Ok(Standard::C11),
+            "gnu11" => Ok(Standard::Gnu11),
+            _ => Err(format!("unknown value '{name}' for '-std'")),
+        }
+    }
+
+    /// Year of the ISO C revision the standard is based on.
+    fn iso_year(self) -> u32 {
+        match self {
+            Standard::C89 => 1989,
+            Standard::C99 => 1999,
+            Standard::C11 | Standard::Gnu11 => 2011,
+        }
+    }
+
+    fn iso_name(self) -> &'static str {
+        match self {
+            Standard::C89 => "ISO C90",
+            Standard::C99 => "ISO C99",
+            Standard::C11 | Standard::Gnu11 => "ISO C11",
+        }
+    }
+
+    fn is_gnu(self) -> bool {
+        self == Standard::Gnu11
+    }
+}
+
+/// A language feature that not every standard has.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Feature {
+    /// `//` comments
+    LineComment,
+    /// a declaration as the first clause of a `for` loop
+    ForDeclaration,
+    /// a declaration after a statement in the same block
+    MixedDeclarations,
+    /// GNU `({ ... })`
+    StatementExpression,
+    /// GNU `cond ?: else`
+    OmittedMiddleOperand,
+    /// GNU `case low ... high:`
+    CaseRange,
+    /// `long long`
+    LongLong,
+    /// an array whose length is computed at run time
+    VariableLengthArray,
+    /// `_Static_assert`
+    StaticAssert,
+    /// `_Thread_local`
+    ThreadLocal,
+    /// `_Atomic`
+    Atomic,
+    /// `asm` spelled without underscores, a keyword only in the GNU standards
+    AsmKeyword,
+}
+
+impl Feature {
+    /// Year of the first ISO C revision with the feature, `None` for GNU extensions.
+    fn since(self) -> Option<u32> {
+        match self {
+            Feature::LineComment
+            | Feature::ForDeclaration
+            | Feature::MixedDeclarations
+            | Feature::LongLong
+            | Feature::VariableLengthArray => Some(1999),
+            Feature::StaticAssert | Feature::ThreadLocal | Feature::Atomic => Some(2011),
+            Feature::StatementExpression
+            | Feature::OmittedMiddleOperand
+            | Feature::CaseRange
+            | Feature::AsmKeyword => None,
+        }
+    }
+
+    /// Whether `standard` has the feature. Of the GNU extensions only the `asm` keyword
+    /// is part of the GNU standards; the others are extensions there too.
+    fn is_in(self, standard: Standard) -> bool {
+        match self.since() {
+            Some(year) => year <= standard.iso_year(),
+            None => self == Feature::AsmKeyword && standard.is_gnu(),
+        }
+    }
+
+    /// Whether a standard without the feature accepts it anyway, like GCC does, as an
+    /// extension that `-Wpedantic` reports; otherwise using it is an error.
+    fn is_extension_in(self, standard: Standard) -> bool {
+        match self {
+            Feature::LineComment => standard.is_gnu(),
+            Feature::ForDeclaration | Feature::AsmKeyword => false,
+            Feature::MixedDeclarations
+            | Feature::StatementExpression
+            | Feature::OmittedMiddleOperand
+            | Feature::CaseRange
+            | Feature::LongLong
+            | Feature::VariableLengthArray
+            | Feature::StaticAssert
+            | Feature::ThreadLocal
+            | Feature::Atomic => true,
+        }
+    }
+
+    fn message(self, standard: Standard) -> String {
+        let iso = standard.iso_name();
+        match self {
+            Feature::LineComment => format!("C++ style comments are not allowed in {iso}"),
+            Feature::ForDeclaration => {
+                format!("'for' loop initial declarations are not allowed in {iso}")
+            }
+            Feature::MixedDeclarations => format!("{iso} forbids mixed declarations and code"),
+            Feature::StatementExpression => {
+                "ISO C forbids braced-groups within expressions".to_string()
+            }
+            Feature::OmittedMiddleOperand => {
+                "ISO C forbids omitting the middle term of a '?:' expression".to_string()
+            }
+            Feature::CaseRange => {
+                "range expressions in switch statements are non-standard".to_string()
+            }
+            Feature::LongLong => format!("{iso} does not support 'long long'"),
+            Feature::VariableLengthArray => format!("{iso} forbids variable length array"),
+            Feature::StaticAssert => format!("{iso} does not support '_Static_assert'"),
+            Feature::ThreadLocal => format!("{iso} does not support '_Thread_local'"),
+            Feature::Atomic => format!("{iso} does not support the '_Atomic' qualifier"),
+            Feature::AsmKeyword => format!("'asm' is not a keyword in {iso}; use '__asm__'"),
+        }
+    }
+}
+
+/// Checks the features a parsed program uses against `standard`. Using one the standard
+/// doesn't have is an error, unless the standard accepts it as an extension; those are
+/// reported as [`Warning::Pedantic`] if it's enabled. Like GCC, the extensions in an
+/// expression or declaration that `__extension__` starts aren't reported.
+pub fn check_standard(
+    tokens: &Tokens,
+    program: &Program,
+    standard: Standard,
+    options: &WarningOptions,
+) -> Result<Vec<Diagnostic>, String> {
+    let mut uses: Vec<(Feature, Location)> = (tokens.line_comments.iter())
+        .map(|location| (Feature::LineComment, *location))
+        .chain((tokens.plain_asm.iter()).map(|location| (Feature::AsmKeyword, *location)))
+        .collect();
+    let mut extensions = keyword_uses(tokens);
+    for item in &program.toplevel_items {
+        if let TopLevel::Function(Function {
+            block_items: Some(items),
+            ..
+        }) = item
+        {
+            block_uses(items, &mut extensions);
+        }
+    }
+    let operands = extension_operands(tokens);
+    extensions.retain(|(_, location)| !operands.iter().any(|operand| operand.contains(location)));
+    uses.extend(extensions);
+    uses.sort_by_key(|(_, location)| *location);
+
+    let mut diagnostics = Vec::new();
+    for (feature, location) in uses {
+        if feature.is_in(standard) {
+            continue;
+        }
+        let message = feature.message(standard);
+        if !feature.is_extension_in(standard) {
+            return Err(format!("{location}: error: {message}"));
+        }
+        if options.is_enabled(Warning::Pedantic) {
+            diagnostics.push(Diagnostic {
+                warning: Warning::Pedantic,
+                location,
+                message,
+            });
+        }
+    }
+    Ok(diagnostics)
+}
+
+/// Where each expression or declaration that `__extension__` starts is, from the keyword
+/// to the token that ends it.
+fn extension_operands(tokens: &Tokens) -> Vec<RangeInclusive<Location>> {
+    let mut operands = Vec::new();
+    // where the current operand starts and how deeply nested in brackets the current token is
+    let mut operand: Option<(usize, usize)> = None;
+    for (pos, token) in tokens.iter().enumerate() {
+        operand = match (token, operand) {
+            (Token::KeywordExtension, None) => Some((pos, 0)),
+            (Token::LParen | Token::LBrace | Token::LBracket, Some((start, depth))) => {
+                Some((start, depth + 1))
+            }
+            (Token::RParen | Token::RBrace | Token::RBracket, Some((start, depth)))
+                if depth > 0 =>
+            {
+                Some((start, depth - 1))
+            }
+            (
+                Token::RParen | Token::RBrace | Token::RBracket | Token::Semicolon | Token::Comma,
+                Some((start, 0)),
+            ) => {
+                operands.push(tokens.location(start)..=tokens.location(pos));
+                None
+            }
+            (_, operand) => operand,
+        };
+    }
+    if let Some((start, _)) = operand {
+        operands.push(tokens.location(start)..=tokens.location(tokens.len()));
+    }
+    operands
+}
+
+/// Uses of GNU extensions and of keywords that not every standard has, found in the
+/// tokens.
+fn keyword_uses(tokens: &Tokens) -> Vec<(Feature, Location)> {
+    let mut uses = Vec::new();
+    for (pos, token) in tokens.iter().enumerate() {
+        // GCC points at the `(` of a braced group, at the `:` after `?` and at the
+        // second `long`
+        let (feature, at) = match (token, tokens.get(pos + 1)) {
+            (Token::LParen, Some(Token::LBrace)) => (Feature::StatementExpression, pos),
+            (Token::QuestionMark, Some(Token::Colon)) => (Feature::OmittedMiddleOperand, pos + 1),
+            (Token::KeywordLong, Some(Token::KeywordLong)) => (Feature::LongLong, pos + 1),
+            (Token::KeywordStaticAssert, _) => (Feature::StaticAssert, pos),
+            (Token::KeywordAtomic, _) => (Feature::Atomic, pos),
+            (Token::KeywordThreadLocal, _)
+                if tokens.thread_local_keywords.contains(&tokens.location(pos)) =>
+            {
+                (Feature::ThreadLocal, pos)
+            }
+            _ => continue,
+        };
+        uses.push((feature, tokens.location(at)));
+    }
+    uses
+}
+
+fn block_uses(items: &[BlockItem], uses: &mut Vec<(Feature, Location)>) {
+    let mut after_statement = false;
+    for item in items {
+        match item {
+            BlockItem::Stmt(stmt, location) => {
+                after_statement = true;
+                stmt_uses(stmt, *location, uses);
+            }
+            // once for the declarations that follow a statement, like GCC
+            BlockItem::Decl(decl) => {
+                let Declaration::Declare { location, .. } = decl;
+                if after_statement {
+                    uses.push((Feature::MixedDeclarations, *location));
+                }
+                after_statement = false;
+                decl_uses(decl, uses);
+            }
+        }
+    }
+}
+
+fn decl_uses(decl: &Declaration, uses: &mut Vec<(Feature, Location)>) {
+    let Declaration::Declare {
+        ty, init, location, ..
+    } = decl;
+    if has_var_array(ty) {
+        uses.push((Feature::VariableLengthArray, *location));
+    }
+    if let Some(init) = init {
+        initializer_uses(init, uses);
+    }
+}
+
+/// Whether `ty` is, or is derived from, a variable-length array type.
+fn has_var_array(ty: &Type) -> bool {
+    match ty {
+        Type::VarArray(..) => true,
+        Type::Pointer(inner) | Type::Array(inner, _) | Type::Qualified(_, inner) => {
+            has_var_array(inner)
+        }
+        _ => false,
+    }
+}
+
+fn initializer_uses(init: &Initializer, uses: &mut Vec<(Feature, Location)>) {
+    match init {
+        Initializer::Single(expr) => expr_uses(expr, uses),
+        Initializer::List(items) => items.iter().for_each(|item| initializer_uses(item, uses)),
+    }
+}
+
+/// Uses in the blocks of the statement expressions in `expr`.
+fn expr_uses(expr: &Expr, uses: &mut Vec<(Feature, Location)>) {
+    match expr {
+        Expr::Block(items) => block_uses(items, uses),
+        _ => (expr.operands().into_iter()).for_each(|operand| expr_uses(operand, uses)),
+    }
+}
+
+/// `location` is where the enclosing block item starts.
+fn stmt_uses(stmt: &Statement, location: Location, uses: &mut Vec<(Feature, Location)>) {
+    match stmt {
+        Statement::Compound(items) => block_uses(items, uses),
+        Statement::If { cond, then, els } => {
+            expr_uses(cond, uses);
+            stmt_uses(then, location, uses);
+            if let Some(els) = els {
+                stmt_uses(els, location, uses);
+            }
+        }
+        Statement::ForDecl {
+            decl,
+            cond,
+            post,
+            body,
+            ..
+        } => {
+            uses.push((Feature::ForDeclaration, location));
+            decl_uses(decl, uses);
+            expr_uses(cond, uses);
+            post.iter().for_each(|post| expr_uses(post, uses));
+            stmt_uses(body, location, uses);
+        }
+        Statement::While { cond, body, .. }
+        | Statement::Do { body, cond, .. }
+        | Statement::Switch { cond, body, .. } => {
+            expr_uses(cond, uses);
+            stmt_uses(body, location, uses);
+        }
+        // GCC points at the `case` of a range
+        Statement::Case {
+            last,
+            body,
+            location: case,
+            ..
+        } => {
+            if last.is_some() {
+                uses.push((Feature::CaseRange, *case));
+            }
+            stmt_uses(body, location, uses);
+        }
+        Statement::Default { body, .. } => stmt_uses(body, location, uses),
+        Statement::For {
+            init,
+            cond,
+            post,
+            body,
+            ..
+        } => {
+            for expr in [init.as_ref(), Some(cond), post.as_ref()]
+                .into_iter()
+                .flatten()
+            {
+                expr_uses(expr, uses);
+            }
+            stmt_uses(body, location, uses);
+        }
+        Statement::Return(Some(expr)) | Statement::Expr(Some(expr)) => expr_uses(expr, uses),
+        Statement::Return(None)
+        | Statement::Expr(None)
+        | Statement::Bingus(_)
+        | Statement::Break(_)
+        | Statement::Continue(_)
+        | Statement::Asm(_) => {}
+    }
+}
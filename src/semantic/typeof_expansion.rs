@@ -0,0 +1,338 @@
+use crate::ast::*;
+use crate::semantic::function_validation::check_builtin_arity;
+use crate::semantic::operators::operand_type;
+use crate::semantic::source_text;
+use std::collections::HashMap;
+
+/// Declared type of each name visible at a point of the program, innermost scope last.
+struct Expander {
+    scopes: Vec<HashMap<String, Type>>,
+}
+
+/// Replaces every `__typeof__(expr)` of `program` with the type of `expr`, which is
+/// not evaluated.
+///
+/// Names are looked up as the source declares them, before identifiers are resolved:
+/// a name is visible from its declarator on, so also in its own initializer. The type
+/// of an object keeps its qualifiers and array types; any other expression has the
+/// type of its value.
+pub fn expand_typeof(program: &Program) -> Result<Program, String> {
+    let mut program = program.clone();
+    let mut expander = Expander {
+        scopes: vec![HashMap::new()],
+    };
+    for item in &mut program.toplevel_items {
+        match item {
+            TopLevel::Function(f) => expander.expand_function(f)?,
+            TopLevel::GlobalVariable(decl) => expander.expand_declaration(decl)?,
+        }
+    }
+    Ok(program)
+}
+
+impl Expander {
+    fn lookup(&self, name: &str) -> Result<&Type, String> {
+        (self.scopes.iter().rev())
+            .find_map(|scope| scope.get(name))
+            .ok_or_else(|| format!("variable {name} not found"))
+    }
+
+    fn declare(&mut self, name: &str, ty: &Type) {
+        if let Some(scope) = self.scopes.last_mut() {
+            scope.insert(name.to_string(), ty.clone());
+        }
+    }
+
+    /// Runs `f` in a scope of its own.
+    fn scoped<T>(&mut self, f: impl FnOnce(&mut Self) -> Result<T, String>) -> Result<T, String> {
+        self.scopes.push(HashMap::new());
+        let result = f(self);
+        self.scopes.pop();
+        result
+    }
+
+    fn expand_function(&mut self, f: &mut Function) -> Result<(), String> {
+        self.expand_type(&mut f.return_type)?;
+        f.return_type = f.return_type.unqualified().clone();
+        if f.return_type.is_array() || f.return_type.is_function() {
+            return Err(format!(
+                "function '{}' cannot return {}",
+                f.name, f.return_type
+            ));
+        }
+        // the parameters are in the scope of the body, and each in those after it
+        self.scoped(|this| {
+            for param in &mut f.params {
+                this.expand_type(&mut param.ty)?;
+                // adjusted like a parameter declared as an array or a function
+                if param.ty.is_array() || param.ty.is_var_array() || param.ty.is_function() {
+                    param.ty = param.ty.decay();
+                }
+                this.declare(&param.name, &param.ty);
+            }
+            let ty = Type::Function(Box::new(f.function_type()));
+            this.scopes[0].insert(f.name.clone(), ty);
+            match &mut f.block_items {
+                Some(items) => this.expand_items(items),
+                None => Ok(()),
+            }
+        })
+    }
+
+    fn expand_items(&mut self, items: &mut [BlockItem]) -> Result<(), String> {
+        for item in items {
+            match item {
+                BlockItem::Stmt(stmt, _) => self.expand_stmt(stmt)?,
+                BlockItem::Decl(decl) => self.expand_declaration(decl)?,
+            }
+        }
+        Ok(())
+    }
+
+    fn expand_declaration(&mut self, decl: &mut Declaration) -> Result<(), String> {
+        let Declaration::Declare { name, ty, init, .. } = decl;
+        self.expand_type(ty)?;
+        self.declare(name, ty);
+        match init {
+            Some(init) => self.expand_initializer(init),
+            None => Ok(()),
+        }
+    }
+
+    fn expand_initializer(&mut self, init: &mut Initializer) -> Result<(), String> {
+        match init {
+            Initializer::Single(expr) => self.expand_expr(expr),
+            Initializer::List(items) => {
+                (items.iter_mut()).try_for_each(|item| self.expand_initializer(item))
+            }
+        }
+    }
+
+    fn expand_type(&mut self, ty: &mut Type) -> Result<(), String> {
+        match ty {
+            Type::Typeof(expr) => *ty = self.typeof_type(expr)?,
+            Type::Pointer(inner) | Type::Array(inner, _) => self.expand_type(inner)?,
+            Type::VarArray(elem, len) => {
+                self.expand_type(elem)?;
+                self.expand_expr(len)?;
+            }
+            Type::Function(f) => {
+                self.expand_type(&mut f.return_type)?;
+                for param in &mut f.params {
+                    self.expand_type(param)?;
+                }
+            }
+            // `const __typeof__(a)` of an array qualifies its elements
+            Type::Qualified(qualifiers, inner) => {
+                let qualifiers = *qualifiers;
+                self.expand_type(inner)?;
+                *ty = std::mem::replace(inner.as_mut(), Type::Void).qualified(qualifiers);
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn expand_stmt(&mut self, stmt: &mut Statement) -> Result<(), String> {
+        match stmt {
+            Statement::Return(expr) | Statement::Expr(expr) => match expr {
+                Some(expr) => self.expand_expr(expr),
+                None => Ok(()),
+            },
+            Statement::Bingus(expr) => self.expand_expr(expr),
+            Statement::If { cond, then, els } => {
+                self.expand_expr(cond)?;
+                self.expand_stmt(then)?;
+                match els {
+                    Some(els) => self.expand_stmt(els),
+                    None => Ok(()),
+                }
+            }
+            Statement::Compound(items) => self.scoped(|this| this.expand_items(items)),
+            Statement::For {
+                init,
+                cond,
+                post,
+                body,
+                ..
+            } => {
+                for expr in [init.as_mut(), Some(cond), post.as_mut()]
+                    .into_iter()
+                    .flatten()
+                {
+                    self.expand_expr(expr)?;
+                }
+                self.expand_stmt(body)
+            }
+            Statement::ForDecl {
+                decl,
+                cond,
+                post,
+                body,
+                ..
+            } => self.scoped(|this| {
+                this.expand_declaration(decl)?;
+                this.expand_expr(cond)?;
+                if let Some(post) = post {
+                    this.expand_expr(post)?;
+                }
+                this.expand_stmt(body)
+            }),
+            Statement::While { cond, body, .. }
+            | Statement::Do { body, cond, .. }
+            | Statement::Switch { cond, body, .. } => {
+                self.expand_expr(cond)?;
+                self.expand_stmt(body)
+            }
+            Statement::Case { body, .. } | Statement::Default { body, .. } => {
+                self.expand_stmt(body)
+            }
+            Statement::Break(_) | Statement::Continue(_) => Ok(()),
+            Statement::Asm(asm) => (asm.outputs.iter_mut())
+                .chain(asm.inputs.iter_mut())
+                .try_for_each(|operand| self.expand_expr(&mut operand.expr)),
+        }
+    }
+
+    /// Expands the type names of casts and `va_arg`, and the declarations of statement
+    /// expressions.
+    fn expand_expr(&mut self, expr: &mut Expr) -> Result<(), String> {
+        match expr {
+            Expr::Cast(ty, inner) | Expr::VaArg(inner, ty) => {
+                self.expand_type(ty)?;
+                self.expand_expr(inner)
+            }
+            Expr::Block(items) => self.scoped(|this| this.expand_items(items)),
+            _ => {
+                (expr.operands_mut().into_iter()).try_for_each(|operand| self.expand_expr(operand))
+            }
+        }
+    }
+
+    /// The type `__typeof__(expr)` names.
+    fn typeof_type(&mut self, expr: &Expr) -> Result<Type, String> {
+        match expr {
+            Expr::Var(_) | Expr::Deref(_) => self.object_type(expr),
+            _ => self.value_type(expr),
+        }
+    }
+
+    /// Declared type of the object `expr` designates.
+    fn object_type(&mut self, expr: &Expr) -> Result<Type, String> {
+        match expr {
+            Expr::Var(name) => self.lookup(name).cloned(),
+            Expr::Deref(inner) => match self.value_type(inner)? {
+                Type::Pointer(pointee) => Ok(*pointee),
+                other => Err(format!(
+                    "indirection requires pointer operand ({other} invalid)"
+                )),
+            },
+            // not an lvalue, which code generation reports where it matters
+            _ => self.value_type(expr),
+        }
+    }
+
+    /// Type of the value of `expr`: arrays have decayed, qualifiers are dropped.
+    /// Operands are not checked; code generation does that where they are evaluated.
+    fn value_type(&mut self, expr: &Expr) -> Result<Type, String> {
+        use BinaryOp::*;
+        Ok(match expr {
+            Expr::Const(c) => c.ty(),
+            Expr::Var(_) | Expr::Deref(_) => self.object_type(expr)?.decay(),
+            Expr::AddrOf(inner) => Type::Pointer(Box::new(self.object_type(inner)?)),
+            Expr::Cast(ty, _) | Expr::VaArg(_, ty) => {
+                let mut ty = ty.clone();
+                self.expand_type(&mut ty)?;
+                ty.unqualified().clone()
+            }
+            Expr::UnOp(UnaryOp::Not, _) => Type::Int,
+            Expr::UnOp(_, inner) => self.value_type(inner)?.promoted(),
+            Expr::BinOp(op, lhs, rhs) => {
+                let (lt, rt) = (self.value_type(lhs)?, self.value_type(rhs)?);
+                match op {
+                    Equal | NotEqual | Less | LessEqual | Greater | GreaterEqual | LogicalAnd
+                    | LogicalOr => Type::Int,
+                    Sub if lt.is_pointer() && rt.is_pointer() => Type::Long,
+                    Add | Sub if lt.is_pointer() => lt,
+                    Add if rt.is_pointer() => rt,
+                    _ => operand_type(*op, &lt, &rt),
+                }
+            }
+            Expr::Assign(lhs, _) => self.object_type(lhs)?.unqualified().clone(),
+            Expr::Conditional { cond, then, els } => {
+                let tt = self.value_type(then.as_deref().unwrap_or(cond))?;
+                let et = self.value_type(els)?;
+                let is_void_pointer = |ty: &Type| match ty {
+                    Type::Pointer(pointee) => pointee.unqualified() == &Type::Void,
+                    _ => false,
+                };
+                match (&tt, &et) {
+                    _ if tt.is_arithmetic() && et.is_arithmetic() => Type::common(&tt, &et),
+                    // a null pointer constant or a `void *` on the other side
+                    (Type::Pointer(_), Type::Pointer(_)) if is_void_pointer(&et) => et,
+                    (_, Type::Pointer(_)) if !tt.is_pointer() => et,
+                    _ => tt,
+                }
+            }
+            Expr::FunCall { name, .. } => {
+                let callee = self.lookup(name)?.clone();
+                return_type(&callee, name)?
+            }
+            Expr::IndirectCall { callee, .. } => {
+                let ty = self.value_type(callee)?;
+                return_type(&ty, &source_text(callee))?
+            }
+            Expr::VaStart(_) | Expr::VaEnd(_) | Expr::VaCopy(..) => Type::Void,
+            Expr::Alloca(_) => Type::Pointer(Box::new(Type::Void)),
+            Expr::Builtin(builtin, args) => {
+                check_builtin_arity(*builtin, args.len())?;
+                match (builtin.function_type(), builtin) {
+                    (Some(signature), _) => signature.return_type,
+                    (None, Builtin::AtomicStore) => Type::Void,
+                    (None, Builtin::AtomicCompareExchange) => Type::Int,
+                    // the type of the object the first argument points to
+                    (None, _) if builtin.is_atomic() => match self.value_type(&args[0])? {
+                        Type::Pointer(object) => object.unqualified().clone(),
+                        other => {
+                            return Err(format!(
+                                "argument 1 of '{}' must be a pointer, not '{other}'",
+                                builtin.name()
+                            ));
+                        }
+                    },
+                    // whether the operation overflowed
+                    (None, _) => Type::Int,
+                }
+            }
+            // the value of the last statement, which may use what the others declare
+            Expr::Block(items) => self.scoped(|this| {
+                for item in items {
+                    match item {
+                        BlockItem::Decl(Declaration::Declare { name, ty, .. }) => {
+                            let mut ty = ty.clone();
+                            this.expand_type(&mut ty)?;
+                            this.declare(name, &ty);
+                        }
+                        BlockItem::Stmt(..) => {}
+                    }
+                }
+                match items.last() {
+                    Some(BlockItem::Stmt(Statement::Expr(Some(value)), _)) => {
+                        this.value_type(value)
+                    }
+                    _ => Ok(Type::Void),
+                }
+            })?,
+        })
+    }
+}
+
+/// Return type of a call through a value of type `callee`.
+fn return_type(callee: &Type, name: &str) -> Result<Type, String> {
+    match callee.callee() {
+        Some(f) => Ok(f.return_type.clone()),
+        None => Err(format!(
+            "called object '{name}' is not a function or function pointer"
+        )),
+    }
+}
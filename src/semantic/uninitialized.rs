@@ -0,0 +1,115 @@
+use crate::ast::Location;
+use crate::semantic::control_flow::{Access, Cfg};
+use std::collections::HashSet;
+
+/// A use of a block-scope object that can happen before it's assigned.
+#[derive(Debug, Clone, PartialEq)]
+pub struct UninitializedRead {
+    pub symbol: String,
+    pub location: Location,
+    /// no way to the use assigns the object, rather than only some of them
+    pub always: bool,
+}
+
+/// Objects that are assigned when control reaches a point.
+#[derive(Debug, Clone, PartialEq)]
+struct Assigned {
+    /// on every way there
+    always: HashSet<String>,
+    /// on at least one way there
+    sometimes: HashSet<String>,
+}
+
+impl Assigned {
+    fn apply(&mut self, access: &Access) {
+        match access {
+            Access::Declare(symbol) => {
+                self.always.remove(symbol);
+                self.sometimes.remove(symbol);
+            }
+            Access::Write(symbol) => {
+                self.always.insert(symbol.clone());
+                self.sometimes.insert(symbol.clone());
+            }
+            Access::MaybeWrite(symbol) => {
+                self.sometimes.insert(symbol.clone());
+            }
+            Access::Read(..) => {}
+        }
+    }
+
+    /// Adds another way to get to the point; returns whether that changed anything.
+    fn merge(&mut self, other: &Assigned) -> bool {
+        let before = (self.always.len(), self.sometimes.len());
+        self.always.retain(|symbol| other.always.contains(symbol));
+        self.sometimes.extend(other.sometimes.iter().cloned());
+        before != (self.always.len(), self.sometimes.len())
+    }
+}
+
+/// Definite assignment: finds the uses of automatic scalars declared without an
+/// initializer that some or all ways through the graph reach before assigning them.
+/// Taking the address of an object counts as assigning it. Each object is reported
+/// at its first such use only.
+pub fn uninitialized_reads(cfg: &Cfg) -> Vec<UninitializedRead> {
+    let tracked: HashSet<&String> = (cfg.accesses.iter().flatten())
+        .filter_map(|access| match access {
+            Access::Declare(symbol) => Some(symbol),
+            _ => None,
+        })
+        .collect();
+    if tracked.is_empty() {
+        return Vec::new();
+    }
+
+    // what is assigned where control enters each point; `None` until a way there is found
+    let mut entering: Vec<Option<Assigned>> = vec![None; cfg.successors.len()];
+    entering[cfg.start] = Some(Assigned {
+        always: HashSet::new(),
+        sometimes: HashSet::new(),
+    });
+    let mut work = vec![cfg.start];
+    while let Some(node) = work.pop() {
+        let Some(mut state) = entering[node].clone() else {
+            continue;
+        };
+        cfg.accesses[node].iter().for_each(|a| state.apply(a));
+        for &next in &cfg.successors[node] {
+            let changed = match &mut entering[next] {
+                Some(known) => known.merge(&state),
+                unknown => {
+                    *unknown = Some(state.clone());
+                    true
+                }
+            };
+            if changed {
+                work.push(next);
+            }
+        }
+    }
+
+    let mut reads: Vec<UninitializedRead> = Vec::new();
+    for (node, state) in entering.into_iter().enumerate() {
+        let Some(mut state) = state else {
+            continue;
+        };
+        for access in &cfg.accesses[node] {
+            match access {
+                Access::Read(symbol, location)
+                    if tracked.contains(symbol) && !state.always.contains(symbol) =>
+                {
+                    reads.push(UninitializedRead {
+                        symbol: symbol.clone(),
+                        location: *location,
+                        always: !state.sometimes.contains(symbol),
+                    });
+                }
+                _ => state.apply(access),
+            }
+        }
+    }
+    reads.sort_by_key(|read| read.location);
+    let mut reported = HashSet::new();
+    reads.retain(|read| reported.insert(read.symbol.clone()));
+    reads
+}
@@ -0,0 +1,669 @@
+use crate::ast::*;
+use crate::semantic::constant::{ConstError, Undefined, evaluate_compile_time_expr};
+use crate::semantic::control_flow::{Cfg, analyze_control_flow};
+use crate::semantic::source_name;
+use crate::semantic::uninitialized::uninitialized_reads;
+use std::collections::{HashMap, HashSet};
+
+/// A diagnostic that doesn't stop compilation, turned on and off by name with `-W<name>`
+/// and `-Wno-<name>`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Warning {
+    /// a block-scope object that is never referred to
+    UnusedVariable,
+    /// a parameter of a function definition that is never referred to
+    UnusedParameter,
+    /// the value of a call to a function that doesn't return `void` is discarded; only
+    /// on request, since most of the C library returns values rarely looked at
+    UnusedResult,
+    /// `=` as the condition of an `if` or a loop, where `==` was probably meant.
+    /// Comparing the result explicitly, as in `(x = f()) != 0`, says it's intended.
+    AssignInCondition,
+    /// a block-scope declaration hides a parameter, a local or a file-scope object
+    Shadow,
+    /// `;` as the body of an `if` or a loop
+    EmptyBody,
+    /// a comparison that always has the same result, because both operands are
+    /// constant or the constant is outside the range of the other operand's type
+    TautologicalCompare,
+    /// control can reach the end of a function that returns a value; `main` returns 0 there
+    ReturnType,
+    /// a statement or declaration that can never run, such as one after a `return`
+    UnreachableCode,
+    /// a loop that nothing can leave once it's entered
+    InfiniteLoop,
+    /// a block-scope object is used before anything assigns it
+    Uninitialized,
+    /// a block-scope object is used where only some ways there assign it
+    MaybeUninitialized,
+    /// a feature that the selected standard doesn't have, accepted as an extension
+    Pedantic,
+    /// a constant operation that overflows, such as `INT_MAX + 1`, left for run time
+    Overflow,
+    /// a constant division or remainder by zero, left for run time
+    DivByZero,
+}
+
+impl Warning {
+    pub const ALL: [Warning; 15] = [
+        Warning::UnusedVariable,
+        Warning::UnusedParameter,
+        Warning::UnusedResult,
+        Warning::AssignInCondition,
+        Warning::Shadow,
+        Warning::EmptyBody,
+        Warning::TautologicalCompare,
+        Warning::ReturnType,
+        Warning::UnreachableCode,
+        Warning::InfiniteLoop,
+        Warning::Uninitialized,
+        Warning::MaybeUninitialized,
+        Warning::Pedantic,
+        Warning::Overflow,
+        Warning::DivByZero,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Warning::UnusedVariable => "unused-variable",
+            Warning::UnusedParameter => "unused-parameter",
+            Warning::UnusedResult => "unused-result",
+            Warning::AssignInCondition => "assign-in-condition",
+            Warning::Shadow => "shadow",
+            Warning::EmptyBody => "empty-body",
+            Warning::TautologicalCompare => "tautological-compare",
+            Warning::ReturnType => "return-type",
+            Warning::UnreachableCode => "unreachable-code",
+            Warning::InfiniteLoop => "infinite-loop",
+            Warning::Uninitialized => "uninitialized",
+            Warning::MaybeUninitialized => "maybe-uninitialized",
+            Warning::Pedantic => "pedantic",
+            Warning::Overflow => "overflow",
+            Warning::DivByZero => "div-by-zero",
+        }
+    }
+
+    /// The flag that turns the warning on besides its own: `all`, `extra` or none.
+    fn group(self) -> Option<&'static str> {
+        match self {
+            Warning::UnusedVariable
+            | Warning::AssignInCondition
+            | Warning::TautologicalCompare
+            | Warning::ReturnType
+            | Warning::Uninitialized
+            | Warning::MaybeUninitialized => Some("all"),
+            Warning::UnusedParameter
+            | Warning::EmptyBody
+            | Warning::UnreachableCode
+            | Warning::InfiniteLoop => Some("extra"),
+            Warning::UnusedResult
+            | Warning::Shadow
+            | Warning::Pedantic
+            | Warning::Overflow
+            | Warning::DivByZero => None,
+        }
+    }
+
+    /// Whether the warning is reported without any `-W` flag.
+    fn on_by_default(self) -> bool {
+        matches!(self, Warning::Overflow | Warning::DivByZero)
+    }
+}
+
+/// Which warnings are reported, set by the `-W` flags.
+#[derive(Debug, Clone, Default)]
+pub struct WarningOptions {
+    enabled: HashSet<Warning>,
+    /// `-Werror`: enabled warnings are errors
+    pub werror: bool,
+}
+
+impl WarningOptions {
+    /// Applies `-W` flags in order, each without its `-W`: `all`, `extra`, `error` or
+    /// the name of a warning, any of them with `no-` in front to turn it off instead.
+    pub fn from_flags(flags: &[String]) -> Result<WarningOptions, String> {
+        let mut options = WarningOptions {
+            enabled: (Warning::ALL.into_iter())
+                .filter(|w| w.on_by_default())
+                .collect(),
+            werror: false,
+        };
+        for flag in flags {
+            let (on, name) = match flag.strip_prefix("no-") {
+                Some(name) => (false, name),
+                None => (true, flag.as_str()),
+            };
+            let warnings: Vec<Warning> = match name {
+                "error" => {
+                    options.werror = on;
+                    continue;
+                }
+                "all" | "extra" => (Warning::ALL.into_iter())
+                    .filter(|w| w.group() == Some(name))
+                    .collect(),
+                _ => match Warning::ALL.into_iter().find(|w| w.name() == name) {
+                    Some(warning) => vec![warning],
+                    None => return Err(format!("unknown warning option '-W{flag}'")),
+                },
+            };
+            for warning in warnings {
+                match on {
+                    true => options.enabled.insert(warning),
+                    false => options.enabled.remove(&warning),
+                };
+            }
+        }
+        Ok(options)
+    }
+
+    pub fn is_enabled(&self, warning: Warning) -> bool {
+        self.enabled.contains(&warning)
+    }
+}
+
+/// A warning about the code at `location`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diagnostic {
+    pub warning: Warning,
+    pub location: Location,
+    pub message: String,
+}
+
+impl Diagnostic {
+    /// The diagnostic as printed for the source file `file`, as an error with `-Werror`.
+    pub fn render(&self, file: &str, werror: bool) -> String {
+        let (kind, flag) = match werror {
+            true => ("error", "-Werror="),
+            false => ("warning", "-W"),
+        };
+        let name = self.warning.name();
+        format!(
+            "{file}:{}: {kind}: {} [{flag}{name}]",
+            self.location, self.message
+        )
+    }
+}
+
+/// What a name declared in an enclosing scope is, for [`Warning::Shadow`].
+#[derive(Clone, Copy)]
+enum Declared {
+    Global,
+    Parameter,
+    Local,
+}
+
+/// A block-scope object or parameter that nothing has referred to yet.
+struct Unused {
+    symbol: String,
+    location: Location,
+    warning: Warning,
+}
+
+struct Checker<'a> {
+    options: &'a WarningOptions,
+    diagnostics: Vec<Diagnostic>,
+    /// type of every object and function, by symbol
+    types: HashMap<String, Type>,
+    /// symbols that are referred to anywhere
+    used: HashSet<String>,
+    /// objects and parameters of the current function, to report if never used
+    declared: Vec<Unused>,
+    /// source names of the objects visible in each enclosing scope, innermost last
+    scopes: Vec<HashMap<String, Declared>>,
+    /// where the statement being checked starts
+    location: Location,
+    /// whether the expression being checked initializes an object of static storage
+    /// duration, where an undefined constant is an error rather than a warning
+    static_initializer: bool,
+}
+
+/// Checks a program that passed [`crate::semantic::analyze`] for the warnings that
+/// `options` enables, and returns them in the order of their locations. A warning is
+/// located at the statement or declaration it's in, so the same warning about two
+/// expressions there is reported once.
+pub fn check_warnings(program: &Program, options: &WarningOptions) -> Vec<Diagnostic> {
+    let mut checker = Checker {
+        options,
+        diagnostics: Vec::new(),
+        types: HashMap::new(),
+        used: HashSet::new(),
+        declared: Vec::new(),
+        scopes: vec![HashMap::new()],
+        location: Location::START,
+        static_initializer: false,
+    };
+    // functions can be called before they're declared
+    for item in &program.toplevel_items {
+        if let TopLevel::Function(f) = item {
+            let ty = Type::Function(Box::new(f.function_type()));
+            checker.types.insert(f.name.clone(), ty);
+        }
+    }
+    for item in &program.toplevel_items {
+        match item {
+            TopLevel::Function(f) => checker.check_function(f),
+            TopLevel::GlobalVariable(Declaration::Declare { name, ty, .. }) => {
+                checker.types.insert(name.clone(), ty.clone());
+                checker.scopes[0].insert(name.clone(), Declared::Global);
+            }
+        }
+    }
+
+    let mut diagnostics = checker.diagnostics;
+    diagnostics.sort_by(|a, b| (a.location, &a.message).cmp(&(b.location, &b.message)));
+    diagnostics.dedup();
+    diagnostics
+}
+
+impl Checker<'_> {
+    fn warn(&mut self, warning: Warning, location: Location, message: String) {
+        if self.options.is_enabled(warning) {
+            self.diagnostics.push(Diagnostic {
+                warning,
+                location,
+                message,
+            });
+        }
+    }
+
+    fn check_function(&mut self, f: &Function) {
+        let Some(items) = &f.block_items else {
+            return;
+        };
+
+        self.scopes.push(HashMap::new());
+        for param in &f.params {
+            self.types.insert(param.name.clone(), param.ty.clone());
+            self.declare(&param.name, Declared::Parameter, param.location);
+            self.declared.push(Unused {
+                symbol: param.name.clone(),
+                location: param.location,
+                warning: Warning::UnusedParameter,
+            });
+        }
+        self.check_items(items);
+        self.scopes.pop();
+
+        let cfg = Cfg::build(f);
+        let flow = analyze_control_flow(&cfg);
+        if flow.falls_off_end && f.return_type != Type::Void && f.name != "main" {
+            let message = "control reaches end of non-void function".to_string();
+            self.warn(Warning::ReturnType, f.location, message);
+        }
+        for location in flow.unreachable {
+            let message = "code will never be executed".to_string();
+            self.warn(Warning::UnreachableCode, location, message);
+        }
+        for location in flow.endless_loops {
+            let message = "loop never exits".to_string();
+            self.warn(Warning::InfiniteLoop, location, message);
+        }
+        for read in uninitialized_reads(&cfg) {
+            let name = source_name(&read.symbol);
+            let (warning, message) = match read.always {
+                true => (
+                    Warning::Uninitialized,
+                    format!("'{name}' is used uninitialized"),
+                ),
+                false => (
+                    Warning::MaybeUninitialized,
+                    format!("'{name}' may be used uninitialized"),
+                ),
+            };
+            self.warn(warning, read.location, message);
+        }
+
+        for unused in std::mem::take(&mut self.declared) {
+            if !self.used.contains(&unused.symbol) {
+                let kind = match unused.warning {
+                    Warning::UnusedParameter => "parameter",
+                    _ => "variable",
+                };
+                let name = source_name(&unused.symbol);
+                let message = format!("unused {kind} '{name}'");
+                self.warn(unused.warning, unused.location, message);
+            }
+        }
+    }
+
+    /// Adds a name to the innermost scope, reporting the declaration it hides if any.
+    fn declare(&mut self, symbol: &str, declared: Declared, location: Location) {
+        let name = source_name(symbol);
+        let hidden = self.scopes.iter().rev().find_map(|scope| scope.get(name));
+        if let Some(hidden) = hidden {
+            let what = match hidden {
+                Declared::Global => "a global declaration",
+                Declared::Parameter => "a parameter",
+                Declared::Local => "a previous local",
+            };
+            let message = format!("declaration of '{name}' shadows {what}");
+            self.warn(Warning::Shadow, location, message);
+        }
+        let scope = self.scopes.last_mut().expect("inside a function");
+        scope.insert(name.to_string(), declared);
+    }
+
+    fn check_items(&mut self, items: &[BlockItem]) {
+        for item in items {
+            match item {
+                BlockItem::Stmt(stmt, location) => {
+                    self.location = *location;
+                    self.check_stmt(stmt);
+                }
+                BlockItem::Decl(decl) => self.check_declaration(decl),
+            }
+        }
+    }
+
+    fn check_declaration(&mut self, decl: &Declaration) {
+        let Declaration::Declare {
+            name,
+            ty,
+            init,
+            location,
+            storage,
+            ..
+        } = decl;
+        self.location = *location;
+        self.types.insert(name.clone(), ty.clone());
+        if let Type::VarArray(_, len) = ty {
+            self.check_expr(len);
+        }
+        if !decl.has_linkage() {
+            self.declare(name, Declared::Local, *location);
+            self.declared.push(Unused {
+                symbol: name.clone(),
+                location: *location,
+                warning: Warning::UnusedVariable,
+            });
+        }
+        if let Some(init) = init {
+            self.static_initializer = storage.is_some();
+            self.check_initializer(init);
+            self.static_initializer = false;
+        }
+    }
+
+    fn check_initializer(&mut self, init: &Initializer) {
+        match init {
+            Initializer::Single(e) => self.check_expr(e),
+            Initializer::List(items) => items.iter().for_each(|i| self.check_initializer(i)),
+        }
+    }
+
+    /// Checks `stmt` in a scope of its own, like the body of an `if` or a loop.
+    fn check_scoped(&mut self, stmt: &Statement) {
+        self.scopes.push(HashMap::new());
+        self.check_stmt(stmt);
+        self.scopes.pop();
+    }
+
+    fn check_condition(&mut self, cond: &Expr) {
+        // `x op= y` is parsed as `x = x op y`, which is as deliberate as it looks
+        if let Expr::Assign(target, value) = cond
+            && !matches!(&**value, Expr::BinOp(_, lhs, _) if lhs == target)
+        {
+            let message = "assignment used as truth value; compare the result if that is intended";
+            self.warn(Warning::AssignInCondition, self.location, message.into());
+        }
+        self.check_expr(cond);
+    }
+
+    fn check_body(&mut self, keyword: &str, body: &Statement) {
+        if *body == Statement::Expr(None) {
+            let message = format!("suggest braces around empty body in '{keyword}' statement");
+            self.warn(Warning::EmptyBody, self.location, message);
+        }
+        self.check_scoped(body);
+    }
+
+    fn check_stmt(&mut self, stmt: &Statement) {
+        match stmt {
+            Statement::Return(e) => e.iter().for_each(|e| self.check_expr(e)),
+            Statement::Expr(Some(e)) => {
+                self.check_unused_result(e);
+                self.check_expr(e);
+            }
+            Statement::Expr(None) | Statement::Break(_) | Statement::Continue(_) => {}
+            Statement::If { cond, then, els } => {
+                self.check_condition(cond);
+                self.check_body("if", then);
+                if let Some(els) = els {
+                    self.check_body("else", els);
+                }
+            }
+            Statement::Compound(items) => {
+                self.scopes.push(HashMap::new());
+                self.check_items(items);
+                self.scopes.pop();
+            }
+            Statement::Bingus(e) => self.check_expr(e),
+            Statement::For {
+                init,
+                cond,
+                post,
+                body,
+                ..
+            } => {
+                init.iter().for_each(|e| self.check_expr(e));
+                self.check_condition(cond);
+                post.iter().for_each(|e| self.check_expr(e));
+                self.check_body("for", body);
+            }
+            Statement::ForDecl {
+                decl,
+                cond,
+                post,
+                body,
+                ..
+            } => {
+                let location = self.location;
+                self.scopes.push(HashMap::new());
+                self.check_declaration(decl);
+                self.location = location;
+                self.check_condition(cond);
+                post.iter().for_each(|e| self.check_expr(e));
+                self.check_body("for", body);
+                self.scopes.pop();
+            }
+            Statement::While { cond, body, .. } => {
+                self.check_condition(cond);
+                self.check_body("while", body);
+            }
+            Statement::Do { body, cond, .. } => {
+                self.check_scoped(body);
+                self.check_condition(cond);
+            }
+            Statement::Switch { cond, body, .. } => {
+                self.check_expr(cond);
+                self.check_scoped(body);
+            }
+            Statement::Case { body, .. } | Statement::Default { body, .. } => self.check_stmt(body),
+            Statement::Asm(asm) => asm.operands().for_each(|op| self.check_expr(&op.expr)),
+        }
+    }
+
+    fn check_unused_result(&mut self, e: &Expr) {
+        let (callee, ty) = match e {
+            Expr::FunCall { name, .. } => (source_name(name), self.types.get(name)),
+            Expr::IndirectCall { callee, .. } => match &**callee {
+                Expr::Var(name) => (source_name(name), self.types.get(name)),
+                Expr::Deref(inner) => match &**inner {
+                    Expr::Var(name) => (source_name(name), self.types.get(name)),
+                    _ => return,
+                },
+                _ => return,
+            },
+            _ => return,
+        };
+        let returns_value = ty
+            .and_then(Type::callee)
+            .is_some_and(|f| f.return_type.unqualified() != &Type::Void);
+        if returns_value {
+            let message = format!("ignoring return value of '{callee}'");
+            self.warn(Warning::UnusedResult, self.location, message);
+        }
+    }
+
+    fn check_comparison(&mut self, op: BinaryOp, lhs: &Expr, rhs: &Expr) {
+        let (l, r) = (
+            evaluate_compile_time_expr(lhs),
+            evaluate_compile_time_expr(rhs),
+        );
+        let limited_range =
+            |holds: bool| format!("comparison is always {holds} due to limited range of data type");
+        let message = match (l, r) {
+            (Ok(_), Ok(_)) => {
+                let comparison = Expr::BinOp(op, Box::new(lhs.clone()), Box::new(rhs.clone()));
+                let value = evaluate_compile_time_expr(&comparison);
+                value
+                    .ok()
+                    .map(|v| format!("comparison of constants is always {}", !v.is_zero()))
+            }
+            (Err(_), Ok(k)) => self
+                .compare_with_range(op, lhs, k, false)
+                .map(limited_range),
+            (Ok(k), Err(_)) => self.compare_with_range(op, rhs, k, true).map(limited_range),
+            (Err(_), Err(_)) => None,
+        };
+        if let Some(message) = message {
+            self.warn(Warning::TautologicalCompare, self.location, message);
+        }
+    }
+
+    /// The result of comparing the variable `var` with the constant `k`, if it's the
+    /// same for every value of the variable's type. `k` is the left operand if `swapped`.
+    fn compare_with_range(
+        &self,
+        op: BinaryOp,
+        var: &Expr,
+        k: Constant,
+        swapped: bool,
+    ) -> Option<bool> {
+        let Expr::Var(name) = var else {
+            return None;
+        };
+        let ty = self.types.get(name)?.unqualified();
+        if !ty.is_integer() || !k.ty().is_integer() {
+            return None;
+        }
+        // the conversion to the common type must keep every value of the variable
+        let common = Type::common(ty, &k.ty());
+        let keeps_values = match (common.is_signed(), ty.is_signed()) {
+            (true, true) | (false, false) => common.size() >= ty.size(),
+            (true, false) => common.size() > ty.size(),
+            (false, true) => false,
+        };
+        if !keeps_values {
+            return None;
+        }
+        let k = k.convert(&common)?.as_i128();
+        let bits = ty.size() * 8;
+        let (lo, hi) = match ty.is_signed() {
+            true => (-(1i128 << (bits - 1)), (1i128 << (bits - 1)) - 1),
+            false => (0, (1i128 << bits) - 1),
+        };
+
+        let holds = |x: i128| {
+            let (a, b) = if swapped { (k, x) } else { (x, k) };
+            match op {
+                BinaryOp::Less => a < b,
+                BinaryOp::LessEqual => a <= b,
+                BinaryOp::Greater => a > b,
+                BinaryOp::GreaterEqual => a >= b,
+                BinaryOp::Equal => a == b,
+                _ => a != b,
+            }
+        };
+        match op {
+            BinaryOp::Equal | BinaryOp::NotEqual => (!(lo..=hi).contains(&k)).then(|| holds(lo)),
+            // the others hold on one side of `k`, so the ends of the range decide
+            _ => (holds(lo) == holds(hi)).then(|| holds(lo)),
+        }
+    }
+
+    /// Reports the operation of `e` if its operands are constant and its result is
+    /// undefined, such as a signed overflow; it's computed at run time instead.
+    fn check_undefined(&mut self, e: &Expr) {
+        let undefined = |e: &Expr| match evaluate_compile_time_expr(e) {
+            Err(ConstError::Undefined(kind, reason)) => Some((kind, reason)),
+            _ => None,
+        };
+        // only the innermost operation, not every expression around it
+        if self.static_initializer || e.operands().into_iter().any(|o| undefined(o).is_some()) {
+            return;
+        }
+        if let Some((kind, reason)) = undefined(e) {
+            let warning = match kind {
+                Undefined::ZeroDivisor => Warning::DivByZero,
+                Undefined::Overflow => Warning::Overflow,
+            };
+            self.warn(warning, self.location, reason);
+        }
+    }
+
+    fn check_expr(&mut self, e: &Expr) {
+        self.check_undefined(e);
+        match e {
+            Expr::Var(name) => {
+                self.used.insert(name.clone());
+            }
+            Expr::Const(_) => {}
+            Expr::BinOp(
+                op @ (BinaryOp::Equal
+                | BinaryOp::NotEqual
+                | BinaryOp::Less
+                | BinaryOp::LessEqual
+                | BinaryOp::Greater
+                | BinaryOp::GreaterEqual),
+                lhs,
+                rhs,
+            ) => {
+                self.check_comparison(*op, lhs, rhs);
+                self.check_expr(lhs);
+                self.check_expr(rhs);
+            }
+            Expr::BinOp(_, lhs, rhs) | Expr::Assign(lhs, rhs) | Expr::VaCopy(lhs, rhs) => {
+                self.check_expr(lhs);
+                self.check_expr(rhs);
+            }
+            Expr::Conditional { cond, then, els } => {
+                self.check_expr(cond);
+                then.iter().for_each(|then| self.check_expr(then));
+                self.check_expr(els);
+            }
+            Expr::FunCall { name, parameters } => {
+                self.used.insert(name.clone());
+                parameters.iter().for_each(|p| self.check_expr(p));
+            }
+            Expr::IndirectCall { callee, parameters } => {
+                self.check_expr(callee);
+                parameters.iter().for_each(|p| self.check_expr(p));
+            }
+            Expr::UnOp(_, inner)
+            | Expr::Cast(_, inner)
+            | Expr::Deref(inner)
+            | Expr::AddrOf(inner)
+            | Expr::VaStart(inner)
+            | Expr::VaArg(inner, _)
+            | Expr::VaEnd(inner)
+            | Expr::Alloca(inner) => self.check_expr(inner),
+            Expr::Builtin(_, args) => args.iter().for_each(|a| self.check_expr(a)),
+            Expr::Block(items) => {
+                let location = self.location;
+                self.scopes.push(HashMap::new());
+                match items.split_last() {
+                    // the value of the last expression statement is used, it's the value of the whole
+                    Some((BlockItem::Stmt(Statement::Expr(Some(value)), at), rest)) => {
+                        self.check_items(rest);
+                        self.location = *at;
+                        self.check_expr(value);
+                    }
+                    _ => self.check_items(items),
+                }
+                self.scopes.pop();
+                self.location = location;
+            }
+        }
+    }
+}
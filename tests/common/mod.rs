@@ -0,0 +1,34 @@
+use std::process::{Command, Output};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Compiles `source` with `flags`, saved as `name.c` in a directory of its own.
+pub fn compile(name: &str, source: &str, flags: &[&str]) -> Output {
+    static COMPILED: AtomicUsize = AtomicUsize::new(0);
+    let n = COMPILED.fetch_add(1, Ordering::Relaxed);
+    let dir = std::env::temp_dir().join(format!("dumbc-{}-{n}", std::process::id()));
+    std::fs::create_dir_all(&dir).expect("create test directory");
+    let path = dir.join(format!("{name}.c"));
+    std::fs::write(&path, source).expect("write test source");
+    let output = Command::new(env!("CARGO_BIN_EXE_dumbc"))
+        .arg(&path)
+        .args(["--arch", "aarch64", "--platform", "linux"])
+        .args(flags)
+        .output()
+        .expect("run dumbc");
+    std::fs::remove_dir_all(&dir).expect("remove test directory");
+    output
+}
+
+/// The standard error, with the directory of the source file left out of each line.
+pub fn stderr(output: &Output) -> String {
+    (String::from_utf8_lossy(&output.stderr).lines())
+        .map(|line| {
+            let path_end = line.find(".c:").unwrap_or(0);
+            match line[..path_end].rfind('/') {
+                Some(slash) => &line[slash + 1..],
+                None => line,
+            }
+        })
+        .map(|line| format!("{line}\n"))
+        .collect()
+}
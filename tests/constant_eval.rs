@@ -0,0 +1,47 @@
+//! Undefined constant expressions: an error where an integer constant is required,
+//! otherwise a warning of the kind of undefined behavior, left for run time.
+
+mod common;
+
+use common::{compile, stderr};
+
+const UNDEFINED: &str = "int main(void) {\n    int x = (-2147483647 - 1) / -1;\n    int y = (-2147483647 - 1) % -1;\n    int z = 1 % 0;\n    return x + y + z;\n}\n";
+
+#[test]
+fn overflow_in_array_size_is_a_located_error() {
+    let source = "int a[2147483647 + 1];\nint main(void) {\n    return 0;\n}\n";
+    let output = compile("array_size", source, &[]);
+    let stderr = stderr(&output);
+    assert_eq!(output.status.code(), Some(1), "{stderr}");
+    assert!(!stderr.contains("panicked"), "{stderr}");
+    let expected = "array_size.c:1:7: integer overflow in constant expression";
+    assert!(stderr.contains(expected), "{stderr}");
+}
+
+#[test]
+fn overflowing_quotient_and_remainder_warn_as_overflow() {
+    let output = compile("undefined", UNDEFINED, &[]);
+    let stderr = stderr(&output);
+    assert!(output.status.success(), "{stderr}");
+    for expected in [
+        "undefined.c:2:",
+        "undefined.c:3:",
+        "integer overflow in constant expression [-Woverflow]",
+        "modulo by zero in constant expression [-Wdiv-by-zero]",
+    ] {
+        assert!(stderr.contains(expected), "{stderr}");
+    }
+    assert_eq!(stderr.matches("[-Woverflow]").count(), 2, "{stderr}");
+    assert_eq!(stderr.matches("[-Wdiv-by-zero]").count(), 1, "{stderr}");
+}
+
+#[test]
+fn each_kind_is_silenced_by_its_own_flag() {
+    let no_overflow = stderr(&compile("no_overflow", UNDEFINED, &["-Wno-overflow"]));
+    assert!(!no_overflow.contains("-Woverflow"), "{no_overflow}");
+    assert!(no_overflow.contains("[-Wdiv-by-zero]"), "{no_overflow}");
+
+    let no_div = stderr(&compile("no_div", UNDEFINED, &["-Wno-div-by-zero"]));
+    assert!(!no_div.contains("-Wdiv-by-zero"), "{no_div}");
+    assert_eq!(no_div.matches("[-Woverflow]").count(), 2, "{no_div}");
+}
@@ -0,0 +1,89 @@
+//! Warnings from the control-flow graph of each function: a missing `return`,
+//! code that never runs and loops that never exit.
+
+mod common;
+
+use common::{compile, stderr};
+
+const SOURCE: &str = "int missing(int x) {
+    if (x)
+        return 1;
+}
+int after_return(int x) {
+    return x;
+    x = 2;
+}
+int after_jumps(int x) {
+    while (x) {
+        break;
+        x = 3;
+    }
+    for (;;) {
+        continue;
+        x = 4;
+    }
+}
+int spins(void) {
+    while (1) {
+    }
+}
+int leaves(int x) {
+    while (1) {
+        if (x)
+            break;
+    }
+    return 0;
+}
+int main(void) { return leaves(1); }
+";
+
+const RETURN_TYPE: &str =
+    "control_flow.c:1:5: warning: control reaches end of non-void function [-Wreturn-type]";
+
+const EXTRA: [&str; 5] = [
+    "control_flow.c:7:5: warning: code will never be executed [-Wunreachable-code]",
+    "control_flow.c:12:9: warning: code will never be executed [-Wunreachable-code]",
+    "control_flow.c:14:5: warning: loop never exits [-Winfinite-loop]",
+    "control_flow.c:16:9: warning: code will never be executed [-Wunreachable-code]",
+    "control_flow.c:20:5: warning: loop never exits [-Winfinite-loop]",
+];
+
+fn warnings(flags: &[&str]) -> String {
+    let output = compile("control_flow", SOURCE, flags);
+    let stderr = stderr(&output);
+    assert!(output.status.success(), "{stderr}");
+    stderr
+}
+
+#[test]
+fn wall_reports_missing_return() {
+    let stderr = warnings(&["-Wall"]);
+    assert_eq!(
+        stderr.lines().collect::<Vec<_>>(),
+        [RETURN_TYPE],
+        "{stderr}"
+    );
+}
+
+#[test]
+fn wextra_reports_unreachable_code_and_endless_loops() {
+    let stderr = warnings(&["-Wextra"]);
+    assert_eq!(stderr.lines().collect::<Vec<_>>(), EXTRA, "{stderr}");
+}
+
+#[test]
+fn wno_disables_unreachable_code() {
+    let stderr = warnings(&["-Wall", "-Wextra", "-Wno-unreachable-code"]);
+    let expected = [RETURN_TYPE, EXTRA[2], EXTRA[4]];
+    assert_eq!(stderr.lines().collect::<Vec<_>>(), expected, "{stderr}");
+}
+
+#[test]
+fn werror_fails_on_missing_return() {
+    let output = compile("control_flow", SOURCE, &["-Wreturn-type", "-Werror"]);
+    let stderr = stderr(&output);
+    assert_eq!(output.status.code(), Some(1), "{stderr}");
+    let expected =
+        "control_flow.c:1:5: error: control reaches end of non-void function [-Werror=return-type]";
+    assert_eq!(stderr.lines().collect::<Vec<_>>(), [expected], "{stderr}");
+}
@@ -0,0 +1,88 @@
+//! `-std=` rejects what the selected standard doesn't have, at the file and line of its use.
+
+mod common;
+
+use common::{compile, stderr};
+
+const C99_FEATURES: &str = include_str!("../examples/c99_features.c");
+
+#[test]
+fn c89_rejects_c99_features() {
+    let output = compile("c99_features", C99_FEATURES, &["-std=c89"]);
+    let stderr = stderr(&output);
+    assert!(!output.status.success(), "compiled: {stderr}");
+    let expected =
+        "c99_features.c:8:5: error: 'for' loop initial declarations are not allowed in ISO C90";
+    assert!(stderr.contains(expected), "{stderr}");
+}
+
+#[test]
+fn c99_accepts_c99_features() {
+    let output = compile(
+        "c99_features",
+        C99_FEATURES,
+        &["-std=c99", "-pedantic", "-Werror"],
+    );
+    assert!(output.status.success(), "{}", stderr(&output));
+}
+
+#[test]
+fn pedantic_reports_c11_keywords_in_c99() {
+    let source = "_Static_assert(1, \"\");\n_Thread_local int t;\nint main(void) {\n    for (;; ({ 0; }))\n        break;\n    return t;\n}\n";
+    let output = compile("c11_keywords", source, &["-std=c99", "-pedantic"]);
+    let stderr = stderr(&output);
+    assert!(output.status.success(), "{stderr}");
+    for expected in [
+        "1:1: warning: ISO C99 does not support '_Static_assert'",
+        "2:1: warning: ISO C99 does not support '_Thread_local'",
+        "4:13: warning: ISO C forbids braced-groups within expressions",
+    ] {
+        assert!(stderr.contains(expected), "{stderr}");
+    }
+}
+
+#[test]
+fn plain_asm_is_gnu_only() {
+    let source = "int main(void) {\n    asm(\"nop\");\n    return 0;\n}\n";
+    let output = compile("plain_asm", source, &["-std=c11"]);
+    assert!(!output.status.success(), "compiled: {}", stderr(&output));
+    let output = compile("plain_asm", source, &["-std=gnu11", "-pedantic", "-Werror"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+}
+
+#[test]
+fn extension_silences_pedantic_in_its_operand() {
+    let source = "int main(void) {
+    int n = 2;
+    n = n + 1;
+    __extension__ int a[n];
+    int r = __extension__ ({
+        int s = 0;
+        s = s + 1;
+        int b = n;
+        switch (n) {
+        case 1 ... 3:
+            s = s + b;
+        }
+        s;
+    });
+    a[0] = r;
+    return a[0];
+}
+";
+    let flags = ["-std=c89", "-pedantic", "-Werror"];
+    let output = compile("extension", source, &flags);
+    assert!(output.status.success(), "{}", stderr(&output));
+
+    let output = compile("extension", &source.replace("__extension__ ", ""), &flags);
+    let stderr = stderr(&output);
+    assert_eq!(output.status.code(), Some(1), "{stderr}");
+    let expected = [
+        "extension.c:4:9: error: ISO C90 forbids mixed declarations and code [-Werror=pedantic]",
+        "extension.c:4:9: error: ISO C90 forbids variable length array [-Werror=pedantic]",
+        "extension.c:5:13: error: ISO C forbids braced-groups within expressions [-Werror=pedantic]",
+        "extension.c:8:13: error: ISO C90 forbids mixed declarations and code [-Werror=pedantic]",
+        "extension.c:10:9: error: range expressions in switch statements are non-standard [-Werror=pedantic]",
+    ];
+    assert_eq!(stderr.lines().collect::<Vec<_>>(), expected, "{stderr}");
+}
@@ -0,0 +1,46 @@
+//! A failing `_Static_assert` stops compilation and says where the assertion is.
+
+mod common;
+
+use common::{compile, stderr};
+use std::process::Output;
+
+fn assert_fails_at(output: &Output, location: &str) {
+    let stderr = stderr(output);
+    assert_eq!(output.status.code(), Some(1), "{stderr}");
+    assert!(!stderr.contains("panicked"), "{stderr}");
+    let expected = format!(".c:{location}: static assertion failed: too small");
+    assert!(stderr.contains(&expected), "{stderr}");
+}
+
+#[test]
+fn failing_assertion_at_file_scope() {
+    let source =
+        "int x;\n_Static_assert(2 + 2 > 4, \"too small\");\nint main(void) { return 0; }\n";
+    assert_fails_at(&compile("file_scope", source, &[]), "2:1");
+}
+
+#[test]
+fn failing_assertion_at_block_scope() {
+    let source = "int main(void) {\n    int x = 1;\n    _Static_assert((short)65536 == 1, \"too small\");\n    return x;\n}\n";
+    assert_fails_at(&compile("block_scope", source, &[]), "3:5");
+}
+
+#[test]
+fn non_constant_assertion_is_an_error() {
+    let source = "int main(void) {\n    int x = 1;\n    _Static_assert(x, \"too small\");\n    return x;\n}\n";
+    let output = compile("non_constant", source, &[]);
+    let stderr = stderr(&output);
+    assert_eq!(output.status.code(), Some(1), "{stderr}");
+    assert!(!stderr.contains("panicked"), "{stderr}");
+    let expected =
+        "non_constant.c:3:5: expression in static assertion is not an integer constant expression";
+    assert!(stderr.contains(expected), "{stderr}");
+}
+
+#[test]
+fn passing_assertions_compile() {
+    let source = "_Static_assert(1, \"never\");\nint main(void) {\n    _Static_assert((char)257 == 1, \"never\");\n    return 0;\n}\n";
+    let output = compile("passing", source, &[]);
+    assert!(output.status.success(), "{}", stderr(&output));
+}
@@ -0,0 +1,86 @@
+//! A `switch` rejects labels that can't be told apart, and `-pedantic` reports case ranges.
+
+mod common;
+
+use common::{compile, stderr};
+
+fn assert_fails_with(name: &str, source: &str, expected: &str) {
+    let output = compile(name, source, &[]);
+    let stderr = stderr(&output);
+    assert_eq!(output.status.code(), Some(1), "{stderr}");
+    assert!(!stderr.contains("panicked"), "{stderr}");
+    assert!(stderr.contains(expected), "{stderr}");
+}
+
+#[test]
+fn non_constant_label_is_rejected() {
+    let source = "int main(void) {\n    int x = 1;\n    switch (x) {\n    case x:\n        return 1;\n    }\n    return 0;\n}\n";
+    assert_fails_with(
+        "non_constant",
+        source,
+        "non_constant.c:4:5: case label does not reduce to an integer constant",
+    );
+}
+
+#[test]
+fn overflow_in_range_is_rejected() {
+    let source = "int main(void) {\n    switch (1) {\n    case 1 ... 2147483647 + 1:\n        return 1;\n    }\n    return 0;\n}\n";
+    assert_fails_with(
+        "overflow",
+        source,
+        "overflow.c:3:5: integer overflow in constant expression",
+    );
+}
+
+#[test]
+fn zero_divisor_in_label_is_rejected() {
+    let source = "int main(void) {\n    switch (1) {\n    case 1 / 0:\n        return 1;\n    }\n    return 0;\n}\n";
+    assert_fails_with(
+        "zero_divisor",
+        source,
+        "zero_divisor.c:3:5: division by zero in constant expression",
+    );
+}
+
+#[test]
+fn duplicate_values_are_rejected() {
+    let source = "int main(void) {\n    switch (1) {\n    case 2 ... 4:\n    case 3:\n        return 1;\n    }\n    return 0;\n}\n";
+    assert_fails_with("duplicate", source, "4:5: duplicate case value");
+}
+
+#[test]
+fn values_are_compared_in_the_promoted_type() {
+    let source = "int main(void) {\n    switch (1u) {\n    case -1:\n    case 4294967295u:\n        return 1;\n    }\n    return 0;\n}\n";
+    assert_fails_with("promoted", source, "4:5: duplicate case value");
+}
+
+#[test]
+fn second_default_is_rejected() {
+    let source = "int main(void) {\n    switch (1) {\n    default:\n    default:\n        return 1;\n    }\n}\n";
+    assert_fails_with(
+        "defaults",
+        source,
+        "4:5: multiple default labels in one switch",
+    );
+}
+
+#[test]
+fn case_outside_switch_is_rejected() {
+    let source = "int main(void) {\n    switch (1)\n        ;\n    case 1:\n    return 0;\n}\n";
+    assert_fails_with(
+        "outside",
+        source,
+        "4:5: `case` label not within a switch statement",
+    );
+}
+
+#[test]
+fn pedantic_reports_case_ranges() {
+    let source = "int main(void) {\n    switch (1) {\n    case 0:\n    case 1 ... 5:\n        return 1;\n    }\n    return 0;\n}\n";
+    let output = compile("ranges", source, &["-pedantic"]);
+    let stderr = stderr(&output);
+    assert!(output.status.success(), "{stderr}");
+    let expected = "4:5: warning: range expressions in switch statements are non-standard";
+    assert!(stderr.contains(expected), "{stderr}");
+    assert!(!stderr.contains("3:5"), "{stderr}");
+}
@@ -0,0 +1,63 @@
+//! Reads of local variables that are never assigned on some or all of the ways to them.
+
+mod common;
+
+use common::{compile, stderr};
+
+const SOURCE: &str = "int never(void) {
+    int x;
+    return x;
+}
+int sometimes(int c) {
+    int y;
+    if (c)
+        y = 1;
+    return y;
+}
+int always(int c) {
+    int z;
+    if (c)
+        z = 1;
+    else
+        z = 2;
+    return z;
+}
+int main(void) { return always(1); }
+";
+
+const IS: &str = "uninitialized.c:3:5: warning: 'x' is used uninitialized [-Wuninitialized]";
+const MAY: &str =
+    "uninitialized.c:9:5: warning: 'y' may be used uninitialized [-Wmaybe-uninitialized]";
+
+fn warnings(flags: &[&str]) -> String {
+    let output = compile("uninitialized", SOURCE, flags);
+    let stderr = stderr(&output);
+    assert!(output.status.success(), "{stderr}");
+    stderr
+}
+
+#[test]
+fn wall_tells_is_from_may_be() {
+    let stderr = warnings(&["-Wall"]);
+    assert_eq!(stderr.lines().collect::<Vec<_>>(), [IS, MAY], "{stderr}");
+}
+
+#[test]
+fn nothing_without_flags() {
+    assert_eq!(warnings(&[]), "");
+}
+
+#[test]
+fn wno_disables_may_be() {
+    let stderr = warnings(&["-Wall", "-Wno-maybe-uninitialized"]);
+    assert_eq!(stderr.lines().collect::<Vec<_>>(), [IS], "{stderr}");
+}
+
+#[test]
+fn werror_fails_on_uninitialized_read() {
+    let output = compile("uninitialized", SOURCE, &["-Wuninitialized", "-Werror"]);
+    let stderr = stderr(&output);
+    assert_eq!(output.status.code(), Some(1), "{stderr}");
+    let expected = "uninitialized.c:3:5: error: 'x' is used uninitialized [-Werror=uninitialized]";
+    assert_eq!(stderr.lines().collect::<Vec<_>>(), [expected], "{stderr}");
+}
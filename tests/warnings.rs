@@ -0,0 +1,124 @@
+//! `-W` flags enable warnings by name or group, `-Wno-` disables them and `-Werror`
+//! makes them errors; each warning is reported at the statement or declaration it's in.
+
+mod common;
+
+use common::{compile, stderr};
+
+const SOURCE: &str = "int value(void) { return 1; }
+int f(int unused, int used) {
+    int x = used;
+    int dead;
+    if (x = used)
+        x = 2;
+    while (x > 5);
+    unsigned char c = 1;
+    if (c > 300)
+        x = 3;
+    {
+        int x = 4;
+        used = x;
+    }
+    value();
+    return x;
+}
+int main(void) { return f(1, 2); }
+";
+
+const ALL: [&str; 3] = [
+    "warnings.c:4:9: warning: unused variable 'dead' [-Wunused-variable]",
+    "warnings.c:5:5: warning: assignment used as truth value; compare the result if that is intended [-Wassign-in-condition]",
+    "warnings.c:9:5: warning: comparison is always false due to limited range of data type [-Wtautological-compare]",
+];
+
+const EXTRA: [&str; 2] = [
+    "warnings.c:2:11: warning: unused parameter 'unused' [-Wunused-parameter]",
+    "warnings.c:7:5: warning: suggest braces around empty body in 'while' statement [-Wempty-body]",
+];
+
+const OPT_IN: [&str; 2] = [
+    "warnings.c:12:13: warning: declaration of 'x' shadows a previous local [-Wshadow]",
+    "warnings.c:15:5: warning: ignoring return value of 'value' [-Wunused-result]",
+];
+
+fn warnings(flags: &[&str]) -> String {
+    let output = compile("warnings", SOURCE, flags);
+    let stderr = stderr(&output);
+    assert!(output.status.success(), "{stderr}");
+    stderr
+}
+
+#[test]
+fn none_without_flags() {
+    assert_eq!(warnings(&[]), "");
+}
+
+#[test]
+fn wall_enables_its_group() {
+    let stderr = warnings(&["-Wall"]);
+    assert_eq!(stderr.lines().collect::<Vec<_>>(), ALL, "{stderr}");
+}
+
+#[test]
+fn wextra_enables_its_group() {
+    let stderr = warnings(&["-Wextra"]);
+    assert_eq!(stderr.lines().collect::<Vec<_>>(), EXTRA, "{stderr}");
+}
+
+#[test]
+fn single_warnings_are_enabled_by_name() {
+    let stderr = warnings(&["-Wshadow", "-Wunused-result"]);
+    assert_eq!(stderr.lines().collect::<Vec<_>>(), OPT_IN, "{stderr}");
+}
+
+#[test]
+fn wno_disables_one_warning_of_a_group() {
+    let stderr = warnings(&["-Wall", "-Wno-unused-variable"]);
+    assert_eq!(stderr.lines().collect::<Vec<_>>(), ALL[1..], "{stderr}");
+}
+
+#[test]
+fn werror_makes_warnings_errors() {
+    let output = compile("warnings", SOURCE, &["-Wall", "-Werror"]);
+    let stderr = stderr(&output);
+    assert_eq!(output.status.code(), Some(1), "{stderr}");
+    let expected = "warnings.c:4:9: error: unused variable 'dead' [-Werror=unused-variable]";
+    assert!(stderr.contains(expected), "{stderr}");
+    assert!(!stderr.contains("warning:"), "{stderr}");
+}
+
+#[test]
+fn werror_without_warnings_compiles() {
+    let output = compile(
+        "warnings",
+        SOURCE,
+        &[
+            "-Wall",
+            "-Wno-unused-variable",
+            "-Wno-assign-in-condition",
+            "-Wno-tautological-compare",
+            "-Werror",
+        ],
+    );
+    assert!(output.status.success(), "{}", stderr(&output));
+}
+
+#[test]
+fn same_warning_twice_in_a_statement_is_reported_once() {
+    let source = "int f(double d) {\n    if (d > 0)\n        return (unsigned int)-1.0 + (int)1e10;\n    return 0;\n}\nint main(void) { return f(-1.0); }\n";
+    let output = compile("twice", source, &[]);
+    let stderr = stderr(&output);
+    let expected = "twice.c:2:5: warning: floating value out of range in conversion [-Woverflow]";
+    assert_eq!(stderr.lines().collect::<Vec<_>>(), [expected], "{stderr}");
+}
+
+#[test]
+fn unknown_warning_is_an_error() {
+    let output = compile("warnings", SOURCE, &["-Wbogus"]);
+    let stderr = stderr(&output);
+    assert_eq!(output.status.code(), Some(1), "{stderr}");
+    assert!(
+        stderr.contains("unknown warning option '-Wbogus'"),
+        "{stderr}"
+    );
+}